@@ -23,3 +23,14 @@ pub fn bench_synth_nested_borrow(c: &mut Criterion) {
         })
     });
 }
+pub fn bench_synth_large_list(c: &mut Criterion) {
+    c.bench_function("synth_large_list", |b| {
+        let placeholder: JP<async_graphql::Value> =
+            JP::init("{ posts { id title user { id name } } }", None).with_posts(10_000);
+        let synth = placeholder.synth();
+        b.iter(|| {
+            let a: async_graphql::Value = synth.synthesize().unwrap();
+            drop(a);
+        })
+    });
+}
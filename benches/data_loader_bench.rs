@@ -85,6 +85,7 @@ pub fn benchmark_data_loader(c: &mut Criterion) {
                     file: Arc::new(File {}),
                     cache: Arc::new(Cache {}),
                     extensions: Arc::new(vec![]),
+                    body_transformers: Arc::new(vec![]),
                     cmd_worker: None,
                     worker: None,
                 };
@@ -38,7 +38,7 @@ pub fn benchmark_handle_request(c: &mut Criterion) {
                     .body(hyper::Body::from(QUERY))
                     .unwrap();
 
-                let _ = handle_request::<GraphQLRequest>(req, server_config.app_ctx.clone())
+                let _ = handle_request::<GraphQLRequest>(req, server_config.app_ctx())
                     .await
                     .unwrap();
             });
@@ -61,7 +61,7 @@ pub fn benchmark_handle_request(c: &mut Criterion) {
                     .body(hyper::Body::from(QUERY))
                     .unwrap();
 
-                let _ = handle_request::<GraphQLRequest>(req, server_config.app_ctx.clone())
+                let _ = handle_request::<GraphQLRequest>(req, server_config.app_ctx())
                     .await
                     .unwrap();
             });
@@ -0,0 +1,273 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use reqwest::header::{HeaderValue, CONTENT_TYPE};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::core::config::Apollo;
+use crate::core::HttpIO;
+
+/// The endpoint of Apollo Studio that receives the schema reports
+pub const SCHEMA_REPORTING_URL: &str = "https://schema-reporting.api.apollographql.com/api/graphql";
+
+const REPORT_SCHEMA_MUTATION: &str = r#"mutation ReportSchemaMutation($report: SchemaReport!, $coreSchema: String) {
+  reportSchema(report: $report, coreSchema: $coreSchema) {
+    __typename
+    ... on ReportSchemaError { message code }
+    ... on ReportSchemaResponse { inSeconds withCoreSchema }
+  }
+}"#;
+
+/// Identifies the server process across the reports of its schemas
+static BOOT_ID: Lazy<String> = Lazy::new(|| uuid::Uuid::new_v4().to_string());
+
+/// The fallback interval of the reports in seconds, used when the report
+/// failed without a response from Apollo Studio.
+const RETRY_IN_SECONDS: u64 = 20;
+
+fn to_report(apollo: &Apollo, schema: &str, with_core_schema: bool) -> Value {
+    let hash = format!("{:x}", Sha256::digest(schema.as_bytes()));
+    let version = option_env!("APP_VERSION").unwrap_or("dev");
+    let platform = apollo
+        .platform
+        .clone()
+        .filter(|platform| !platform.is_empty());
+    let user_version = apollo
+        .user_version
+        .clone()
+        .filter(|version| !version.is_empty());
+
+    json!({
+        "query": REPORT_SCHEMA_MUTATION,
+        "operationName": "ReportSchemaMutation",
+        "variables": {
+            "report": {
+                "bootId": BOOT_ID.as_str(),
+                "coreSchemaHash": hash,
+                "graphRef": apollo.graph_ref,
+                "libraryVersion": format!("tailcall@{}", version),
+                "platform": platform.unwrap_or_else(|| "local".to_string()),
+                "runtimeVersion": version,
+                "userVersion": user_version,
+            },
+            "coreSchema": with_core_schema.then_some(schema),
+        }
+    })
+}
+
+/// A report rejected by Apollo Studio, e.g. for an unknown graph
+#[derive(Debug, thiserror::Error)]
+#[error("{code}: {message}")]
+pub struct ReportSchemaError {
+    pub code: String,
+    pub message: String,
+}
+
+/// When and how Apollo Studio expects the next report
+#[derive(Debug, PartialEq)]
+pub struct NextReport {
+    pub in_seconds: u64,
+    pub with_core_schema: bool,
+}
+
+/// Reports the schema to Apollo Studio, so that the usage reported by the
+/// tracing extension is matched to the fields of the schema. The schema itself
+/// is sent only when Apollo Studio asks for it, otherwise just its hash.
+pub async fn report_schema(
+    http: &dyn HttpIO,
+    url: &str,
+    apollo: &Apollo,
+    schema: &str,
+    with_core_schema: bool,
+) -> Result<NextReport> {
+    let mut request = reqwest::Request::new(reqwest::Method::POST, url.parse()?);
+    let headers = request.headers_mut();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    headers.insert("x-api-key", HeaderValue::from_str(&apollo.api_key)?);
+    headers.insert(
+        "apollographql-client-name",
+        HeaderValue::from_static("tailcall"),
+    );
+    let report = to_report(apollo, schema, with_core_schema);
+    *request.body_mut() = Some(serde_json::to_vec(&report)?.into());
+
+    let response = http.execute(request).await?;
+    let body: Value = serde_json::from_slice(&response.body)?;
+    let result = &body["data"]["reportSchema"];
+
+    match result["__typename"].as_str() {
+        Some("ReportSchemaResponse") => Ok(NextReport {
+            in_seconds: result["inSeconds"].as_u64().unwrap_or(RETRY_IN_SECONDS),
+            with_core_schema: result["withCoreSchema"].as_bool().unwrap_or_default(),
+        }),
+        Some("ReportSchemaError") => Err(ReportSchemaError {
+            code: result["code"].as_str().unwrap_or_default().to_string(),
+            message: result["message"].as_str().unwrap_or_default().to_string(),
+        }
+        .into()),
+        _ => Err(anyhow!("Unexpected response from Apollo Studio: {}", body)),
+    }
+}
+
+/// Keeps reporting the schema at the interval requested by Apollo Studio
+/// until the task is aborted, e.g. when the config is reloaded. A report that
+/// is rejected by Apollo Studio stops the reporting, the other failures are
+/// retried.
+pub async fn run_schema_reporting(
+    http: Arc<dyn HttpIO>,
+    url: String,
+    apollo: Apollo,
+    schema: String,
+) {
+    let mut with_core_schema = false;
+    loop {
+        let in_seconds =
+            match report_schema(http.as_ref(), &url, &apollo, &schema, with_core_schema).await {
+                Ok(next) => {
+                    with_core_schema = next.with_core_schema;
+                    next.in_seconds
+                }
+                Err(err) if err.is::<ReportSchemaError>() => {
+                    tracing::warn!("Stopped reporting the schema to Apollo Studio: {}", err);
+                    return;
+                }
+                Err(err) => {
+                    tracing::warn!("Failed to report the schema to Apollo Studio: {}", err);
+                    RETRY_IN_SECONDS
+                }
+            };
+
+        tokio::time::sleep(Duration::from_secs(in_seconds)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::runtime::NativeHttp;
+
+    #[tokio::test]
+    async fn test_report_schema() {
+        let server = httpmock::MockServer::start();
+        let schema = "type Query { hello: String }";
+        let ingress = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/api/graphql")
+                .header("x-api-key", "service:key")
+                .json_body_partial(
+                    json!({
+                        "operationName": "ReportSchemaMutation",
+                        "variables": {
+                            "report": {
+                                "coreSchemaHash": format!("{:x}", Sha256::digest(schema)),
+                                "graphRef": "graph@current",
+                            },
+                            "coreSchema": schema,
+                        }
+                    })
+                    .to_string(),
+                );
+            then.status(200).json_body(json!({
+                "data": {
+                    "reportSchema": {
+                        "__typename": "ReportSchemaResponse",
+                        "inSeconds": 60,
+                        "withCoreSchema": false
+                    }
+                }
+            }));
+        });
+
+        let apollo = Apollo {
+            api_key: "service:key".to_string(),
+            graph_ref: "graph@current".to_string(),
+            schema_reporting: true,
+            ..Default::default()
+        };
+        let url = server.url("/api/graphql");
+
+        let next = report_schema(&NativeHttp::default(), &url, &apollo, schema, true)
+            .await
+            .unwrap();
+        ingress.assert();
+        assert_eq!(next, NextReport { in_seconds: 60, with_core_schema: false });
+    }
+
+    #[tokio::test]
+    async fn test_report_schema_error() {
+        let server = httpmock::MockServer::start();
+        server.mock(|when, then| {
+            when.method(httpmock::Method::POST).path("/api/graphql");
+            then.status(200).json_body(json!({
+                "data": {
+                    "reportSchema": {
+                        "__typename": "ReportSchemaError",
+                        "code": "INVALID_GRAPH_REF",
+                        "message": "Unknown graph"
+                    }
+                }
+            }));
+        });
+
+        let apollo = Apollo { graph_ref: "graph@current".to_string(), ..Default::default() };
+        let url = server.url("/api/graphql");
+        let error = report_schema(&NativeHttp::default(), &url, &apollo, "", false)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.to_string(), "INVALID_GRAPH_REF: Unknown graph");
+    }
+
+    #[tokio::test]
+    async fn test_run_schema_reporting() {
+        let server = httpmock::MockServer::start();
+        let schema = "type Query { hello: String }";
+        // the first report has only the hash of the schema
+        let hash_only = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/api/graphql")
+                .body_contains(r#""coreSchema":null"#);
+            then.status(200).json_body(json!({
+                "data": {
+                    "reportSchema": {
+                        "__typename": "ReportSchemaResponse",
+                        "inSeconds": 0,
+                        "withCoreSchema": true
+                    }
+                }
+            }));
+        });
+        // the next one has the schema asked for, its rejection stops the reporting
+        let with_schema = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/api/graphql")
+                .body_contains(schema);
+            then.status(200).json_body(json!({
+                "data": {
+                    "reportSchema": {
+                        "__typename": "ReportSchemaError",
+                        "code": "BOOT_ID_IS_NOT_VALID_UUID",
+                        "message": "Invalid boot id"
+                    }
+                }
+            }));
+        });
+
+        let apollo = Apollo { graph_ref: "graph@current".to_string(), ..Default::default() };
+        let reporting = run_schema_reporting(
+            Arc::new(NativeHttp::default()),
+            server.url("/api/graphql"),
+            apollo,
+            schema.to_string(),
+        );
+        tokio::time::timeout(Duration::from_secs(5), reporting)
+            .await
+            .unwrap();
+
+        hash_only.assert();
+        with_schema.assert();
+    }
+}
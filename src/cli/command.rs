@@ -2,6 +2,8 @@ use clap::{Parser, Subcommand};
 use strum_macros::Display;
 use tailcall_version::VERSION;
 
+use crate::core::config::Source;
+
 const ABOUT: &str = r"
    __        _ __           ____
   / /_____ _(_) /________ _/ / /
@@ -22,7 +24,7 @@ pub enum Command {
     Start {
         /// Path for the configuration files or http(s) link to config files
         /// separated by spaces if more than one
-        #[arg(required = true)]
+        #[arg(required_unless_present = "stdin")]
         file_paths: Vec<String>,
 
         /// Controls SSL/TLS certificate verification for remote config files
@@ -30,6 +32,23 @@ pub enum Command {
         /// production)
         #[arg(short, long, action = clap::ArgAction::Set, default_value_t = true)]
         verify_ssl: bool,
+
+        /// Reads the configuration from the standard input in the given
+        /// format (graphql, json or yml), instead of from files. Relative
+        /// links are resolved against the current directory
+        #[arg(
+            long,
+            value_name = "FORMAT",
+            num_args = 0..=1,
+            default_missing_value = "graphql",
+            conflicts_with_all = ["file_paths", "watch"]
+        )]
+        stdin: Option<Source>,
+
+        /// Reloads the server when the local configuration files, or the
+        /// files they link to, change
+        #[arg(short, long)]
+        watch: bool,
     },
 
     /// Validate a composition spec
@@ -61,6 +80,48 @@ pub enum Command {
         folder_path: String,
     },
 
+    /// Compares the public schemas of two configs and reports breaking
+    /// changes
+    Diff {
+        /// Path of the current configuration file
+        #[arg(required = true)]
+        old: String,
+
+        /// Path of the updated configuration file
+        #[arg(required = true)]
+        new: String,
+    },
+
+    /// Prints the resolved blueprint of the configuration as JSON, with the
+    /// upstream settings and the resolver of every field
+    Inspect {
+        /// Path for the configuration files separated by spaces if more than
+        /// one
+        #[arg(required = true)]
+        file_paths: Vec<String>,
+
+        /// Controls SSL/TLS certificate verification for remote config files
+        /// Set to false to skip certificate verification (not recommended for
+        /// production)
+        #[arg(short, long, action = clap::ArgAction::Set, default_value_t = true)]
+        verify_ssl: bool,
+    },
+
+    /// Prints TypeScript declarations of the types of the schema served by
+    /// the configuration
+    Typescript {
+        /// Path for the configuration files separated by spaces if more than
+        /// one
+        #[arg(required = true)]
+        file_paths: Vec<String>,
+
+        /// Controls SSL/TLS certificate verification for remote config files
+        /// Set to false to skip certificate verification (not recommended for
+        /// production)
+        #[arg(short, long, action = clap::ArgAction::Set, default_value_t = true)]
+        verify_ssl: bool,
+    },
+
     /// Generates a Tailcall Configuration from one or more source files.
     Gen {
         /// Path of the configuration file
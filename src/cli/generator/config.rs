@@ -45,6 +45,7 @@ pub struct PresetConfig {
     pub infer_type_names: Option<bool>,
     pub tree_shake: Option<bool>,
     pub unwrap_single_field_types: Option<bool>,
+    pub infer_relations: Option<bool>,
 }
 #[derive(Deserialize, Serialize, Debug, Default)]
 #[serde(transparent)]
@@ -93,6 +94,11 @@ pub enum Source<Status = UnResolved> {
     Config {
         src: Location<Status>,
     },
+    #[serde(rename_all = "camelCase")]
+    GraphQL {
+        src: Location<Status>,
+        headers: Headers,
+    },
 }
 
 #[derive(Deserialize, Serialize, Debug, Default)]
@@ -148,6 +154,10 @@ impl ValidateFrom<PresetConfig> for Preset {
             preset = preset.tree_shake(tree_shake);
         }
 
+        if let Some(infer_relations) = config.infer_relations {
+            preset = preset.infer_relations(infer_relations);
+        }
+
         // TODO: The field names in trace should be inserted at compile time.
         Valid::succeed(preset)
             .and_then(|preset| {
@@ -236,6 +246,10 @@ impl Source<UnResolved> {
                 let resolved_path = src.into_resolved(parent_dir);
                 Ok(Source::Config { src: resolved_path })
             }
+            Source::GraphQL { src, headers } => {
+                let resolved_path = src.into_resolved(parent_dir);
+                Ok(Source::GraphQL { src: resolved_path, headers })
+            }
         }
     }
 }
@@ -342,6 +356,7 @@ mod tests {
             infer_type_names: None,
             merge_type: Some(2.0),
             unwrap_single_field_types: None,
+            infer_relations: None,
         };
 
         let transform_preset: Result<Preset, ValidationError<String>> =
@@ -356,12 +371,14 @@ mod tests {
             infer_type_names: Some(true),
             merge_type: Some(0.5),
             unwrap_single_field_types: None,
+            infer_relations: Some(true),
         };
         let transform_preset: Preset = config_preset.validate_into().to_result().unwrap();
         let expected_preset = Preset::new()
             .infer_type_names(true)
             .tree_shake(true)
-            .merge_type(0.5);
+            .merge_type(0.5)
+            .infer_relations(true);
         assert_eq!(transform_preset, expected_preset);
     }
 
@@ -1,8 +1,9 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
 use anyhow::anyhow;
-use http::header::{HeaderMap, HeaderName, HeaderValue};
+use http::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
 use inquire::Confirm;
 use pathdiff::diff_paths;
 use tailcall_valid::{ValidateInto, Validator};
@@ -12,7 +13,7 @@ use super::source::ConfigSource;
 use crate::cli::llm::InferTypeName;
 use crate::core::config::transformer::{Preset, RenameTypes};
 use crate::core::config::{self, ConfigModule, ConfigReaderContext};
-use crate::core::generator::{Generator as ConfigGenerator, Input};
+use crate::core::generator::{Generator as ConfigGenerator, Input, INTROSPECTION_QUERY};
 use crate::core::proto_reader::ProtoReader;
 use crate::core::resource_reader::{Resource, ResourceReader};
 use crate::core::runtime::TargetRuntime;
@@ -114,13 +115,7 @@ impl Generator {
                         request.body_mut().replace(req_body.to_string().into());
                     }
                     if let Some(headers_inner) = headers.as_btree_map() {
-                        let mut header_map = HeaderMap::new();
-                        for (key, value) in headers_inner {
-                            let header_name = HeaderName::try_from(key)?;
-                            let header_value = HeaderValue::try_from(value.to_string())?;
-                            header_map.insert(header_name, header_value);
-                        }
-                        *request.headers_mut() = header_map;
+                        *request.headers_mut() = to_header_map(headers_inner)?;
                     }
 
                     let resource: Resource = request.into();
@@ -151,6 +146,25 @@ impl Generator {
                     let schema = reader.read_file(path).await?.content;
                     input_samples.push(Input::Config { schema, source });
                 }
+                Source::GraphQL { src, headers } => {
+                    let url = src.0;
+                    let mut request = reqwest::Request::new(reqwest::Method::POST, url.parse()?);
+                    let body = serde_json::json!({ "query": INTROSPECTION_QUERY });
+                    request.body_mut().replace(body.to_string().into());
+                    if let Some(headers_inner) = headers.as_btree_map() {
+                        *request.headers_mut() = to_header_map(headers_inner)?;
+                    }
+                    request
+                        .headers_mut()
+                        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+                    let resource: Resource = request.into();
+                    let response = reader.read_file(resource).await?;
+                    input_samples.push(Input::GraphQL {
+                        url,
+                        introspection: serde_json::from_str(&response.content)?,
+                    });
+                }
             }
         }
 
@@ -196,6 +210,17 @@ impl Generator {
     }
 }
 
+/// Converts the headers of the config into a header map of the request.
+fn to_header_map(headers: &BTreeMap<String, String>) -> anyhow::Result<HeaderMap> {
+    let mut header_map = HeaderMap::new();
+    for (key, value) in headers {
+        let header_name = HeaderName::try_from(key)?;
+        let header_value = HeaderValue::try_from(value.to_string())?;
+        header_map.insert(header_name, header_value);
+    }
+    Ok(header_map)
+}
+
 /// Checks if file or folder already exists or not.
 fn is_exists(path: &str) -> bool {
     fs::metadata(path).is_ok()
@@ -1,5 +1,8 @@
 use anyhow::{anyhow, Result};
+use opentelemetry::KeyValue;
 
+use crate::cli::runtime::UPSTREAM_REQUEST_COUNT;
+use crate::core::cache::metrics::CACHE_METRICS;
 use crate::core::runtime::TargetRuntime;
 
 fn cache_metrics(runtime: &TargetRuntime) -> Result<()> {
@@ -19,6 +22,52 @@ fn cache_metrics(runtime: &TargetRuntime) -> Result<()> {
     Ok(())
 }
 
+fn cache_field_metrics() -> Result<()> {
+    let meter = opentelemetry::global::meter("cache");
+    let hits = meter
+        .u64_observable_counter("cache.hits")
+        .with_description("Number of field lookups served from the cache")
+        .init();
+    let misses = meter
+        .u64_observable_counter("cache.misses")
+        .with_description("Number of field lookups not found in the cache")
+        .init();
+
+    meter.register_callback(&[hits.as_any(), misses.as_any()], move |observer| {
+        CACHE_METRICS.for_each(|label, hit_count, miss_count| {
+            let attributes = [
+                KeyValue::new("type", label.type_name.clone()),
+                KeyValue::new("field", label.field_name.clone()),
+            ];
+            observer.observe_u64(&hits, hit_count, &attributes);
+            observer.observe_u64(&misses, miss_count, &attributes);
+        });
+    })?;
+
+    Ok(())
+}
+
+fn upstream_metrics() -> Result<()> {
+    let meter = opentelemetry::global::meter("http_request");
+    let counter = meter
+        .u64_observable_counter("http.client.upstream.count")
+        .with_description("Number of upstream requests per host, port and status class")
+        .init();
+
+    meter.register_callback(&[counter.as_any()], move |observer| {
+        UPSTREAM_REQUEST_COUNT.for_each(|host, port, class, count| {
+            let attributes = [
+                KeyValue::new("server.address", host.to_string()),
+                KeyValue::new("server.port", port as i64),
+                KeyValue::new("http.response.status_class", class.to_string()),
+            ];
+            observer.observe_u64(&counter, count, &attributes);
+        });
+    })?;
+
+    Ok(())
+}
+
 fn process_resources_metrics() -> Result<()> {
     let meter = opentelemetry::global::meter("process-resources");
 
@@ -27,6 +76,8 @@ fn process_resources_metrics() -> Result<()> {
 
 pub fn init_metrics(runtime: &TargetRuntime) -> Result<()> {
     cache_metrics(runtime)?;
+    cache_field_metrics()?;
+    upstream_metrics()?;
     process_resources_metrics()?;
 
     Ok(())
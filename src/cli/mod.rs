@@ -1,3 +1,4 @@
+pub mod apollo;
 pub mod command;
 mod fmt;
 pub mod generator;
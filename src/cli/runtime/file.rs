@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 use crate::core::{Errata, FileIO};
@@ -24,6 +26,33 @@ async fn write<'a>(path: &'a str, content: &'a [u8]) -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn read_dir(path: &str) -> anyhow::Result<Option<Vec<String>>> {
+    let root = Path::new(path);
+    if !tokio::fs::metadata(root)
+        .await
+        .is_ok_and(|meta| meta.is_dir())
+    {
+        return Ok(None);
+    }
+
+    let mut files = vec![];
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let mut entries = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.file_type().await?.is_dir() {
+                dirs.push(entry.path());
+            } else {
+                let path = entry.path();
+                files.push(path.strip_prefix(root)?.to_string_lossy().to_string());
+            }
+        }
+    }
+    files.sort();
+
+    Ok(Some(files))
+}
+
 #[async_trait::async_trait]
 impl FileIO for NativeFileIO {
     async fn write<'a>(&'a self, path: &'a str, content: &'a [u8]) -> anyhow::Result<()> {
@@ -43,6 +72,14 @@ impl FileIO for NativeFileIO {
         tracing::info!("File read: {} ... ok", path);
         Ok(content)
     }
+
+    async fn read_dir<'a>(&'a self, path: &'a str) -> anyhow::Result<Option<Vec<String>>> {
+        let files = read_dir(path).await.map_err(|err| {
+            Errata::new(format!("Failed to read directory: {}", path).as_str())
+                .description(err.to_string())
+        })?;
+        Ok(files)
+    }
 }
 
 #[cfg(test)]
@@ -68,4 +105,28 @@ mod tests {
         // Verify that an error is returned
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_read_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("types")).unwrap();
+        std::fs::write(dir.path().join("news.proto"), "").unwrap();
+        std::fs::write(dir.path().join("types/news_type.proto"), "").unwrap();
+
+        let file_io = NativeFileIO::init();
+        let path = dir.path().to_string_lossy();
+        let files = file_io.read_dir(&path).await.unwrap();
+        assert_eq!(
+            files,
+            Some(vec![
+                "news.proto".to_string(),
+                "types/news_type.proto".to_string()
+            ])
+        );
+
+        // files aren't directories
+        let path = dir.path().join("news.proto");
+        let files = file_io.read_dir(&path.to_string_lossy()).await.unwrap();
+        assert_eq!(files, None);
+    }
 }
@@ -1,6 +1,9 @@
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 
 use anyhow::Result;
+use dashmap::DashMap;
+use futures_util::stream::{self, StreamExt};
 use http_cache_reqwest::{Cache, CacheMode, HttpCache, HttpCacheOptions};
 use hyper::body::Bytes;
 use once_cell::sync::Lazy;
@@ -11,6 +14,7 @@ use opentelemetry_http::HeaderInjector;
 use opentelemetry_semantic_conventions::trace::{
     HTTP_REQUEST_METHOD, HTTP_RESPONSE_STATUS_CODE, NETWORK_PROTOCOL_VERSION, URL_FULL,
 };
+use reqwest::redirect::Policy;
 use reqwest::Client;
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use tailcall_http_cache::HttpCacheManager;
@@ -19,7 +23,8 @@ use tracing_opentelemetry::OpenTelemetrySpanExt;
 use super::HttpIO;
 use crate::core::blueprint::telemetry::Telemetry;
 use crate::core::blueprint::Upstream;
-use crate::core::http::Response;
+use crate::core::config::RedirectPolicy;
+use crate::core::http::{BodyStream, Response};
 
 static HTTP_CLIENT_REQUEST_COUNT: Lazy<Counter<u64>> = Lazy::new(|| {
     let meter = opentelemetry::global::meter("http_request");
@@ -30,6 +35,57 @@ static HTTP_CLIENT_REQUEST_COUNT: Lazy<Counter<u64>> = Lazy::new(|| {
         .init()
 });
 
+/// Upstream requests per host, port and status class, exported by the metrics
+/// of the cli. The `@grpc` calls go through the same client, so they are
+/// counted too.
+pub static UPSTREAM_REQUEST_COUNT: Lazy<UpstreamRequestCount> =
+    Lazy::new(UpstreamRequestCount::default);
+
+#[derive(Default)]
+pub struct UpstreamRequestCount {
+    counts: DashMap<(String, u16, String), AtomicU64>,
+}
+
+impl UpstreamRequestCount {
+    fn add(
+        &self,
+        (host, port): (String, u16),
+        response: &reqwest_middleware::Result<reqwest::Response>,
+    ) {
+        let status = match response {
+            Ok(resp) => Some(resp.status()),
+            Err(err) => err.status(),
+        };
+        // requests that failed without a response, e.g. on connection errors
+        let class = status
+            .map(|status| format!("{}xx", status.as_u16() / 100))
+            .unwrap_or_else(|| "error".to_string());
+
+        self.counts
+            .entry((host, port, class))
+            .or_default()
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the number of requests made to the host and port with the
+    /// status class
+    pub fn get(&self, host: &str, port: u16, class: &str) -> u64 {
+        self.counts
+            .get(&(host.to_string(), port, class.to_string()))
+            .map(|count| count.load(Ordering::Relaxed))
+            .unwrap_or_default()
+    }
+
+    /// Calls `f` with the host, the port, the status class and the number of
+    /// requests
+    pub fn for_each(&self, mut f: impl FnMut(&str, u16, &str, u64)) {
+        for entry in self.counts.iter() {
+            let (host, port, class) = entry.key();
+            f(host, *port, class, entry.load(Ordering::Relaxed));
+        }
+    }
+}
+
 #[derive(Default)]
 struct RequestCounter {
     attributes: Option<Vec<KeyValue>>,
@@ -67,11 +123,26 @@ fn get_response_status(response: &reqwest_middleware::Result<reqwest::Response>)
     KeyValue::new(HTTP_RESPONSE_STATUS_CODE, status_code as i64)
 }
 
+fn redirect_policy(redirect: &RedirectPolicy) -> Policy {
+    match redirect {
+        RedirectPolicy::None => Policy::none(),
+        RedirectPolicy::Limited(max) => Policy::limited(*max),
+        RedirectPolicy::All => Policy::custom(|attempt| {
+            if attempt.previous().contains(attempt.url()) {
+                attempt.error("redirect loop detected")
+            } else {
+                attempt.follow()
+            }
+        }),
+    }
+}
+
 #[derive(Clone)]
 pub struct NativeHttp {
     client: ClientWithMiddleware,
     http2_only: bool,
     enable_telemetry: bool,
+    error_body_size: usize,
 }
 
 impl Default for NativeHttp {
@@ -80,6 +151,7 @@ impl Default for NativeHttp {
             client: ClientBuilder::new(Client::new()).build(),
             http2_only: false,
             enable_telemetry: false,
+            error_body_size: Upstream::default().error_body_size,
         }
     }
 }
@@ -96,7 +168,8 @@ impl NativeHttp {
             .pool_idle_timeout(Some(Duration::from_secs(upstream.pool_idle_timeout)))
             .pool_max_idle_per_host(upstream.pool_max_idle_per_host)
             .user_agent(upstream.user_agent.clone())
-            .danger_accept_invalid_certs(!upstream.verify_ssl);
+            .danger_accept_invalid_certs(!upstream.verify_ssl)
+            .redirect(redirect_policy(&upstream.redirect));
 
         // Add Http2 Prior Knowledge
         if upstream.http2_only {
@@ -124,12 +197,12 @@ impl NativeHttp {
             client: client.build(),
             http2_only: upstream.http2_only,
             enable_telemetry: telemetry.export.is_some(),
+            error_body_size: upstream.error_body_size,
         }
     }
-}
 
-#[async_trait::async_trait]
-impl HttpIO for NativeHttp {
+    /// Sends the request and returns the response as soon as its head is
+    /// received.
     #[allow(clippy::blocks_in_conditions)]
     // because of the issue with tracing and clippy - https://github.com/rust-lang/rust-clippy/issues/12281
     #[tracing::instrument(
@@ -143,7 +216,7 @@ impl HttpIO for NativeHttp {
             network.protocol.version = ?request.version()
         )
     )]
-    async fn execute(&self, mut request: reqwest::Request) -> Result<Response<Bytes>> {
+    async fn send(&self, mut request: reqwest::Request) -> Result<reqwest::Response> {
         if self.http2_only {
             *request.version_mut() = reqwest::Version::HTTP_2;
         }
@@ -166,22 +239,58 @@ impl HttpIO for NativeHttp {
             request.version()
         );
         tracing::debug!("request: {:?}", request);
+        let origin = self.enable_telemetry.then(|| {
+            let url = request.url();
+            let host = url.host_str().unwrap_or_default().to_string();
+            (host, url.port_or_known_default().unwrap_or_default())
+        });
         let response = self.client.execute(request).await;
         tracing::debug!("response: {:?}", response);
 
         req_counter.update(&response);
+        if let Some(origin) = origin {
+            UPSTREAM_REQUEST_COUNT.add(origin, &response);
+        }
 
         if self.enable_telemetry {
             let status_code = get_response_status(&response);
             tracing::Span::current().set_attribute(status_code.key, status_code.value);
         }
 
-        Ok(Response::from_reqwest(
-            response?
-                .error_for_status()
-                .map_err(|err| err.without_url())?,
-        )
-        .await?)
+        Ok(response?)
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpIO for NativeHttp {
+    async fn execute(&self, request: reqwest::Request) -> Result<Response<Bytes>> {
+        let response = Response::from_reqwest(self.send(request).await?).await?;
+        if response.status.is_client_error() || response.status.is_server_error() {
+            return Err(response.to_http_error(self.error_body_size));
+        }
+
+        Ok(response)
+    }
+
+    async fn execute_stream(&self, request: reqwest::Request) -> Result<Response<BodyStream>> {
+        let response = self.send(request).await?;
+        if response.status().is_client_error() || response.status().is_server_error() {
+            let response = Response::from_reqwest(response).await?;
+            return Err(response.to_http_error(self.error_body_size));
+        }
+
+        let status = response.status();
+        let headers = response.headers().to_owned();
+        let body = stream::unfold(Some(response), |response| async move {
+            let mut response = response?;
+            match response.chunk().await {
+                Ok(Some(chunk)) => Some((Ok(chunk), Some(response))),
+                Ok(None) => None,
+                Err(err) => Some((Err(err.into()), None)),
+            }
+        });
+
+        Ok(Response { status, headers, body: body.boxed() })
     }
 }
 
@@ -191,6 +300,8 @@ mod tests {
     use tokio;
 
     use super::*;
+    use crate::core::blueprint::telemetry::TelemetryExporter;
+    use crate::core::config::StdoutExporter;
     use crate::core::http::Response;
 
     fn start_mock_server() -> httpmock::MockServer {
@@ -282,4 +393,78 @@ mod tests {
         let resp = make_request(&url1, &native_http).await;
         assert_eq!(resp.headers.get("x-cache-lookup").unwrap(), "MISS");
     }
+
+    #[tokio::test]
+    async fn test_native_http_upstream_request_count() {
+        let server = start_mock_server();
+
+        server.mock(|when, then| {
+            when.method(httpmock::Method::GET).path("/ok");
+            then.status(200).body("Hello");
+        });
+
+        server.mock(|when, then| {
+            when.method(httpmock::Method::GET).path("/missing");
+            then.status(404);
+        });
+
+        let telemetry = Telemetry {
+            export: Some(TelemetryExporter::Stdout(StdoutExporter { pretty: false })),
+            ..Default::default()
+        };
+        let native_http = NativeHttp::init(&Default::default(), &telemetry);
+        // the count is global, it's keyed by the port of the mock server that
+        // no other test uses at the same time. The mock servers are pooled, so
+        // the port could be counted before.
+        let (host, port) = ("127.0.0.1", server.port());
+        let request = |path: &str| {
+            let url = format!("http://{}:{}{}", host, port, path);
+            reqwest::Request::new(Method::GET, url.parse().unwrap())
+        };
+
+        let before_2xx = UPSTREAM_REQUEST_COUNT.get(host, port, "2xx");
+        let before_4xx = UPSTREAM_REQUEST_COUNT.get(host, port, "4xx");
+
+        native_http.execute(request("/ok")).await.unwrap();
+        native_http.execute(request("/ok")).await.unwrap();
+        native_http.execute(request("/missing")).await.unwrap_err();
+
+        assert_eq!(
+            UPSTREAM_REQUEST_COUNT.get(host, port, "2xx") - before_2xx,
+            2
+        );
+        assert_eq!(
+            UPSTREAM_REQUEST_COUNT.get(host, port, "4xx") - before_4xx,
+            1
+        );
+        assert_eq!(UPSTREAM_REQUEST_COUNT.get("localhost", port, "2xx"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_native_http_redirect_policy() {
+        let server = start_mock_server();
+
+        server.mock(|when, then| {
+            when.method(httpmock::Method::GET).path("/redirect");
+            then.status(302).header("Location", "/target");
+        });
+
+        server.mock(|when, then| {
+            when.method(httpmock::Method::GET).path("/target");
+            then.status(200).body("Hello");
+        });
+
+        let request_url = format!("http://localhost:{}/redirect", server.port());
+
+        let upstream = Upstream { redirect: RedirectPolicy::None, ..Default::default() };
+        let native_http = NativeHttp::init(&upstream, &Default::default());
+        let response = make_request(&request_url, &native_http).await;
+        assert_eq!(response.status, reqwest::StatusCode::FOUND);
+
+        let upstream = Upstream { redirect: RedirectPolicy::All, ..Default::default() };
+        let native_http = NativeHttp::init(&upstream, &Default::default());
+        let response = make_request(&request_url, &native_http).await;
+        assert_eq!(response.status, reqwest::StatusCode::OK);
+        assert_eq!(response.body, Bytes::from("Hello"));
+    }
 }
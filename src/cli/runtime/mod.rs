@@ -6,7 +6,8 @@ use std::fs;
 use std::hash::Hash;
 use std::sync::Arc;
 
-pub use http::NativeHttp;
+pub use file::NativeFileIO;
+pub use http::{NativeHttp, UPSTREAM_REQUEST_COUNT};
 use inquire::{Confirm, Select};
 
 use crate::core::blueprint::Blueprint;
@@ -80,6 +81,7 @@ pub fn init(blueprint: &Blueprint) -> TargetRuntime {
         file: init_file(),
         cache: Arc::new(init_in_memory_cache()),
         extensions: Arc::new(vec![]),
+        body_transformers: Arc::new(vec![]),
         cmd_worker: init_http_worker_io(blueprint.server.script.clone()),
         worker: init_resolver_worker_io(blueprint.server.script.clone()),
     }
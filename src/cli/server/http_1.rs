@@ -1,38 +1,93 @@
 use std::sync::Arc;
 
+use hyper::server::accept::Accept;
+use hyper::server::conn::AddrIncoming;
 use hyper::service::{make_service_fn, service_fn};
+use hyper_rustls::TlsAcceptor;
+use rustls_pki_types::CertificateDer;
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::sync::oneshot;
 
+use super::idle_timeout::{IdleIncoming, IdleStream};
+use super::proxy_protocol::ProxiedIncoming;
 use super::server_config::ServerConfig;
+use super::RemoteAddr;
 use crate::core::async_graphql_hyper::{GraphQLBatchRequest, GraphQLRequest};
+use crate::core::config::PrivateKey;
 use crate::core::http::handle_request;
 use crate::core::Errata;
 
+/// Starts an HTTP/1.1 server, which reads the address of the client from the
+/// PROXY protocol header of every connection when it's behind an L4 load
+/// balancer.
 pub async fn start_http_1(
     sc: Arc<ServerConfig>,
     server_up_sender: Option<oneshot::Sender<()>>,
 ) -> anyhow::Result<()> {
-    let addr = sc.addr();
-    let make_svc_single_req = make_service_fn(|_conn| {
-        let state = Arc::clone(&sc);
-        async move {
-            Ok::<_, anyhow::Error>(service_fn(move |req| {
-                handle_request::<GraphQLRequest>(req, state.app_ctx.clone())
-            }))
-        }
-    });
+    let incoming = AddrIncoming::bind(&sc.addr()).map_err(Errata::from)?;
+
+    if sc.app_ctx().blueprint.server.enable_proxy_protocol {
+        start(sc, ProxiedIncoming::new(incoming), server_up_sender).await
+    } else {
+        start(sc, incoming, server_up_sender).await
+    }
+}
+
+/// Starts an HTTP/1.1 server that terminates TLS itself with the given
+/// certificate chain and private key.
+pub async fn start_https_1(
+    sc: Arc<ServerConfig>,
+    cert: Vec<CertificateDer<'static>>,
+    key: PrivateKey,
+    server_up_sender: Option<oneshot::Sender<()>>,
+) -> anyhow::Result<()> {
+    let incoming = AddrIncoming::bind(&sc.addr()).map_err(Errata::from)?;
+    let acceptor = TlsAcceptor::builder()
+        .with_single_cert(cert, key.into_inner())?
+        .with_http11_alpn()
+        .with_incoming(incoming);
+
+    start(sc, acceptor, server_up_sender).await
+}
 
-    let make_svc_batch_req = make_service_fn(|_conn| {
+/// Serves HTTP/1.1 on the connections of the acceptor.
+async fn start<A>(
+    sc: Arc<ServerConfig>,
+    incoming: A,
+    server_up_sender: Option<oneshot::Sender<()>>,
+) -> anyhow::Result<()>
+where
+    A: Accept + Unpin,
+    A::Conn: RemoteAddr + AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    A::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    let app_ctx = sc.app_ctx();
+    let server = &app_ctx.blueprint.server;
+    let incoming = IdleIncoming::new(incoming, super::idle_timeout(server));
+    let enable_batch_requests = server.enable_batch_requests;
+
+    let make_svc = make_service_fn(|conn: &IdleStream<A::Conn>| {
         let state = Arc::clone(&sc);
+        let remote_addr = conn.remote_addr();
+        let requests = conn.requests();
         async move {
-            Ok::<_, anyhow::Error>(service_fn(move |req| {
-                handle_request::<GraphQLBatchRequest>(req, state.app_ctx.clone())
+            Ok::<_, anyhow::Error>(service_fn(move |mut req| {
+                if let Some(remote_addr) = remote_addr {
+                    req.extensions_mut().insert(remote_addr);
+                }
+                let app_ctx = state.app_ctx();
+                requests.serve(async move {
+                    if enable_batch_requests {
+                        handle_request::<GraphQLBatchRequest>(req, app_ctx).await
+                    } else {
+                        handle_request::<GraphQLRequest>(req, app_ctx).await
+                    }
+                })
             }))
         }
     });
-    let builder = hyper::Server::try_bind(&addr)
-        .map_err(Errata::from)?
-        .http1_pipeline_flush(sc.app_ctx.blueprint.server.pipeline_flush);
+    let builder = hyper::Server::builder(incoming).http1_pipeline_flush(server.pipeline_flush);
+    let builder = super::with_timeouts(builder, server);
     super::log_launch(sc.as_ref());
 
     if let Some(sender) = server_up_sender {
@@ -41,14 +96,7 @@ pub async fn start_http_1(
             .or(Err(anyhow::anyhow!("Failed to send message")))?;
     }
 
-    let server: std::prelude::v1::Result<(), hyper::Error> =
-        if sc.blueprint.server.enable_batch_requests {
-            builder.serve(make_svc_batch_req).await
-        } else {
-            builder.serve(make_svc_single_req).await
-        };
-
-    let result = server.map_err(Errata::from);
+    let result = builder.serve(make_svc).await.map_err(Errata::from);
 
     Ok(result?)
 }
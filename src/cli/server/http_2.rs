@@ -4,11 +4,13 @@ use std::sync::Arc;
 use hyper::server::conn::AddrIncoming;
 use hyper::service::{make_service_fn, service_fn};
 use hyper::Server;
+use hyper_rustls::acceptor::TlsStream;
 use hyper_rustls::TlsAcceptor;
 use rustls_pki_types::CertificateDer;
 use tokio::sync::oneshot;
 
 use super::server_config::ServerConfig;
+use super::RemoteAddr;
 use crate::core::async_graphql_hyper::{GraphQLBatchRequest, GraphQLRequest};
 use crate::core::config::PrivateKey;
 use crate::core::http::handle_request;
@@ -26,25 +28,34 @@ pub async fn start_http_2(
         .with_single_cert(cert, key.into_inner())?
         .with_http2_alpn()
         .with_incoming(incoming);
-    let make_svc_single_req = make_service_fn(|_conn| {
+    let make_svc_single_req = make_service_fn(|conn: &TlsStream| {
         let state = Arc::clone(&sc);
+        let remote_addr = conn.remote_addr();
         async move {
-            Ok::<_, anyhow::Error>(service_fn(move |req| {
-                handle_request::<GraphQLRequest>(req, state.app_ctx.clone())
+            Ok::<_, anyhow::Error>(service_fn(move |mut req| {
+                if let Some(remote_addr) = remote_addr {
+                    req.extensions_mut().insert(remote_addr);
+                }
+                handle_request::<GraphQLRequest>(req, state.app_ctx())
             }))
         }
     });
 
-    let make_svc_batch_req = make_service_fn(|_conn| {
+    let make_svc_batch_req = make_service_fn(|conn: &TlsStream| {
         let state = Arc::clone(&sc);
+        let remote_addr = conn.remote_addr();
         async move {
-            Ok::<_, anyhow::Error>(service_fn(move |req| {
-                handle_request::<GraphQLBatchRequest>(req, state.app_ctx.clone())
+            Ok::<_, anyhow::Error>(service_fn(move |mut req| {
+                if let Some(remote_addr) = remote_addr {
+                    req.extensions_mut().insert(remote_addr);
+                }
+                handle_request::<GraphQLBatchRequest>(req, state.app_ctx())
             }))
         }
     });
 
     let builder = Server::builder(acceptor).http2_only(true);
+    let builder = super::with_timeouts(builder, &sc.app_ctx().blueprint.server);
 
     super::log_launch(sc.as_ref());
 
@@ -55,7 +66,7 @@ pub async fn start_http_2(
     }
 
     let server: std::prelude::v1::Result<(), hyper::Error> =
-        if sc.blueprint.server.enable_batch_requests {
+        if sc.app_ctx().blueprint.server.enable_batch_requests {
             builder.serve(make_svc_batch_req).await
         } else {
             builder.serve(make_svc_single_req).await
@@ -4,9 +4,10 @@ use std::sync::Arc;
 use anyhow::Result;
 use tokio::sync::oneshot::{self};
 
-use super::http_1::start_http_1;
+use super::http_1::{start_http_1, start_https_1};
 use super::http_2::start_http_2;
 use super::server_config::ServerConfig;
+use super::watch::ConfigWatcher;
 use crate::cli::telemetry::init_opentelemetry;
 use crate::core::blueprint::{Blueprint, Http};
 use crate::core::config::ConfigModule;
@@ -15,11 +16,17 @@ use crate::core::Errata;
 pub struct Server {
     config_module: ConfigModule,
     server_up_sender: Option<oneshot::Sender<()>>,
+    watcher: Option<ConfigWatcher>,
 }
 
 impl Server {
     pub fn new(config_module: ConfigModule) -> Self {
-        Self { config_module, server_up_sender: None }
+        Self { config_module, server_up_sender: None, watcher: None }
+    }
+
+    /// Reloads the server with the watcher whenever the config changes
+    pub fn watch(self, watcher: ConfigWatcher) -> Self {
+        Self { watcher: Some(watcher), ..self }
     }
 
     pub fn server_up_receiver(&mut self) -> oneshot::Receiver<()> {
@@ -36,12 +43,22 @@ impl Server {
         let endpoints = self.config_module.extensions().endpoint_set.clone();
         let server_config = Arc::new(ServerConfig::new(blueprint.clone(), endpoints).await?);
 
-        init_opentelemetry(blueprint.telemetry.clone(), &server_config.app_ctx.runtime)?;
+        init_opentelemetry(
+            blueprint.telemetry.clone(),
+            &server_config.app_ctx().runtime,
+        )?;
+
+        if let Some(watcher) = self.watcher {
+            tokio::spawn(watcher.run(server_config.clone()));
+        }
 
         match blueprint.server.http.clone() {
             Http::HTTP2 { cert, key } => {
                 start_http_2(server_config, cert, key, self.server_up_sender).await
             }
+            Http::HTTPS { cert, key } => {
+                start_https_1(server_config, cert, key, self.server_up_sender).await
+            }
             Http::HTTP1 => start_http_1(server_config, self.server_up_sender).await,
         }
     }
@@ -0,0 +1,264 @@
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use hyper::body::{Bytes, HttpBody, SizeHint};
+use hyper::server::accept::Accept;
+use hyper::{Body, HeaderMap, Response};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::time::{Instant, Sleep};
+
+/// Accepts the connections of the inner acceptor and closes the HTTP/1
+/// connections that neither receive nor send anything for longer than the
+/// timeout while none of their requests is served.
+pub struct IdleIncoming<A> {
+    incoming: A,
+    timeout: Option<Duration>,
+}
+
+impl<A> IdleIncoming<A> {
+    pub fn new(incoming: A, timeout: Option<Duration>) -> Self {
+        Self { incoming, timeout }
+    }
+}
+
+impl<A: Accept + Unpin> Accept for IdleIncoming<A> {
+    type Conn = IdleStream<A::Conn>;
+    type Error = A::Error;
+
+    fn poll_accept(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Conn, Self::Error>>> {
+        let timeout = self.timeout;
+        Pin::new(&mut self.incoming)
+            .poll_accept(cx)
+            .map(|conn| conn.map(|conn| conn.map(|conn| IdleStream::new(conn, timeout))))
+    }
+}
+
+/// A connection that ends its stream once it's idle for the timeout, which
+/// makes hyper close it as it would when the client closes it.
+pub struct IdleStream<S> {
+    stream: S,
+    timeout: Option<Duration>,
+    /// Fires once the connection is idle for the timeout
+    sleep: Option<Pin<Box<Sleep>>>,
+    requests: Requests,
+}
+
+impl<S> IdleStream<S> {
+    fn new(stream: S, timeout: Option<Duration>) -> Self {
+        Self {
+            stream,
+            timeout,
+            sleep: timeout.map(|timeout| Box::pin(tokio::time::sleep(timeout))),
+            requests: Requests::default(),
+        }
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// The requests served on the connection, it isn't idle while any is.
+    pub fn requests(&self) -> Requests {
+        self.requests.clone()
+    }
+
+    /// Postpones the timeout after the connection received or sent something.
+    fn reset(&mut self) {
+        if let (Some(sleep), Some(timeout)) = (self.sleep.as_mut(), self.timeout) {
+            sleep.as_mut().reset(Instant::now() + timeout);
+        }
+    }
+
+    /// Checks if the connection is idle for the timeout, otherwise the task
+    /// is woken once it could be. The task is polled again after a request is
+    /// served, since the response is written.
+    fn poll_idle(&mut self, cx: &mut Context<'_>) -> bool {
+        match self.sleep.as_mut() {
+            Some(sleep) if !self.requests.is_serving() => sleep.as_mut().poll(cx).is_ready(),
+            _ => false,
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for IdleStream<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled = buf.filled().len();
+        let read = Pin::new(&mut self.stream).poll_read(cx, buf);
+        match read {
+            // the end of the stream
+            Poll::Pending if self.poll_idle(cx) => Poll::Ready(Ok(())),
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(result) => {
+                if buf.filled().len() > filled {
+                    self.reset();
+                }
+                Poll::Ready(result)
+            }
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for IdleStream<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let written = Pin::new(&mut self.stream).poll_write(cx, buf);
+        if matches!(written, Poll::Ready(Ok(n)) if n > 0) {
+            self.reset();
+        }
+        written
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let written = Pin::new(&mut self.stream).poll_write_vectored(cx, bufs);
+        if matches!(written, Poll::Ready(Ok(n)) if n > 0) {
+            self.reset();
+        }
+        written
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.stream.is_write_vectored()
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+}
+
+/// Counts the requests that are served on a connection.
+#[derive(Clone, Default)]
+pub struct Requests(Arc<AtomicUsize>);
+
+impl Requests {
+    /// Counts the request as served until the body of its response is sent,
+    /// hyper reads the connection meanwhile to notice when it's closed.
+    pub fn serve<E>(
+        &self,
+        response: impl Future<Output = Result<Response<Body>, E>>,
+    ) -> impl Future<Output = Result<Response<ServedBody>, E>> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        let serving = Serving(self.0.clone());
+
+        async move {
+            Ok(response
+                .await?
+                .map(|body| ServedBody { body, _serving: serving }))
+        }
+    }
+
+    fn is_serving(&self) -> bool {
+        self.0.load(Ordering::SeqCst) > 0
+    }
+}
+
+/// Stops counting the request once it's dropped.
+struct Serving(Arc<AtomicUsize>);
+
+impl Drop for Serving {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// The body of a response whose request is counted as served until it's
+/// sent in full.
+pub struct ServedBody {
+    body: Body,
+    _serving: Serving,
+}
+
+impl HttpBody for ServedBody {
+    type Data = Bytes;
+    type Error = hyper::Error;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        Pin::new(&mut self.body).poll_data(cx)
+    }
+
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        Pin::new(&mut self.body).poll_trailers(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.body.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_millis(50);
+
+    #[tokio::test]
+    async fn test_idle_connection_ends() {
+        let (mut client, server) = tokio::io::duplex(64);
+        let mut stream = IdleStream::new(server, Some(TIMEOUT));
+
+        // every byte received postpones the timeout
+        for _ in 0..3 {
+            tokio::time::sleep(TIMEOUT / 2).await;
+            client.write_all(b"a").await.unwrap();
+            assert_eq!(stream.read_u8().await.unwrap(), b'a');
+        }
+
+        let start = Instant::now();
+        let mut rest = vec![];
+        stream.read_to_end(&mut rest).await.unwrap();
+        assert!(rest.is_empty());
+        assert!(start.elapsed() >= TIMEOUT / 2);
+    }
+
+    #[tokio::test]
+    async fn test_connection_serving_a_request_is_kept() {
+        let (_client, server) = tokio::io::duplex(64);
+        let mut stream = IdleStream::new(server, Some(TIMEOUT));
+        let response = stream
+            .requests()
+            .serve(async { Ok::<_, hyper::Error>(Response::new(Body::from("done"))) });
+
+        let mut buf = [0; 1];
+        let read = tokio::time::timeout(TIMEOUT * 2, stream.read(&mut buf)).await;
+        assert!(read.is_err());
+
+        // the connection is idle once the body of the response is sent
+        let body = response.await.unwrap().into_body();
+        assert_eq!(&hyper::body::to_bytes(body).await.unwrap()[..], b"done");
+        assert_eq!(stream.read(&mut buf).await.unwrap(), 0);
+    }
+}
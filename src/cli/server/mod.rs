@@ -1,12 +1,57 @@
 pub mod http_1;
 pub mod http_2;
 pub mod http_server;
+pub mod idle_timeout;
 pub mod playground;
+pub mod proxy_protocol;
 pub mod server_config;
+pub mod watch;
+
+use std::net::SocketAddr;
+use std::time::Duration;
 
 pub use http_server::Server;
+use hyper::server::conn::AddrStream;
+use hyper::server::Builder;
+use hyper_rustls::acceptor::TlsStream;
+use tokio::io::{AsyncRead, AsyncWrite};
 
+use self::idle_timeout::IdleStream;
+use self::proxy_protocol::ProxiedStream;
 use self::server_config::ServerConfig;
+use crate::core::blueprint;
+
+/// A connection that knows the address of the client on its other end, which
+/// is set in the extensions of every request it carries.
+pub trait RemoteAddr {
+    fn remote_addr(&self) -> Option<SocketAddr>;
+}
+
+impl RemoteAddr for AddrStream {
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        Some(AddrStream::remote_addr(self))
+    }
+}
+
+/// The address of the client, or of the load balancer for the connections
+/// that aren't proxied on behalf of a client.
+impl<C: RemoteAddr + AsyncRead + Unpin> RemoteAddr for ProxiedStream<C> {
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        self.client_addr().or_else(|| self.get_ref().remote_addr())
+    }
+}
+
+impl<S: RemoteAddr> RemoteAddr for IdleStream<S> {
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        self.get_ref().remote_addr()
+    }
+}
+
+impl<C: RemoteAddr + AsyncRead + AsyncWrite + Unpin> RemoteAddr for TlsStream<C> {
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        self.io().and_then(RemoteAddr::remote_addr)
+    }
+}
 
 fn log_launch(sc: &ServerConfig) {
     let addr = sc.addr().to_string();
@@ -16,9 +61,38 @@ fn log_launch(sc: &ServerConfig) {
         sc.http_version()
     );
 
-    let gql_slug = sc.app_ctx.blueprint.server.routes.graphql();
+    let gql_slug = sc.app_ctx().blueprint.server.routes.graphql();
 
     let graphiql_url = sc.graphiql_url() + gql_slug;
     let url = playground::build_url(&graphiql_url);
     tracing::info!("🌍 Playground: {}", url);
 }
+
+/// Applies the keep-alive and the header read timeouts of the server, the
+/// defaults of hyper are kept for the ones that aren't set. The idle HTTP/1
+/// connections are closed by the acceptor, see [idle_timeout].
+fn with_timeouts<I, E>(mut builder: Builder<I, E>, server: &blueprint::Server) -> Builder<I, E> {
+    match server.keep_alive_timeout {
+        Some(timeout) if timeout.is_zero() => builder = builder.http1_keepalive(false),
+        Some(timeout) => {
+            builder = builder
+                .http2_keep_alive_interval(timeout)
+                .http2_keep_alive_timeout(timeout)
+        }
+        None => {}
+    }
+
+    if let Some(timeout) = server.header_read_timeout {
+        builder = builder.http1_header_read_timeout(timeout);
+    }
+
+    builder
+}
+
+/// The time an HTTP/1 connection is kept open without any request, none
+/// when keep-alive is off or isn't limited.
+fn idle_timeout(server: &blueprint::Server) -> Option<Duration> {
+    server
+        .keep_alive_timeout
+        .filter(|timeout| !timeout.is_zero())
+}
@@ -0,0 +1,312 @@
+use std::fmt::Display;
+use std::future::poll_fn;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use hyper::server::accept::Accept;
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, BufReader, ReadBuf,
+};
+use tokio::sync::mpsc;
+
+use super::RemoteAddr;
+
+/// The signature that starts the header of the version 2 of the protocol
+const V2_SIGNATURE: &[u8; 12] = b"\r\n\r\n\0\r\nQUIT\n";
+
+/// The length of the longest header of the version 1 of the protocol
+const V1_MAX_LENGTH: usize = 107;
+
+/// The time a new connection has to send its header before it's rejected
+const HEADER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The connections whose header was read and that wait to be served
+const BACKLOG: usize = 1024;
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid PROXY protocol header: {}", message),
+    )
+}
+
+/// Reads the PROXY protocol header, version 1 or 2, that an L4 load balancer
+/// sends at the start of a connection, and returns the address of the client
+/// it carries. `None` is returned for the connections that aren't proxied on
+/// behalf of a client, e.g. the health checks of the load balancer.
+pub async fn read_header<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+) -> io::Result<Option<SocketAddr>> {
+    let mut signature = [0; 12];
+    reader.read_exact(&mut signature).await?;
+
+    if &signature == V2_SIGNATURE {
+        read_v2(reader).await
+    } else if signature.starts_with(b"PROXY ") {
+        let mut line = signature.to_vec();
+        reader
+            .take((V1_MAX_LENGTH - signature.len()) as u64)
+            .read_until(b'\n', &mut line)
+            .await?;
+        let line = std::str::from_utf8(&line).map_err(|_| invalid("not ASCII"))?;
+        parse_v1(line)
+    } else {
+        Err(invalid("missing signature"))
+    }
+}
+
+fn parse_v1(line: &str) -> io::Result<Option<SocketAddr>> {
+    let line = line
+        .strip_suffix("\r\n")
+        .ok_or_else(|| invalid("missing CRLF"))?;
+
+    match line.split(' ').collect::<Vec<_>>().as_slice() {
+        ["PROXY", "UNKNOWN", ..] => Ok(None),
+        ["PROXY", protocol @ ("TCP4" | "TCP6"), src, dst, src_port, dst_port] => {
+            let src = src.parse::<IpAddr>().map_err(|_| invalid("bad address"))?;
+            let dst = dst.parse::<IpAddr>().map_err(|_| invalid("bad address"))?;
+            let src_port = src_port.parse::<u16>().map_err(|_| invalid("bad port"))?;
+            dst_port.parse::<u16>().map_err(|_| invalid("bad port"))?;
+
+            let is_v4 = *protocol == "TCP4";
+            if src.is_ipv4() != is_v4 || dst.is_ipv4() != is_v4 {
+                return Err(invalid("address doesn't match the protocol"));
+            }
+
+            Ok(Some(SocketAddr::new(src, src_port)))
+        }
+        _ => Err(invalid("malformed line")),
+    }
+}
+
+async fn read_v2<R: AsyncBufRead + Unpin>(reader: &mut R) -> io::Result<Option<SocketAddr>> {
+    let mut header = [0; 4];
+    reader.read_exact(&mut header).await?;
+    let [version_command, family, ..] = header;
+    let mut addresses = vec![0; u16::from_be_bytes([header[2], header[3]]) as usize];
+    reader.read_exact(&mut addresses).await?;
+
+    if version_command >> 4 != 2 {
+        return Err(invalid("unsupported version"));
+    }
+
+    let port = |at: usize| u16::from_be_bytes([addresses[at], addresses[at + 1]]);
+    match (version_command & 0x0f, family >> 4) {
+        // LOCAL, sent by the load balancer on its own behalf
+        (0x0, _) => Ok(None),
+        (0x1, 0x1) if addresses.len() >= 12 => {
+            let ip = Ipv4Addr::new(addresses[0], addresses[1], addresses[2], addresses[3]);
+            Ok(Some(SocketAddr::new(ip.into(), port(8))))
+        }
+        (0x1, 0x2) if addresses.len() >= 36 => {
+            let octets: [u8; 16] = addresses[..16].try_into().unwrap_or_default();
+            Ok(Some(SocketAddr::new(
+                Ipv6Addr::from(octets).into(),
+                port(32),
+            )))
+        }
+        // unspecified or unix socket addresses
+        (0x1, 0x0 | 0x3) => Ok(None),
+        _ => Err(invalid("unsupported command or address family")),
+    }
+}
+
+/// A connection whose PROXY protocol header was read, so the rest of the
+/// stream is the HTTP traffic of the client.
+pub struct ProxiedStream<C> {
+    stream: BufReader<C>,
+    /// The address of the client carried by the header
+    client_addr: Option<SocketAddr>,
+}
+
+impl<C: AsyncRead + Unpin> ProxiedStream<C> {
+    async fn accept(stream: C) -> io::Result<Self> {
+        let mut stream = BufReader::new(stream);
+        let client_addr = read_header(&mut stream).await?;
+
+        Ok(Self { stream, client_addr })
+    }
+
+    /// The connection to the load balancer
+    pub fn get_ref(&self) -> &C {
+        self.stream.get_ref()
+    }
+
+    /// The address of the client, `None` when the header carries none.
+    pub fn client_addr(&self) -> Option<SocketAddr> {
+        self.client_addr
+    }
+}
+
+impl<C: AsyncRead + Unpin> AsyncRead for ProxiedStream<C> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_read(cx, buf)
+    }
+}
+
+impl<C: AsyncRead + AsyncWrite + Unpin> AsyncWrite for ProxiedStream<C> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.stream.is_write_vectored()
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+}
+
+/// Accepts the connections of an acceptor that sits behind an L4 load
+/// balancer. The header of every connection is read on its own task, and the
+/// connections with a malformed or missing header are closed.
+pub struct ProxiedIncoming<C> {
+    receiver: mpsc::Receiver<ProxiedStream<C>>,
+}
+
+impl<C: RemoteAddr + AsyncRead + Unpin + Send + 'static> ProxiedIncoming<C> {
+    pub fn new<A>(mut incoming: A) -> Self
+    where
+        A: Accept<Conn = C> + Unpin + Send + 'static,
+        A::Error: Display + Send,
+    {
+        let (sender, receiver) = mpsc::channel(BACKLOG);
+
+        tokio::spawn(async move {
+            while !sender.is_closed() {
+                let stream = match poll_fn(|cx| Pin::new(&mut incoming).poll_accept(cx)).await {
+                    Some(Ok(stream)) => stream,
+                    Some(Err(err)) => {
+                        // e.g. the limit of open files is reached, so the
+                        // accept is retried once connections are closed
+                        tracing::error!("Failed to accept a connection: {}", err);
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                        continue;
+                    }
+                    None => break,
+                };
+
+                let peer = stream
+                    .remote_addr()
+                    .map_or_else(|| "an unknown address".to_string(), |peer| peer.to_string());
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    let stream =
+                        tokio::time::timeout(HEADER_TIMEOUT, ProxiedStream::accept(stream));
+                    match stream.await {
+                        Ok(Ok(stream)) => {
+                            let _ = sender.send(stream).await;
+                        }
+                        Ok(Err(err)) => {
+                            tracing::debug!("Rejected the connection from {}: {}", peer, err)
+                        }
+                        Err(_) => tracing::debug!(
+                            "Rejected the connection from {}: no PROXY protocol header",
+                            peer
+                        ),
+                    }
+                });
+            }
+        });
+
+        Self { receiver }
+    }
+}
+
+impl<C> Accept for ProxiedIncoming<C> {
+    type Conn = ProxiedStream<C>;
+    type Error = io::Error;
+
+    fn poll_accept(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Conn, Self::Error>>> {
+        self.receiver.poll_recv(cx).map(|stream| stream.map(Ok))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn read(header: &[u8]) -> io::Result<Option<SocketAddr>> {
+        let mut reader = BufReader::new(header);
+        let addr = read_header(&mut reader).await?;
+
+        // the request that follows the header is left untouched
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).await?;
+        assert_eq!(rest, "GET / HTTP/1.1\r\n");
+
+        Ok(addr)
+    }
+
+    fn addr(addr: &str) -> Option<SocketAddr> {
+        Some(addr.parse().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_v1() {
+        let header = b"PROXY TCP4 203.0.113.7 10.0.0.1 56324 8000\r\nGET / HTTP/1.1\r\n";
+        assert_eq!(read(header).await.unwrap(), addr("203.0.113.7:56324"));
+
+        let header = b"PROXY TCP6 2001:db8::1 2001:db8::2 56324 8000\r\nGET / HTTP/1.1\r\n";
+        assert_eq!(read(header).await.unwrap(), addr("[2001:db8::1]:56324"));
+
+        let header = b"PROXY UNKNOWN\r\nGET / HTTP/1.1\r\n";
+        assert_eq!(read(header).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_v2() {
+        let mut header = V2_SIGNATURE.to_vec();
+        header.extend([
+            0x21, 0x11, 0, 12, 203, 0, 113, 7, 10, 0, 0, 1, 0xdc, 0x04, 0x1f, 0x40,
+        ]);
+        header.extend(b"GET / HTTP/1.1\r\n");
+        assert_eq!(read(&header).await.unwrap(), addr("203.0.113.7:56324"));
+
+        let mut header = V2_SIGNATURE.to_vec();
+        header.extend([0x20, 0x00, 0, 0]);
+        header.extend(b"GET / HTTP/1.1\r\n");
+        assert_eq!(read(&header).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_malformed() {
+        for header in [
+            &b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n"[..],
+            b"PROXY TCP4 203.0.113.7 10.0.0.1 56324\r\n",
+            b"PROXY TCP4 2001:db8::1 10.0.0.1 56324 8000\r\n",
+            b"PROXY TCP4 203.0.113.7 10.0.0.1 56324 8000\n",
+            b"PROXY TCP4 203.0.113.7 10.0.0.1 56324 8000",
+        ] {
+            let mut reader = BufReader::new(header);
+            assert!(read_header(&mut reader).await.is_err());
+        }
+    }
+}
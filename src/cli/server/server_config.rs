@@ -1,8 +1,10 @@
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, RwLock};
 
 use async_graphql_extension_apollo_tracing::ApolloTracing;
+use tokio::task::JoinHandle;
 
+use crate::cli::apollo::{run_schema_reporting, SCHEMA_REPORTING_URL};
 use crate::cli::runtime::init;
 use crate::core::app_context::AppContext;
 use crate::core::blueprint::telemetry::TelemetryExporter;
@@ -11,8 +13,8 @@ use crate::core::rest::{EndpointSet, Unchecked};
 use crate::core::schema_extension::SchemaExtension;
 
 pub struct ServerConfig {
-    pub blueprint: Blueprint,
-    pub app_ctx: Arc<AppContext>,
+    app_ctx: RwLock<Arc<AppContext>>,
+    schema_reporting: Mutex<Option<JoinHandle<()>>>,
 }
 
 impl ServerConfig {
@@ -20,6 +22,76 @@ impl ServerConfig {
         blueprint: Blueprint,
         endpoints: EndpointSet<Unchecked>,
     ) -> anyhow::Result<Self> {
+        let app_ctx = Arc::new(Self::init_app_ctx(blueprint, endpoints).await?);
+        let server_config = Self {
+            app_ctx: RwLock::new(app_ctx.clone()),
+            schema_reporting: Mutex::new(None),
+        };
+        server_config.report_schema(&app_ctx);
+
+        Ok(server_config)
+    }
+
+    /// Returns the context that serves the requests at the moment
+    pub fn app_ctx(&self) -> Arc<AppContext> {
+        self.app_ctx
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Replaces the context that serves the requests. The requests in flight
+    /// are completed with the context they started with.
+    ///
+    /// NOTE: the server keeps listening with the settings of the initial
+    /// blueprint, so changes of the server settings that affect the listener
+    /// (e.g. the port, the HTTP version or the timeouts) aren't applied.
+    pub async fn reload(
+        &self,
+        blueprint: Blueprint,
+        endpoints: EndpointSet<Unchecked>,
+    ) -> anyhow::Result<()> {
+        let app_ctx = Arc::new(Self::init_app_ctx(blueprint, endpoints).await?);
+        self.report_schema(&app_ctx);
+        *self
+            .app_ctx
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = app_ctx;
+
+        Ok(())
+    }
+
+    /// Starts reporting the schema of the context to Apollo Studio, if it's
+    /// enabled, in place of the schema of the previous context.
+    fn report_schema(&self, app_ctx: &AppContext) {
+        let reporting = match &app_ctx.blueprint.telemetry.export {
+            Some(TelemetryExporter::Apollo(apollo)) if apollo.schema_reporting => {
+                Some(tokio::spawn(run_schema_reporting(
+                    app_ctx.runtime.http.clone(),
+                    SCHEMA_REPORTING_URL.to_string(),
+                    apollo.clone(),
+                    app_ctx.schema.sdl(),
+                )))
+            }
+            _ => None,
+        };
+
+        let previous = std::mem::replace(
+            &mut *self
+                .schema_reporting
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+            reporting,
+        );
+        if let Some(previous) = previous {
+            previous.abort();
+        }
+    }
+
+    async fn init_app_ctx(
+        blueprint: Blueprint,
+        endpoints: EndpointSet<Unchecked>,
+    ) -> anyhow::Result<AppContext> {
         let mut rt = init(&blueprint);
 
         let mut extensions = vec![];
@@ -37,26 +109,31 @@ impl ServerConfig {
         rt.add_extensions(extensions);
 
         let endpoints = endpoints.into_checked(&blueprint, rt.clone()).await?;
-        let app_context = Arc::new(AppContext::new(blueprint.clone(), rt, endpoints));
 
-        Ok(Self { app_ctx: app_context, blueprint })
+        Ok(AppContext::new(blueprint, rt, endpoints))
     }
 
     pub fn addr(&self) -> SocketAddr {
-        (self.blueprint.server.hostname, self.blueprint.server.port).into()
+        let app_ctx = self.app_ctx();
+        (
+            app_ctx.blueprint.server.hostname,
+            app_ctx.blueprint.server.port,
+        )
+            .into()
     }
 
     pub fn http_version(&self) -> String {
-        match self.blueprint.server.http {
+        match self.app_ctx().blueprint.server.http {
             Http::HTTP2 { cert: _, key: _ } => "HTTP/2".to_string(),
-            _ => "HTTP/1.1".to_string(),
+            Http::HTTPS { cert: _, key: _ } => "HTTP/1.1 (TLS)".to_string(),
+            Http::HTTP1 => "HTTP/1.1".to_string(),
         }
     }
 
     pub fn graphiql_url(&self) -> String {
-        let protocol = match self.http_version().as_str() {
-            "HTTP/2" => "https",
-            _ => "http",
+        let protocol = match self.app_ctx().blueprint.server.http {
+            Http::HTTP1 => "http",
+            _ => "https",
         };
         let mut addr = self.addr();
 
@@ -0,0 +1,234 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use url::Url;
+
+use super::server_config::ServerConfig;
+use crate::core::blueprint::Blueprint;
+use crate::core::config::reader::ConfigReader;
+use crate::core::config::ConfigModule;
+use crate::core::runtime::TargetRuntime;
+use crate::core::Errata;
+
+/// Watches the local config files, and the files they link to, and reloads
+/// the server whenever one of them changes. A config that fails to load is
+/// logged and the server keeps serving the previous one.
+pub struct ConfigWatcher {
+    file_paths: Vec<String>,
+    runtime: TargetRuntime,
+    prepare: fn(ConfigModule, &TargetRuntime) -> anyhow::Result<ConfigModule>,
+    interval: Duration,
+    watched: Vec<(PathBuf, Option<(SystemTime, u64)>)>,
+}
+
+impl ConfigWatcher {
+    pub fn new(file_paths: Vec<String>, runtime: TargetRuntime, config: &ConfigModule) -> Self {
+        let mut watcher = Self {
+            file_paths,
+            runtime,
+            prepare: |config_module, _| Ok(config_module),
+            interval: Duration::from_millis(500),
+            watched: vec![],
+        };
+        watcher.watch(config);
+        watcher
+    }
+
+    /// Applies the function to every reloaded config before it's compiled,
+    /// e.g. to apply the overrides from the environment.
+    pub fn prepare(
+        self,
+        prepare: fn(ConfigModule, &TargetRuntime) -> anyhow::Result<ConfigModule>,
+    ) -> Self {
+        Self { prepare, ..self }
+    }
+
+    /// Sets how often the files are checked for changes
+    pub fn interval(self, interval: Duration) -> Self {
+        Self { interval, ..self }
+    }
+
+    /// Checks the files for changes until the server stops
+    pub async fn run(mut self, server_config: Arc<ServerConfig>) {
+        loop {
+            tokio::time::sleep(self.interval).await;
+            self.poll(&server_config).await;
+        }
+    }
+
+    /// Reloads the server if any of the watched files has changed since the
+    /// last check. Returns `true` if the server was reloaded.
+    pub async fn poll(&mut self, server_config: &ServerConfig) -> bool {
+        let changed = self
+            .watched
+            .iter()
+            .any(|(path, version)| version_of(path) != *version);
+
+        if !changed {
+            return false;
+        }
+
+        match self.reload(server_config).await {
+            Ok(config_module) => {
+                self.watch(&config_module);
+                tracing::info!("🔄 Reloaded the config");
+                true
+            }
+            Err(err) => {
+                // don't retry until the files are changed again
+                self.watched = self
+                    .watched
+                    .drain(..)
+                    .map(|(path, _)| {
+                        let version = version_of(&path);
+                        (path, version)
+                    })
+                    .collect();
+                tracing::error!(
+                    "Failed to reload the config, serving the previous one: {}",
+                    Errata::from(err)
+                );
+                false
+            }
+        }
+    }
+
+    async fn reload(&self, server_config: &ServerConfig) -> anyhow::Result<ConfigModule> {
+        // a new reader every time, since the reader caches the files it reads
+        let config_reader = ConfigReader::init(self.runtime.clone());
+        let config_module = config_reader.read_all(&self.file_paths).await?;
+        let config_module = (self.prepare)(config_module, &self.runtime)?;
+        let blueprint = Blueprint::try_from(&config_module).map_err(Errata::from)?;
+        let endpoints = config_module.extensions().endpoint_set.clone();
+
+        server_config.reload(blueprint, endpoints).await?;
+
+        Ok(config_module)
+    }
+
+    fn watch(&mut self, config: &ConfigModule) {
+        self.watched = watched_paths(&self.file_paths, config)
+            .into_iter()
+            .map(|path| {
+                let version = version_of(&path);
+                (path, version)
+            })
+            .collect();
+    }
+}
+
+/// Identifies the content of the file by its modification time and size, the
+/// size catches the changes made within the resolution of the timestamps.
+fn version_of(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+/// Returns the local files of the config: the config files themselves and the
+/// files they link to. Remote files aren't watched.
+fn watched_paths(file_paths: &[String], config: &ConfigModule) -> Vec<PathBuf> {
+    let files = file_paths
+        .iter()
+        .filter(|path| Url::parse(path).is_err())
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+
+    let links = config
+        .config()
+        .links
+        .iter()
+        .filter(|link| Url::parse(&link.src).is_err())
+        .flat_map(|link| {
+            let src = Path::new(&link.src);
+            if src.is_absolute() {
+                vec![src.to_path_buf()]
+            } else {
+                // links are relative to the file that declares them
+                files
+                    .iter()
+                    .filter_map(|file| Some(file.parent()?.join(src)))
+                    .filter(|path| path.exists())
+                    .collect()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut paths = files;
+    for link in links {
+        if !paths.contains(&link) {
+            paths.push(link);
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use hyper::{Body, Request};
+
+    use super::ConfigWatcher;
+    use crate::cli::server::server_config::ServerConfig;
+    use crate::core::async_graphql_hyper::GraphQLRequest;
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::reader::ConfigReader;
+    use crate::core::http::handle_request;
+
+    const CONFIG: &str = r#"
+        schema @server(port: 8000) {
+          query: Query
+        }
+
+        type Query {
+          greet: String @expr(body: "Hello")
+        }
+    "#;
+
+    async fn greet(server_config: &ServerConfig) -> String {
+        let req = Request::post("http://localhost:8000/graphql")
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"query": "{ greet }"}"#))
+            .unwrap();
+        let resp = handle_request::<GraphQLRequest>(req, server_config.app_ctx())
+            .await
+            .unwrap();
+        let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reload_on_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.graphql");
+        std::fs::write(&path, CONFIG).unwrap();
+        let file_paths = vec![path.to_string_lossy().to_string()];
+
+        let runtime = crate::cli::runtime::init(&Blueprint::default());
+        let config_module = ConfigReader::init(runtime.clone())
+            .read_all(&file_paths)
+            .await
+            .unwrap();
+        let blueprint = Blueprint::try_from(&config_module).unwrap();
+        let endpoints = config_module.extensions().endpoint_set.clone();
+        let server_config = Arc::new(ServerConfig::new(blueprint, endpoints).await.unwrap());
+        let mut watcher = ConfigWatcher::new(file_paths, runtime, &config_module);
+
+        assert_eq!(greet(&server_config).await, r#"{"data":{"greet":"Hello"}}"#);
+        assert!(!watcher.poll(&server_config).await);
+
+        std::fs::write(&path, CONFIG.replace("Hello", "Hi")).unwrap();
+        assert!(watcher.poll(&server_config).await);
+        assert_eq!(greet(&server_config).await, r#"{"data":{"greet":"Hi"}}"#);
+
+        // an invalid config keeps the previous one
+        std::fs::write(&path, CONFIG.replace("String", "Unknown")).unwrap();
+        assert!(!watcher.poll(&server_config).await);
+        assert_eq!(greet(&server_config).await, r#"{"data":{"greet":"Hi"}}"#);
+
+        // the listener is bound once, the address stays the same
+        assert_eq!(server_config.addr().port(), 8000);
+    }
+}
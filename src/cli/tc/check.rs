@@ -24,6 +24,9 @@ pub(super) async fn check_command(params: CheckParams, config_reader: &ConfigRea
     match blueprint {
         Ok(blueprint) => {
             tracing::info!("Config {} ... ok", file_paths.join(", "));
+            for type_name in config_module.config().unreachable_types() {
+                tracing::warn!("Type {} is not reachable from the schema roots", type_name);
+            }
             Fmt::log_n_plus_one(n_plus_one_queries, config_module.config());
             // Check the endpoints' schema
             let _ = config_module
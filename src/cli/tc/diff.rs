@@ -0,0 +1,27 @@
+use anyhow::Result;
+
+use crate::cli::fmt::Fmt;
+use crate::core::config::reader::ConfigReader;
+use crate::core::schema_diff;
+
+pub(super) async fn diff_command(old: &str, new: &str, config_reader: &ConfigReader) -> Result<()> {
+    let old_config = config_reader.read(old).await?;
+    let new_config = config_reader.read(new).await?;
+    let changes = schema_diff::diff(&old_config, &new_config)?;
+
+    if changes.is_empty() {
+        tracing::info!("No changes found between {} and {}", old, new);
+        return Ok(());
+    }
+
+    for change in changes.iter() {
+        let kind = if change.is_breaking() {
+            Fmt::meta("breaking")
+        } else {
+            "non-breaking".to_string()
+        };
+        Fmt::display(format!("[{}] {}", kind, change));
+    }
+
+    schema_diff::ensure_non_breaking(&changes)
+}
@@ -0,0 +1,18 @@
+use anyhow::Result;
+
+use crate::cli::fmt::Fmt;
+use crate::core::blueprint::Blueprint;
+use crate::core::config::reader::ConfigReader;
+use crate::core::Errata;
+
+pub(super) async fn inspect_command(
+    file_paths: &[String],
+    config_reader: &ConfigReader,
+) -> Result<()> {
+    let config_module = config_reader.read_all(file_paths).await?;
+    let blueprint = Blueprint::try_from(&config_module).map_err(Errata::from)?;
+
+    Fmt::display(serde_json::to_string_pretty(&blueprint.inspect())?);
+
+    Ok(())
+}
@@ -1,7 +1,10 @@
 mod check;
+mod diff;
 mod gen;
 mod helpers;
 mod init;
+mod inspect;
 pub mod run;
 mod start;
+mod typescript;
 mod validate_rc;
@@ -4,7 +4,7 @@ use dotenvy::dotenv;
 
 use super::helpers::TRACKER;
 use super::validate_rc::validate_rc_config_files;
-use super::{check, gen, init, start};
+use super::{check, diff, gen, init, inspect, start, typescript};
 use crate::cli::command::{Cli, Command};
 use crate::cli::{self, update_checker};
 use crate::core::blueprint::Blueprint;
@@ -41,10 +41,14 @@ fn get_runtime_and_config_reader(verify_ssl: bool) -> (TargetRuntime, ConfigRead
 
 async fn run_command(cli: Cli) -> Result<()> {
     match cli.command {
-        Command::Start { file_paths, verify_ssl } => {
+        Command::Start { verify_ssl, stdin: Some(source), .. } => {
             let (runtime, config_reader) = get_runtime_and_config_reader(verify_ssl);
-            validate_rc_config_files(runtime, &file_paths).await;
-            start::start_command(file_paths, &config_reader).await?;
+            start::start_from_stdin(source, &config_reader, runtime).await?;
+        }
+        Command::Start { file_paths, verify_ssl, watch, stdin: None } => {
+            let (runtime, config_reader) = get_runtime_and_config_reader(verify_ssl);
+            validate_rc_config_files(runtime.clone(), &file_paths).await;
+            start::start_command(file_paths, &config_reader, runtime, watch).await?;
         }
         Command::Check { file_paths, n_plus_one_queries, schema, verify_ssl } => {
             let (runtime, config_reader) = get_runtime_and_config_reader(verify_ssl);
@@ -59,6 +63,18 @@ async fn run_command(cli: Cli) -> Result<()> {
             let (runtime, _) = get_runtime_and_config_reader(true);
             init::init_command(runtime, &folder_path).await?;
         }
+        Command::Diff { old, new } => {
+            let (_, config_reader) = get_runtime_and_config_reader(true);
+            diff::diff_command(&old, &new, &config_reader).await?;
+        }
+        Command::Inspect { file_paths, verify_ssl } => {
+            let (_, config_reader) = get_runtime_and_config_reader(verify_ssl);
+            inspect::inspect_command(&file_paths, &config_reader).await?;
+        }
+        Command::Typescript { file_paths, verify_ssl } => {
+            let (_, config_reader) = get_runtime_and_config_reader(verify_ssl);
+            typescript::typescript_command(&file_paths, &config_reader).await?;
+        }
         Command::Gen { file_path } => {
             let (runtime, _) = get_runtime_and_config_reader(true);
             gen::gen_command(&file_path, runtime).await?;
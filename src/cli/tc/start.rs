@@ -1,18 +1,76 @@
 use anyhow::Result;
+use tokio::io::AsyncReadExt;
 
 use super::helpers::log_endpoint_set;
 use crate::cli::fmt::Fmt;
+use crate::cli::server::watch::ConfigWatcher;
 use crate::cli::server::Server;
 use crate::core::config::reader::ConfigReader;
+use crate::core::config::{ConfigModule, Source};
+use crate::core::runtime::TargetRuntime;
 
 pub(super) async fn start_command(
     file_paths: Vec<String>,
     config_reader: &ConfigReader,
+    runtime: TargetRuntime,
+    watch: bool,
 ) -> Result<()> {
     let config_module = config_reader.read_all(&file_paths).await?;
+    let config_module = with_env_overrides(config_module, &runtime)?;
     log_endpoint_set(&config_module.extensions().endpoint_set);
     Fmt::log_n_plus_one(false, config_module.config());
-    let server = Server::new(config_module);
+    let mut server = Server::new(config_module.clone());
+    if watch {
+        let watcher =
+            ConfigWatcher::new(file_paths, runtime, &config_module).prepare(with_env_overrides);
+        server = server.watch(watcher);
+    }
     server.fork_start().await?;
     Ok(())
 }
+
+/// Starts the server with the configuration piped through the standard input,
+/// e.g. `cat config.graphql | tailcall start --stdin`.
+pub(super) async fn start_from_stdin(
+    source: Source,
+    config_reader: &ConfigReader,
+    runtime: TargetRuntime,
+) -> Result<()> {
+    let mut content = String::new();
+    tokio::io::stdin().read_to_string(&mut content).await?;
+    let config_module = config_reader.read_content(&content, source).await?;
+    let config_module = with_env_overrides(config_module, &runtime)?;
+    log_endpoint_set(&config_module.extensions().endpoint_set);
+    Fmt::log_n_plus_one(false, config_module.config());
+    Server::new(config_module).fork_start().await?;
+    Ok(())
+}
+
+/// A [JSON Merge Patch](https://datatracker.ietf.org/doc/html/rfc7386) applied
+/// over the runtime settings of the config, e.g.
+/// `TAILCALL_CONFIG_PATCH='{"upstream": {"proxy": {"url": "http://proxy:3000"}}}'`.
+const CONFIG_PATCH_ENV: &str = "TAILCALL_CONFIG_PATCH";
+
+/// Lets the environment override the settings of the config, e.g.
+/// `TAILCALL_PORT=9000 tailcall start` binds the server to port 9000.
+fn with_env_overrides(
+    config_module: ConfigModule,
+    runtime: &TargetRuntime,
+) -> Result<ConfigModule> {
+    let mut config = config_module.config().clone();
+    if let Some(patch) = runtime.env.get(CONFIG_PATCH_ENV) {
+        let patch = serde_json::from_str(&patch).map_err(|err| {
+            anyhow::anyhow!(
+                "Invalid JSON merge patch in the {} environment variable: {}",
+                CONFIG_PATCH_ENV,
+                err
+            )
+        })?;
+        config = config.merge_patch(&patch)?;
+    }
+    config.server = config.server.with_env_overrides(runtime.env.as_ref())?;
+    Ok(ConfigModule::new(
+        config,
+        config_module.extensions().clone(),
+    ))
+}
@@ -1,18 +1,21 @@
 use std::io::Write;
+use std::time::Duration;
 
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use once_cell::sync::Lazy;
 use opentelemetry::logs::{LogError, LogResult};
 use opentelemetry::metrics::{MetricsError, Result as MetricsResult};
 use opentelemetry::trace::{TraceError, TraceResult, TracerProvider as _};
 use opentelemetry::{global, KeyValue};
 use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
-use opentelemetry_otlp::{TonicExporterBuilder, WithExportConfig};
+use opentelemetry_otlp::{SpanExporterBuilder, TonicExporterBuilder, WithExportConfig};
 use opentelemetry_sdk::logs::{Logger, LoggerProvider};
 use opentelemetry_sdk::metrics::{MeterProviderBuilder, PeriodicReader};
 use opentelemetry_sdk::propagation::TraceContextPropagator;
 use opentelemetry_sdk::runtime::Tokio;
-use opentelemetry_sdk::trace::{Tracer, TracerProvider};
+use opentelemetry_sdk::trace::{
+    BatchConfig, BatchConfigBuilder, BatchSpanProcessor, Tracer, TracerProvider,
+};
 use opentelemetry_sdk::{runtime, Resource};
 use serde::Serialize;
 use tonic::metadata::MetadataMap;
@@ -52,7 +55,6 @@ fn pretty_encoder<T: Serialize>(writer: &mut dyn Write, data: T) -> Result<()> {
     Ok(writer.write_all(&buf)?)
 }
 
-// TODO: add more options for otlp exporter if needed
 fn otlp_exporter(config: &OtlpExporter) -> TonicExporterBuilder {
     opentelemetry_otlp::new_exporter()
         .tonic()
@@ -60,6 +62,41 @@ fn otlp_exporter(config: &OtlpExporter) -> TonicExporterBuilder {
         .with_metadata(MetadataMap::from_headers(config.headers.clone()))
 }
 
+fn otlp_batch_config(config: &OtlpExporter) -> BatchConfig {
+    let mut builder = BatchConfigBuilder::default();
+
+    if let Some(batch) = &config.batch {
+        if let Some(size) = batch.max_queue_size {
+            builder = builder.with_max_queue_size(size);
+        }
+        if let Some(delay) = batch.scheduled_delay {
+            builder = builder.with_scheduled_delay(Duration::from_millis(delay));
+        }
+        if let Some(size) = batch.max_export_batch_size {
+            builder = builder.with_max_export_batch_size(size);
+        }
+        if let Some(timeout) = batch.max_export_timeout {
+            builder = builder.with_max_export_timeout(Duration::from_millis(timeout));
+        }
+    }
+
+    builder.build()
+}
+
+/// Builds the provider that exports the spans to the OTLP collector in
+/// batches, tuned by the `batch` options of the exporter.
+fn otlp_tracer_provider(config: &OtlpExporter) -> TraceResult<TracerProvider> {
+    let exporter = SpanExporterBuilder::from(otlp_exporter(config)).build_span_exporter()?;
+    let processor = BatchSpanProcessor::builder(exporter, runtime::Tokio)
+        .with_batch_config(otlp_batch_config(config))
+        .build();
+
+    Ok(TracerProvider::builder()
+        .with_span_processor(processor)
+        .with_config(opentelemetry_sdk::trace::config().with_resource(RESOURCE.clone()))
+        .build())
+}
+
 fn set_trace_provider(
     exporter: &TelemetryExporter,
 ) -> TraceResult<Option<OpenTelemetryLayer<Registry, Tracer>>> {
@@ -82,15 +119,7 @@ fn set_trace_provider(
             )
             .with_config(opentelemetry_sdk::trace::config().with_resource(RESOURCE.clone()))
             .build(),
-        TelemetryExporter::Otlp(config) => opentelemetry_otlp::new_pipeline()
-            .tracing()
-            .with_exporter(otlp_exporter(config))
-            .with_trace_config(opentelemetry_sdk::trace::config().with_resource(RESOURCE.clone()))
-            .install_batch(runtime::Tokio)?
-            .provider()
-            .ok_or(TraceError::Other(
-                anyhow!("Failed to instantiate OTLP provider").into(),
-            ))?,
+        TelemetryExporter::Otlp(config) => otlp_tracer_provider(config)?,
         // Prometheus works only with metrics
         TelemetryExporter::Prometheus(_) => return Ok(None),
         TelemetryExporter::Apollo(_) => return Ok(None),
@@ -245,3 +274,52 @@ pub fn init_opentelemetry(config: Telemetry, runtime: &TargetRuntime) -> anyhow:
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderMap;
+    use opentelemetry::trace::Tracer as _;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::core::config::OtlpBatch;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_otlp_exports_spans_in_tuned_batches() {
+        let collector = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let provider = otlp_tracer_provider(&OtlpExporter {
+            url: format!("http://{}", collector.local_addr().unwrap())
+                .parse()
+                .unwrap(),
+            headers: HeaderMap::new(),
+            batch: Some(OtlpBatch {
+                scheduled_delay: Some(10),
+                max_export_timeout: Some(100),
+                ..Default::default()
+            }),
+        })
+        .unwrap();
+
+        provider.tracer("test").in_span("test-span", |_| {});
+
+        // the batch is exported after the tuned delay, way before the default
+        // delay of 5 seconds
+        let received = tokio::time::timeout(Duration::from_secs(2), async {
+            let (mut stream, _) = collector.accept().await.unwrap();
+            let mut preface = [0u8; 24];
+            stream.read_exact(&mut preface).await.unwrap();
+            preface
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(&received, b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n");
+
+        // the provider waits for the pending export, which times out since the
+        // collector never responds
+        tokio::task::spawn_blocking(move || drop(provider))
+            .await
+            .unwrap();
+    }
+}
@@ -1,8 +1,10 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use async_graphql::dynamic::{self, DynamicRequest};
 use async_graphql_value::ConstValue;
 use dashmap::DashMap;
+use tokio::sync::Semaphore;
 
 use super::jit::AnyResponse;
 use crate::core::async_graphql_hyper::OperationId;
@@ -11,10 +13,10 @@ use crate::core::data_loader::{DataLoader, DedupeResult};
 use crate::core::graphql::GraphqlDataLoader;
 use crate::core::grpc;
 use crate::core::grpc::data_loader::GrpcDataLoader;
-use crate::core::http::{DataLoaderRequest, HttpDataLoader};
+use crate::core::http::{CircuitBreakerHttp, DataLoaderRequest, HttpDataLoader, LoggingHttp};
 use crate::core::ir::model::{DataLoaderId, IoId, IO, IR};
 use crate::core::ir::Error;
-use crate::core::jit::{OPHash, OperationPlan};
+use crate::core::jit::{OPHash, PlanCache};
 use crate::core::rest::{Checked, EndpointSet};
 use crate::core::runtime::TargetRuntime;
 
@@ -28,18 +30,31 @@ pub struct AppContext {
     pub endpoints: EndpointSet<Checked>,
     pub dedupe_handler: Arc<DedupeResult<IoId, ConstValue, Error>>,
     pub dedupe_operation_handler: DedupeResult<OperationId, AnyResponse<Vec<u8>>, Error>,
-    pub operation_plans: DashMap<OPHash, OperationPlan<async_graphql_value::Value>>,
+    pub operation_plans: PlanCache,
     pub const_execution_cache: DashMap<OPHash, AnyResponse<Vec<u8>>>,
+    pub concurrency_limiter: Option<Arc<Semaphore>>,
 }
 
 impl AppContext {
     pub fn new(
         mut blueprint: Blueprint,
-        runtime: TargetRuntime,
+        mut runtime: TargetRuntime,
         endpoints: EndpointSet<Checked>,
     ) -> Self {
+        if let Some(logging) = &blueprint.upstream.logging {
+            runtime.http = Arc::new(LoggingHttp::new(runtime.http, logging));
+            runtime.http2_only = Arc::new(LoggingHttp::new(runtime.http2_only, logging));
+        }
+
+        if let Some(circuit_breaker) = &blueprint.upstream.circuit_breaker {
+            runtime.http = Arc::new(CircuitBreakerHttp::new(runtime.http, circuit_breaker));
+            runtime.http2_only =
+                Arc::new(CircuitBreakerHttp::new(runtime.http2_only, circuit_breaker));
+        }
+
         let mut http_data_loaders = vec![];
         let mut gql_data_loaders = vec![];
+        let mut merged_data_loaders = HashMap::new();
         let mut grpc_data_loaders = vec![];
 
         for def in blueprint.definitions.iter_mut() {
@@ -49,8 +64,19 @@ impl AppContext {
                     field.map_expr(|expr| {
                         expr.modify(&mut |expr| match expr {
                             IR::IO(io) => match io {
+                                // the loader merges the identical requests, so the fields that
+                                // opt out of the deduplication make their calls themselves
+                                IO::Http { group_by: None, dedupe: Some(false), .. } => None,
                                 IO::Http {
-                                    req_template, group_by, is_list, dedupe, hook, ..
+                                    req_template,
+                                    group_by,
+                                    is_list,
+                                    dedupe,
+                                    hook,
+                                    when,
+                                    on_status,
+                                    base_urls,
+                                    ..
                                 } => {
                                     let is_list = *is_list;
                                     let dedupe = *dedupe;
@@ -59,6 +85,7 @@ impl AppContext {
                                         group_by.clone(),
                                         is_list,
                                     )
+                                    .response_decoder(req_template.response_decoder)
                                     .to_data_loader(upstream_batch.clone().unwrap_or_default());
 
                                     let result = Some(IR::IO(IO::Http {
@@ -68,6 +95,9 @@ impl AppContext {
                                         hook: hook.clone(),
                                         is_list,
                                         dedupe,
+                                        when: when.clone(),
+                                        on_status: on_status.clone(),
+                                        base_urls: base_urls.clone(),
                                     }));
 
                                     http_data_loaders.push(data_loader);
@@ -75,25 +105,44 @@ impl AppContext {
                                     result
                                 }
 
-                                IO::GraphQL { req_template, field_name, batch, dedupe, .. } => {
+                                IO::GraphQL {
+                                    req_template,
+                                    field_name,
+                                    batch,
+                                    merge,
+                                    dedupe,
+                                    ..
+                                } => {
                                     let dedupe = *dedupe;
-                                    let graphql_data_loader =
-                                        GraphqlDataLoader::new(runtime.clone(), *batch)
-                                            .into_data_loader(
-                                                upstream_batch.clone().unwrap_or_default(),
-                                            );
+                                    // the fields that are merged share the data loader of
+                                    // their upstream
+                                    let dl_id = match merged_data_loaders.get(&req_template.url) {
+                                        Some(dl_id) if *merge => *dl_id,
+                                        _ => {
+                                            let dl_id = DataLoaderId::new(gql_data_loaders.len());
+                                            let graphql_data_loader =
+                                                GraphqlDataLoader::new(runtime.clone(), *batch)
+                                                    .merge(*merge)
+                                                    .into_data_loader(
+                                                        upstream_batch.clone().unwrap_or_default(),
+                                                    );
+                                            gql_data_loaders.push(graphql_data_loader);
+                                            if *merge {
+                                                merged_data_loaders
+                                                    .insert(req_template.url.clone(), dl_id);
+                                            }
+                                            dl_id
+                                        }
+                                    };
 
-                                    let result = Some(IR::IO(IO::GraphQL {
+                                    Some(IR::IO(IO::GraphQL {
                                         req_template: req_template.clone(),
                                         field_name: field_name.clone(),
                                         batch: *batch,
-                                        dl_id: Some(DataLoaderId::new(gql_data_loaders.len())),
+                                        merge: *merge,
+                                        dl_id: Some(dl_id),
                                         dedupe,
-                                    }));
-
-                                    gql_data_loaders.push(graphql_data_loader);
-
-                                    result
+                                    }))
                                 }
 
                                 IO::Grpc { req_template, group_by, dedupe, hook, .. } => {
@@ -130,6 +179,11 @@ impl AppContext {
             }
         }
 
+        let concurrency_limiter = blueprint
+            .upstream
+            .concurrency
+            .map(|limit| Arc::new(Semaphore::new(limit)));
+
         let schema = blueprint
             .to_schema_with(SchemaModifiers::default().extensions(runtime.extensions.clone()));
 
@@ -144,8 +198,9 @@ impl AppContext {
 
             dedupe_handler: Arc::new(DedupeResult::new(false)),
             dedupe_operation_handler: DedupeResult::new(false),
-            operation_plans: DashMap::new(),
+            operation_plans: PlanCache::default(),
             const_execution_cache: DashMap::default(),
+            concurrency_limiter,
         }
     }
 
@@ -1,16 +1,26 @@
 use std::any::Any;
+use std::convert::Infallible;
 use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
 
 use anyhow::Result;
 use async_graphql::parser::types::{ExecutableDocument, OperationType};
 use async_graphql::{BatchResponse, Executor, Value};
-use http::header::{HeaderMap, HeaderValue, CACHE_CONTROL, CONTENT_TYPE};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use futures_util::future;
+use futures_util::stream::{self, BoxStream, StreamExt};
+use http::header::{
+    HeaderMap, HeaderValue, ACCEPT, ACCEPT_ENCODING, CACHE_CONTROL, CONTENT_ENCODING, CONTENT_TYPE,
+    VARY,
+};
 use http::{Response, StatusCode};
 use hyper::Body;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use tailcall_hasher::TailcallHasher;
 
+use super::config::JsonFormat;
 use super::jit::{BatchResponse as JITBatchResponse, JITExecutor};
 
 #[derive(PartialEq, Eq, Clone, Hash, Debug)]
@@ -117,6 +127,14 @@ impl GraphQLRequestLike for GraphQLRequest {
         self
     }
     async fn execute_with_jit(self, executor: JITExecutor) -> GraphQLArcResponse {
+        if executor.is_incremental(&self.0) {
+            return GraphQLArcResponse::incremental(executor.execute_incremental(self.0));
+        }
+
+        if executor.is_chunked() {
+            return GraphQLArcResponse::chunked(executor.execute_chunked(self.0));
+        }
+
         let response = executor.execute(self.0).await;
         GraphQLArcResponse::new(JITBatchResponse::Single(response))
     }
@@ -187,6 +205,12 @@ impl GraphQLQuery {
 static APPLICATION_JSON: Lazy<HeaderValue> =
     Lazy::new(|| HeaderValue::from_static("application/json"));
 
+static MULTIPART_MIXED: Lazy<HeaderValue> =
+    Lazy::new(|| HeaderValue::from_static(r#"multipart/mixed; boundary="-"; deferSpec=20220824"#));
+
+const MULTIPART_PART: &str = "\r\n---\r\nContent-Type: application/json; charset=utf-8\r\n\r\n";
+const MULTIPART_END: &str = "\r\n-----\r\n";
+
 impl GraphQLResponse {
     fn build_response(&self, status: StatusCode, body: Body) -> Result<Response<Body>> {
         let mut response = Response::builder()
@@ -339,20 +363,172 @@ impl CacheControl {
     }
 }
 
+/// Escapes the non-ASCII characters of the strings as `\uXXXX` sequences.
+struct AsciiFormatter;
+
+impl serde_json::ser::Formatter for AsciiFormatter {
+    fn write_string_fragment<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        fragment: &str,
+    ) -> io::Result<()> {
+        for c in fragment.chars() {
+            if c.is_ascii() {
+                writer.write_all(&[c as u8])?;
+            } else {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    write!(writer, "\\u{:04x}", unit)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Reserializes the compact JSON of a response in the given format.
+fn format_body(body: Vec<u8>, format: JsonFormat) -> Result<Vec<u8>> {
+    if format == JsonFormat::Compact {
+        return Ok(body);
+    }
+
+    let value: serde_json::Value = serde_json::from_slice(&body)?;
+    let mut buf = Vec::with_capacity(body.len());
+    match format {
+        JsonFormat::Pretty => serde_json::to_writer_pretty(&mut buf, &value)?,
+        _ => value.serialize(&mut serde_json::Serializer::with_formatter(
+            &mut buf,
+            AsciiFormatter,
+        ))?,
+    }
+
+    Ok(buf)
+}
+
+/// Checks whether the client accepts gzip through the `Accept-Encoding`
+/// header, an encoding with `q=0` is refused. An explicit `gzip` entry takes
+/// precedence over `*`, e.g. `gzip;q=0, *` refuses gzip.
+fn accepts_gzip(headers: &HeaderMap) -> bool {
+    let mut gzip = None;
+    let mut any = None;
+    let encodings = headers
+        .get_all(ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','));
+
+    for encoding in encodings {
+        let mut parts = encoding.split(';').map(str::trim);
+        let name = parts.next().unwrap_or_default();
+        let refused = parts
+            .filter_map(|param| param.strip_prefix("q="))
+            .any(|q| q.parse::<f32>().is_ok_and(|q| q == 0.0));
+
+        if name.eq_ignore_ascii_case("gzip") {
+            gzip = Some(!refused);
+        } else if name == "*" {
+            any = Some(!refused);
+        }
+    }
+
+    gzip.or(any).unwrap_or_default()
+}
+
+/// Checks whether the client accepts an incremental delivery response through
+/// the `Accept` header, i.e. lists `multipart/mixed` without `q=0`.
+pub fn accepts_multipart(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|media_range| {
+            let mut parts = media_range.split(';').map(str::trim);
+            let name = parts.next().unwrap_or_default();
+            let refused = parts
+                .filter_map(|param| param.strip_prefix("q="))
+                .any(|q| q.parse::<f32>().is_ok_and(|q| q == 0.0));
+
+            name.eq_ignore_ascii_case("multipart/mixed") && !refused
+        })
+}
+
+fn gzip(body: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::with_capacity(body.len() / 2), Compression::default());
+    encoder.write_all(body)?;
+    Ok(encoder.finish()?)
+}
+
 pub struct GraphQLArcResponse {
     response: JITBatchResponse<Vec<u8>>,
     cache_control: Option<CacheControl>,
+    format: JsonFormat,
+    /// The size from which the body is compressed with gzip, when the client
+    /// accepts it.
+    compression: Option<usize>,
+    /// Payloads of an incremental delivery response, they're sent in place of
+    /// the `response` when present
+    incremental: Option<BoxStream<'static, Vec<u8>>>,
+    /// Chunks of the JSON of a streamed response, they're sent in place of
+    /// the `response` when present
+    chunked: Option<BoxStream<'static, Vec<u8>>>,
 }
 
 impl GraphQLArcResponse {
     pub fn new(response: JITBatchResponse<Vec<u8>>) -> Self {
-        Self { response, cache_control: None }
+        Self {
+            response,
+            cache_control: None,
+            format: JsonFormat::default(),
+            compression: None,
+            incremental: None,
+            chunked: None,
+        }
+    }
+
+    pub fn incremental(payloads: BoxStream<'static, Vec<u8>>) -> Self {
+        Self {
+            response: JITBatchResponse::Single(Default::default()),
+            cache_control: None,
+            format: JsonFormat::default(),
+            compression: None,
+            incremental: Some(payloads),
+            chunked: None,
+        }
+    }
+
+    pub fn chunked(chunks: BoxStream<'static, Vec<u8>>) -> Self {
+        Self {
+            response: JITBatchResponse::Single(Default::default()),
+            cache_control: None,
+            format: JsonFormat::default(),
+            compression: None,
+            incremental: None,
+            chunked: Some(chunks),
+        }
     }
 
     pub fn set_cache_control(self, enable_cache_header: bool, max_age: i32, public: bool) -> Self {
         Self {
-            response: self.response,
             cache_control: enable_cache_header.then_some(CacheControl { max_age, public }),
+            ..self
+        }
+    }
+
+    /// Sets how the JSON of the response is serialized, the payloads of an
+    /// incremental delivery response and the chunks of a streamed response
+    /// are always compact.
+    pub fn set_format(self, format: JsonFormat) -> Self {
+        Self { format, ..self }
+    }
+
+    /// Compresses the body with gzip when it reaches `min_size` and the
+    /// request accepts it, the bodies of the incremental delivery and the
+    /// streamed responses aren't compressed.
+    pub fn set_compression(self, min_size: Option<usize>, headers: &HeaderMap) -> Self {
+        Self {
+            compression: min_size.filter(|_| accepts_gzip(headers)),
+            ..self
         }
     }
 }
@@ -379,7 +555,7 @@ impl GraphQLArcResponse {
         Ok(response)
     }
 
-    fn default_body(&self) -> Result<Body> {
+    fn default_body(&self) -> Result<Vec<u8>> {
         let str_repr: Vec<u8> = match &self.response {
             JITBatchResponse::Batch(resp) => {
                 // Use iterators and collect for more efficient concatenation
@@ -402,11 +578,48 @@ impl GraphQLArcResponse {
             }
             JITBatchResponse::Single(resp) => resp.body.as_ref().to_owned(),
         };
-        Ok(Body::from(str_repr))
+        format_body(str_repr, self.format)
     }
 
-    pub fn into_response(self) -> Result<Response<hyper::Body>> {
-        self.build_response(StatusCode::OK, self.default_body()?)
+    /// Streams the payloads of an incremental delivery response as the parts
+    /// of a `multipart/mixed` body, the deferred payloads are sent as soon as
+    /// they're resolved.
+    fn multipart_response(payloads: BoxStream<'static, Vec<u8>>) -> Result<Response<Body>> {
+        let parts = payloads
+            .map(|payload| [MULTIPART_PART.as_bytes(), &payload[..]].concat())
+            .chain(stream::once(future::ready(
+                MULTIPART_END.as_bytes().to_vec(),
+            )))
+            .map(Ok::<_, Infallible>);
+
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, MULTIPART_MIXED.as_ref())
+            .body(Body::wrap_stream(parts))?)
+    }
+
+    pub fn into_response(mut self) -> Result<Response<hyper::Body>> {
+        if let Some(payloads) = self.incremental.take() {
+            return Self::multipart_response(payloads);
+        }
+
+        // the chunks are resolved only when the body is polled for them
+        if let Some(chunks) = self.chunked.take() {
+            let body = Body::wrap_stream(chunks.map(Ok::<_, Infallible>));
+            return self.build_response(StatusCode::OK, body);
+        }
+
+        let body = self.default_body()?;
+        match self.compression {
+            Some(min_size) if body.len() >= min_size => {
+                let mut response = self.build_response(StatusCode::OK, Body::from(gzip(&body)?))?;
+                let headers = response.headers_mut();
+                headers.insert(CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+                headers.insert(VARY, HeaderValue::from_static("accept-encoding"));
+                Ok(response)
+            }
+            _ => self.build_response(StatusCode::OK, Body::from(body)),
+        }
     }
 }
 
@@ -506,6 +719,147 @@ mod tests {
         );
     }
 
+    async fn format(format: JsonFormat) -> String {
+        let body = json!({"data": {"user": {"name": "Jürgen"}}}).to_string();
+        let response = crate::core::jit::AnyResponse {
+            body: std::sync::Arc::new(body.into_bytes()),
+            ..Default::default()
+        };
+        let response = GraphQLArcResponse::new(JITBatchResponse::Single(response))
+            .set_format(format)
+            .into_response()
+            .unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_response_format() {
+        assert_eq!(
+            format(JsonFormat::Compact).await,
+            r#"{"data":{"user":{"name":"Jürgen"}}}"#
+        );
+        assert_eq!(
+            format(JsonFormat::Pretty).await,
+            "{\n  \"data\": {\n    \"user\": {\n      \"name\": \"Jürgen\"\n    }\n  }\n}"
+        );
+        assert_eq!(
+            format(JsonFormat::Ascii).await,
+            r#"{"data":{"user":{"name":"J\u00fcrgen"}}}"#
+        );
+    }
+
+    fn large_response() -> (String, GraphQLArcResponse) {
+        let users = (1..=100)
+            .map(|id| json!({"id": id, "name": format!("User {}", id)}))
+            .collect::<Vec<_>>();
+        let body = json!({"data": {"users": users}}).to_string();
+        let response = crate::core::jit::AnyResponse {
+            body: std::sync::Arc::new(body.clone().into_bytes()),
+            ..Default::default()
+        };
+
+        (
+            body,
+            GraphQLArcResponse::new(JITBatchResponse::Single(response)),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_gzip_compression() {
+        let (body, response) = large_response();
+        let headers =
+            HeaderMap::from_iter([(ACCEPT_ENCODING, HeaderValue::from_static("br, gzip;q=0.8"))]);
+        let response = response
+            .set_compression(Some(1024), &headers)
+            .into_response()
+            .unwrap();
+
+        assert_eq!(response.headers()[CONTENT_ENCODING], "gzip");
+        let compressed = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert!(compressed.len() < body.len());
+
+        let mut decompressed = String::new();
+        std::io::Read::read_to_string(
+            &mut flate2::read::GzDecoder::new(&compressed[..]),
+            &mut decompressed,
+        )
+        .unwrap();
+        assert_eq!(decompressed, body);
+    }
+
+    #[tokio::test]
+    async fn test_gzip_compression_skipped() {
+        let gzip = HeaderMap::from_iter([(ACCEPT_ENCODING, HeaderValue::from_static("gzip"))]);
+        let refused =
+            HeaderMap::from_iter([(ACCEPT_ENCODING, HeaderValue::from_static("gzip;q=0"))]);
+        let refused_over_any =
+            HeaderMap::from_iter([(ACCEPT_ENCODING, HeaderValue::from_static("gzip;q=0, *"))]);
+
+        // the client doesn't accept gzip
+        for headers in [HeaderMap::new(), refused, refused_over_any] {
+            let (body, response) = large_response();
+            let response = response
+                .set_compression(Some(1024), &headers)
+                .into_response()
+                .unwrap();
+
+            assert!(!response.headers().contains_key(CONTENT_ENCODING));
+            assert_eq!(
+                hyper::body::to_bytes(response.into_body()).await.unwrap(),
+                body
+            );
+        }
+
+        // the response is under the minimum size
+        let (body, response) = large_response();
+        let response = response
+            .set_compression(Some(body.len() + 1), &gzip)
+            .into_response()
+            .unwrap();
+        assert!(!response.headers().contains_key(CONTENT_ENCODING));
+    }
+
+    #[test]
+    fn test_accepts_multipart() {
+        let accepts = |value: &'static str| {
+            accepts_multipart(&HeaderMap::from_iter([(
+                ACCEPT,
+                HeaderValue::from_static(value),
+            )]))
+        };
+
+        assert!(accepts("multipart/mixed"));
+        assert!(accepts(
+            "multipart/mixed;deferSpec=20220824, application/json"
+        ));
+        assert!(!accepts("application/json"));
+        assert!(!accepts("*/*"));
+        assert!(!accepts("multipart/mixed;q=0, application/json"));
+        assert!(!accepts_multipart(&HeaderMap::new()));
+    }
+
+    #[tokio::test]
+    async fn test_chunked_response() {
+        use hyper::body::HttpBody;
+
+        let (sender, receiver) = tokio::sync::oneshot::channel::<Vec<u8>>();
+        let chunks = stream::once(future::ready(br#"{"data":{"a":1"#.to_vec()))
+            .chain(stream::once(async move { receiver.await.unwrap() }))
+            .boxed();
+        let response = GraphQLArcResponse::chunked(chunks).into_response().unwrap();
+        let mut body = response.into_body();
+
+        // the first chunk is sent before the rest of the response is resolved
+        let first = body.data().await.unwrap().unwrap();
+        assert_eq!(&first[..], br#"{"data":{"a":1"#);
+
+        sender.send(br#","b":2}}"#.to_vec()).unwrap();
+        let rest = hyper::body::to_bytes(body).await.unwrap();
+        assert_eq!(&rest[..], br#","b":2}}"#);
+    }
+
     #[test]
     fn to_value() {
         assert_eq!(CacheControl { public: true, max_age: 0 }.value(), None);
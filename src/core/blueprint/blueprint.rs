@@ -8,9 +8,10 @@ use derive_setters::Setters;
 
 use super::directive::Directive;
 use super::telemetry::Telemetry;
-use super::{GlobalTimeout, Index};
+use super::{Constraint, GlobalTimeout, Index};
 use crate::core::blueprint::{Server, Upstream};
 use crate::core::ir::model::IR;
+use crate::core::mustache::Mustache;
 use crate::core::schema_extension::SchemaExtension;
 use crate::core::{scalar, Type};
 
@@ -60,6 +61,18 @@ impl Definition {
             Definition::Union(def) => &def.directives,
         }
     }
+
+    /// gets the description of the definition
+    pub fn description(&self) -> Option<&String> {
+        match self {
+            Definition::Interface(def) => def.description.as_ref(),
+            Definition::Object(def) => def.description.as_ref(),
+            Definition::InputObject(def) => def.description.as_ref(),
+            Definition::Scalar(def) => def.description.as_ref(),
+            Definition::Enum(def) => def.description.as_ref(),
+            Definition::Union(def) => def.description.as_ref(),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -102,6 +115,7 @@ pub struct EnumValueDefinition {
     pub name: String,
     pub directives: Vec<Directive>,
     pub alias: BTreeSet<String>,
+    pub upstream_value: Option<String>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -117,6 +131,8 @@ pub struct InputFieldDefinition {
     pub of_type: Type,
     pub default_value: Option<serde_json::Value>,
     pub description: Option<String>,
+    pub constraint: Option<Constraint>,
+    pub context_default: Option<Mustache>,
 }
 
 #[derive(Clone, Debug, Setters, Default)]
@@ -128,6 +144,7 @@ pub struct FieldDefinition {
     pub directives: Vec<Directive>,
     pub description: Option<String>,
     pub default_value: Option<serde_json::Value>,
+    pub prefetch: bool,
 }
 
 impl FieldDefinition {
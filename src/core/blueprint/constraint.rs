@@ -0,0 +1,69 @@
+use regex::Regex;
+use tailcall_valid::Valid;
+
+use crate::core::blueprint::BlueprintError;
+use crate::core::config;
+use crate::core::json::JsonLike;
+
+/// Compiled form of the `@constraint` directive applied on an argument.
+#[derive(Clone, Debug)]
+pub struct Constraint {
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub pattern: Option<Regex>,
+}
+
+impl Constraint {
+    pub fn try_from_config(constraint: &config::Constraint) -> Valid<Self, BlueprintError> {
+        let pattern = match &constraint.pattern {
+            Some(pattern) => match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(_) => return Valid::fail(BlueprintError::InvalidRegexPattern(pattern.clone())),
+            },
+            None => None,
+        };
+
+        Valid::succeed(Self {
+            min: constraint.min,
+            max: constraint.max,
+            min_length: constraint.min_length,
+            max_length: constraint.max_length,
+            pattern,
+        })
+    }
+
+    /// Checks the value against the constraint and returns the reason of the
+    /// violation if any. Lists are validated item by item and nulls are
+    /// always accepted.
+    pub fn validate<'a, Value: JsonLike<'a>>(&self, value: &'a Value) -> Result<(), String> {
+        if let Some(arr) = value.as_array() {
+            return arr.iter().try_for_each(|item| self.validate(item));
+        }
+
+        if let Some(number) = value.as_f64() {
+            if let Some(min) = self.min.filter(|min| number < *min as f64) {
+                return Err(format!("must be at least {}", min));
+            }
+            if let Some(max) = self.max.filter(|max| number > *max as f64) {
+                return Err(format!("must be at most {}", max));
+            }
+        }
+
+        if let Some(s) = value.as_str() {
+            let len = s.chars().count();
+            if let Some(min_length) = self.min_length.filter(|min_length| len < *min_length) {
+                return Err(format!("must be at least {} characters long", min_length));
+            }
+            if let Some(max_length) = self.max_length.filter(|max_length| len > *max_length) {
+                return Err(format!("must be at most {} characters long", max_length));
+            }
+            if let Some(pattern) = self.pattern.as_ref().filter(|regex| !regex.is_match(s)) {
+                return Err(format!("must match the pattern `{}`", pattern.as_str()));
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -6,6 +6,10 @@ use tailcall_valid::ValidationError;
 use super::BlueprintError;
 use crate::core::config;
 
+/// The time (in seconds) browsers can cache a preflight response for when
+/// `maxAge` isn't set, which is the most Chromium-based browsers allow.
+pub const DEFAULT_MAX_AGE: usize = 7200;
+
 #[derive(Clone, Debug, Setters, Default)]
 pub struct Cors {
     pub allow_credentials: bool,
@@ -26,7 +30,13 @@ impl Cors {
         if self.allow_origins.iter().any(is_wildcard) {
             Some((header::ACCESS_CONTROL_ALLOW_ORIGIN, origin.cloned()?))
         } else {
-            let allow_origin = origin.filter(|o| self.allow_origins.contains(o))?.clone();
+            let allow_origin = origin
+                .filter(|o| {
+                    self.allow_origins
+                        .iter()
+                        .any(|allowed| origin_matches(allowed, o))
+                })?
+                .clone();
             Some((header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin))
         }
     }
@@ -98,15 +108,30 @@ impl Cors {
         ))
     }
 
-    pub fn vary_to_header(&self) -> Option<(HeaderName, HeaderValue)> {
-        let values = &self.vary;
-        let mut res = values.first()?.as_bytes().to_owned();
-        for val in &values[1..] {
-            res.extend_from_slice(b", ");
-            res.extend_from_slice(val.as_bytes());
+    /// Composes the `Vary` header from the configured values, adding the
+    /// request headers the response always depends on: `Origin`, since the
+    /// allowed origin is reflected, and for preflight requests the requested
+    /// method and headers too. Duplicates are dropped.
+    pub fn vary_to_header(&self, is_preflight: bool) -> Option<(HeaderName, HeaderValue)> {
+        let required = if is_preflight {
+            &[
+                header::ORIGIN,
+                header::ACCESS_CONTROL_REQUEST_METHOD,
+                header::ACCESS_CONTROL_REQUEST_HEADERS,
+            ][..]
+        } else {
+            &[header::ORIGIN][..]
+        };
+
+        let mut values: Vec<&str> = vec![];
+        let configured = self.vary.iter().filter_map(|val| val.to_str().ok());
+        for val in configured.chain(required.iter().map(|name| name.as_str())) {
+            if !values.iter().any(|v| v.eq_ignore_ascii_case(val)) {
+                values.push(val);
+            }
         }
 
-        let header_val = HeaderValue::from_bytes(&res).ok()?;
+        let header_val = HeaderValue::from_str(&values.join(", ")).ok()?;
         Some((header::VARY, header_val))
     }
 
@@ -213,7 +238,7 @@ impl TryFrom<config::cors::Cors> for Cors {
                     .parse()
                     .map_err(|e: InvalidHeaderValue| ValidationError::new(e.into()))?,
             ),
-            max_age: value.max_age.map(|val| val.into()),
+            max_age: Some(value.max_age.unwrap_or(DEFAULT_MAX_AGE).into()),
             vary: value
                 .vary
                 .iter()
@@ -228,6 +253,37 @@ impl TryFrom<config::cors::Cors> for Cors {
     }
 }
 
+/// Checks if an origin is allowed by an entry of `allowOrigins`, which is
+/// either an exact origin or a pattern with a wildcard subdomain, like
+/// `https://*.example.com` or `*.example.com` for any scheme. The wildcard
+/// matches one or more subdomains, but not the domain itself.
+fn origin_matches(allowed: &HeaderValue, origin: &HeaderValue) -> bool {
+    if allowed == origin {
+        return true;
+    }
+
+    let (Ok(allowed), Ok(origin)) = (allowed.to_str(), origin.to_str()) else {
+        return false;
+    };
+
+    let Some((scheme, domain)) = allowed.split_once("*.") else {
+        return false;
+    };
+
+    let host = if scheme.is_empty() {
+        origin.split_once("://").map_or(origin, |(_, host)| host)
+    } else {
+        match origin.strip_prefix(scheme) {
+            Some(host) => host,
+            None => return false,
+        }
+    };
+
+    host.strip_suffix(domain)
+        .and_then(|host| host.strip_suffix('.'))
+        .is_some_and(|subdomain| !subdomain.is_empty() && !subdomain.contains(['/', ':', '@']))
+}
+
 #[allow(clippy::declare_interior_mutable_const)]
 const WILDCARD: HeaderValue = HeaderValue::from_static("*");
 
@@ -257,4 +313,107 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn test_allow_origin_wildcard_subdomain() {
+        let cors = Cors {
+            allow_origins: vec![
+                HeaderValue::from_static("https://*.example.com"),
+                HeaderValue::from_static("*.tailcall.run"),
+            ],
+            ..std::default::Default::default()
+        };
+
+        let allowed = [
+            "https://api.example.com",
+            "https://a.b.example.com",
+            "http://app.tailcall.run",
+        ];
+        for origin in allowed {
+            let origin = HeaderValue::from_static(origin);
+            assert_eq!(
+                cors.allow_origin_to_header(Some(&origin)),
+                Some((header::ACCESS_CONTROL_ALLOW_ORIGIN, origin))
+            );
+        }
+
+        let rejected = [
+            "https://example.com",
+            "http://api.example.com",
+            "https://api.example.com.evil.com",
+            "https://evilexample.com",
+            "https://api.example.com:8080",
+            "https://tailcall.run",
+        ];
+        for origin in rejected {
+            let origin = HeaderValue::from_static(origin);
+            assert_eq!(cors.allow_origin_to_header(Some(&origin)), None);
+        }
+    }
+
+    #[test]
+    fn test_preflight_vary_and_max_age() {
+        let cors = Cors::try_from(config::cors::Cors {
+            allow_methods: vec![crate::core::http::Method::POST],
+            vary: vec!["Accept-Encoding".to_string(), "origin".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(
+            cors.vary_to_header(true),
+            Some((
+                header::VARY,
+                HeaderValue::from_static(
+                    "Accept-Encoding, origin, access-control-request-method, \
+                     access-control-request-headers"
+                )
+            ))
+        );
+        assert_eq!(
+            cors.vary_to_header(false),
+            Some((
+                header::VARY,
+                HeaderValue::from_static("Accept-Encoding, origin")
+            ))
+        );
+        assert_eq!(
+            cors.max_age_to_header(),
+            Some((
+                header::ACCESS_CONTROL_MAX_AGE,
+                HeaderValue::from(DEFAULT_MAX_AGE)
+            ))
+        );
+
+        let cors = Cors::try_from(config::cors::Cors {
+            allow_methods: vec![crate::core::http::Method::POST],
+            max_age: Some(600),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(
+            cors.max_age_to_header(),
+            Some((header::ACCESS_CONTROL_MAX_AGE, HeaderValue::from(600)))
+        );
+    }
+
+    #[test]
+    fn test_credentials_with_wildcard_origin() {
+        let cors = config::cors::Cors {
+            allow_credentials: Some(true),
+            allow_methods: vec![crate::core::http::Method::GET],
+            allow_origins: vec!["*".to_string()],
+            ..Default::default()
+        };
+        assert!(Cors::try_from(cors).is_err());
+
+        let cors = config::cors::Cors {
+            allow_credentials: Some(true),
+            allow_methods: vec![crate::core::http::Method::GET],
+            allow_origins: vec!["https://*.example.com".to_string()],
+            ..Default::default()
+        };
+        assert!(Cors::try_from(cors).is_ok());
+    }
 }
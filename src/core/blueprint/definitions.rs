@@ -1,4 +1,5 @@
 use std::collections::HashSet;
+use std::sync::Arc;
 
 use async_graphql_value::ConstValue;
 use directive::Directive;
@@ -8,25 +9,43 @@ use tailcall_valid::{Valid, Validator};
 use union_resolver::update_union_resolver;
 
 use crate::core::blueprint::*;
+use crate::core::cache::metrics::CacheLabel;
 use crate::core::config::{Config, Enum, Field, GraphQLOperationType, Protected, Union};
 use crate::core::directive::DirectiveCodec;
 use crate::core::ir::model::{Cache, IR};
+use crate::core::mustache::Mustache;
 use crate::core::try_fold::TryFold;
 use crate::core::{config, scalar, Type};
 
-pub fn to_scalar_type_definition(name: &str) -> Valid<Definition, BlueprintError> {
+pub fn to_scalar_type_definition(
+    name: &str,
+    type_of: &config::Type,
+    scalars: &scalar::ScalarRegistry,
+) -> Valid<Definition, BlueprintError> {
     if scalar::Scalar::is_predefined(name) {
-        Valid::fail(BlueprintError::ScalarTypeIsPredefined(name.to_string()))
-    } else {
-        Valid::succeed(Definition::Scalar(ScalarTypeDefinition {
-            name: name.to_string(),
-            directives: Vec::new(),
-            description: None,
-            scalar: scalar::Scalar::find(name)
-                .unwrap_or(&scalar::Scalar::Empty)
-                .clone(),
-        }))
+        return Valid::fail(BlueprintError::ScalarTypeIsPredefined(name.to_string()));
     }
+
+    let scalar = match &type_of.regex {
+        Some(regex) => match scalar::RegexScalar::try_new(name, &regex.pattern) {
+            Ok(regex) => scalar::Scalar::Custom(Arc::new(regex)),
+            Err(_) => {
+                return Valid::fail(BlueprintError::InvalidRegexPattern(regex.pattern.clone()))
+                    .trace(config::Regex::trace_name().as_str())
+            }
+        },
+        None => scalars
+            .find(name)
+            .or_else(|| scalar::Scalar::find(name))
+            .unwrap_or(scalar::Scalar::Empty),
+    };
+
+    Valid::succeed(Definition::Scalar(ScalarTypeDefinition {
+        name: name.to_string(),
+        directives: Vec::new(),
+        description: None,
+        scalar,
+    }))
 }
 
 pub fn to_union_type_definition((name, u): (&String, &Union)) -> Definition {
@@ -51,6 +70,8 @@ pub fn to_input_object_type_definition(
                 description: field.description.clone(),
                 default_value: field.default_value.clone(),
                 of_type: field.of_type.clone(),
+                constraint: None,
+                context_default: None,
             })
             .collect(),
         description: definition.description,
@@ -254,6 +275,10 @@ fn to_enum_type_definition((name, eu): (&String, &Enum)) -> Definition {
                 name: variant.name.clone(),
                 directives: vec![],
                 alias: variant.alias.clone().unwrap_or_default().options,
+                upstream_value: variant
+                    .upstream_value
+                    .as_ref()
+                    .map(|value| value.name.clone()),
             })
             .collect(),
     })
@@ -285,12 +310,24 @@ fn update_args<'a>() -> TryFold<
         move |(_, field, _typ, name), _| {
             // TODO: assert type name
             Valid::from_iter(field.args.iter(), |(name, arg)| {
-                Valid::succeed(InputFieldDefinition {
+                match &arg.constraint {
+                    Some(constraint) => Constraint::try_from_config(constraint)
+                        .trace(config::Constraint::trace_name().as_str())
+                        .map(Some),
+                    None => Valid::succeed(None),
+                }
+                .map(|constraint| InputFieldDefinition {
                     name: name.clone(),
                     description: arg.doc.clone(),
                     of_type: arg.type_of.clone(),
                     default_value: arg.default_value.clone(),
+                    constraint,
+                    context_default: arg
+                        .default
+                        .as_ref()
+                        .map(|default| Mustache::parse(&default.value)),
                 })
+                .trace(name)
             })
             .map(|args| FieldDefinition {
                 name: name.to_string(),
@@ -300,6 +337,7 @@ fn update_args<'a>() -> TryFold<
                 directives: to_directives(&field.directives),
                 resolver: None,
                 default_value: field.default_value.clone(),
+                prefetch: false,
             })
         },
     )
@@ -364,16 +402,19 @@ pub fn fix_dangling_resolvers<'a>() -> TryFold<
 
 /// Wraps the IO Expression with Expression::Cached
 /// if `Field::cache` is present for that field
-pub fn update_cache_resolvers<'a>() -> TryFold<
+pub fn update_cache_resolvers<'a>(
+    type_name: &'a str,
+) -> TryFold<
     'a,
     (&'a ConfigModule, &'a Field, &'a config::Type, &'a str),
     FieldDefinition,
     BlueprintError,
 > {
     TryFold::<(&ConfigModule, &Field, &config::Type, &str), FieldDefinition, BlueprintError>::new(
-        move |(_config, field, typ, _name), mut b_field| {
+        move |(_config, field, typ, name), mut b_field| {
             if let Some(config::Cache { max_age }) = field.cache.as_ref().or(typ.cache.as_ref()) {
-                b_field.map_expr(|expression| Cache::wrap(*max_age, expression))
+                let label = CacheLabel::new(type_name, *name);
+                b_field.map_expr(|expression| Cache::wrap(*max_age, label, expression))
             }
 
             Valid::succeed(b_field)
@@ -544,11 +585,13 @@ pub fn to_field_definition(
 ) -> Valid<FieldDefinition, BlueprintError> {
     update_args()
         .and(update_resolver(operation_type, object_name))
+        .and(update_omitted_args().trace(config::Omit::trace_name().as_str()))
         .and(update_modify().trace(config::Modify::trace_name().as_str()))
         .and(fix_dangling_resolvers())
-        .and(update_cache_resolvers())
+        .and(update_cache_resolvers(object_name))
         .and(update_protected(object_name).trace(Protected::trace_name().as_str()))
-        .and(update_enum_alias())
+        .and(update_prefetch().trace(config::Prefetch::trace_name().as_str()))
+        .and(update_enum_alias(object_name))
         .and(update_union_resolver())
         .and(update_interface_resolver())
         .try_fold(
@@ -561,7 +604,8 @@ pub fn to_definitions<'a>() -> TryFold<'a, ConfigModule, Vec<Definition>, Bluepr
     TryFold::<ConfigModule, Vec<Definition>, BlueprintError>::new(|config_module, _| {
         Valid::from_iter(config_module.types.iter(), |(name, type_)| {
             if type_.scalar() {
-                to_scalar_type_definition(name).trace(name)
+                to_scalar_type_definition(name, type_, &config_module.extensions().scalars)
+                    .trace(name)
             } else {
                 to_object_type_definition(name, type_, config_module)
                     .trace(name)
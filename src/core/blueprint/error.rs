@@ -58,6 +58,9 @@ pub enum BlueprintError {
     #[error("batchKey requires either body or query parameters")]
     BatchKeyRequiresEitherBodyOrQuery,
 
+    #[error("dedupe: false can't be used with batchKey, the batched requests are deduplicated by their key")]
+    DedupeRequiredForBatching,
+
     #[error("script is required")]
     ScriptIsRequired,
 
@@ -136,6 +139,9 @@ pub enum BlueprintError {
     #[error("unknown template directive '{0}'")]
     UnknownTemplateDirective(String),
 
+    #[error("unknown request field '{0}'")]
+    UnknownRequestField(String),
+
     #[error("Query root is missing")]
     QueryRootIsMissing,
 
@@ -154,6 +160,15 @@ pub enum BlueprintError {
     #[error("Key is required for HTTP2")]
     KeyIsRequiredForHTTP2,
 
+    #[error("Certificate is required for TLS")]
+    CertificateIsRequiredForTLS,
+
+    #[error("Key is required for TLS")]
+    KeyIsRequiredForTLS,
+
+    #[error("PROXY protocol is only supported over HTTP/1 without TLS")]
+    ProxyProtocolRequiresHTTP1,
+
     #[error("Experimental headers must start with 'x-' or 'X-'. Got: '{0}'")]
     ExperimentalHeaderInvalidFormat(String),
 
@@ -163,6 +178,36 @@ pub enum BlueprintError {
     #[error("Invalid CORS configuration: Cannot combine `Access-Control-Allow-Credentials: true` with `{0}: *`")]
     InvalidCORSConfiguration(String),
 
+    #[error("Invalid status code {0}")]
+    InvalidStatusCode(u16),
+
+    #[error("Invalid regex pattern '{0}'")]
+    InvalidRegexPattern(String),
+
+    #[error("Concurrency limit must be greater than 0")]
+    InvalidConcurrencyLimit,
+
+    #[error("CSV delimiter must be a single character other than a quote or a line break")]
+    InvalidCsvDelimiter,
+
+    #[error("Non-null fields form a cycle that can never be resolved: {0}")]
+    NonNullCycle(String),
+
+    #[error("Invalid base URL, expected an http URL without a path: {0}")]
+    InvalidBaseUrl(String),
+
+    #[error("@join requires at least two resolvers on the field")]
+    JoinRequiresResolvers,
+
+    #[error("@prefetch requires a resolver on the field")]
+    PrefetchRequiresResolver,
+
+    #[error("@prefetch can't be used on a field whose resolver reads the value of its parent")]
+    PrefetchReadsParent,
+
+    #[error("@graphQL can't merge the requests that send the args as variables")]
+    GraphQLMergeWithVariables,
+
     #[error("{0}")]
     Cause(String),
 
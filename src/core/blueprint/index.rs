@@ -40,6 +40,15 @@ impl Index {
         matches!(def, Some(Definition::Scalar(_))) || scalar::Scalar::is_predefined(type_name)
     }
 
+    pub fn get_scalar(&self, type_name: &str) -> Option<scalar::Scalar> {
+        let def = self.map.get(type_name).map(|(def, _)| def);
+
+        match def {
+            Some(Definition::Scalar(scalar)) => Some(scalar.scalar.clone()),
+            _ => scalar::Scalar::find(type_name),
+        }
+    }
+
     pub fn get_interfaces(&self) -> HashSet<String> {
         self.map
             .iter()
@@ -56,6 +65,12 @@ impl Index {
         matches!(def, Some(Definition::Enum(_)))
     }
 
+    pub fn type_is_object(&self, type_name: &str) -> bool {
+        let def = self.map.get(type_name).map(|(def, _)| def);
+
+        matches!(def, Some(Definition::Object(_)))
+    }
+
     pub fn validate_enum_value(&self, type_name: &str, value: &str) -> bool {
         let def = self.map.get(type_name).map(|(def, _)| def);
 
@@ -69,6 +84,22 @@ impl Index {
         }
     }
 
+    /// Returns the value that stands for the enum value in the upstream, if it
+    /// is set with `@upstreamValue`. The value can also be an alias.
+    pub fn get_upstream_enum_value(&self, type_name: &str, value: &str) -> Option<&str> {
+        let def = self.map.get(type_name).map(|(def, _)| def);
+
+        if let Some(Definition::Enum(enum_)) = def {
+            enum_
+                .enum_values
+                .iter()
+                .find(|v| v.name == value || v.alias.contains(value))
+                .and_then(|v| v.upstream_value.as_deref())
+        } else {
+            None
+        }
+    }
+
     pub fn get_field(&self, type_name: &str, field_name: &str) -> Option<&QueryField> {
         self.map
             .get(type_name)
@@ -83,11 +114,17 @@ impl Index {
         self.schema.mutation.as_deref()
     }
 
+    /// Checks if the type is the given type, implements the given interface
+    /// or is a member of the given union.
     pub fn is_type_implements(&self, type_name: &str, type_or_interface: &str) -> bool {
         if type_name == type_or_interface {
             return true;
         }
 
+        if let Some((Definition::Union(union_), _)) = self.map.get(type_or_interface) {
+            return union_.types.contains(type_name);
+        }
+
         if let Some((Definition::Object(obj), _)) = self.map.get(type_name) {
             obj.implements.contains(type_or_interface)
         } else {
@@ -0,0 +1,215 @@
+use serde_json::{json, Map, Value};
+
+use super::{Blueprint, Definition, FieldDefinition};
+use crate::core::ir::model::{IO, IR};
+
+impl Blueprint {
+    /// Returns a JSON projection of the blueprint for debugging: the resolved
+    /// upstream settings and, for every field with a resolver, what the
+    /// resolver does, e.g. the method and the URL of the `@http` call.
+    pub fn inspect(&self) -> Value {
+        let upstream = &self.upstream;
+        let types = self
+            .definitions
+            .iter()
+            .filter_map(|definition| {
+                let fields = match definition {
+                    Definition::Object(def) => &def.fields,
+                    Definition::Interface(def) => &def.fields,
+                    _ => return None,
+                };
+                let fields = inspect_fields(fields);
+
+                (!fields.is_empty()).then(|| (definition.name().to_string(), Value::Object(fields)))
+            })
+            .collect::<Map<_, _>>();
+
+        json!({
+            "schema": {
+                "query": self.schema.query,
+                "mutation": self.schema.mutation,
+                "subscription": self.schema.subscription,
+            },
+            "upstream": {
+                "allowedHeaders": upstream.allowed_headers,
+                "batch": upstream.batch.as_ref().map(|batch| json!({
+                    "delay": batch.delay,
+                    "headers": batch.headers,
+                    "maxSize": batch.max_size,
+                })),
+                "concurrency": upstream.concurrency,
+                "connectTimeout": upstream.connect_timeout,
+                "http2Only": upstream.http2_only,
+                "httpCache": upstream.http_cache,
+                "keepAliveInterval": upstream.keep_alive_interval,
+                "keepAliveTimeout": upstream.keep_alive_timeout,
+                "keepAliveWhileIdle": upstream.keep_alive_while_idle,
+                "poolIdleTimeout": upstream.pool_idle_timeout,
+                "poolMaxIdlePerHost": upstream.pool_max_idle_per_host,
+                "proxy": upstream.proxy.as_ref().map(|proxy| &proxy.url),
+                "tcpKeepAlive": upstream.tcp_keep_alive,
+                "timeout": upstream.timeout,
+                "userAgent": upstream.user_agent,
+                "verifySsl": upstream.verify_ssl,
+            },
+            "types": types,
+        })
+    }
+}
+
+fn inspect_fields(fields: &[FieldDefinition]) -> Map<String, Value> {
+    fields
+        .iter()
+        .filter_map(|field| {
+            let resolver = field.resolver.as_ref()?;
+            let value = json!({
+                "type": format!("{:?}", field.of_type),
+                "resolver": inspect_ir(resolver),
+            });
+
+            Some((field.name.clone(), value))
+        })
+        .collect()
+}
+
+fn inspect_ir(ir: &IR) -> Value {
+    match ir {
+        IR::Dynamic(_) => json!({"kind": "expr"}),
+        IR::IO(io) => inspect_io(io),
+        IR::Cache(cache) => json!({
+            "kind": "cache",
+            "maxAge": cache.max_age,
+            "resolver": inspect_io(&cache.io),
+        }),
+        IR::Path(ir, path) => json!({"kind": "path", "path": path, "resolver": inspect_ir(ir)}),
+        IR::ContextPath(path) => json!({"kind": "context", "path": path}),
+        IR::Protect(_, ir) => json!({"kind": "protect", "resolver": inspect_ir(ir)}),
+        IR::Map(map) => json!({"kind": "map", "resolver": inspect_ir(&map.input)}),
+        IR::Pipe(first, second) => json!({
+            "kind": "pipe",
+            "resolvers": [inspect_ir(first), inspect_ir(second)],
+        }),
+        IR::Merge(irs) => json!({
+            "kind": "merge",
+            "resolvers": irs.iter().map(inspect_ir).collect::<Vec<_>>(),
+        }),
+        IR::Join(_, irs) => json!({
+            "kind": "join",
+            "resolvers": irs.iter().map(inspect_ir).collect::<Vec<_>>(),
+        }),
+        IR::Discriminate(_, ir) => json!({"kind": "discriminate", "resolver": inspect_ir(ir)}),
+        IR::Entity(entities) => json!({
+            "kind": "entity",
+            "resolvers": entities
+                .iter()
+                .map(|(type_name, ir)| (type_name.clone(), inspect_ir(ir)))
+                .collect::<Map<_, _>>(),
+        }),
+        IR::Service(_) => json!({"kind": "service"}),
+    }
+}
+
+fn inspect_io(io: &IO) -> Value {
+    match io {
+        IO::Http { req_template, group_by, dedupe, .. } => json!({
+            "kind": "http",
+            "method": req_template.method.as_str(),
+            "url": req_template.root_url.to_string(),
+            "batchKey": group_by.as_ref().map(|group_by| group_by.path()),
+            "dedupe": dedupe.unwrap_or_default(),
+        }),
+        IO::GraphQL { req_template, field_name, batch, merge, dedupe, .. } => json!({
+            "kind": "graphql",
+            "url": req_template.url,
+            "field": field_name,
+            "batch": batch,
+            "merge": merge,
+            "dedupe": dedupe,
+        }),
+        IO::Grpc { req_template, group_by, dedupe, .. } => json!({
+            "kind": "grpc",
+            "url": req_template.url.to_string(),
+            "method": req_template.operation.method.full_name(),
+            "batchKey": group_by.as_ref().map(|group_by| group_by.path()),
+            "dedupe": dedupe,
+        }),
+        IO::Js { name } => json!({"kind": "js", "name": name}),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tailcall_valid::Validator;
+
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::{Config, ConfigModule};
+
+    #[test]
+    fn test_inspect() {
+        let config = Config::from_sdl(
+            r#"
+            schema @upstream(timeout: 10, batch: {delay: 5}) {
+              query: Query
+            }
+
+            type Query {
+              posts: [Post] @http(url: "http://upstream/posts")
+              version: String @expr(body: "1.0")
+            }
+
+            type Post {
+              id: Int!
+              userId: Int!
+              title: String
+              user: User @http(url: "http://upstream/users", query: [{key: "id", value: "{{.value.userId}}"}], batchKey: ["id"])
+            }
+
+            type User {
+              id: Int!
+            }
+            "#,
+        )
+        .to_result()
+        .unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+
+        let actual = blueprint.inspect();
+
+        assert_eq!(actual["upstream"]["timeout"], json!(10));
+        assert_eq!(actual["upstream"]["batch"]["delay"], json!(5));
+        assert_eq!(
+            actual["types"],
+            json!({
+                "Post": {
+                    "user": {
+                        "type": "User",
+                        "resolver": {
+                            "kind": "http",
+                            "method": "GET",
+                            "url": "http://upstream/users",
+                            "batchKey": ["id"],
+                            "dedupe": false,
+                        },
+                    },
+                },
+                "Query": {
+                    "posts": {
+                        "type": "[Post]",
+                        "resolver": {
+                            "kind": "http",
+                            "method": "GET",
+                            "url": "http://upstream/posts",
+                            "batchKey": null,
+                            "dedupe": false,
+                        },
+                    },
+                    "version": {
+                        "type": "String",
+                        "resolver": {"kind": "expr"},
+                    },
+                },
+            })
+        );
+    }
+}
@@ -211,7 +211,6 @@ impl From<&Blueprint> for SchemaBuilder {
 fn inject_custom_scalars(mut schema: SchemaBuilder, blueprint: &Blueprint) -> SchemaBuilder {
     fn inject_scalar(schema: SchemaBuilder, type_name: &str) -> SchemaBuilder {
         if let Some(scalar) = Scalar::find(type_name) {
-            let scalar = scalar.clone();
             schema.register(dynamic::Type::Scalar(
                 dynamic::Scalar::new(scalar.name()).validator(move |val| scalar.validate(val)),
             ))
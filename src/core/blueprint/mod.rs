@@ -1,6 +1,7 @@
 mod auth;
 mod blueprint;
 mod compress;
+mod constraint;
 mod cors;
 mod definitions;
 mod directive;
@@ -8,6 +9,7 @@ mod dynamic_value;
 mod error;
 mod from_config;
 mod index;
+mod inspect;
 mod interface_resolver;
 mod into_document;
 mod into_schema;
@@ -19,11 +21,13 @@ mod server;
 pub mod telemetry;
 mod template_validation;
 mod timeout;
+mod typescript;
 mod union_resolver;
 mod upstream;
 
 pub use auth::*;
 pub use blueprint::*;
+pub use constraint::*;
 pub use cors::*;
 pub use definitions::*;
 pub use dynamic_value::*;
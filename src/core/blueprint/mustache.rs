@@ -89,10 +89,27 @@ impl<'a> MustachePartsValidator<'a> {
                     return Valid::fail(BlueprintError::VarNotSetInServerConfig(tail.to_string()));
                 }
             }
+            "parent" => {
+                // the ancestors depend on the query the field is resolved
+                // within, so they can't be validated here
+            }
             "headers" | "env" => {
                 // "headers" and "env" refers to values known at runtime, which
                 // we can't validate here
             }
+            "request" => {
+                // values of the request are known at runtime, only the field names
+                // can be validated here
+                let is_known = match tail {
+                    "method" | "path" | "clientIp" | "locale" => parts.len() == 2,
+                    "query" => parts.len() == 3,
+                    _ => false,
+                };
+
+                if !is_known {
+                    return Valid::fail(BlueprintError::UnknownRequestField(parts[1..].join(".")));
+                }
+            }
             _ => {
                 return Valid::fail(BlueprintError::UnknownTemplateDirective(head.to_string()));
             }
@@ -103,7 +120,7 @@ impl<'a> MustachePartsValidator<'a> {
 
     fn validate_resolver(&self, resolver: &IR) -> Valid<(), BlueprintError> {
         match resolver {
-            IR::Merge(resolvers) => {
+            IR::Merge(resolvers) | IR::Join(_, resolvers) => {
                 Valid::from_iter(resolvers, |resolver| self.validate_resolver(resolver)).unit()
             }
             IR::IO(IO::Http { req_template, .. }) => {
@@ -230,12 +247,15 @@ mod test {
                 of_type: type_,
                 default_value: None,
                 description: None,
+                constraint: None,
+                context_default: None,
             }],
             of_type: Type::Named { name: "T1".to_string(), non_null: false },
             resolver: None,
             directives: vec![],
             description: None,
             default_value: None,
+            prefetch: false,
         };
 
         (config, fld)
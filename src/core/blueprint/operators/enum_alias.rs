@@ -8,32 +8,52 @@ use crate::core::config::Field;
 use crate::core::ir::model::{Map, IR};
 use crate::core::try_fold::TryFold;
 
-pub fn update_enum_alias<'a>() -> TryFold<
+pub fn update_enum_alias<'a>(
+    object_name: &'a str,
+) -> TryFold<
     'a,
     (&'a ConfigModule, &'a Field, &'a config::Type, &'a str),
     FieldDefinition,
     BlueprintError,
 > {
     TryFold::<(&ConfigModule, &Field, &config::Type, &'a str), FieldDefinition, BlueprintError>::new(
-        |(config, field, _, _), mut b_field| {
+        move |(config, field, _, _), mut b_field| {
             let enum_type = config.enums.get(field.type_of.name());
             if let Some(enum_type) = enum_type {
                 let has_alias = enum_type.variants.iter().any(|v| v.alias.is_some());
-                if !has_alias {
+                let has_upstream_value = enum_type
+                    .variants
+                    .iter()
+                    .any(|v| v.upstream_value.is_some());
+                if !has_alias && !has_upstream_value {
                     return Valid::succeed(b_field);
                 }
                 let mut map = HashMap::<String, String>::new();
                 for v in enum_type.variants.iter() {
-                    map.insert(v.name.clone(), v.name.clone());
+                    // the upstream sends the value set with `@upstreamValue` in
+                    // place of the name of the variant
+                    let upstream_value = v.upstream_value.as_ref().map_or(&v.name, |u| &u.name);
+                    map.insert(upstream_value.clone(), v.name.clone());
                     if let Some(alias) = &v.alias {
                         for option in &alias.options {
                             map.insert(option.to_owned(), v.name.clone());
                         }
                     }
                 }
+
+                // the upstream values are read back from the parent value too,
+                // the fields of input types are translated with the arguments
+                if has_upstream_value
+                    && b_field.resolver.is_none()
+                    && !config.input_types().contains(object_name)
+                {
+                    b_field.resolver = Some(IR::ContextPath(vec![b_field.name.clone()]));
+                }
+
+                let unmapped = enum_type.unmapped.clone().unwrap_or_default().action;
                 b_field.resolver = b_field
                     .resolver
-                    .map(|r| IR::Map(Map { input: Box::new(r), map }));
+                    .map(|r| IR::Map(Map { input: Box::new(r), map, unmapped }));
             }
             Valid::succeed(b_field)
         },
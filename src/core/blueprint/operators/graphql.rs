@@ -3,11 +3,12 @@ use std::collections::{HashMap, HashSet};
 use tailcall_valid::{Valid, Validator};
 
 use crate::core::blueprint::BlueprintError;
-use crate::core::config::{Config, ConfigModule, GraphQL, GraphQLOperationType};
+use crate::core::config::{Config, ConfigModule, Field, GraphQL, GraphQLOperationType};
 use crate::core::graphql::RequestTemplate;
-use crate::core::helpers;
 use crate::core::ir::model::{IO, IR};
 use crate::core::ir::RelatedFields;
+use crate::core::mustache::{Mustache, Segment};
+use crate::core::{helpers, Type};
 
 fn create_related_fields(
     config: &Config,
@@ -53,20 +54,70 @@ fn create_related_fields(
     RelatedFields(map)
 }
 
+/// Returns the type of the value of the arg if the value is a single
+/// `{{.args.*}}` or `{{.value.*}}` expression, i.e. if the arg can be sent as a
+/// variable.
+fn variable_type(config: &Config, field: &Field, object_name: &str, value: &str) -> Option<Type> {
+    let mustache = Mustache::parse(value);
+    let [Segment::Expression(parts)] = mustache.segments().as_slice() else {
+        return None;
+    };
+
+    let mut type_of = match (parts.first()?.as_str(), parts.get(1)) {
+        ("args", Some(name)) => field.args.get(name)?.type_of.clone(),
+        ("value", Some(name)) => config
+            .find_type(object_name)?
+            .fields
+            .get(name)?
+            .type_of
+            .clone(),
+        _ => return None,
+    };
+    for name in parts.iter().skip(2) {
+        type_of = config
+            .find_type(type_of.name())?
+            .fields
+            .get(name)?
+            .type_of
+            .clone();
+    }
+
+    Some(type_of)
+}
+
 pub fn compile_graphql(
     config: &ConfigModule,
     operation_type: &GraphQLOperationType,
-    type_name: &str,
+    field: &Field,
+    object_name: &str,
     graphql: &GraphQL,
 ) -> Valid<IR, BlueprintError> {
     let args = graphql.args.as_ref();
+    let type_name = field.type_of.name();
+    let variables = args
+        .filter(|_| graphql.variables)
+        .into_iter()
+        .flatten()
+        .filter_map(|kv| {
+            let type_of = variable_type(config, field, object_name, &kv.value)?;
+            Some((kv.key.clone(), format!("{:?}", type_of)))
+        })
+        .collect();
 
     let mustache = match helpers::headers::to_mustache_headers(&graphql.headers).to_result() {
         Ok(mustache) => Valid::succeed(mustache),
         Err(err) => Valid::from_validation_err(BlueprintError::from_validation_string(err)),
     };
 
-    Valid::succeed(graphql.url.as_str())
+    Valid::<(), BlueprintError>::fail(BlueprintError::IncorrectBatchingUsage)
+        .when(|| {
+            (config.upstream.get_delay() < 1 || config.upstream.get_max_size() < 1) && graphql.merge
+        })
+        .and(
+            Valid::<(), BlueprintError>::fail(BlueprintError::GraphQLMergeWithVariables)
+                .when(|| graphql.merge && graphql.variables),
+        )
+        .and(Valid::succeed(graphql.url.as_str()))
         .zip(mustache)
         .and_then(|(base_url, headers)| {
             match RequestTemplate::new(
@@ -82,9 +133,11 @@ pub fn compile_graphql(
             }
         })
         .map(|req_template| {
+            let req_template = req_template.variables(variables);
             let field_name = graphql.name.clone();
             let batch = graphql.batch;
+            let merge = graphql.merge;
             let dedupe = graphql.dedupe;
-            IR::IO(IO::GraphQL { req_template, field_name, batch, dl_id: None, dedupe })
+            IR::IO(IO::GraphQL { req_template, field_name, batch, merge, dl_id: None, dedupe })
         })
 }
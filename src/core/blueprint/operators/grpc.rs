@@ -225,6 +225,7 @@ pub fn compile_grpc(inputs: CompileGrpc) -> Valid<IR, BlueprintError> {
                 operation,
                 body,
                 operation_type: operation_type.clone(),
+                web: grpc.web,
             };
             let on_response = grpc.on_response_body.clone();
             let hook = WorkerHooks::try_new(None, on_response).ok();
@@ -1,11 +1,16 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use http::header::HeaderName;
 use tailcall_valid::{Valid, Validator};
 use template_validation::validate_argument;
+use url::Url;
 
 use crate::core::blueprint::*;
 use crate::core::config::group_by::GroupBy;
-use crate::core::config::Field;
+use crate::core::config::{Field, ResponseFormat};
 use crate::core::endpoint::Endpoint;
-use crate::core::http::{Method, RequestTemplate};
+use crate::core::http::{BaseUrls, Method, RequestTemplate, ResponseDecoder, XmlTypes};
 use crate::core::ir::model::{IO, IR};
 use crate::core::worker_hooks::WorkerHooks;
 use crate::core::{config, helpers, Mustache};
@@ -16,7 +21,7 @@ pub fn compile_http(
     field: &Field,
 ) -> Valid<IR, BlueprintError> {
     let is_list = field.type_of.is_list();
-    let dedupe = http.dedupe.unwrap_or_default();
+    let dedupe = http.dedupe;
     let mustache_headers = match helpers::headers::to_mustache_headers(&http.headers).to_result() {
         Ok(mustache_headers) => Valid::succeed(mustache_headers),
         Err(e) => Valid::from_validation_err(BlueprintError::from_validation_string(e)),
@@ -34,15 +39,29 @@ pub fn compile_http(
             .unit()
             .trace("query"),
         )
+        .and(
+            Valid::from_iter(http.when.iter(), |when| {
+                validate_argument(config_module, Mustache::parse(when), field)
+            })
+            .unit()
+            .trace("when"),
+        )
         .and(
             Valid::<(), BlueprintError>::fail(BlueprintError::BatchKeyRequiresEitherBodyOrQuery)
                 .when(|| {
                     !http.batch_key.is_empty() && (http.body.is_none() && http.query.is_empty())
                 }),
         )
+        .and(
+            Valid::<(), BlueprintError>::fail(BlueprintError::DedupeRequiredForBatching)
+                .when(|| !http.batch_key.is_empty() && dedupe == Some(false))
+                .trace("dedupe"),
+        )
+        .and(validate_output(config_module, http, field).trace("output"))
         .and(Valid::succeed(http.url.as_str()))
         .zip(mustache_headers)
-        .and_then(|(base_url, headers)| {
+        .zip(to_response_decoder(http).trace("csv"))
+        .and_then(|((base_url, headers), response_decoder)| {
             let query = http
                 .query
                 .clone()
@@ -63,29 +82,41 @@ pub fn compile_http(
                     .body(http.body.clone())
                     .encoding(http.encoding.clone()),
             )
-            .map(|req_tmpl| req_tmpl.headers(headers))
-            {
+            .map(|req_tmpl| {
+                // the text of XML is converted to the types of the field, unless
+                // the response is reshaped by `select`
+                let xml_types = (response_decoder == ResponseDecoder::Xml && http.select.is_none())
+                    .then(|| Arc::new(XmlTypes::new(&field.type_of, config_module)));
+
+                req_tmpl
+                    .headers(headers)
+                    .response_decoder(response_decoder)
+                    .xml_types(xml_types)
+            }) {
                 Ok(data) => Valid::succeed(data),
                 Err(e) => Valid::fail(BlueprintError::Error(e)),
             }
         })
-        .and_then(|request_template| {
+        .zip(to_forward_headers(http).trace("forwardHeaders"))
+        .map(|(req_template, forward_headers)| req_template.forward_headers(forward_headers))
+        .zip(to_base_urls(http).trace("baseURLs"))
+        .and_then(|(request_template, base_urls)| {
             if !http.batch_key.is_empty() && (http.body.is_some() || http.method != Method::GET) {
                 if let Some(body) = http.body.as_ref() {
                     let dynamic_paths = count_dynamic_paths(body);
                     if dynamic_paths != 1 {
                         Valid::fail(BlueprintError::BatchRequiresDynamicParameter).trace("body")
                     } else {
-                        Valid::succeed(request_template)
+                        Valid::succeed((request_template, base_urls))
                     }
                 } else {
                     Valid::fail(BlueprintError::BatchRequiresDynamicParameter).trace("body")
                 }
             } else {
-                Valid::succeed(request_template)
+                Valid::succeed((request_template, base_urls))
             }
         })
-        .map(|req_template| {
+        .map(|(req_template, base_urls)| {
             // marge http and upstream on_request
             let on_request = http
                 .on_request
@@ -93,6 +124,12 @@ pub fn compile_http(
                 .or(config_module.upstream.on_request.clone());
             let on_response_body = http.on_response_body.clone();
             let hook = WorkerHooks::try_new(on_request, on_response_body).ok();
+            let when = http.when.as_deref().map(Mustache::parse);
+            let on_status = http
+                .on_status
+                .iter()
+                .map(|mapping| (mapping.status, mapping.action))
+                .collect::<HashMap<_, _>>();
 
             let io = if !http.batch_key.is_empty() {
                 // Find a query parameter that contains a reference to the {{.value}} key
@@ -108,11 +145,17 @@ pub fn compile_http(
 
                 IR::IO(IO::Http {
                     req_template,
-                    group_by: Some(GroupBy::new(http.batch_key.clone(), key)),
+                    group_by: Some(
+                        GroupBy::new(http.batch_key.clone(), key)
+                            .with_on_missing(http.on_missing_batch_key),
+                    ),
                     dl_id: None,
                     is_list,
                     dedupe,
                     hook,
+                    when,
+                    on_status,
+                    base_urls,
                 })
             } else {
                 IR::IO(IO::Http {
@@ -122,6 +165,9 @@ pub fn compile_http(
                     is_list,
                     dedupe,
                     hook,
+                    when,
+                    on_status,
+                    base_urls,
                 })
             };
             (io, &http.select)
@@ -129,6 +175,80 @@ pub fn compile_http(
         .and_then(apply_select)
 }
 
+/// Parses the names of the incoming headers forwarded to the API call.
+fn to_forward_headers(http: &config::Http) -> Valid<Vec<HeaderName>, BlueprintError> {
+    Valid::from_iter(
+        http.forward_headers.iter(),
+        |name| match HeaderName::from_bytes(name.as_bytes()) {
+            Ok(name) => Valid::succeed(name),
+            Err(e) => Valid::fail(BlueprintError::InvalidHeaderName(e)).trace(name),
+        },
+    )
+}
+
+/// Parses the base URLs the calls are spread across, each one must be an http
+/// url with a host and without a path, query or fragment, since only the
+/// scheme, host and port of the calls are replaced.
+fn to_base_urls(http: &config::Http) -> Valid<Option<Arc<BaseUrls>>, BlueprintError> {
+    if http.base_urls.is_empty() {
+        return Valid::succeed(None);
+    }
+
+    Valid::from_iter(http.base_urls.iter(), |base_url| {
+        match Url::parse(base_url) {
+            Ok(url)
+                if url.has_host()
+                    && matches!(url.scheme(), "http" | "https")
+                    && url.path() == "/"
+                    && url.query().is_none()
+                    && url.fragment().is_none() =>
+            {
+                Valid::succeed(url)
+            }
+            _ => Valid::fail(BlueprintError::InvalidBaseUrl(base_url.clone())),
+        }
+    })
+    .map(|urls| Some(Arc::new(BaseUrls::new(urls))))
+}
+
+/// Builds the decoder of the upstream response body from the response format
+/// and the CSV options.
+fn to_response_decoder(http: &config::Http) -> Valid<ResponseDecoder, BlueprintError> {
+    match http.response_format {
+        ResponseFormat::Json => Valid::succeed(ResponseDecoder::Json),
+        ResponseFormat::Xml => Valid::succeed(ResponseDecoder::Xml),
+        ResponseFormat::JsonSeq => Valid::succeed(ResponseDecoder::JsonSeq),
+        ResponseFormat::Csv => {
+            let csv = http.csv.clone().unwrap_or_default();
+            let mut chars = csv.delimiter.chars();
+            match (chars.next(), chars.next()) {
+                (Some(delimiter), None) if !matches!(delimiter, '"' | '\r' | '\n') => {
+                    Valid::succeed(ResponseDecoder::Csv { delimiter, header: csv.header })
+                }
+                _ => Valid::fail(BlueprintError::InvalidCsvDelimiter),
+            }
+        }
+    }
+}
+
+/// Ensures the declared output schema has the same shape as the GraphQL type of
+/// the field.
+fn validate_output(
+    config_module: &config::ConfigModule,
+    http: &config::Http,
+    field: &Field,
+) -> Valid<(), BlueprintError> {
+    let Some(output) = http.output.as_ref() else {
+        return Valid::succeed(());
+    };
+
+    let expected = to_json_schema(&field.type_of, config_module);
+    match output.is_compatible_with(&expected).to_result() {
+        Ok(_) => Valid::succeed(()),
+        Err(e) => Valid::from_validation_err(BlueprintError::from_validation_string(e)),
+    }
+}
+
 /// Count the number of dynamic expressions in the JSON value.
 fn count_dynamic_paths(json: &serde_json::Value) -> usize {
     let mut count = 0;
@@ -158,6 +278,7 @@ mod test {
     use serde_json::json;
 
     use super::*;
+    use crate::core::json::JsonSchema;
 
     #[test]
     fn test_extract_expression_keys_from_nested_objects() {
@@ -175,10 +296,84 @@ mod test {
         assert_eq!(keys, 3);
     }
 
+    fn compile_output(output: JsonSchema, type_of: crate::core::Type) -> Valid<IR, BlueprintError> {
+        let config = config::Config::default().types(vec![(
+            "Post",
+            config::Type::default().fields(vec![("id", Field::int()), ("title", Field::string())]),
+        )]);
+        let config_module = config::ConfigModule::from(config);
+        let http = config::Http {
+            url: "http://jsonplaceholder.typicode.com/posts".to_string(),
+            output: Some(output),
+            ..Default::default()
+        };
+        let field = Field { type_of, ..Default::default() };
+
+        compile_http(&config_module, &http, &field)
+    }
+
+    #[test]
+    fn test_compatible_output() {
+        let output = JsonSchema::Arr(Box::new(JsonSchema::from([
+            ("id", JsonSchema::Num),
+            ("title", JsonSchema::Str.optional()),
+            ("body", JsonSchema::Str),
+        ])));
+        let type_of = crate::core::Type::from("Post".to_string()).into_list();
+
+        assert!(compile_output(output, type_of).is_succeed());
+    }
+
+    #[test]
+    fn test_incompatible_output() {
+        let output = JsonSchema::Arr(Box::new(JsonSchema::Num));
+        let type_of = crate::core::Type::from("Int".to_string());
+
+        let actual = compile_output(output, type_of).to_result().unwrap_err();
+        let expected = "expected Number, found array";
+        assert_eq!(actual.as_vec()[0].message.to_string(), expected);
+        assert_eq!(actual.as_vec()[0].trace, vec!["output"]);
+
+        let output = JsonSchema::Arr(Box::new(JsonSchema::from([("id", JsonSchema::Str)])));
+        let type_of = crate::core::Type::from("Post".to_string()).into_list();
+
+        let actual = compile_output(output, type_of).to_result().unwrap_err();
+        assert_eq!(actual.as_vec()[0].trace, vec!["output", "id"]);
+    }
+
     #[test]
     fn test_with_non_json_value() {
         let json = json!(r#"{{.value}}"#);
         let keys = count_dynamic_paths(&json);
         assert_eq!(keys, 1);
     }
+
+    #[test]
+    fn test_csv_delimiter() {
+        let config_module = config::ConfigModule::default();
+        let field = Field {
+            type_of: crate::core::Type::from("JSON".to_string()),
+            ..Default::default()
+        };
+        let compile = |delimiter: &str| {
+            let http = config::Http {
+                url: "http://example.com/users.csv".to_string(),
+                response_format: ResponseFormat::Csv,
+                csv: Some(config::Csv { delimiter: delimiter.to_string(), header: true }),
+                ..Default::default()
+            };
+            compile_http(&config_module, &http, &field)
+        };
+
+        assert!(compile(";").is_succeed());
+        assert!(compile("\t").is_succeed());
+
+        let actual = compile(",,").to_result().unwrap_err();
+        assert_eq!(
+            actual.as_vec()[0].message.to_string(),
+            "CSV delimiter must be a single character other than a quote or a line break"
+        );
+        assert_eq!(actual.as_vec()[0].trace, vec!["csv"]);
+        assert!(compile("\"").is_fail());
+    }
 }
@@ -7,6 +7,8 @@ mod grpc;
 mod http;
 mod js;
 mod modify;
+mod omit;
+mod prefetch;
 mod protected;
 mod resolver;
 mod select;
@@ -20,6 +22,8 @@ pub use grpc::*;
 pub use http::*;
 pub use js::*;
 pub use modify::*;
+pub use omit::*;
+pub use prefetch::*;
 pub use protected::*;
 pub use resolver::*;
 pub use select::*;
@@ -0,0 +1,141 @@
+use async_graphql_value::Name;
+use indexmap::IndexMap;
+use tailcall_valid::{Valid, Validator};
+
+use crate::core::blueprint::*;
+use crate::core::config;
+use crate::core::config::Field;
+use crate::core::ir::model::IR;
+use crate::core::mustache::Mustache;
+use crate::core::try_fold::TryFold;
+
+/// Removes the arguments marked with `@omit` from the public field definition.
+/// The default values of such arguments are still injected into the resolver
+/// context, so they can be referenced with `{{.args.<name>}}` and can use
+/// mustache templates themselves e.g. `{{.headers.x-user-id}}`.
+pub fn update_omitted_args<'a>() -> TryFold<
+    'a,
+    (&'a ConfigModule, &'a Field, &'a config::Type, &'a str),
+    FieldDefinition,
+    BlueprintError,
+> {
+    TryFold::<(&ConfigModule, &Field, &config::Type, &'a str), FieldDefinition, BlueprintError>::new(
+        |(_, field, _, _), mut b_field| {
+            let omitted = field
+                .args
+                .iter()
+                .filter(|(_, arg)| arg.is_omitted())
+                .collect::<Vec<_>>();
+
+            if omitted.is_empty() {
+                return Valid::succeed(b_field);
+            }
+
+            b_field
+                .args
+                .retain(|arg| !omitted.iter().any(|(name, _)| **name == arg.name));
+
+            let Some(resolver) = b_field.resolver.take() else {
+                return Valid::succeed(b_field);
+            };
+
+            Valid::from_iter(omitted, |(name, arg)| match &arg.default_value {
+                Some(value) => match DynamicValue::try_from(value) {
+                    Ok(value) => Valid::succeed(Some((Name::new(name), value))),
+                    Err(e) => Valid::fail(BlueprintError::Error(e)).trace(name),
+                },
+                None => Valid::succeed(None),
+            })
+            .map(|values| {
+                let values = values.into_iter().flatten().collect::<IndexMap<_, _>>();
+
+                // merge the omitted arguments over the ones passed by the client
+                let args = IR::Merge(vec![
+                    IR::Dynamic(DynamicValue::Mustache(Mustache::parse("{{.args}}"))),
+                    IR::Dynamic(DynamicValue::Object(values)),
+                ]);
+
+                b_field.resolver(Some(args.pipe(resolver)))
+            })
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql_value::ConstValue;
+    use http::header::{HeaderMap, HeaderValue};
+    use serde_json::json;
+    use tailcall_valid::Validator;
+
+    use crate::core::blueprint::{Blueprint, Definition};
+    use crate::core::config::{Config, ConfigModule};
+    use crate::core::http::RequestContext;
+    use crate::core::ir::model::IR;
+    use crate::core::ir::{EmptyResolverContext, EvalContext};
+    use crate::core::print_schema::print_schema;
+
+    const CONFIG: &str = r#"
+        schema @server @upstream {
+          query: Query
+        }
+
+        type Query {
+          posts(
+            limit: Int
+            userId: String @omit = "{{.headers.x-user-id}}"
+          ): [Post] @http(url: "http://jsonplaceholder.typicode.com/posts", query: [{key: "userId", value: "{{.args.userId}}"}, {key: "limit", value: "{{.args.limit}}"}])
+        }
+
+        type Post {
+          id: Int
+        }
+    "#;
+
+    fn blueprint() -> Blueprint {
+        let config = Config::from_sdl(CONFIG).to_result().unwrap();
+        Blueprint::try_from(&ConfigModule::from(config)).unwrap()
+    }
+
+    #[test]
+    fn test_omitted_arg_is_hidden() {
+        let schema = print_schema(blueprint().to_schema());
+
+        assert!(schema.contains("posts(limit: Int): [Post]"));
+        assert!(!schema.contains("userId"));
+    }
+
+    #[tokio::test]
+    async fn test_omitted_arg_resolves_from_context() {
+        let blueprint = blueprint();
+        let resolver = blueprint
+            .definitions
+            .iter()
+            .find_map(|def| match def {
+                Definition::Object(obj) if obj.name == "Query" => obj
+                    .fields
+                    .iter()
+                    .find(|field| field.name == "posts")
+                    .and_then(|field| field.resolver.clone()),
+                _ => None,
+            })
+            .unwrap();
+
+        let IR::Pipe(args, _) = resolver else {
+            panic!("expected the resolver to inject the omitted arguments");
+        };
+
+        let mut req_ctx = RequestContext::default();
+        req_ctx.allowed_headers = HeaderMap::from_iter([(
+            "x-user-id".parse().unwrap(),
+            HeaderValue::from_static("user-42"),
+        )]);
+        let res_ctx = EmptyResolverContext {};
+        let mut eval_ctx = EvalContext::new(&req_ctx, &res_ctx);
+
+        let actual = args.eval(&mut eval_ctx).await.unwrap();
+        let expected = ConstValue::from_json(json!({"userId": "user-42"})).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,35 @@
+use tailcall_valid::Valid;
+
+use crate::core::blueprint::*;
+use crate::core::config;
+use crate::core::config::Field;
+use crate::core::try_fold::TryFold;
+
+/// Marks the fields with `@prefetch` to be resolved along with their parent.
+/// Only the resolvers that don't read the value of the parent can start
+/// before the parent is resolved.
+pub fn update_prefetch<'a>() -> TryFold<
+    'a,
+    (&'a ConfigModule, &'a Field, &'a config::Type, &'a str),
+    FieldDefinition,
+    BlueprintError,
+> {
+    TryFold::<(&ConfigModule, &Field, &config::Type, &'a str), FieldDefinition, BlueprintError>::new(
+        |(_, field, _, _), b_field| {
+            if field.prefetch.is_none() {
+                return Valid::succeed(b_field);
+            }
+
+            match &b_field.resolver {
+                Some(resolver) if field.has_resolver() => {
+                    if resolver.reads_parent() {
+                        Valid::fail(BlueprintError::PrefetchReadsParent)
+                    } else {
+                        Valid::succeed(b_field.prefetch(true))
+                    }
+                }
+                _ => Valid::fail(BlueprintError::PrefetchRequiresResolver),
+            }
+        },
+    )
+}
@@ -33,7 +33,7 @@ pub fn compile_resolver(
         })
         .trace(config::Grpc::trace_name().as_str()),
         Resolver::Graphql(graphql) => {
-            compile_graphql(config_module, operation_type, field.type_of.name(), graphql)
+            compile_graphql(config_module, operation_type, field, object_name, graphql)
                 .trace(config::GraphQL::trace_name().as_str())
         }
         Resolver::Call(call) => compile_call(config_module, call, operation_type, object_name)
@@ -71,10 +71,21 @@ pub fn update_resolver<'a>(
             Valid::from_iter(field.resolvers.iter(), |resolver| {
                 compile_resolver(&inputs, resolver)
             })
-            .map(|mut resolvers| match resolvers.len() {
-                0 => None,
-                1 => resolvers.pop().unwrap(),
-                _ => Some(IR::Merge(resolvers.into_iter().flatten().collect())),
+            .and_then(|resolvers| match &field.join {
+                Some(_) if resolvers.len() < 2 => {
+                    Valid::fail(BlueprintError::JoinRequiresResolvers)
+                        .trace(config::Join::trace_name().as_str())
+                }
+                _ => Valid::succeed(resolvers),
+            })
+            .map(|mut resolvers| match (resolvers.len(), &field.join) {
+                (0, _) => None,
+                (1, _) => resolvers.pop().unwrap(),
+                (_, Some(join)) => Some(IR::Join(
+                    join.on_conflict,
+                    resolvers.into_iter().flatten().collect(),
+                )),
+                (_, None) => Some(IR::Merge(resolvers.into_iter().flatten().collect())),
             })
             .map(|resolver| b_field.resolver(resolver))
             .and_then(|b_field| {
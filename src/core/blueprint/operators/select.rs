@@ -8,6 +8,10 @@ pub fn apply_select(input: (IR, &Option<Value>)) -> Valid<IR, BlueprintError> {
     let (mut ir, select) = input;
 
     if let Some(select_value) = select {
+        if let Some(path) = to_path(select_value) {
+            return Valid::succeed(IR::Path(Box::new(ir), path));
+        }
+
         let dynamic_value = match DynamicValue::try_from(select_value) {
             Ok(dynamic_value) => dynamic_value.prepend("args"),
             Err(e) => {
@@ -24,3 +28,17 @@ pub fn apply_select(input: (IR, &Option<Value>)) -> Valid<IR, BlueprintError> {
         Valid::succeed(ir)
     }
 }
+
+/// Returns the keys of the select if it's a list of plain keys, i.e. the path
+/// to the subtree of the response.
+fn to_path(select: &Value) -> Option<Vec<String>> {
+    let keys = select.as_array().filter(|keys| !keys.is_empty())?;
+
+    keys.iter()
+        .map(|key| {
+            key.as_str()
+                .filter(|key| !key.contains("{{"))
+                .map(str::to_string)
+        })
+        .collect()
+}
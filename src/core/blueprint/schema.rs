@@ -79,10 +79,67 @@ fn validate_mutation(config: &Config) -> Valid<(), BlueprintError> {
     }
 }
 
+/// Validates that there are no cycles of non-null object fields, e.g.
+/// `type A { b: B! }` and `type B { a: A! }`, since a value for such types
+/// would have to be infinitely nested. Nullable and list fields break the
+/// cycle, and so do fields with a resolver, since they are resolved on their
+/// own.
+fn validate_non_null_cycles(config: &Config) -> Valid<(), BlueprintError> {
+    let mut visited = HashSet::new();
+    let mut cycles = vec![];
+
+    for name in config.types.keys() {
+        find_non_null_cycles(name, &config.types, &mut vec![], &mut visited, &mut cycles);
+    }
+
+    Valid::from_iter(cycles, |(name, cycle)| {
+        Valid::<(), BlueprintError>::fail(BlueprintError::NonNullCycle(cycle)).trace(name)
+    })
+    .unit()
+}
+
+fn find_non_null_cycles<'a>(
+    name: &'a str,
+    types: &'a BTreeMap<String, Type>,
+    path: &mut Vec<(&'a str, &'a str)>,
+    visited: &mut HashSet<&'a str>,
+    cycles: &mut Vec<(&'a str, String)>,
+) {
+    if let Some(start) = path.iter().position(|(type_name, _)| *type_name == name) {
+        let cycle = path[start..]
+            .iter()
+            .map(|(type_name, field_name)| format!("{}.{}", type_name, field_name))
+            .chain(std::iter::once(name.to_string()))
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        cycles.push((name, cycle));
+        return;
+    }
+
+    if !visited.insert(name) {
+        return;
+    }
+
+    let Some(ty) = types.get(name) else {
+        return;
+    };
+
+    for (field_name, field) in ty.fields.iter() {
+        if let crate::core::Type::Named { name: type_name, non_null: true } = &field.type_of {
+            if !field.has_resolver() {
+                path.push((name, field_name));
+                find_non_null_cycles(type_name, types, path, visited, cycles);
+                path.pop();
+            }
+        }
+    }
+}
+
 pub fn to_schema<'a>() -> TryFoldConfig<'a, SchemaDefinition> {
     TryFoldConfig::new(|config, _| {
         validate_query(config)
             .and(validate_mutation(config))
+            .and(validate_non_null_cycles(config))
             .and(Valid::from_option(
                 config.schema.query.as_ref(),
                 BlueprintError::QueryRootIsMissing,
@@ -95,3 +152,85 @@ pub fn to_schema<'a>() -> TryFoldConfig<'a, SchemaDefinition> {
             })
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use tailcall_valid::Validator;
+
+    use super::validate_non_null_cycles;
+    use crate::core::blueprint::BlueprintError;
+    use crate::core::config::Config;
+
+    fn config(sdl: &str) -> Config {
+        Config::from_sdl(sdl).to_result().unwrap()
+    }
+
+    #[test]
+    fn test_non_null_cycle() {
+        let config = config(
+            r#"
+            type A {
+              id: Int
+              b: B!
+            }
+
+            type B {
+              a: A!
+            }
+
+            type C {
+              c: C!
+            }
+            "#,
+        );
+
+        let errors = validate_non_null_cycles(&config).to_result().unwrap_err();
+        let errors = errors
+            .as_vec()
+            .iter()
+            .map(|cause| {
+                (
+                    cause.message.to_string(),
+                    cause.trace.iter().cloned().collect(),
+                )
+            })
+            .collect::<Vec<(String, Vec<String>)>>();
+
+        assert_eq!(
+            errors,
+            vec![
+                (
+                    BlueprintError::NonNullCycle("A.b -> B.a -> A".to_string()).to_string(),
+                    vec!["A".to_string()]
+                ),
+                (
+                    BlueprintError::NonNullCycle("C.c -> C".to_string()).to_string(),
+                    vec!["C".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nullable_cycle() {
+        let config = config(
+            r#"
+            type A {
+              b: B!
+              list: [A!]!
+            }
+
+            type B {
+              a: A
+              c: C!
+            }
+
+            type C {
+              b: B! @http(url: "http://example.com/b")
+            }
+            "#,
+        );
+
+        assert!(validate_non_null_cycles(&config).is_succeed());
+    }
+}
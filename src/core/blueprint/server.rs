@@ -3,24 +3,45 @@ use std::net::{AddrParseError, IpAddr};
 use std::str::FromStr;
 use std::time::Duration;
 
+use async_graphql::parser::types::OperationType;
 use derive_setters::Setters;
-use http::header::{HeaderMap, HeaderName, HeaderValue};
+use http::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE, LOCATION};
+use http::StatusCode;
 use rustls_pki_types::CertificateDer;
 use tailcall_valid::{Valid, ValidationError, Validator};
 
 use super::BlueprintError;
 use crate::core::blueprint::Cors;
-use crate::core::config::{self, ConfigModule, HttpVersion, PrivateKey, Routes};
+use crate::core::config::{
+    self, ConfigModule, HttpVersion, JsonFormat, NullPropagation, PrivateKey, Routes,
+};
+use crate::core::mustache::Mustache;
 
 #[derive(Clone, Debug, Setters)]
 pub struct Server {
+    pub enable_access_log: bool,
     pub enable_apollo_tracing: bool,
     pub enable_cache_control_header: bool,
     pub enable_set_cookie_header: bool,
+    pub enable_request_id: bool,
     pub enable_introspection: bool,
     pub enable_query_validation: bool,
     pub enable_response_validation: bool,
+    pub enable_strict_response_validation: bool,
+    pub enable_response_streaming: bool,
+    pub null_propagation: NullPropagation,
+    pub response_format: JsonFormat,
+    /// The response to the requests that don't match any route, when it's
+    /// customized.
+    pub not_found: Option<NotFound>,
+    pub max_selections_per_level: Option<usize>,
+    pub query_timeout: Option<Duration>,
+    pub mutation_timeout: Option<Duration>,
     pub enable_batch_requests: bool,
+    pub enable_lazy_resolvers: bool,
+    /// The size in bytes from which the responses are compressed, when the
+    /// compression is enabled.
+    pub compression: Option<usize>,
     pub enable_showcase: bool,
     pub global_response_timeout: i64,
     pub worker: usize,
@@ -28,12 +49,28 @@ pub struct Server {
     pub hostname: IpAddr,
     pub vars: BTreeMap<String, String>,
     pub response_headers: HeaderMap,
+    /// Response headers with a mustache template as value, rendered against
+    /// the request context once the request has been executed.
+    pub response_header_templates: Vec<(HeaderName, Mustache)>,
     pub http: Http,
     pub pipeline_flush: bool,
+    pub keep_alive_timeout: Option<Duration>,
+    pub header_read_timeout: Option<Duration>,
+    pub enable_proxy_protocol: bool,
     pub script: Option<Script>,
     pub cors: Option<Cors>,
+    /// CORS rules of the REST endpoints, when they differ from `cors`.
+    pub rest_cors: Option<Cors>,
     pub experimental_headers: HashSet<HeaderName>,
     pub routes: Routes,
+    pub trusted_proxies: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct NotFound {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
 }
 
 /// Mimic of mini_v8::Script that's wasm compatible
@@ -46,6 +83,12 @@ pub struct Script {
 #[derive(Clone, Debug)]
 pub enum Http {
     HTTP1,
+    /// HTTP/1.1 over TLS, used when a cert and a key are linked to an HTTP1
+    /// server.
+    HTTPS {
+        cert: Vec<CertificateDer<'static>>,
+        key: PrivateKey,
+    },
     HTTP2 {
         cert: Vec<CertificateDer<'static>>,
         key: PrivateKey,
@@ -63,6 +106,9 @@ impl Server {
     pub fn get_enable_http_validation(&self) -> bool {
         self.enable_response_validation
     }
+    pub fn get_enable_strict_response_validation(&self) -> bool {
+        self.enable_strict_response_validation
+    }
     pub fn get_enable_cache_control(&self) -> bool {
         self.enable_cache_control_header
     }
@@ -78,6 +124,16 @@ impl Server {
     pub fn get_experimental_headers(&self) -> HashSet<HeaderName> {
         self.experimental_headers.clone()
     }
+
+    /// Returns the maximum duration of the operations of the type, if any.
+    /// Subscriptions are long-lived, so they are never cut off.
+    pub fn get_timeout(&self, operation_type: OperationType) -> Option<Duration> {
+        match operation_type {
+            OperationType::Query => self.query_timeout,
+            OperationType::Mutation => self.mutation_timeout,
+            OperationType::Subscription => None,
+        }
+    }
 }
 
 impl TryFrom<crate::core::config::ConfigModule> for Server {
@@ -103,8 +159,26 @@ impl TryFrom<crate::core::config::ConfigModule> for Server {
 
                 Valid::succeed(Http::HTTP2 { cert, key })
             }
-            _ => Valid::succeed(Http::HTTP1),
-        };
+            HttpVersion::HTTPS => {
+                let extensions = config_module.extensions();
+                match (extensions.cert.is_empty(), extensions.keys.first()) {
+                    (false, Some(key)) => Valid::succeed(Http::HTTPS {
+                        cert: extensions.cert.clone(),
+                        key: key.clone(),
+                    }),
+                    (true, _) => Valid::fail(BlueprintError::CertificateIsRequiredForTLS),
+                    (false, None) => Valid::fail(BlueprintError::KeyIsRequiredForTLS),
+                }
+            }
+            HttpVersion::HTTP1 => Valid::succeed(Http::HTTP1),
+        }
+        .and_then(|http| {
+            if config_server.enable_proxy_protocol() && !matches!(http, Http::HTTP1) {
+                Valid::fail(BlueprintError::ProxyProtocolRequiresHTTP1).trace("proxyProtocol")
+            } else {
+                Valid::succeed(http)
+            }
+        });
 
         validate_hostname((config_server).get_hostname().to_lowercase())
             .fuse(http_server)
@@ -115,21 +189,53 @@ impl TryFrom<crate::core::config::ConfigModule> for Server {
             .fuse(handle_experimental_headers(
                 (config_server).get_experimental_headers(),
             ))
-            .fuse(validate_cors(
-                config_server
-                    .headers
-                    .as_ref()
-                    .and_then(|headers| headers.get_cors()),
-            ))
+            .fuse(to_not_found((config_server).get_not_found()))
+            .fuse(
+                validate_cors(
+                    config_server
+                        .headers
+                        .as_ref()
+                        .and_then(|headers| headers.get_cors()),
+                    "cors",
+                )
+                .zip(validate_cors(
+                    config_server
+                        .headers
+                        .as_ref()
+                        .and_then(|headers| headers.get_rest_cors()),
+                    "restCors",
+                )),
+            )
             .map(
-                |(hostname, http, response_headers, script, experimental_headers, cors)| Server {
+                |(
+                    hostname,
+                    http,
+                    (response_headers, response_header_templates),
+                    script,
+                    experimental_headers,
+                    not_found,
+                    (cors, rest_cors),
+                )| Server {
+                    enable_access_log: (config_server).enable_access_log(),
                     enable_apollo_tracing: (config_server).enable_apollo_tracing(),
                     enable_cache_control_header: (config_server).enable_cache_control(),
                     enable_set_cookie_header: (config_server).enable_set_cookies(),
+                    enable_request_id: (config_server).enable_request_id(),
                     enable_introspection: (config_server).enable_introspection(),
                     enable_query_validation: (config_server).enable_query_validation(),
                     enable_response_validation: (config_server).enable_http_validation(),
+                    enable_strict_response_validation: (config_server)
+                        .enable_strict_response_validation(),
+                    enable_response_streaming: (config_server).enable_response_streaming(),
+                    null_propagation: (config_server).get_null_propagation(),
+                    response_format: (config_server).get_response_format(),
+                    not_found,
+                    max_selections_per_level: (config_server).get_max_selections_per_level(),
+                    query_timeout: (config_server).get_query_timeout(),
+                    mutation_timeout: (config_server).get_mutation_timeout(),
                     enable_batch_requests: (config_server).enable_batch_requests(),
+                    enable_lazy_resolvers: (config_server).enable_lazy_resolvers(),
+                    compression: (config_server).get_compression_min_size(),
                     enable_showcase: (config_server).enable_showcase(),
                     experimental_headers,
                     global_response_timeout: (config_server).get_global_response_timeout(),
@@ -139,10 +245,16 @@ impl TryFrom<crate::core::config::ConfigModule> for Server {
                     hostname,
                     vars: (config_server).get_vars(),
                     pipeline_flush: (config_server).get_pipeline_flush(),
+                    keep_alive_timeout: (config_server).get_keep_alive_timeout(),
+                    header_read_timeout: (config_server).get_header_read_timeout(),
+                    enable_proxy_protocol: (config_server).enable_proxy_protocol(),
                     response_headers,
+                    response_header_templates,
                     script,
                     cors,
+                    rest_cors,
                     routes: config_server.get_routes(),
+                    trusted_proxies: (config_server).get_trusted_proxies(),
                 },
             )
             .to_result()
@@ -168,9 +280,55 @@ fn to_script(
     )
 }
 
-fn validate_cors(cors: Option<config::cors::Cors>) -> Valid<Option<Cors>, BlueprintError> {
+fn to_not_found(not_found: Option<config::NotFound>) -> Valid<Option<NotFound>, BlueprintError> {
+    let Some(not_found) = not_found else {
+        return Valid::succeed(None);
+    };
+
+    let default_status = if not_found.redirect.is_some() {
+        StatusCode::FOUND
+    } else {
+        StatusCode::NOT_FOUND
+    };
+    let status = match not_found.status {
+        Some(status) => match StatusCode::from_u16(status) {
+            Ok(status) => Valid::succeed(status),
+            Err(_) => Valid::fail(BlueprintError::InvalidStatusCode(status)).trace("status"),
+        },
+        None => Valid::succeed(default_status),
+    };
+
+    let headers = [
+        (CONTENT_TYPE, not_found.content_type),
+        (LOCATION, not_found.redirect),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| value.map(|value| (name, value)))
+    .collect::<Vec<_>>();
+    let headers = Valid::from_iter(headers, |(name, value)| {
+        match HeaderValue::from_str(&value) {
+            Ok(value) => Valid::succeed((name, value)),
+            Err(e) => Valid::fail(BlueprintError::InvalidHeaderValue(e)),
+        }
+    })
+    .map(HeaderMap::from_iter);
+
+    status
+        .zip(headers)
+        .map(|(status, headers)| {
+            Some(NotFound { status, headers, body: not_found.body.unwrap_or_default() })
+        })
+        .trace("notFound")
+        .trace("@server")
+        .trace("schema")
+}
+
+fn validate_cors(
+    cors: Option<config::cors::Cors>,
+    name: &str,
+) -> Valid<Option<Cors>, BlueprintError> {
     Valid::from(cors.map(|cors| cors.try_into()).transpose())
-        .trace("cors")
+        .trace(name)
         .trace("headers")
         .trace("@server")
         .trace("schema")
@@ -191,9 +349,10 @@ fn validate_hostname(hostname: String) -> Valid<IpAddr, BlueprintError> {
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn handle_response_headers(
     resp_headers: Vec<(String, String)>,
-) -> Valid<HeaderMap, BlueprintError> {
+) -> Valid<(HeaderMap, Vec<(HeaderName, Mustache)>), BlueprintError> {
     Valid::from_iter(resp_headers.iter(), |(k, v)| {
         let name = match HeaderName::from_bytes(k.as_bytes()) {
             Ok(name) => Valid::succeed(name),
@@ -207,7 +366,27 @@ fn handle_response_headers(
 
         name.zip(value)
     })
-    .map(|headers| headers.into_iter().collect::<HeaderMap>())
+    .map(|headers| {
+        let mut response_headers = HeaderMap::new();
+        let mut templates = vec![];
+
+        for (name, value) in headers {
+            let template = value
+                .to_str()
+                .ok()
+                .map(Mustache::parse)
+                .filter(|template| !template.is_const());
+
+            match template {
+                Some(template) => templates.push((name, template)),
+                None => {
+                    response_headers.append(name, value);
+                }
+            }
+        }
+
+        (response_headers, templates)
+    })
     .trace("custom")
     .trace("headers")
     .trace("@server")
@@ -236,11 +415,30 @@ fn handle_experimental_headers(
 
 #[cfg(test)]
 mod tests {
-    use crate::core::config::ConfigModule;
+    use rustls_pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer};
+
+    use super::Http;
+    use crate::core::config::{self, ConfigModule, Extensions, HttpVersion};
 
     #[test]
     fn test_try_from_default() {
         let actual = super::Server::try_from(ConfigModule::default());
         assert!(actual.is_ok())
     }
+
+    #[test]
+    fn test_linked_key_keeps_http1() {
+        let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(vec![0]));
+        let extensions = Extensions { keys: vec![key.into()], ..Default::default() };
+
+        let config_module = ConfigModule::default().set_extensions(extensions.clone());
+        let actual = super::Server::try_from(config_module).unwrap();
+        assert!(matches!(actual.http, Http::HTTP1));
+
+        // TLS is used only when it's asked for
+        let mut config = config::Config::default();
+        config.server.version = Some(HttpVersion::HTTPS);
+        let config_module = ConfigModule::from(config).set_extensions(extensions);
+        assert!(super::Server::try_from(config_module).is_err());
+    }
 }
@@ -0,0 +1,336 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use convert_case::{Case, Casing};
+
+use super::{Blueprint, Definition, FieldDefinition, InputFieldDefinition};
+use crate::core::scalar::Scalar;
+use crate::core::Type;
+
+/// A property of a generated interface.
+struct Property<'a> {
+    name: &'a str,
+    type_of: &'a Type,
+    optional: bool,
+    description: Option<&'a String>,
+}
+
+impl<'a> Property<'a> {
+    fn from_field(field: &'a FieldDefinition) -> Self {
+        Self {
+            name: &field.name,
+            type_of: &field.of_type,
+            optional: false,
+            description: field.description.as_ref(),
+        }
+    }
+
+    /// Inputs that are nullable or that have a default can be left out.
+    fn from_input(input: &'a InputFieldDefinition) -> Self {
+        Self {
+            name: &input.name,
+            type_of: &input.of_type,
+            optional: input.of_type.is_nullable()
+                || input.default_value.is_some()
+                || input.context_default.is_some(),
+            description: input.description.as_ref(),
+        }
+    }
+}
+
+impl Blueprint {
+    /// Returns TypeScript declarations of the types of the schema, so the
+    /// clients can type the data they send and receive. Objects, interfaces
+    /// and input objects become interfaces, enums become unions of string
+    /// literals and the arguments of every field become a `<Type><Field>Args`
+    /// interface. The declarations are sorted by name.
+    pub fn to_typescript(&self) -> String {
+        let mut declarations = BTreeMap::new();
+        let mut scalars = BTreeMap::new();
+
+        for definition in self.definitions.iter() {
+            let name = definition.name();
+            let declaration = match definition {
+                Definition::Object(def) => {
+                    declare_args(&mut declarations, name, &def.fields);
+                    let properties = def.fields.iter().map(Property::from_field).collect();
+                    interface(name, &def.implements, properties)
+                }
+                Definition::Interface(def) => {
+                    declare_args(&mut declarations, name, &def.fields);
+                    let properties = def.fields.iter().map(Property::from_field).collect();
+                    interface(name, &def.implements, properties)
+                }
+                Definition::InputObject(def) => {
+                    let properties = def.fields.iter().map(Property::from_input).collect();
+                    interface(name, &BTreeSet::new(), properties)
+                }
+                Definition::Enum(def) => {
+                    let values = def
+                        .enum_values
+                        .iter()
+                        .map(|value| format!("{:?}", value.name))
+                        .collect::<Vec<_>>();
+                    format!("export type {} = {};", name, values.join(" | "))
+                }
+                Definition::Union(def) => {
+                    let types = def.types.iter().cloned().collect::<Vec<_>>();
+                    format!("export type {} = {};", name, types.join(" | "))
+                }
+                Definition::Scalar(def) => {
+                    scalars.insert(name.to_string(), scalar_type(&def.scalar));
+                    continue;
+                }
+            };
+
+            declarations.insert(
+                name.to_string(),
+                format!(
+                    "{}{}",
+                    doc_comment(definition.description(), ""),
+                    declaration
+                ),
+            );
+        }
+
+        // the built-in scalars, e.g. `Email`, are referenced without being
+        // defined in the config
+        for type_name in self.referenced_types() {
+            if let Some(scalar) = Scalar::find(type_name) {
+                scalars
+                    .entry(type_name.to_string())
+                    .or_insert(scalar_type(&scalar));
+            }
+        }
+        for (name, type_of) in scalars {
+            declarations.insert(name.clone(), format!("export type {} = {};", name, type_of));
+        }
+
+        let declarations = declarations.into_values().collect::<Vec<_>>();
+        declarations.join("\n\n")
+    }
+
+    /// Returns the names of the types of all the fields and arguments.
+    fn referenced_types(&self) -> BTreeSet<&str> {
+        let mut types = BTreeSet::new();
+        for definition in self.definitions.iter() {
+            let (fields, inputs) = match definition {
+                Definition::Object(def) => (def.fields.as_slice(), &[][..]),
+                Definition::Interface(def) => (def.fields.as_slice(), &[][..]),
+                Definition::InputObject(def) => (&[][..], def.fields.as_slice()),
+                _ => continue,
+            };
+
+            let args = fields.iter().flat_map(|field| field.args.iter());
+            types.extend(fields.iter().map(|field| field.of_type.name().as_str()));
+            types.extend(
+                inputs
+                    .iter()
+                    .chain(args)
+                    .map(|input| input.of_type.name().as_str()),
+            );
+        }
+
+        types
+    }
+}
+
+/// Declares an interface for the arguments of each field that has any.
+fn declare_args(
+    declarations: &mut BTreeMap<String, String>,
+    type_name: &str,
+    fields: &[FieldDefinition],
+) {
+    for field in fields.iter().filter(|field| !field.args.is_empty()) {
+        let name = format!("{}{}Args", type_name, field.name.to_case(Case::Pascal));
+        let properties = field.args.iter().map(Property::from_input).collect();
+        let declaration = interface(&name, &BTreeSet::new(), properties);
+        declarations.insert(name, declaration);
+    }
+}
+
+fn interface(name: &str, implements: &BTreeSet<String>, properties: Vec<Property>) -> String {
+    let extends = if implements.is_empty() {
+        String::new()
+    } else {
+        let implements = implements.iter().cloned().collect::<Vec<_>>();
+        format!(" extends {}", implements.join(", "))
+    };
+
+    let mut declaration = format!("export interface {}{} {{\n", name, extends);
+    for property in properties {
+        let optional = if property.optional { "?" } else { "" };
+        declaration.push_str(&doc_comment(property.description, "  "));
+        declaration.push_str(&format!(
+            "  {}{}: {};\n",
+            property.name,
+            optional,
+            to_typescript_type(property.type_of)
+        ));
+    }
+    declaration.push('}');
+
+    declaration
+}
+
+fn to_typescript_type(type_of: &Type) -> String {
+    let typescript_type = match type_of {
+        Type::Named { name, .. } => match name.as_str() {
+            "Int" | "Float" => "number".to_string(),
+            "String" | "ID" => "string".to_string(),
+            "Boolean" => "boolean".to_string(),
+            name => name.to_string(),
+        },
+        Type::List { of_type, .. } => format!("Array<{}>", to_typescript_type(of_type)),
+    };
+
+    if type_of.is_nullable() {
+        format!("{} | null", typescript_type)
+    } else {
+        typescript_type
+    }
+}
+
+/// Returns the TypeScript type of the values of a custom scalar, following
+/// how the scalar validates them, e.g. 64-bit integers are strings.
+fn scalar_type(scalar: &Scalar) -> &'static str {
+    match scalar {
+        Scalar::Int8
+        | Scalar::Int16
+        | Scalar::Int32
+        | Scalar::UInt8
+        | Scalar::UInt16
+        | Scalar::UInt32 => "number",
+        Scalar::Email
+        | Scalar::PhoneNumber
+        | Scalar::Date
+        | Scalar::DateTime
+        | Scalar::Url
+        | Scalar::Int64
+        | Scalar::Int128
+        | Scalar::UInt64
+        | Scalar::UInt128
+        | Scalar::Bytes
+        | Scalar::IPAddress => "string",
+        Scalar::Empty | Scalar::JSON | Scalar::Custom(_) => "unknown",
+    }
+}
+
+fn doc_comment(description: Option<&String>, indent: &str) -> String {
+    let Some(description) = description else {
+        return String::new();
+    };
+    let description = description.trim().replace("*/", "*\\/");
+
+    if description.lines().count() <= 1 {
+        format!("{}/** {} */\n", indent, description)
+    } else {
+        let lines = description
+            .lines()
+            .map(|line| format!("{} * {}", indent, line).trim_end().to_string())
+            .collect::<Vec<_>>();
+        format!("{}/**\n{}\n{} */\n", indent, lines.join("\n"), indent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tailcall_valid::Validator;
+
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::{Config, ConfigModule};
+
+    fn to_typescript(sdl: &str) -> String {
+        let config = Config::from_sdl(sdl).to_result().unwrap();
+        Blueprint::try_from(&ConfigModule::from(config))
+            .unwrap()
+            .to_typescript()
+    }
+
+    #[test]
+    fn test_jsonplaceholder() {
+        let sdl = std::fs::read_to_string(tailcall_fixtures::configs::JSONPLACEHOLDER).unwrap();
+        insta::assert_snapshot!(to_typescript(&sdl));
+    }
+
+    #[test]
+    fn test_enums_unions_and_inputs() {
+        let actual = to_typescript(
+            r#"
+            schema {
+              query: Query
+              mutation: Mutation
+            }
+
+            "A person"
+            type User {
+              id: ID!
+              email: Email
+              role: Role!
+            }
+
+            type Robot {
+              serial: Int64!
+            }
+
+            union Actor = User | Robot
+
+            enum Role {
+              ADMIN
+              MEMBER
+            }
+
+            input UserInput {
+              email: Email!
+              role: Role = MEMBER
+              tags: [String!]
+            }
+
+            type Query {
+              actors: [Actor!]! @http(url: "http://upstream/actors")
+            }
+
+            type Mutation {
+              createUser(user: UserInput!): User @http(url: "http://upstream/users", method: POST, body: "{{.args.user}}")
+            }
+            "#,
+        );
+
+        let expected = r#"export type Actor = Robot | User;
+
+export type Email = string;
+
+export type Int64 = string;
+
+export interface Mutation {
+  createUser: User | null;
+}
+
+export interface MutationCreateUserArgs {
+  user: UserInput;
+}
+
+export interface Query {
+  actors: Array<Actor>;
+}
+
+export interface Robot {
+  serial: Int64;
+}
+
+export type Role = "ADMIN" | "MEMBER";
+
+/** A person */
+export interface User {
+  email: Email | null;
+  id: string;
+  role: Role;
+}
+
+export interface UserInput {
+  email: Email;
+  role?: Role | null;
+  tags?: Array<string> | null;
+}"#;
+        assert_eq!(actual, expected);
+    }
+}
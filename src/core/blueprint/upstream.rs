@@ -4,7 +4,9 @@ use derive_setters::Setters;
 use tailcall_valid::{Valid, ValidationError, Validator};
 
 use super::BlueprintError;
-use crate::core::config::{self, Batch, ConfigModule};
+use crate::core::config::{
+    self, Batch, CircuitBreaker, ConfigModule, RedirectPolicy, RequestLogging, Resolver,
+};
 
 #[derive(PartialEq, Eq, Clone, Debug, schemars::JsonSchema)]
 pub struct Proxy {
@@ -19,16 +21,24 @@ pub struct Upstream {
     pub keep_alive_timeout: u64,
     pub keep_alive_while_idle: bool,
     pub proxy: Option<Proxy>,
+    pub redirect: RedirectPolicy,
     pub connect_timeout: u64,
     pub timeout: u64,
     pub tcp_keep_alive: u64,
     pub user_agent: String,
     pub allowed_headers: BTreeSet<String>,
+    /// The headers forwarded by some `@http(forwardHeaders: ...)`.
+    pub forwarded_headers: BTreeSet<String>,
     pub http_cache: u64,
+    pub error_body_size: usize,
     pub batch: Option<Batch>,
     pub http2_only: bool,
+    pub coalesce: bool,
     pub on_request: Option<String>,
     pub verify_ssl: bool,
+    pub concurrency: Option<usize>,
+    pub circuit_breaker: Option<CircuitBreaker>,
+    pub logging: Option<RequestLogging>,
 }
 
 impl Upstream {
@@ -65,28 +75,56 @@ impl TryFrom<&ConfigModule> for Upstream {
 
         get_batch(&config_upstream)
             .fuse(get_proxy(&config_upstream))
-            .map(|(batch, proxy)| Upstream {
+            .fuse(get_concurrency(&config_upstream))
+            .map(|(batch, proxy, concurrency)| Upstream {
                 pool_idle_timeout: (config_upstream).get_pool_idle_timeout(),
                 pool_max_idle_per_host: (config_upstream).get_pool_max_idle_per_host(),
                 keep_alive_interval: (config_upstream).get_keep_alive_interval(),
                 keep_alive_timeout: (config_upstream).get_keep_alive_timeout(),
                 keep_alive_while_idle: (config_upstream).get_keep_alive_while_idle(),
                 proxy,
+                redirect: (config_upstream).get_redirect(),
                 connect_timeout: (config_upstream).get_connect_timeout(),
                 timeout: (config_upstream).get_timeout(),
                 tcp_keep_alive: (config_upstream).get_tcp_keep_alive(),
                 user_agent: (config_upstream).get_user_agent(),
                 allowed_headers,
+                forwarded_headers: get_forwarded_headers(config_module),
                 http_cache: (config_upstream).get_http_cache_size(),
+                error_body_size: (config_upstream).get_error_body_size(),
                 batch,
                 http2_only: (config_upstream).get_http_2_only(),
+                coalesce: (config_upstream).get_coalesce(),
                 on_request: (config_upstream).get_on_request(),
                 verify_ssl: (config_upstream).get_verify_ssl(),
+                concurrency,
+                circuit_breaker: config_upstream.circuit_breaker.clone(),
+                logging: config_upstream.logging.clone(),
             })
             .to_result()
     }
 }
 
+fn get_forwarded_headers(config_module: &ConfigModule) -> BTreeSet<String> {
+    let resolvers = config_module.types.values().flat_map(|type_| {
+        type_.resolvers.0.iter().chain(
+            type_
+                .fields
+                .values()
+                .flat_map(|field| field.resolvers.0.iter()),
+        )
+    });
+
+    resolvers
+        .filter_map(|resolver| match resolver {
+            Resolver::Http(http) => Some(http.forward_headers.iter()),
+            _ => None,
+        })
+        .flatten()
+        .map(|name| name.to_lowercase())
+        .collect()
+}
+
 fn get_batch(upstream: &config::Upstream) -> Valid<Option<Batch>, BlueprintError> {
     upstream.batch.as_ref().map_or_else(
         || Valid::succeed(None),
@@ -107,3 +145,10 @@ fn get_proxy(upstream: &config::Upstream) -> Valid<Option<Proxy>, BlueprintError
         Valid::succeed(None)
     }
 }
+
+fn get_concurrency(upstream: &config::Upstream) -> Valid<Option<usize>, BlueprintError> {
+    match upstream.get_concurrency() {
+        Some(0) => Valid::fail(BlueprintError::InvalidConcurrencyLimit).trace("concurrency"),
+        concurrency => Valid::succeed(concurrency),
+    }
+}
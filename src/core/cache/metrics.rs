@@ -0,0 +1,75 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+
+/// Hits and misses of the field level cache, recorded per cached field and
+/// exported by the metrics of the cli
+pub static CACHE_METRICS: Lazy<CacheMetrics> = Lazy::new(CacheMetrics::default);
+
+/// The schema field a cached resolver belongs to. Labels are built only from
+/// the fields of the schema, so their number is bounded.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CacheLabel {
+    pub type_name: String,
+    pub field_name: String,
+}
+
+impl CacheLabel {
+    pub fn new(type_name: impl Into<String>, field_name: impl Into<String>) -> Self {
+        Self { type_name: type_name.into(), field_name: field_name.into() }
+    }
+}
+
+#[derive(Default)]
+struct Counts {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Default)]
+pub struct CacheMetrics {
+    counts: DashMap<CacheLabel, Counts>,
+}
+
+impl CacheMetrics {
+    pub fn hit(&self, label: &CacheLabel) {
+        self.update(label, |counts| &counts.hits);
+    }
+
+    pub fn miss(&self, label: &CacheLabel) {
+        self.update(label, |counts| &counts.misses);
+    }
+
+    fn update(&self, label: &CacheLabel, counter: impl Fn(&Counts) -> &AtomicU64) {
+        match self.counts.get(label) {
+            Some(counts) => counter(&counts).fetch_add(1, Ordering::Relaxed),
+            None => counter(&self.counts.entry(label.clone()).or_default())
+                .fetch_add(1, Ordering::Relaxed),
+        };
+    }
+
+    /// Returns the hits and the misses recorded for the field
+    pub fn get(&self, label: &CacheLabel) -> (u64, u64) {
+        self.counts
+            .get(label)
+            .map(|counts| {
+                (
+                    counts.hits.load(Ordering::Relaxed),
+                    counts.misses.load(Ordering::Relaxed),
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    /// Calls `f` with the hits and the misses of every cached field
+    pub fn for_each(&self, mut f: impl FnMut(&CacheLabel, u64, u64)) {
+        for entry in self.counts.iter() {
+            f(
+                entry.key(),
+                entry.hits.load(Ordering::Relaxed),
+                entry.misses.load(Ordering::Relaxed),
+            );
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod cache;
 pub mod error;
+pub mod metrics;
 pub use cache::*;
 pub use error::Error;
@@ -25,11 +25,17 @@ pub struct Apollo {
     /// Setting `version` for Apollo.
     #[serde(default, skip_serializing_if = "is_default")]
     pub version: Option<String>,
+    ///
+    /// Report the schema to Apollo Studio while the server runs, at the
+    /// interval requested by Apollo Studio and whenever the config is
+    /// reloaded, so that the usage can be matched to the fields of the schema.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub schema_reporting: bool,
 }
 
 impl Apollo {
     pub fn render_mustache(&mut self, reader_ctx: &ConfigReaderContext) -> anyhow::Result<()> {
-        let Apollo { api_key, graph_ref, user_version, platform, version } = self;
+        let Apollo { api_key, graph_ref, user_version, platform, version, .. } = self;
 
         let api_key_tmpl = Mustache::parse(api_key);
         *api_key = api_key_tmpl.render(reader_ctx);
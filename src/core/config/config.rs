@@ -16,8 +16,9 @@ use tailcall_valid::{Valid, Validator};
 use super::directive::Directive;
 use super::from_document::from_document;
 use super::{
-    AddField, Alias, Cache, Call, Discriminate, Expr, GraphQL, Grpc, Http, Link, Modify, Omit,
-    Protected, ResolverSet, Server, Telemetry, Upstream, JS,
+    AddField, Alias, Cache, Call, Constraint, ContextDefault, Discriminate, Expr, GraphQL, Grpc,
+    Http, Join, Link, Modify, Omit, Prefetch, Protected, Regex, ResolverSet, Server, Telemetry,
+    Unmapped, Upstream, UpstreamValue, JS,
 };
 use crate::core::config::npo::QueryPath;
 use crate::core::config::source::Source;
@@ -130,6 +131,9 @@ pub struct Type {
     ///
     /// Any additional directives
     pub directives: Vec<Directive>,
+    ///
+    /// Pattern that the values of a custom scalar must match.
+    pub regex: Option<Regex>,
 }
 
 impl Display for Type {
@@ -216,6 +220,14 @@ pub struct Field {
     /// Used to overwrite the default discrimination strategy
     pub discriminate: Option<Discriminate>,
 
+    ///
+    /// Merges the results of the resolvers of the field
+    pub join: Option<Join>,
+
+    ///
+    /// Starts the resolver of the field along with the one of its parent
+    pub prefetch: Option<Prefetch>,
+
     ///
     /// Resolver for the field
     pub resolvers: ResolverSet,
@@ -281,7 +293,21 @@ pub struct Arg {
     pub type_of: crate::core::Type,
     pub doc: Option<String>,
     pub modify: Option<Modify>,
+    pub omit: Option<Omit>,
     pub default_value: Option<Value>,
+    pub constraint: Option<Constraint>,
+    pub default: Option<ContextDefault>,
+}
+
+impl Arg {
+    pub fn is_omitted(&self) -> bool {
+        self.omit.is_some()
+            || self
+                .modify
+                .as_ref()
+                .and_then(|m| m.omit)
+                .unwrap_or_default()
+    }
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, MergeRight)]
@@ -295,6 +321,8 @@ pub struct Union {
 pub struct Enum {
     pub variants: BTreeSet<Variant>,
     pub doc: Option<String>,
+    // directive: unmapped
+    pub unmapped: Option<Unmapped>,
 }
 
 /// Definition of GraphQL value
@@ -303,6 +331,8 @@ pub struct Variant {
     pub name: String,
     // directive: alias
     pub alias: Option<Alias>,
+    // directive: upstreamValue
+    pub upstream_value: Option<UpstreamValue>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
@@ -349,6 +379,15 @@ impl RuntimeConfig {
             Ok(serde_json::to_string(self)?)
         }
     }
+
+    /// Applies a [JSON Merge Patch](https://datatracker.ietf.org/doc/html/rfc7386)
+    /// over the json representation of the config.
+    pub fn merge_patch(&self, patch: &Value) -> Result<Self> {
+        let mut value = serde_json::to_value(self)?;
+        merge_patch(&mut value, patch);
+
+        Ok(serde_json::from_value(value)?)
+    }
 }
 
 impl Config {
@@ -362,6 +401,21 @@ impl Config {
         }
     }
 
+    /// Overrides the runtime settings (server, upstream, links and telemetry)
+    /// with a [JSON Merge Patch](https://datatracker.ietf.org/doc/html/rfc7386)
+    /// e.g. `{"upstream": {"proxy": {"url": "http://localhost:3000"}}}`.
+    pub fn merge_patch(self, patch: &Value) -> Result<Self> {
+        let runtime_config = RuntimeConfig {
+            server: self.server.clone(),
+            upstream: self.upstream.clone(),
+            links: self.links.clone(),
+            telemetry: self.telemetry.clone(),
+        }
+        .merge_patch(patch)?;
+
+        Ok(self.with_runtime_config(runtime_config))
+    }
+
     pub fn is_root_operation_type(&self, type_name: &str) -> bool {
         let type_name = type_name.to_lowercase();
 
@@ -624,6 +678,57 @@ impl Config {
         set
     }
 
+    /// Returns the types, unions and enums that can't be reached from the
+    /// schema roots. A type reaches the types of its fields and their
+    /// arguments and the interfaces it implements, a union reaches its
+    /// members and an interface reaches the types that implement it.
+    pub fn unreachable_types(&self) -> BTreeSet<String> {
+        let mut implementers: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for (type_name, type_) in self.types.iter() {
+            for interface in type_.implements.iter() {
+                implementers.entry(interface).or_default().push(type_name);
+            }
+        }
+
+        let mut reachable = HashSet::new();
+        let mut stack = [
+            &self.schema.query,
+            &self.schema.mutation,
+            &self.schema.subscription,
+        ]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+
+        while let Some(type_name) = stack.pop() {
+            if !reachable.insert(type_name) {
+                continue;
+            }
+            if let Some(union_) = self.unions.get(type_name) {
+                stack.extend(union_.types.iter().map(String::as_str));
+            }
+            if let Some(type_) = self.types.get(type_name) {
+                for field in type_.fields.values() {
+                    stack.push(field.type_of.name().as_str());
+                    stack.extend(field.args.values().map(|arg| arg.type_of.name().as_str()));
+                }
+                stack.extend(type_.implements.iter().map(String::as_str));
+            }
+            if let Some(types) = implementers.get(type_name) {
+                stack.extend(types);
+            }
+        }
+
+        self.types
+            .keys()
+            .chain(self.unions.keys())
+            .chain(self.enums.keys())
+            .filter(|name| !reachable.contains(name.as_str()))
+            .cloned()
+            .collect()
+    }
+
     pub fn graphql_schema() -> ServiceDocument {
         // Multiple structs may contain a field of the same type when creating directive
         // definitions. To avoid generating the same GraphQL type multiple times,
@@ -647,6 +752,13 @@ impl Config {
             .add_directive(Omit::directive_definition(generated_types))
             .add_directive(Protected::directive_definition(generated_types))
             .add_directive(Discriminate::directive_definition(generated_types))
+            .add_directive(Regex::directive_definition(generated_types))
+            .add_directive(Constraint::directive_definition(generated_types))
+            .add_directive(ContextDefault::directive_definition(generated_types))
+            .add_directive(Join::directive_definition(generated_types))
+            .add_directive(Prefetch::directive_definition(generated_types))
+            .add_directive(UpstreamValue::directive_definition(generated_types))
+            .add_directive(Unmapped::directive_definition(generated_types))
             .add_input(GraphQL::input_definition())
             .add_input(Grpc::input_definition())
             .add_input(Http::input_definition())
@@ -663,6 +775,31 @@ impl Config {
     }
 }
 
+/// Merges the patch into the target as described in
+/// [RFC 7386](https://datatracker.ietf.org/doc/html/rfc7386#section-2): null
+/// removes the key, objects are merged recursively and any other value
+/// replaces the target.
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(target.entry(key).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
 impl From<RuntimeConfig> for Config {
     fn from(config: RuntimeConfig) -> Self {
         Self {
@@ -692,6 +829,34 @@ mod tests {
     use crate::core::config::Resolver;
     use crate::core::directive::DirectiveCodec;
 
+    #[test]
+    fn test_merge_patch() {
+        let config = Config::from_sdl(
+            r#"
+            schema
+              @server(port: 8000, hostname: "0.0.0.0")
+              @upstream(httpCache: 42, batch: {delay: 100}) {
+              query: Query
+            }
+
+            type Query {
+              hello: String @expr(body: "world")
+            }
+            "#,
+        )
+        .to_result()
+        .unwrap();
+
+        let patch = serde_json::json!({"server": {"port": 8080}, "upstream": {"httpCache": null}});
+        let actual = config.clone().merge_patch(&patch).unwrap();
+
+        let mut expected = config;
+        expected.server.port = Some(8080);
+        expected.upstream.http_cache = None;
+
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_field_has_or_not_batch_resolver() {
         let f1 = Field { ..Default::default() };
@@ -731,6 +896,19 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_from_sdl_malformed_argument_directives() {
+        let constraint = Config::from_sdl(
+            "type Query {users(limit: Int @constraint(maxx: 100)): [Int] @expr(body: [1])}",
+        );
+        assert!(constraint.to_result().is_err());
+
+        let default = Config::from_sdl(
+            "type Query {users(region: String @default(values: \"eu\")): [Int] @expr(body: [1])}",
+        );
+        assert!(default.to_result().is_err());
+    }
+
     #[test]
     fn test_unused_types_with_cyclic_types() {
         let config = Config::from_sdl(
@@ -757,6 +935,40 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_unreachable_types() {
+        let config = Config::from_sdl(
+            "
+            interface Node {id: ID!}
+            type User implements Node {id: ID!, status: Status}
+            type Post {id: ID!}
+            type Comment {id: ID!}
+            type Orphan {a: Int}
+            union Content = Post | Comment
+            input PostFilter {author: String, order: Order}
+            enum Status {ACTIVE INACTIVE}
+            enum Order {ASC DESC}
+            enum Unused {A B}
+
+            type Query {
+                node(id: ID!): Node
+                content(filter: PostFilter): [Content]
+            }
+
+            schema {
+                query: Query
+            }
+            ",
+        )
+        .to_result()
+        .unwrap();
+
+        let actual = config.unreachable_types();
+        let expected = BTreeSet::from(["Orphan".to_string(), "Unused".to_string()]);
+
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_is_root_operation_type_with_query() {
         let mut config = Config::default();
@@ -11,6 +11,7 @@ use crate::core::macros::MergeRight;
 use crate::core::merge_right::MergeRight;
 use crate::core::proto_reader::ProtoMetadata;
 use crate::core::rest::{EndpointSet, Unchecked};
+use crate::core::scalar::ScalarRegistry;
 use crate::core::Transform;
 
 mod merge;
@@ -83,6 +84,22 @@ impl ConfigModule {
         &self.cache.interfaces_types_map
     }
 
+    /// Makes the scalars of the registry available to the config, as if they
+    /// were declared in it, and validates their values with the registered
+    /// implementations. Fails if the config defines a type with the same name.
+    pub fn with_scalars(self, scalars: ScalarRegistry) -> anyhow::Result<Self> {
+        let mut config = self.cache.config;
+        for name in scalars.names() {
+            let type_of = config.types.entry(name.clone()).or_default();
+            if !type_of.scalar() {
+                anyhow::bail!("Scalar `{}` is already defined as a type", name);
+            }
+        }
+
+        let extensions = Extensions { scalars, ..self.extensions };
+        Ok(ConfigModule::new(config, extensions))
+    }
+
     pub fn transform<T: Transform<Value = Config>>(self, transformer: T) -> Valid<Self, T::Error> {
         transformer
             .transform(self.cache.config)
@@ -147,6 +164,9 @@ pub struct Extensions {
     pub htpasswd: Vec<Content<String>>,
 
     pub jwks: Vec<Content<JwkSet>>,
+
+    /// Contains the scalars supplied by the embedder
+    pub scalars: ScalarRegistry,
 }
 
 impl Extensions {
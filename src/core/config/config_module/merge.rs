@@ -81,7 +81,10 @@ impl Contravariant for Arg {
             type_of,
             doc: self.doc.merge_right(other.doc),
             modify: self.modify.merge_right(other.modify),
+            omit: self.omit.merge_right(other.omit),
             default_value: self.default_value.or(other.default_value),
+            constraint: self.constraint.merge_right(other.constraint),
+            default: self.default.merge_right(other.default),
         })
     }
 }
@@ -101,6 +104,8 @@ impl Contravariant for Field {
                 default_value: self.default_value.or(other.default_value),
                 protected: self.protected.merge_right(other.protected),
                 discriminate: self.discriminate.merge_right(other.discriminate),
+                join: self.join.merge_right(other.join),
+                prefetch: self.prefetch.merge_right(other.prefetch),
                 resolvers: self.resolvers.merge_right(other.resolvers),
                 directives: self.directives.merge_right(other.directives),
             })
@@ -123,6 +128,8 @@ impl Covariant for Field {
                 default_value: self.default_value.or(other.default_value),
                 protected: self.protected.merge_right(other.protected),
                 discriminate: self.discriminate.merge_right(other.discriminate),
+                join: self.join.merge_right(other.join),
+                prefetch: self.prefetch.merge_right(other.prefetch),
                 resolvers: self.resolvers.merge_right(other.resolvers),
                 directives: self.directives.merge_right(other.directives),
             })
@@ -141,6 +148,7 @@ impl Contravariant for Type {
             protected: self.protected.merge_right(other.protected),
             resolvers: self.resolvers.merge_right(other.resolvers),
             directives: self.directives.merge_right(other.directives),
+            regex: self.regex.merge_right(other.regex),
         })
     }
 }
@@ -157,6 +165,7 @@ impl Covariant for Type {
             protected: self.protected.merge_right(other.protected),
             resolvers: self.resolvers.merge_right(other.resolvers),
             directives: self.directives.merge_right(other.directives),
+            regex: self.regex.merge_right(other.regex),
         })
     }
 }
@@ -168,6 +177,7 @@ impl Contravariant for Enum {
         Valid::succeed(Self {
             variants: self.variants,
             doc: self.doc.merge_right(other.doc),
+            unmapped: self.unmapped.merge_right(other.unmapped),
         })
     }
 }
@@ -179,6 +189,7 @@ impl Covariant for Enum {
         Valid::succeed(Self {
             variants: self.variants,
             doc: self.doc.merge_right(other.doc),
+            unmapped: self.unmapped.merge_right(other.unmapped),
         })
     }
 }
@@ -30,7 +30,8 @@ pub struct Cors {
 
     /// A list of origins that are allowed to access the server's resources in
     /// cross-origin requests. An origin can be a domain, a subdomain, or
-    /// even 'null' for local file schemes.
+    /// even 'null' for local file schemes. Subdomains can be matched with a
+    /// wildcard, e.g. `https://*.example.com`.
     #[serde(default, skip_serializing_if = "is_default")]
     pub allow_origins: Vec<String>,
 
@@ -48,7 +49,7 @@ pub struct Cors {
 
     /// The maximum time (in seconds) that the client should cache preflight
     /// OPTIONS requests in order to avoid sending excessive requests to the
-    /// server.
+    /// server. @default `7200`.
     #[serde(default, skip_serializing_if = "is_default")]
     pub max_age: Option<usize>,
 
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use tailcall_macros::{DirectiveDefinition, MergeRight};
+
+use crate::core::is_default;
+
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    schemars::JsonSchema,
+    DirectiveDefinition,
+    MergeRight,
+)]
+#[directive_definition(locations = "ArgumentDefinition")]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+/// The `@constraint` directive validates the value of an argument before the
+/// field is resolved, for example `limit: Int @constraint(min: 1, max: 100)`.
+pub struct Constraint {
+    /// Minimum allowed value of a numeric argument.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub min: Option<i64>,
+    /// Maximum allowed value of a numeric argument.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub max: Option<i64>,
+    /// Minimum number of characters of a string argument.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub min_length: Option<usize>,
+    /// Maximum number of characters of a string argument.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub max_length: Option<usize>,
+    /// Regular expression that a string argument must match.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub pattern: Option<String>,
+}
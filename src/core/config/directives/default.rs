@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use tailcall_macros::{DirectiveDefinition, MergeRight};
+
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    schemars::JsonSchema,
+    DirectiveDefinition,
+    MergeRight,
+)]
+#[directive_definition(locations = "ArgumentDefinition")]
+#[serde(deny_unknown_fields)]
+/// The `@default` directive supplies the value of an argument omitted by the
+/// client from the request context, for example
+/// `region: String @default(value: "{{.headers.x-region}}")`. It takes
+/// precedence over the static default value whenever the template resolves.
+/// The rendered value is coerced to the type of the argument, lists and input
+/// objects are parsed as JSON. When the template refers to a missing header or
+/// variable the argument stays omitted, so the static default value is used
+/// and a required argument without one fails the request.
+pub struct Default {
+    /// Mustache template rendered against the request context.
+    pub value: String,
+}
@@ -56,4 +56,20 @@ pub struct GraphQL {
     /// with APIs that expect unique results for identical inputs, such as
     /// nonce-based APIs.
     pub dedupe: bool,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// Merges the requests of the fields with `merge` that go to the same
+    /// upstream into a single GraphQL query, where every field is aliased, and
+    /// splits the response back to the fields. The requests are collected
+    /// within the batch window, so the batch settings of `@upstream` are
+    /// required. Can't be combined with `variables`.
+    pub merge: bool,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// Sends the args to the upstream as GraphQL variables instead of inlining
+    /// their values into the query. Only the args whose value is a single
+    /// `{{.args.*}}` or `{{.value.*}}` expression are sent as variables, and
+    /// the type of a variable is the type of the argument or the field the
+    /// value comes from.
+    pub variables: bool,
 }
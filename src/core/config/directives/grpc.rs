@@ -5,6 +5,14 @@ use tailcall_macros::{DirectiveDefinition, InputDefinition};
 use crate::core::config::KeyValue;
 use crate::core::is_default;
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, schemars::JsonSchema)]
+/// The framing used to talk to a gRPC-Web upstream, e.g. a service behind
+/// Envoy. `Binary` sends the frames as they are, `Text` encodes them in base64.
+pub enum GrpcWeb {
+    Binary,
+    Text,
+}
+
 #[derive(
     Serialize,
     Deserialize,
@@ -39,6 +47,7 @@ pub struct Grpc {
     pub body: Option<Value>,
     #[serde(rename = "batchKey", default, skip_serializing_if = "is_default")]
     /// The `batchKey` dictates the path Tailcall will follow to group the returned items from the batch request. For more details please refer out [n + 1 guide](https://tailcall.run/docs/guides/n+1#solving-using-batching).
+    /// The last segment can be a dotted path, e.g. `meta.userId`, to group the items by a key nested within them.
     pub batch_key: Vec<String>,
     #[serde(default, skip_serializing_if = "is_default")]
     /// The `headers` parameter allows you to customize the headers of the HTTP
@@ -67,6 +76,8 @@ pub struct Grpc {
     /// * EXAMPLE 2: if we have a call that returns `{ "foo": "bar", "fizz": {
     ///   "buzz": "eggs", ... }, ... }` we can use { foo: "{{.foo}}", buzz:
     ///   "{{.fizz.buzz}}" }`
+    /// * EXAMPLE 3: a list of keys like `["data", "items"]` extracts the value
+    ///   at that path, or `null` when the path is missing.
     pub select: Option<Value>,
 
     /// Specifies a JavaScript function to be executed after receiving the
@@ -74,4 +85,12 @@ pub struct Grpc {
     /// body before it's sent back to the client.
     #[serde(rename = "onResponseBody", default, skip_serializing_if = "is_default")]
     pub on_response_body: Option<String>,
+
+    /// Calls the upstream with the gRPC-Web protocol instead of gRPC, for the
+    /// services that are only exposed through a gRPC-Web proxy. The request
+    /// is sent over HTTP/1.1 with the content-type
+    /// `application/grpc-web+proto`, or `application/grpc-web-text+proto` for
+    /// `Text`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub web: Option<GrpcWeb>,
 }
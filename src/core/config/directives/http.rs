@@ -7,6 +7,50 @@ use crate::core::http::Method;
 use crate::core::is_default;
 use crate::core::json::JsonSchema;
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, schemars::JsonSchema)]
+pub enum StatusAction {
+    Null,
+    Error,
+}
+
+#[derive(
+    Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, schemars::JsonSchema,
+)]
+pub enum ResponseFormat {
+    #[default]
+    Json,
+    Xml,
+    Csv,
+    JsonSeq,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+/// The options used to parse a CSV response body.
+pub struct Csv {
+    /// The character that separates the fields of a row. @default `,`.
+    pub delimiter: String,
+    /// Whether the first row holds the names of the fields. Otherwise the
+    /// fields are named `column1`, `column2` and so on. @default `true`.
+    pub header: bool,
+}
+
+impl Default for Csv {
+    fn default() -> Self {
+        Csv { delimiter: ",".to_string(), header: true }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, schemars::JsonSchema)]
+/// Maps the status code of an upstream response to the way the field is
+/// resolved.
+pub struct StatusMapping {
+    /// The status code of the upstream response.
+    pub status: u16,
+    /// `Null` resolves the field to `null` and `Error` produces an error.
+    pub action: StatusAction,
+}
+
 #[derive(
     Serialize,
     Deserialize,
@@ -37,6 +81,13 @@ pub struct Http {
     /// This refers to URL of the API.
     pub url: String,
 
+    #[serde(rename = "baseURLs", default, skip_serializing_if = "is_default")]
+    /// The base URLs of the replicas of the API that the calls are spread
+    /// across. Every call replaces the scheme, host and port of `url` with the
+    /// next base URL in turn, skipping the ones whose circuit breaker is open.
+    /// The base URLs can't have a path, e.g. `http://replica-1:8080`.
+    pub base_urls: Vec<String>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// The body of the API call. It's used for methods like POST or PUT that
     /// send data to the server. You can pass it as a static object or use a
@@ -52,14 +103,34 @@ pub struct Http {
 
     #[serde(rename = "batchKey", default, skip_serializing_if = "is_default")]
     /// The `batchKey` dictates the path Tailcall will follow to group the returned items from the batch request. For more details please refer out [n + 1 guide](https://tailcall.run/docs/guides/n+1#solving-using-batching).
+    /// The last segment can be a dotted path, e.g. `meta.userId`, to group the
+    /// items by a key nested within them.
     pub batch_key: Vec<String>,
 
+    #[serde(
+        rename = "onMissingBatchKey",
+        default,
+        skip_serializing_if = "is_default"
+    )]
+    /// Controls how a batched call is resolved when the response of the batch
+    /// has no item for its key: `Null` resolves the field to `null` and
+    /// `Error` produces an error. The calls that have an item are resolved
+    /// from the response in both cases. @default `Null`.
+    pub on_missing_batch_key: Option<StatusAction>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// The `headers` parameter allows you to customize the headers of the HTTP
     /// request made by the `@http` operator. It is used by specifying a
     /// key-value map of header names and their values.
     pub headers: Vec<KeyValue>,
 
+    #[serde(rename = "forwardHeaders", default, skip_serializing_if = "is_default")]
+    /// The names of the incoming request headers that are forwarded to the API
+    /// call, even when they aren't listed in `@upstream(allowedHeaders: ...)`.
+    /// When a forwarded header is also set in `headers`, the incoming value
+    /// takes precedence.
+    pub forward_headers: Vec<String>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// Schema of the input of the API call. It is automatically inferred in
     /// most cases.
@@ -83,13 +154,34 @@ pub struct Http {
     /// first parameter referencing a field in the current value using mustache
     /// syntax is automatically selected as the batching parameter.
     pub query: Vec<URLQuery>,
+
+    #[serde(rename = "responseFormat", default, skip_serializing_if = "is_default")]
+    /// The format of the upstream response body. It can be `Json`, `Xml`,
+    /// `Csv` or `JsonSeq`. `Xml` bodies are converted to JSON values: the root
+    /// element becomes the value, attributes and child elements become fields
+    /// and repeated elements become lists. Their text is converted to the type
+    /// of the field, e.g. `<zip>01234</zip>` stays a string on a `String`
+    /// field, and the elements of a list field become a list even when they
+    /// appear once. `Csv` bodies become a list of objects, one per row.
+    /// `JsonSeq` bodies are JSON text sequences (`application/json-seq`) that
+    /// become a list of their values, which are sent one by one as they arrive
+    /// when the field is requested with `@stream`. @default `Json`.
+    pub response_format: ResponseFormat,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The delimiter and header options used when the `responseFormat` is
+    /// `Csv`.
+    pub csv: Option<Csv>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// Enables deduplication of IO operations to enhance performance.
     ///
     /// This flag prevents duplicate IO requests from being executed
     /// concurrently, reducing resource load. Caution: May lead to issues
     /// with APIs that expect unique results for identical inputs, such as
-    /// nonce-based APIs.
+    /// nonce-based APIs. Set to `false` to always execute the calls of the
+    /// field, even when the upstream coalesces or batches identical requests.
+    /// It can't be used with `batchKey`.
     pub dedupe: Option<bool>,
 
     /// You can use `select` with mustache syntax to re-construct the directives
@@ -101,6 +193,8 @@ pub struct Http {
     /// * EXAMPLE 2: if we have a call that returns `{ "foo": "bar", "fizz": {
     ///   "buzz": "eggs", ... }, ... }` we can use { foo: "{{.foo}}", buzz:
     ///   "{{.fizz.buzz}}" }`
+    /// * EXAMPLE 3: a list of keys like `["data", "items"]` extracts the value
+    ///   at that path, or `null` when the path is missing.
     pub select: Option<Value>,
 
     /// Specifies a JavaScript function to be executed after receiving the
@@ -108,4 +202,17 @@ pub struct Http {
     /// body before it's sent back to the client.
     #[serde(rename = "onResponseBody", default, skip_serializing_if = "is_default")]
     pub on_response_body: Option<String>,
+
+    #[serde(rename = "onStatus", default, skip_serializing_if = "is_default")]
+    /// Controls how the upstream responses with the listed status codes are
+    /// resolved, e.g. `onStatus: [{status: 404, action: Null}]` resolves the
+    /// field to `null` when the upstream responds with `404`. Unmapped status
+    /// codes keep the default behavior.
+    pub on_status: Vec<StatusMapping>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// Mustache template evaluated before the API call is made. When it
+    /// renders to a falsy value (empty, `false`, `0` or `null`) the call is
+    /// skipped and the field resolves to `null`.
+    pub when: Option<String>,
 }
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use tailcall_macros::{DirectiveDefinition, MergeRight};
+
+use crate::core::is_default;
+
+/// What to do when the objects merged by `@join` have a field in common with
+/// different values.
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    schemars::JsonSchema,
+    MergeRight,
+)]
+pub enum JoinConflict {
+    LeftWins,
+    #[default]
+    RightWins,
+    Error,
+}
+
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    schemars::JsonSchema,
+    DirectiveDefinition,
+    MergeRight,
+)]
+#[directive_definition(locations = "FieldDefinition")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+/// The `@join` directive resolves a field with all of its resolver directives
+/// in parallel and merges the objects they return shallowly, e.g. a profile
+/// from one upstream and preferences from another, in the order the resolvers
+/// are declared.
+pub struct Join {
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// Controls which value is kept when the objects have a field in common:
+    /// `LeftWins` keeps the first one, `RightWins` the last one and `Error`
+    /// fails the field. @default `RightWins`.
+    pub on_conflict: JoinConflict,
+}
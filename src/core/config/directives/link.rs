@@ -79,6 +79,8 @@ pub struct Link {
     ///
     /// The source of the link. It can be a URL or a path to a file.
     /// If a path is provided, it is relative to the file that imports the link.
+    /// A `Protobuf` link can also point to a directory to load all the proto
+    /// files in it.
     #[serde(default, skip_serializing_if = "is_default")]
     pub src: String,
     ///
@@ -94,7 +96,9 @@ pub struct Link {
     #[serde(default, skip_serializing_if = "is_default")]
     pub meta: Option<serde_json::Value>,
     ///
-    /// The proto paths to be used when resolving dependencies.
+    /// The proto paths to be used when resolving dependencies, like the `-I`
+    /// option of `protoc`. Imports are looked up in these paths first and then
+    /// relative to the proto file.
     /// Only valid when [`Link::type_of`] is [`LinkType::Protobuf`]
     #[serde(default, skip_serializing_if = "is_default")]
     pub proto_paths: Option<Vec<String>>,
@@ -2,36 +2,51 @@ mod add_field;
 mod alias;
 mod cache;
 mod call;
+mod constraint;
+mod default;
 mod discriminate;
 mod expr;
 mod federation;
 mod graphql;
 mod grpc;
 mod http;
+mod join;
 mod js;
 mod link;
 mod modify;
 mod omit;
+mod prefetch;
 mod protected;
+mod regex;
 mod server;
 mod telemetry;
+mod unmapped;
 mod upstream;
+mod upstream_value;
 
 pub use add_field::*;
 pub use alias::*;
 pub use cache::*;
 pub use call::*;
+pub use constraint::*;
+// re-exported under an alias to avoid shadowing the `Default` trait
+pub use default::Default as ContextDefault;
 pub use discriminate::*;
 pub use expr::*;
 pub use federation::*;
 pub use graphql::*;
 pub use grpc::*;
 pub use http::*;
+pub use join::*;
 pub use js::*;
 pub use link::*;
 pub use modify::*;
 pub use omit::*;
+pub use prefetch::*;
 pub use protected::*;
+pub use regex::*;
 pub use server::*;
 pub use telemetry::*;
+pub use unmapped::*;
 pub use upstream::*;
+pub use upstream_value::*;
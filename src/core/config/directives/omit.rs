@@ -12,7 +12,7 @@ use tailcall_macros::{DirectiveDefinition, MergeRight};
     DirectiveDefinition,
     MergeRight,
 )]
-#[directive_definition(locations = "FieldDefinition")]
+#[directive_definition(locations = "FieldDefinition, ArgumentDefinition")]
 #[serde(deny_unknown_fields)]
-/// Used to omit a field from public consumption.
+/// Used to omit a field or an argument from public consumption.
 pub struct Omit {}
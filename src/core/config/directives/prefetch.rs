@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use tailcall_macros::{DirectiveDefinition, MergeRight};
+
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    schemars::JsonSchema,
+    DirectiveDefinition,
+    MergeRight,
+)]
+#[directive_definition(locations = "FieldDefinition")]
+#[serde(deny_unknown_fields)]
+/// The `@prefetch` directive starts the upstream call of a field along with
+/// the call of its parent instead of after it. It applies to the fields whose
+/// resolver doesn't read the value of the parent, e.g. the ones that take
+/// their inputs from the arguments or the headers. It's ignored in
+/// mutations, whose fields are resolved after the mutation is done.
+pub struct Prefetch {}
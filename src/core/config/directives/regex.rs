@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use tailcall_macros::{DirectiveDefinition, MergeRight};
+
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    schemars::JsonSchema,
+    DirectiveDefinition,
+    MergeRight,
+)]
+#[directive_definition(locations = "Scalar")]
+#[serde(deny_unknown_fields)]
+/// The `@regex` directive declares a custom scalar whose values are strings
+/// matching the given pattern, for example
+/// `scalar Sku @regex(pattern: "^SKU-[0-9]+$")`.
+pub struct Regex {
+    /// Regular expression that the values of the scalar must match.
+    pub pattern: String,
+}
@@ -1,4 +1,5 @@
 use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
 
 use derive_getters::Getters;
 use schemars::JsonSchema;
@@ -7,8 +8,14 @@ use tailcall_macros::DirectiveDefinition;
 
 use crate::core::config::headers::Headers;
 use crate::core::config::{merge_key_value_vecs, KeyValue};
-use crate::core::is_default;
 use crate::core::macros::MergeRight;
+use crate::core::{is_default, EnvIO};
+
+/// Environment variable that overrides `@server(port: ...)`.
+pub const PORT_ENV: &str = "TAILCALL_PORT";
+
+/// Environment variable that overrides `@server(hostname: ...)`.
+pub const HOSTNAME_ENV: &str = "TAILCALL_HOST";
 
 #[derive(
     Serialize,
@@ -34,6 +41,12 @@ pub struct Server {
     #[schemars(skip)]
     pub enable_jit: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `accessLog` logs a line per request in the Apache Combined Log Format
+    /// with the `tailcall::access_log` tracing target, so it can be filtered
+    /// separately from the other logs. @default `false`.
+    pub access_log: Option<bool>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// `apolloTracing` exposes GraphQL query performance data, including
     /// execution time of queries and individual resolvers.
@@ -45,6 +58,18 @@ pub struct Server {
     /// debugging. Use judiciously. @default `false`.
     pub batch_requests: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `compression` compresses the GraphQL responses with gzip for the
+    /// clients that accept it through the `Accept-Encoding` header. @default
+    /// disabled.
+    pub compression: Option<Compression>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `headerReadTimeout` sets the time in milliseconds a client has to send
+    /// the headers of a request over HTTP/1, after which the connection is
+    /// closed. @default no limit.
+    pub header_read_timeout: Option<u64>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// `headers` contains key-value pairs that are included as default headers
     /// in server responses, allowing for consistent header management across
@@ -57,7 +82,8 @@ pub struct Server {
     pub global_response_timeout: Option<i64>,
 
     #[serde(default, skip_serializing_if = "is_default")]
-    /// `hostname` sets the server hostname.
+    /// `hostname` sets the server hostname. The `TAILCALL_HOST` environment
+    /// variable takes precedence over it.
     pub hostname: Option<String>,
 
     #[serde(default, skip_serializing_if = "is_default")]
@@ -71,26 +97,108 @@ pub struct Server {
     #[serde(default, skip_serializing_if = "is_default")]
     pub enable_federation: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `keepAliveTimeout` sets the time in milliseconds an HTTP/1 connection
+    /// is kept open while no request is served, and the interval at which idle
+    /// HTTP/2 connections are pinged along with the time they have to
+    /// acknowledge the ping before they're closed. `0` disables keep-alive, so
+    /// HTTP/1 connections are closed after every response. @default keep-alive
+    /// without a limit.
+    pub keep_alive_timeout: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `lazyResolvers` skips the upstream calls of the non-null object fields
+    /// of a query when none of the selected fields read their response, e.g.
+    /// when only the fields resolved from their own upstream are selected.
+    /// The calls that are conditional or whose failures are handled with
+    /// `onStatus` or hooks are always made. The failures of the skipped calls
+    /// aren't reported. @default `true`.
+    pub lazy_resolvers: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `maxSelectionsPerLevel` limits the number of fields, aliases included,
+    /// that any single selection set of an operation can request. Operations
+    /// over the limit are rejected before execution. @default no limit.
+    pub max_selections_per_level: Option<usize>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `mutationTimeout` sets the maximum duration of a mutation in
+    /// milliseconds, after which its execution is cut off with an error.
+    /// @default no limit.
+    pub mutation_timeout: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `notFound` customizes the response to the requests that don't match
+    /// any route, e.g. with a friendlier body or a redirect. @default an empty
+    /// `404`.
+    pub not_found: Option<NotFound>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `nullPropagation` decides what happens to the response when a field
+    /// fails to resolve. With `Strict` the error nulls out the whole data,
+    /// while with `Isolate` only the failed field is set to null, even when
+    /// it's non-null, and the data of its siblings is kept. @default
+    /// `Strict`.
+    pub null_propagation: Option<NullPropagation>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// `pipelineFlush` allows to control flushing behavior of the server
     /// pipeline.
     pub pipeline_flush: Option<bool>,
 
     #[serde(default, skip_serializing_if = "is_default")]
-    /// `port` sets the Tailcall running port. @default `8000`.
+    /// `port` sets the Tailcall running port. The `TAILCALL_PORT` environment
+    /// variable takes precedence over it. @default `8000`.
     pub port: Option<u16>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `proxyProtocol` reads the address of the client from the PROXY protocol
+    /// header, version 1 or 2, that L4 load balancers such as AWS NLB send at
+    /// the start of every connection. Connections without a valid header are
+    /// rejected. It's supported over HTTP/1 without TLS. @default `false`.
+    pub proxy_protocol: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `queryTimeout` sets the maximum duration of a query in milliseconds,
+    /// after which its execution is cut off with an error. @default no limit.
+    pub query_timeout: Option<u64>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// `queryValidation` checks incoming GraphQL queries against the schema,
     /// preventing errors from invalid queries. Can be disabled for performance.
     /// @default `false`.
     pub query_validation: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `responseFormat` sets how the JSON of the GraphQL responses is
+    /// serialized: `Compact`, `Pretty` to indent it for reading it e.g. with
+    /// curl, or `Ascii` to escape the non-ASCII characters of the strings.
+    /// @default `Compact`.
+    pub response_format: Option<JsonFormat>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `responseStreaming` sends every root field of the GraphQL responses as
+    /// soon as it and the fields before it are resolved instead of buffering
+    /// the response in full, which cuts the latency of large responses. The
+    /// errors follow the data, and nothing is sent until the last non-nullable
+    /// root field is resolved, so that its failure still nulls the whole data.
+    /// Such responses are always compact, uncompressed, without the
+    /// `Cache-Control` header and neither deduplicated nor cached.
+    /// @default `false`.
+    pub response_streaming: Option<bool>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// `responseValidation` Tailcall automatically validates responses from
     /// upstream services using inferred schema. @default `false`.
     pub response_validation: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `strictResponseValidation` makes the upstream values that don't match
+    /// the GraphQL type of the field (e.g. an object for a list field) produce
+    /// an error at that path instead of being coerced to null. @default
+    /// `false`.
+    pub strict_response_validation: Option<bool>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// A link to an external JS file that listens on every HTTP request
     /// response event.
@@ -100,6 +208,12 @@ pub struct Server {
     /// `showcase` enables the /showcase/graphql endpoint.
     pub showcase: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `trustedProxies` is the number of proxies in front of the server whose
+    /// `Forwarded` and `X-Forwarded-For` headers are trusted to find the IP of
+    /// the client, available as `{{.request.clientIp}}`. @default `0`.
+    pub trusted_proxies: Option<usize>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     #[merge_right(merge_right_fn = "merge_right_vars")]
     /// This configuration defines local variables for server operations. Useful
@@ -107,7 +221,8 @@ pub struct Server {
     pub vars: Vec<KeyValue>,
 
     #[serde(default, skip_serializing_if = "is_default")]
-    /// `version` sets the HTTP version for the server. Options are `HTTP1` and
+    /// `version` sets the HTTP version for the server. Options are `HTTP1`,
+    /// `HTTPS` (HTTP/1.1 over TLS with the linked `Cert` and `Key`) and
     /// `HTTP2`. @default `HTTP1`.
     pub version: Option<HttpVersion>,
 
@@ -175,10 +290,97 @@ pub struct ScriptOptions {
 pub enum HttpVersion {
     #[default]
     HTTP1,
+    HTTPS,
     HTTP2,
 }
 
+/// Compression of the GraphQL responses.
+#[derive(
+    Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, schemars::JsonSchema, MergeRight,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Compression {
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The size in bytes under which the responses are sent uncompressed.
+    /// @default `1024`.
+    pub min_size: Option<usize>,
+}
+
+/// The response to the requests that don't match any route.
+#[derive(
+    Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, schemars::JsonSchema, MergeRight,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct NotFound {
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The body of the response. @default empty.
+    pub body: Option<String>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The `Content-Type` header of the response.
+    pub content_type: Option<String>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// Redirects the request to this location through the `Location` header.
+    pub redirect: Option<String>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The status code of the response. @default `404`, or `302` with a
+    /// `redirect`.
+    pub status: Option<u16>,
+}
+
+/// Policy for the errors of the fields in the response.
+#[derive(
+    Deserialize,
+    Serialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Default,
+    schemars::JsonSchema,
+    MergeRight,
+)]
+pub enum NullPropagation {
+    /// A failed field makes the data of the whole response null.
+    #[default]
+    Strict,
+    /// A failed field is set to null and its error is reported, the rest of
+    /// the data is kept.
+    Isolate,
+}
+
+/// Serialization of the JSON of the responses.
+#[derive(
+    Deserialize,
+    Serialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Default,
+    schemars::JsonSchema,
+    MergeRight,
+)]
+pub enum JsonFormat {
+    /// The JSON is written without any whitespace.
+    #[default]
+    Compact,
+    /// The JSON is indented with two spaces.
+    Pretty,
+    /// The JSON is written without any whitespace and the non-ASCII characters
+    /// of the strings are escaped as `\uXXXX`.
+    Ascii,
+}
+
 impl Server {
+    pub fn enable_access_log(&self) -> bool {
+        self.access_log.unwrap_or(false)
+    }
+
     pub fn enable_apollo_tracing(&self) -> bool {
         self.apollo_tracing.unwrap_or(false)
     }
@@ -194,9 +396,29 @@ impl Server {
     pub fn get_port(&self) -> u16 {
         self.port.unwrap_or(8000)
     }
+    pub fn enable_response_streaming(&self) -> bool {
+        self.response_streaming.unwrap_or(false)
+    }
     pub fn enable_http_validation(&self) -> bool {
         self.response_validation.unwrap_or(false)
     }
+    pub fn enable_strict_response_validation(&self) -> bool {
+        self.strict_response_validation.unwrap_or(false)
+    }
+    pub fn get_compression_min_size(&self) -> Option<usize> {
+        self.compression
+            .as_ref()
+            .map(|compression| compression.min_size.unwrap_or(1024))
+    }
+    pub fn get_not_found(&self) -> Option<NotFound> {
+        self.not_found.clone()
+    }
+    pub fn get_null_propagation(&self) -> NullPropagation {
+        self.null_propagation.unwrap_or_default()
+    }
+    pub fn get_response_format(&self) -> JsonFormat {
+        self.response_format.unwrap_or_default()
+    }
     pub fn enable_cache_control(&self) -> bool {
         self.headers
             .as_ref()
@@ -209,6 +431,12 @@ impl Server {
             .map(|h| h.set_cookies())
             .unwrap_or(false)
     }
+    pub fn enable_request_id(&self) -> bool {
+        self.headers
+            .as_ref()
+            .map(|h| h.enable_request_id())
+            .unwrap_or(false)
+    }
     pub fn enable_introspection(&self) -> bool {
         self.introspection.unwrap_or(true)
     }
@@ -218,14 +446,66 @@ impl Server {
     pub fn enable_batch_requests(&self) -> bool {
         self.batch_requests.unwrap_or(false)
     }
+    pub fn enable_lazy_resolvers(&self) -> bool {
+        self.lazy_resolvers.unwrap_or(true)
+    }
     pub fn enable_showcase(&self) -> bool {
         self.showcase.unwrap_or(false)
     }
+    pub fn enable_proxy_protocol(&self) -> bool {
+        self.proxy_protocol.unwrap_or(false)
+    }
+
+    pub fn get_max_selections_per_level(&self) -> Option<usize> {
+        self.max_selections_per_level
+    }
+
+    pub fn get_query_timeout(&self) -> Option<Duration> {
+        self.query_timeout.map(Duration::from_millis)
+    }
+
+    pub fn get_mutation_timeout(&self) -> Option<Duration> {
+        self.mutation_timeout.map(Duration::from_millis)
+    }
+
+    pub fn get_keep_alive_timeout(&self) -> Option<Duration> {
+        self.keep_alive_timeout.map(Duration::from_millis)
+    }
+
+    pub fn get_header_read_timeout(&self) -> Option<Duration> {
+        self.header_read_timeout.map(Duration::from_millis)
+    }
+
+    pub fn get_trusted_proxies(&self) -> usize {
+        self.trusted_proxies.unwrap_or(0)
+    }
 
     pub fn get_hostname(&self) -> String {
         self.hostname.clone().unwrap_or("127.0.0.1".to_string())
     }
 
+    /// Overrides the port and the hostname with the `TAILCALL_PORT` and
+    /// `TAILCALL_HOST` environment variables when they are set, so the
+    /// precedence is env > config > default.
+    pub fn with_env_overrides(mut self, env: &dyn EnvIO) -> anyhow::Result<Self> {
+        if let Some(port) = env.get(PORT_ENV) {
+            let port = port.parse::<u16>().map_err(|_| {
+                anyhow::anyhow!(
+                    "Invalid port in the {} environment variable: {}",
+                    PORT_ENV,
+                    port
+                )
+            })?;
+            self.port = Some(port);
+        }
+
+        if let Some(hostname) = env.get(HOSTNAME_ENV) {
+            self.hostname = Some(hostname.to_string());
+        }
+
+        Ok(self)
+    }
+
     pub fn get_vars(&self) -> BTreeMap<String, String> {
         self.vars
             .clone()
@@ -275,6 +555,7 @@ mod tests {
     use super::*;
     use crate::core::config::ScriptOptions;
     use crate::core::merge_right::MergeRight;
+    use crate::core::tests::TestEnvIO;
 
     fn server_with_script_options(so: ScriptOptions) -> Server {
         Server { script: Some(so), ..Default::default() }
@@ -379,4 +660,29 @@ mod tests {
 
         assert_eq!(merge_vec, expected_vec)
     }
+
+    #[test]
+    fn test_env_overrides() {
+        let server = Server {
+            port: Some(8080),
+            hostname: Some("0.0.0.0".to_string()),
+            ..Default::default()
+        };
+
+        let env = TestEnvIO::from_iter([(PORT_ENV.to_string(), "9000".to_string())]);
+        let actual = server.clone().with_env_overrides(&env).unwrap();
+        assert_eq!(actual.get_port(), 9000);
+        assert_eq!(actual.get_hostname(), "0.0.0.0");
+
+        let env = TestEnvIO::from_iter([(HOSTNAME_ENV.to_string(), "127.0.0.1".to_string())]);
+        let actual = Server::default().with_env_overrides(&env).unwrap();
+        assert_eq!(actual.get_port(), 8000);
+        assert_eq!(actual.get_hostname(), "127.0.0.1");
+
+        let actual = server.clone().with_env_overrides(&TestEnvIO::default());
+        assert_eq!(actual.unwrap(), server);
+
+        let env = TestEnvIO::from_iter([(PORT_ENV.to_string(), "http".to_string())]);
+        assert!(Server::default().with_env_overrides(&env).is_err());
+    }
 }
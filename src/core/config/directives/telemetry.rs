@@ -34,6 +34,31 @@ pub struct OtlpExporter {
     pub url: String,
     #[serde(default, skip_serializing_if = "is_default")]
     pub headers: Vec<KeyValue>,
+    /// Tuning of the batch processor that buffers the spans before they are
+    /// exported to the collector
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub batch: Option<OtlpBatch>,
+}
+
+/// Settings of the batch span processor used by the otlp exporter. The
+/// opentelemetry defaults are used for the options that are not set
+#[derive(
+    Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema, MergeRight,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct OtlpBatch {
+    /// The maximum number of spans buffered before new spans are dropped
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub max_queue_size: Option<usize>,
+    /// The delay in milliseconds between two consecutive exports
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub scheduled_delay: Option<u64>,
+    /// The maximum number of spans sent to the collector in a single export
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub max_export_batch_size: Option<usize>,
+    /// The time in milliseconds after which an export is cancelled
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub max_export_timeout: Option<u64>,
 }
 
 /// Output format for prometheus data
@@ -145,6 +170,7 @@ mod tests {
             export: Some(TelemetryExporter::Otlp(OtlpExporter {
                 url: "test-url".to_owned(),
                 headers: vec![KeyValue { key: "header_a".to_owned(), value: "a".to_owned() }],
+                batch: None,
             })),
             request_headers: vec!["Api-Key-A".to_owned()],
         };
@@ -152,6 +178,7 @@ mod tests {
             export: Some(TelemetryExporter::Otlp(OtlpExporter {
                 url: "test-url-2".to_owned(),
                 headers: vec![KeyValue { key: "header_b".to_owned(), value: "b".to_owned() }],
+                batch: Some(OtlpBatch { scheduled_delay: Some(500), ..Default::default() }),
             })),
             request_headers: vec!["Api-Key-B".to_owned()],
         };
@@ -200,7 +227,8 @@ mod tests {
             Telemetry {
                 export: Some(TelemetryExporter::Otlp(OtlpExporter {
                     url: "test-url-2".to_owned(),
-                    headers: vec![KeyValue { key: "header_b".to_owned(), value: "b".to_owned() }]
+                    headers: vec![KeyValue { key: "header_b".to_owned(), value: "b".to_owned() }],
+                    batch: Some(OtlpBatch { scheduled_delay: Some(500), ..Default::default() }),
                 })),
                 request_headers: vec!["Api-Key-A".to_string(), "Api-Key-B".to_string(),]
             }
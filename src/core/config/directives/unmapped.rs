@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use tailcall_macros::{DirectiveDefinition, MergeRight};
+
+/// What to do with a value from the upstream that doesn't stand for any value
+/// of the enum: `Error` fails the field and `PassThrough` returns the value as
+/// it is.
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    schemars::JsonSchema,
+    MergeRight,
+)]
+pub enum UnmappedAction {
+    #[default]
+    Error,
+    PassThrough,
+}
+
+/// The @unmapped directive sets how the values returned by the upstream that
+/// don't stand for any value of the enum are handled, e.g. the name of a value
+/// that has an `@upstreamValue`. By default they fail the field.
+#[derive(
+    Default,
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    schemars::JsonSchema,
+    MergeRight,
+    DirectiveDefinition,
+)]
+#[directive_definition(locations = "Enum")]
+pub struct Unmapped {
+    pub action: UnmappedAction,
+}
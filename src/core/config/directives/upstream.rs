@@ -29,6 +29,63 @@ impl Default for Batch {
     }
 }
 
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, schemars::JsonSchema, MergeRight)]
+#[serde(rename_all = "camelCase")]
+pub enum RedirectPolicy {
+    /// Redirects aren't followed and the 3xx response is the final one.
+    None,
+    /// Follows up to the given number of redirects.
+    Limited(usize),
+    /// Follows all the redirects, failing only on a redirect loop.
+    All,
+}
+
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        RedirectPolicy::Limited(10)
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Setters, schemars::JsonSchema, MergeRight,
+)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CircuitBreaker {
+    /// The number of consecutive failures after which the breaker opens.
+    pub failure_threshold: usize,
+    /// The time in milliseconds the breaker stays open before a single probe
+    /// request is let through.
+    pub open_duration: u64,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        CircuitBreaker { failure_threshold: 5, open_duration: 30_000 }
+    }
+}
+
+#[derive(
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    Debug,
+    Default,
+    Setters,
+    schemars::JsonSchema,
+    MergeRight,
+)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RequestLogging {
+    /// The names of the headers and JSON body fields whose values are masked
+    /// in the logs. Matched case-insensitively, `*` matches any characters
+    /// e.g. `*token*`.
+    pub redact: BTreeSet<String>,
+    /// Includes the request and response bodies in the logs.
+    pub body: bool,
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, schemars::JsonSchema, MergeRight)]
 pub struct Proxy {
     pub url: String,
@@ -73,11 +130,35 @@ pub struct Upstream {
     /// the batch).
     pub batch: Option<Batch>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The `circuitBreaker` setting makes the calls to an upstream fail fast
+    /// after repeated failures instead of piling up timeouts. The breaker is
+    /// kept per upstream origin.
+    pub circuit_breaker: Option<CircuitBreaker>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// When set to `true`, identical `GET` requests made by different fields
+    /// of the same query share a single call to the upstream. @default
+    /// `false`.
+    pub coalesce: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The maximum number of resolver calls that are executed concurrently.
+    /// Protects fragile upstreams from the fan-out of large lists. Unlimited
+    /// if unspecified.
+    pub concurrency: Option<usize>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// The time in seconds that the connection will wait for a response before
     /// timing out.
     pub connect_timeout: Option<u64>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The maximum number of bytes of an upstream error response body that is
+    /// exposed in the `upstreamBody` extension of the resolver error. Set it
+    /// to `0` to redact the body. @default `256`.
+    pub error_body_size: Option<usize>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// Providing httpCache size enables Tailcall's HTTP caching, adhering to the [HTTP Caching RFC](https://tools.ietf.org/html/rfc7234), to enhance performance by minimizing redundant data fetches. Defaults to `0` if unspecified.
     pub http_cache: Option<u64>,
@@ -105,6 +186,13 @@ pub struct Upstream {
     /// sent while the connection is idle.
     pub keep_alive_while_idle: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The `logging` setting enables the logging of the upstream requests and
+    /// responses (method, URL, status and timing) at the debug level, for both
+    /// `@http` and `@grpc`. Sensitive headers and body fields are masked with
+    /// the `redact` patterns.
+    pub logging: Option<RequestLogging>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// The maximum number of idle connections that will be maintained per host.
     pub pool_max_idle_per_host: Option<usize>,
@@ -121,6 +209,13 @@ pub struct Upstream {
     /// enabling custom routing and security policies.
     pub proxy: Option<Proxy>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The `redirect` setting controls which redirects returned by the
+    /// upstream are followed. Use `none` for the upstreams that redirect to
+    /// pages that must not be followed, e.g. to an auth page. @default
+    /// `{limited: 10}`.
+    pub redirect: Option<RedirectPolicy>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// The time in seconds between each TCP keep-alive message sent to maintain
     /// the connection.
@@ -168,9 +263,15 @@ impl Upstream {
     pub fn get_connect_timeout(&self) -> u64 {
         self.connect_timeout.unwrap_or(60)
     }
+    pub fn get_concurrency(&self) -> Option<usize> {
+        self.concurrency
+    }
     pub fn get_timeout(&self) -> u64 {
         self.timeout.unwrap_or(60)
     }
+    pub fn get_redirect(&self) -> RedirectPolicy {
+        self.redirect.clone().unwrap_or_default()
+    }
     pub fn get_tcp_keep_alive(&self) -> u64 {
         self.tcp_keep_alive.unwrap_or(5)
     }
@@ -179,6 +280,9 @@ impl Upstream {
             .clone()
             .unwrap_or("Tailcall/1.0".to_string())
     }
+    pub fn get_error_body_size(&self) -> usize {
+        self.error_body_size.unwrap_or(256)
+    }
     pub fn get_http_cache_size(&self) -> u64 {
         self.http_cache.unwrap_or(0)
     }
@@ -198,6 +302,10 @@ impl Upstream {
         self.http2_only.unwrap_or(false)
     }
 
+    pub fn get_coalesce(&self) -> bool {
+        self.coalesce.unwrap_or(false)
+    }
+
     pub fn get_on_request(&self) -> Option<String> {
         self.on_request.clone()
     }
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use tailcall_macros::{DirectiveDefinition, MergeRight};
+
+/// The @upstreamValue directive sets the value that stands for the enum value
+/// in the upstream, e.g. `ACTIVE @upstreamValue(name: "status_active")`. The
+/// enum value is sent as this value in the arguments and the upstream value is
+/// read back as the enum value in the responses.
+#[derive(
+    Default,
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    schemars::JsonSchema,
+    MergeRight,
+    DirectiveDefinition,
+)]
+#[directive_definition(locations = "EnumValue")]
+pub struct UpstreamValue {
+    pub name: String,
+}
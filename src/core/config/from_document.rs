@@ -12,12 +12,16 @@ use indexmap::IndexMap;
 use tailcall_valid::{Valid, ValidationError, Validator};
 
 use super::directive::{to_directive, Directive};
-use super::{Alias, Discriminate, Resolver, RuntimeConfig, Telemetry, FEDERATION_DIRECTIVES};
+use super::{
+    Alias, Constraint, ContextDefault, Discriminate, Join, Prefetch, Regex, Resolver,
+    RuntimeConfig, Telemetry, FEDERATION_DIRECTIVES,
+};
 use crate::core::config::{
-    self, Cache, Config, Enum, Link, Modify, Omit, Protected, RootSchema, Server, Union, Upstream,
-    Variant,
+    self, Cache, Config, Enum, Link, Modify, Omit, Protected, RootSchema, Server, Union, Unmapped,
+    Upstream, UpstreamValue, Variant,
 };
 use crate::core::directive::DirectiveCodec;
+use crate::core::merge_right::MergeRight;
 
 const DEFAULT_SCHEMA_DEFINITION: &SchemaDefinition = &SchemaDefinition {
     extend: false,
@@ -40,16 +44,15 @@ pub fn from_document(doc: ServiceDocument) -> Valid<Config, String> {
     let types = to_types(&type_definitions);
     let unions = to_union_types(&type_definitions);
     let enums = to_enum_types(&type_definitions);
-    let schema = schema_definition(&doc).map(to_root_schema);
     schema_definition(&doc).and_then(|sd| {
-        server(sd)
-            .fuse(upstream(sd))
+        server(&sd)
+            .fuse(upstream(&sd))
             .fuse(types)
             .fuse(unions)
             .fuse(enums)
-            .fuse(schema)
-            .fuse(links(sd))
-            .fuse(telemetry(sd))
+            .fuse(Valid::succeed(to_root_schema(&sd)))
+            .fuse(links(&sd))
+            .fuse(telemetry(&sd))
             .map(
                 |(server, upstream, types, unions, enums, schema, links, telemetry)| {
                     let runtime_config = RuntimeConfig { server, upstream, links, telemetry };
@@ -61,14 +64,58 @@ pub fn from_document(doc: ServiceDocument) -> Valid<Config, String> {
     })
 }
 
-fn schema_definition(doc: &ServiceDocument) -> Valid<&SchemaDefinition, String> {
-    doc.definitions
+/// Combines the schema definition with all the `extend schema` definitions of
+/// the document. Extensions append their directives and can only define the
+/// operation types that are missing in the schema.
+fn schema_definition(doc: &ServiceDocument) -> Valid<SchemaDefinition, String> {
+    let mut definitions: Vec<_> = doc
+        .definitions
         .iter()
-        .find_map(|def| match def {
+        .filter_map(|def| match def {
             TypeSystemDefinition::Schema(schema_definition) => Some(&schema_definition.node),
             _ => None,
         })
-        .map_or_else(|| Valid::succeed(DEFAULT_SCHEMA_DEFINITION), Valid::succeed)
+        .collect();
+
+    // the extensions are applied on top of the base definition
+    definitions.sort_by_key(|definition| definition.extend);
+
+    let mut definitions = definitions.into_iter();
+    let Some(base) = definitions.next() else {
+        return Valid::succeed(DEFAULT_SCHEMA_DEFINITION.clone());
+    };
+
+    definitions.fold(Valid::succeed(base.clone()), |schema, extension| {
+        schema.and_then(|mut schema| {
+            let operations = [
+                ("query", &mut schema.query, &extension.query),
+                ("mutation", &mut schema.mutation, &extension.mutation),
+                (
+                    "subscription",
+                    &mut schema.subscription,
+                    &extension.subscription,
+                ),
+            ];
+
+            Valid::from_iter(operations, |(name, operation, extension)| {
+                match (operation.as_ref(), extension) {
+                    (Some(_), Some(_)) => Valid::fail(format!(
+                        "Operation type `{name}` is already defined in the schema"
+                    )),
+                    (None, Some(extension)) => {
+                        *operation = Some(extension.clone());
+                        Valid::succeed(())
+                    }
+                    _ => Valid::succeed(()),
+                }
+            })
+            .map(|_| {
+                schema.extend = false;
+                schema.directives.extend(extension.directives.clone());
+                schema
+            })
+        })
+    })
 }
 
 fn process_schema_directives<T: DirectiveCodec + Default>(
@@ -167,19 +214,62 @@ fn to_types(
             .trace(&type_name)
             .some(),
             TypeKind::Union(_) => Valid::none(),
-            TypeKind::Scalar => Valid::succeed(Some(to_scalar_type())),
+            TypeKind::Scalar => to_scalar_type(&type_definition.node.directives)
+                .trace(&type_name)
+                .some(),
         }
-        .map(|option| (type_name, option))
+        .map(|option| (type_name, type_definition.node.extend, option))
     })
-    .map(|vec| {
-        BTreeMap::from_iter(
-            vec.into_iter()
-                .filter_map(|(name, option)| option.map(|tpe| (name, tpe))),
-        )
+    .and_then(|mut vec| {
+        // the extensions are merged into the base type definitions
+        vec.sort_by_key(|(_, extend, _)| *extend);
+
+        vec.into_iter()
+            .filter_map(|(name, extend, option)| option.map(|tpe| (name, extend, tpe)))
+            .fold(
+                Valid::succeed(BTreeMap::new()),
+                |types, (name, extend, tpe)| {
+                    types.and_then(|mut types| match types.remove(&name) {
+                        Some(base) if extend => extend_type(base, tpe).trace(&name).map(|tpe| {
+                            types.insert(name, tpe);
+                            types
+                        }),
+                        _ => {
+                            types.insert(name, tpe);
+                            Valid::succeed(types)
+                        }
+                    })
+                },
+            )
     })
 }
-fn to_scalar_type() -> config::Type {
-    config::Type { ..Default::default() }
+
+fn extend_type(mut base: config::Type, extension: config::Type) -> Valid<config::Type, String> {
+    Valid::from_iter(extension.fields, |(name, field)| {
+        if base.fields.contains_key(&name) {
+            Valid::fail("Field is already defined in the extended type".to_string()).trace(&name)
+        } else {
+            Valid::succeed((name, field))
+        }
+    })
+    .map(|fields| {
+        base.fields.extend(fields);
+        config::Type {
+            added_fields: base.added_fields.merge_right(extension.added_fields),
+            doc: base.doc.or(extension.doc),
+            implements: base.implements.merge_right(extension.implements),
+            cache: base.cache.merge_right(extension.cache),
+            protected: base.protected.merge_right(extension.protected),
+            resolvers: base.resolvers.merge_right(extension.resolvers),
+            directives: base.directives.merge_right(extension.directives),
+            regex: base.regex.merge_right(extension.regex),
+            ..base
+        }
+    })
+}
+fn to_scalar_type(directives: &[Positioned<ConstDirective>]) -> Valid<config::Type, String> {
+    Regex::from_directives(directives.iter())
+        .map(|regex| config::Type { regex, ..Default::default() })
 }
 fn to_union_types(
     type_definitions: &[&Positioned<TypeDefinition>],
@@ -215,6 +305,7 @@ fn to_enum_types(
                     .description
                     .to_owned()
                     .map(|pos| pos.node),
+                &type_definition.node.directives,
             ),
             _ => return Valid::succeed(None),
         };
@@ -253,6 +344,7 @@ where
                     protected,
                     resolvers,
                     directives: unknown_directives,
+                    regex: None,
                 }
             },
         )
@@ -295,7 +387,9 @@ fn to_input_object_fields(
     to_fields_inner(input_object_fields, to_input_object_field)
 }
 fn to_field(field_definition: &FieldDefinition) -> Valid<config::Field, String> {
-    to_common_field(field_definition, to_args(field_definition), None)
+    to_args(field_definition)
+        .trace(pos_name_to_string(&field_definition.name).as_str())
+        .and_then(|args| to_common_field(field_definition, args, None))
 }
 fn to_input_object_field(field_definition: &InputValueDefinition) -> Valid<config::Field, String> {
     to_common_field(
@@ -330,7 +424,11 @@ where
         .fuse(Omit::from_directives(directives.iter()))
         .fuse(Modify::from_directives(directives.iter()))
         .fuse(Protected::from_directives(directives.iter()))
-        .fuse(Discriminate::from_directives(directives.iter()))
+        .fuse(
+            Discriminate::from_directives(directives.iter())
+                .zip(Join::from_directives(directives.iter()))
+                .zip(Prefetch::from_directives(directives.iter())),
+        )
         .fuse(default_value)
         .fuse(to_federation_directives(directives))
         .map(
@@ -340,7 +438,7 @@ where
                 omit,
                 modify,
                 protected,
-                discriminate,
+                ((discriminate, join), prefetch),
                 default_value,
                 directives,
             )| config::Field {
@@ -352,6 +450,8 @@ where
                 cache,
                 protected,
                 discriminate,
+                join,
+                prefetch,
                 default_value,
                 resolvers,
                 directives,
@@ -360,18 +460,16 @@ where
         .trace(pos_name_to_string(field.name()).as_str())
 }
 
-fn to_args(field_definition: &FieldDefinition) -> IndexMap<String, config::Arg> {
-    let mut args = IndexMap::new();
-
-    for arg in field_definition.arguments.iter() {
+fn to_args(field_definition: &FieldDefinition) -> Valid<IndexMap<String, config::Arg>, String> {
+    Valid::from_iter(field_definition.arguments.iter(), |arg| {
         let arg_name = pos_name_to_string(&arg.node.name);
-        let arg_val = to_arg(&arg.node);
-        args.insert(arg_name, arg_val);
-    }
-
-    args
+        to_arg(&arg.node)
+            .map(|arg| (arg_name.clone(), arg))
+            .trace(arg_name.as_str())
+    })
+    .map(IndexMap::from_iter)
 }
-fn to_arg(input_value_definition: &InputValueDefinition) -> config::Arg {
+fn to_arg(input_value_definition: &InputValueDefinition) -> Valid<config::Arg, String> {
     let type_of = &input_value_definition.ty.node;
     let doc = input_value_definition
         .description
@@ -381,13 +479,32 @@ fn to_arg(input_value_definition: &InputValueDefinition) -> config::Arg {
         .to_result()
         .ok()
         .flatten();
+    let omit = Omit::from_directives(input_value_definition.directives.iter())
+        .to_result()
+        .ok()
+        .flatten();
     let default_value = if let Some(pos) = input_value_definition.default_value.as_ref() {
         let value = &pos.node;
         serde_json::to_value(value).ok()
     } else {
         None
     };
-    config::Arg { type_of: type_of.into(), doc, modify, default_value }
+
+    // the directives that validate and default the argument fail the config
+    // when they're malformed, rather than being left out silently
+    Constraint::from_directives(input_value_definition.directives.iter())
+        .zip(ContextDefault::from_directives(
+            input_value_definition.directives.iter(),
+        ))
+        .map(|(constraint, default)| config::Arg {
+            type_of: type_of.into(),
+            doc,
+            modify,
+            omit,
+            default_value,
+            constraint,
+            default,
+        })
 }
 
 fn to_union(union_type: UnionType, doc: &Option<String>) -> Valid<Union, String> {
@@ -400,21 +517,27 @@ fn to_union(union_type: UnionType, doc: &Option<String>) -> Valid<Union, String>
     Valid::succeed(Union { types, doc: doc.clone() })
 }
 
-fn to_enum(enum_type: EnumType, doc: Option<String>) -> Valid<Enum, String> {
+fn to_enum(
+    enum_type: EnumType,
+    doc: Option<String>,
+    directives: &[Positioned<ConstDirective>],
+) -> Valid<Enum, String> {
     let variants = Valid::from_iter(enum_type.values.iter(), |member| {
         let name = member.node.value.node.as_str().to_owned();
-        let alias = member
-            .node
-            .directives
-            .iter()
-            .find(|d| d.node.name.node.as_str() == Alias::directive_name());
-        if let Some(alias) = alias {
-            Alias::from_directive(&alias.node).map(|alias| Variant { name, alias: Some(alias) })
-        } else {
-            Valid::succeed(Variant { name, alias: None })
-        }
+        let directives = &member.node.directives;
+
+        Alias::from_directives(directives.iter())
+            .fuse(UpstreamValue::from_directives(directives.iter()))
+            .map(|(alias, upstream_value)| Variant { name, alias, upstream_value })
     });
-    variants.map(|v| Enum { variants: v.into_iter().collect::<BTreeSet<Variant>>(), doc })
+
+    variants
+        .fuse(Unmapped::from_directives(directives.iter()))
+        .map(|(variants, unmapped)| Enum {
+            variants: variants.into_iter().collect::<BTreeSet<Variant>>(),
+            doc,
+            unmapped,
+        })
 }
 
 fn to_add_fields_from_directives(
@@ -509,3 +632,105 @@ impl ObjectLike for InterfaceType {
         &self.implements
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tailcall_valid::Validator;
+
+    use crate::core::config::Config;
+
+    #[test]
+    fn test_extend_schema() {
+        let sdl = r#"
+            extend schema @link(src: "types.graphql", type: Config) {
+              mutation: Mutation
+            }
+
+            schema @server(port: 8000) {
+              query: Query
+            }
+
+            type Query {
+              foo: String
+            }
+
+            type Mutation {
+              bar: String
+            }
+        "#;
+
+        let config = Config::from_sdl(sdl).to_result().unwrap();
+
+        assert_eq!(config.schema.query.as_deref(), Some("Query"));
+        assert_eq!(config.schema.mutation.as_deref(), Some("Mutation"));
+        assert_eq!(config.server.port, Some(8000));
+        assert_eq!(config.links.len(), 1);
+    }
+
+    #[test]
+    fn test_extend_type() {
+        let sdl = r#"
+            extend type Query {
+              bar: Int
+            }
+
+            type Query {
+              foo: String
+            }
+
+            extend type Query @cache(maxAge: 100) {
+              baz: [String]
+            }
+        "#;
+
+        let config = Config::from_sdl(sdl).to_result().unwrap();
+        let query = config.types.get("Query").unwrap();
+
+        assert_eq!(
+            query.fields.keys().collect::<Vec<_>>(),
+            vec!["bar", "baz", "foo"]
+        );
+        assert_eq!(
+            query.cache.as_ref().map(|cache| cache.max_age.get()),
+            Some(100)
+        );
+    }
+
+    #[test]
+    fn test_extend_type_with_defined_field() {
+        let sdl = r#"
+            type Query {
+              foo: String
+            }
+
+            extend type Query {
+              foo: Int
+            }
+        "#;
+
+        let actual = Config::from_sdl(sdl).to_result();
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn test_extend_schema_with_defined_operation() {
+        let sdl = r#"
+            schema {
+              query: Query
+            }
+
+            extend schema {
+              query: Foo
+            }
+
+            type Query {
+              foo: String
+            }
+        "#;
+
+        let actual = Config::from_sdl(sdl).to_result();
+
+        assert!(actual.is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 
+use crate::core::config::StatusAction;
 use crate::core::is_default;
 
 /// The `groupBy` parameter groups multiple data requests into a single call. For more details please refer out [n + 1 guide](https://tailcall.run/docs/guides/n+1#solving-using-batching).
@@ -9,11 +10,23 @@ pub struct GroupBy {
     path: Vec<String>,
     #[serde(default, skip_serializing_if = "is_default")]
     key: Option<String>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    on_missing: Option<StatusAction>,
 }
 
 impl GroupBy {
     pub fn new(path: Vec<String>, key: Option<String>) -> Self {
-        Self { path, key }
+        Self { path, key, on_missing: None }
+    }
+
+    /// Sets how the requests without an item in the batched response are
+    /// resolved.
+    pub fn with_on_missing(self, on_missing: Option<StatusAction>) -> Self {
+        Self { on_missing, ..self }
+    }
+
+    pub fn on_missing(&self) -> StatusAction {
+        self.on_missing.unwrap_or(StatusAction::Null)
     }
 
     pub fn path(&self) -> Vec<String> {
@@ -40,6 +53,6 @@ const ID: &str = "id";
 
 impl Default for GroupBy {
     fn default() -> Self {
-        Self { path: vec![ID.to_string()], key: None }
+        Self { path: vec![ID.to_string()], key: None, on_missing: None }
     }
 }
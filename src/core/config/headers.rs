@@ -22,6 +22,8 @@ pub struct Headers {
     /// `headers` are key-value pairs included in every server
     /// response. Useful for setting headers like `Access-Control-Allow-Origin`
     /// for cross-origin requests or additional headers for downstream services.
+    /// Values can be mustache templates, e.g. `{{.headers.x-request-id}}`,
+    /// headers whose template renders to nothing are skipped.
     pub custom: Vec<KeyValue>,
 
     #[serde(default, skip_serializing_if = "is_default")]
@@ -37,6 +39,18 @@ pub struct Headers {
     #[serde(default, skip_serializing_if = "is_default")]
     /// `cors` allows Cross-Origin Resource Sharing (CORS) for a server.
     pub cors: Option<Cors>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `restCors` configures Cross-Origin Resource Sharing (CORS) for the REST
+    /// endpoints under `/api` separately. Falls back to `cors` when unset.
+    pub rest_cors: Option<Cors>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `requestId` gives every request an id, read from its `X-Request-Id`
+    /// header or generated as a UUID when absent. The id is sent as
+    /// `X-Request-Id` to the upstream calls and in the response. @default
+    /// `false`.
+    pub request_id: Option<bool>,
 }
 
 impl Headers {
@@ -49,4 +63,10 @@ impl Headers {
     pub fn get_cors(&self) -> Option<Cors> {
         self.cors.clone()
     }
+    pub fn get_rest_cors(&self) -> Option<Cors> {
+        self.rest_cors.clone()
+    }
+    pub fn enable_request_id(&self) -> bool {
+        self.request_id.unwrap_or_default()
+    }
 }
@@ -132,7 +132,16 @@ fn config_document(config: &Config) -> ServiceDocument {
                                         arg.default_value.clone(),
                                     )
                                     .map(pos),
-                                    directives: Vec::new(),
+                                    directives: arg
+                                        .omit
+                                        .as_ref()
+                                        .map(|d| pos(d.to_directive()))
+                                        .into_iter()
+                                        .chain(
+                                            arg.constraint.as_ref().map(|d| pos(d.to_directive())),
+                                        )
+                                        .chain(arg.default.as_ref().map(|d| pos(d.to_directive())))
+                                        .collect(),
                                 })
                             })
                             .collect::<Vec<Positioned<InputValueDefinition>>>();
@@ -180,7 +189,10 @@ fn config_document(config: &Config) -> ServiceDocument {
             extend: false,
             description: values.doc.clone().map(pos),
             name: pos(Name::new(name)),
-            directives: Vec::new(),
+            directives: values
+                .unmapped
+                .as_ref()
+                .map_or(vec![], |unmapped| vec![pos(unmapped.to_directive())]),
             kind: TypeKind::Enum(EnumType {
                 values: values
                     .variants
@@ -191,8 +203,16 @@ fn config_document(config: &Config) -> ServiceDocument {
                             value: pos(Name::new(&variant.name)),
                             directives: variant
                                 .alias
-                                .clone()
-                                .map_or(vec![], |v| vec![pos(v.to_directive())]),
+                                .as_ref()
+                                .map(|alias| pos(alias.to_directive()))
+                                .into_iter()
+                                .chain(
+                                    variant
+                                        .upstream_value
+                                        .as_ref()
+                                        .map(|value| pos(value.to_directive())),
+                                )
+                                .collect(),
                         })
                     })
                     .collect(),
@@ -221,6 +241,8 @@ fn field_directives(field: &crate::core::config::Field) -> Vec<Positioned<ConstD
         .chain(field.omit.as_ref().map(|d| pos(d.to_directive())))
         .chain(field.cache.as_ref().map(|d| pos(d.to_directive())))
         .chain(field.protected.as_ref().map(|d| pos(d.to_directive())))
+        .chain(field.join.as_ref().map(|d| pos(d.to_directive())))
+        .chain(field.prefetch.as_ref().map(|d| pos(d.to_directive())))
         .chain(into_directives(&field.directives))
         .collect()
 }
@@ -242,6 +264,12 @@ fn type_directives(type_def: &crate::core::config::Type) -> Vec<Positioned<Const
                 .as_ref()
                 .map(|protected| pos(protected.to_directive())),
         )
+        .chain(
+            type_def
+                .regex
+                .as_ref()
+                .map(|regex| pos(regex.to_directive())),
+        )
         .chain(
             type_def
                 .resolvers
@@ -27,3 +27,4 @@ mod resolver;
 mod source;
 pub mod transformer;
 mod url_query;
+mod validate;
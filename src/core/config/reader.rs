@@ -11,7 +11,7 @@ use url::Url;
 use super::{ConfigModule, Content, Link, LinkType, PrivateKey};
 use crate::core::config::{Config, ConfigReaderContext, Source};
 use crate::core::proto_reader::ProtoReader;
-use crate::core::resource_reader::{Cached, Resource, ResourceReader};
+use crate::core::resource_reader::{Cached, FileRead, Resource, ResourceReader};
 use crate::core::rest::EndpointSet;
 use crate::core::runtime::TargetRuntime;
 use crate::core::variance::Invariant;
@@ -228,6 +228,23 @@ impl ConfigReader {
         Ok(config_module.to_result()?)
     }
 
+    /// Reads a config from its content rather than from a file, e.g. when it's
+    /// piped through the standard input. There's no file the relative links
+    /// can be resolved against, so they're resolved against the current
+    /// directory.
+    pub async fn read_content(
+        &self,
+        content: &str,
+        source: Source,
+    ) -> anyhow::Result<ConfigModule> {
+        let reader_ctx = ConfigReaderContext::new(&self.runtime);
+        let file =
+            FileRead { content: content.to_string(), path: String::new() }.render(&reader_ctx);
+
+        self.resolve(Config::from_source(source, &file.content)?, None)
+            .await
+    }
+
     /// Resolves all the links in a Config to create a ConfigModule
     pub async fn resolve(
         &self,
@@ -275,8 +292,11 @@ mod reader_tests {
 
     use pretty_assertions::assert_eq;
 
+    use crate::core::app_context::AppContext;
+    use crate::core::blueprint::Blueprint;
     use crate::core::config::reader::ConfigReader;
-    use crate::core::config::{Config, Type};
+    use crate::core::config::{Config, Source, Type};
+    use crate::core::rest::EndpointSet;
 
     fn start_mock_server() -> httpmock::MockServer {
         httpmock::MockServer::start()
@@ -372,6 +392,36 @@ mod reader_tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_read_content() {
+        let runtime = crate::core::runtime::test::init(None);
+        let sdl = r#"
+            schema @server(port: 8000) @link(src: "examples/scripts/echo.js", type: Script) {
+              query: Query
+            }
+
+            type Query {
+              hello: String @expr(body: "Hello World!")
+            }
+        "#;
+
+        let reader = ConfigReader::init(runtime.clone());
+        let config_module = reader.read_content(sdl, Source::GraphQL).await.unwrap();
+
+        // relative links are resolved against the current directory
+        let script = runtime.file.read("examples/scripts/echo.js").await.unwrap();
+        assert_eq!(config_module.extensions().script, Some(script));
+
+        let blueprint = Blueprint::try_from(&config_module).unwrap();
+        let app_ctx = AppContext::new(blueprint, runtime, EndpointSet::default());
+        assert_eq!(app_ctx.blueprint.server.port, 8000);
+        assert!(app_ctx
+            .blueprint
+            .definitions
+            .iter()
+            .any(|def| def.name() == "Query"));
+    }
+
     #[test]
     fn test_relative_path() {
         let path_dir = Path::new("abc/xyz");
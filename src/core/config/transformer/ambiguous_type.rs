@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use tailcall_valid::{Valid, Validator};
 
-use crate::core::config::Config;
+use crate::core::config::{Config, Field, Type};
 use crate::core::transform::Transform;
 
 /// Resolves the ambiguous types by renaming the input and
@@ -56,6 +56,28 @@ fn insert_resolution(
     map
 }
 
+/// Keeps the parts of the type that an input type supports, the resolvers,
+/// arguments and interfaces are left to the output type.
+fn to_input_type(ty: &Type) -> Type {
+    let fields = ty
+        .fields
+        .iter()
+        .map(|(name, field)| {
+            let field = Field {
+                type_of: field.type_of.clone(),
+                doc: field.doc.clone(),
+                modify: field.modify.clone(),
+                omit: field.omit.clone(),
+                default_value: field.default_value.clone(),
+                ..Default::default()
+            };
+            (name.clone(), field)
+        })
+        .collect();
+
+    Type { fields, doc: ty.doc.clone(), ..Default::default() }
+}
+
 impl Transform for AmbiguousType {
     type Value = Config;
     type Error = String;
@@ -112,7 +134,9 @@ impl Transform for AmbiguousType {
 
                 // add new types
                 if let Some(og_ty) = og_ty {
-                    config.types.insert(input_name.clone(), og_ty.clone());
+                    config
+                        .types
+                        .insert(input_name.clone(), to_input_type(&og_ty));
                     input_types.insert(input_name.clone());
 
                     config.types.insert(output_name.clone(), og_ty);
@@ -238,6 +262,76 @@ mod tests {
         assert_snapshot!(config.to_sdl());
     }
 
+    #[test]
+    fn test_split_dual_use_type() {
+        let config = Config::from_sdl(
+            r#"
+            schema @server @upstream {
+              query: Query
+              mutation: Mutation
+            }
+
+            type Query {
+              user(id: Int!): User @http(url: "http://upstream/users/{{.args.id}}")
+            }
+
+            type Mutation {
+              createUser(user: User!): User
+                @http(url: "http://upstream/users", method: POST, body: "{{.args.user}}")
+            }
+
+            interface Node {
+              id: Int!
+            }
+
+            type User implements Node {
+              id: Int!
+              address: Address
+              friends(first: Int): [User] @http(url: "http://upstream/users/{{.value.id}}/friends")
+            }
+
+            type Address {
+              city: String
+            }
+            "#,
+        )
+        .to_result()
+        .unwrap();
+
+        let config = AmbiguousType::default()
+            .transform(config)
+            .to_result()
+            .unwrap();
+
+        let user_input = &config.types["UserInput"];
+        assert!(user_input.implements.is_empty());
+        assert_eq!(user_input.fields["id"].type_of.name(), "Int");
+        assert_eq!(user_input.fields["address"].type_of.name(), "AddressInput");
+        let friends = &user_input.fields["friends"];
+        assert_eq!(friends.type_of.name(), "UserInput");
+        assert!(friends.type_of.is_list());
+        assert!(friends.args.is_empty() && !friends.has_resolver());
+        assert_eq!(
+            config.types["AddressInput"].fields["city"].type_of.name(),
+            "String"
+        );
+
+        let user = &config.types["User"];
+        assert!(user.implements.contains("Node"));
+        assert_eq!(user.fields["address"].type_of.name(), "Address");
+        assert_eq!(user.fields["friends"].type_of.name(), "User");
+        assert!(user.fields["friends"].has_resolver());
+
+        let create_user = &config.types["Mutation"].fields["createUser"];
+        assert_eq!(create_user.args["user"].type_of.name(), "UserInput");
+        assert!(!create_user.args["user"].type_of.is_nullable());
+        assert_eq!(create_user.type_of.name(), "User");
+        assert_eq!(config.types["Query"].fields["user"].type_of.name(), "User");
+
+        assert!(config.input_types().contains("UserInput"));
+        assert!(!config.output_types().contains("UserInput"));
+    }
+
     fn compile_protobuf(files: &[&str]) -> anyhow::Result<FileDescriptorSet> {
         Ok(protox::compile(files, [protobuf::SELF])?)
     }
@@ -0,0 +1,231 @@
+use std::collections::BTreeMap;
+
+use convert_case::{Case, Casing};
+use tailcall_valid::Valid;
+
+use crate::core::config::{Batch, Config, Field, Http, Resolver, URLQuery};
+use crate::core::http::Method;
+use crate::core::transform::Transform;
+use crate::core::Type;
+
+/// Connects the types of a generated config by detecting the fields that look
+/// like foreign keys, e.g. `userId` or `user_id`, and adding a field next to
+/// them, e.g. `user`, that resolves the referenced item with a batched `@http`
+/// call to the list endpoint of the query whose name matches, e.g. `users`.
+#[derive(Default)]
+pub struct InferRelations;
+
+/// The delay in milliseconds of the batches, when the config doesn't batch
+/// the upstream requests yet.
+const BATCH_DELAY: usize = 10;
+
+/// A list endpoint of the query whose items can be looked up by their `id`.
+struct Endpoint<'a> {
+    type_name: &'a str,
+    http: &'a Http,
+}
+
+/// Returns the singular form of a plural field name, e.g. `users` or
+/// `categories`, so it can be compared with the prefix of a foreign key.
+fn singularize(name: &str) -> Option<String> {
+    if let Some(stem) = name.strip_suffix("ies") {
+        Some(format!("{}y", stem))
+    } else if let Some(stem) = name.strip_suffix("sses") {
+        Some(format!("{}ss", stem))
+    } else {
+        name.strip_suffix('s')
+            .filter(|stem| !stem.is_empty() && !stem.ends_with('s'))
+            .map(str::to_string)
+    }
+}
+
+/// Returns the name of the referenced entity of a foreign key field in the
+/// camel case, e.g. `user` for `userId` and `user_id`.
+fn foreign_key_prefix(field_name: &str) -> Option<String> {
+    let prefix = field_name
+        .strip_suffix("Id")
+        .or_else(|| field_name.strip_suffix("_id"))?;
+
+    (!prefix.is_empty()).then(|| prefix.to_case(Case::Camel))
+}
+
+impl InferRelations {
+    /// Finds the list fields of the query that are resolved by a plain `GET`
+    /// call and whose items have an `id`, keyed by their singular name.
+    fn endpoints<'a>(&self, config: &'a Config) -> BTreeMap<String, Endpoint<'a>> {
+        let Some(query) = config.schema.query.as_ref() else {
+            return BTreeMap::new();
+        };
+        let Some(query) = config.find_type(query) else {
+            return BTreeMap::new();
+        };
+
+        query
+            .fields
+            .iter()
+            .filter(|(_, field)| field.type_of.is_list() && field.args.is_empty())
+            .filter_map(|(field_name, field)| {
+                let http = match field.resolvers.0.as_slice() {
+                    [Resolver::Http(http)] => http,
+                    _ => return None,
+                };
+                let is_plain_get = http.method == Method::GET
+                    && http.query.is_empty()
+                    && http.batch_key.is_empty();
+                let type_name = field.type_of.name();
+                let has_id = config
+                    .find_type(type_name)
+                    .and_then(|ty| ty.fields.get("id"))
+                    .is_some_and(|id| config.is_scalar(id.type_of.name()));
+
+                if !is_plain_get || !has_id {
+                    return None;
+                }
+
+                let key = singularize(field_name)?.to_case(Case::Camel);
+                Some((key, Endpoint { type_name, http }))
+            })
+            .collect()
+    }
+}
+
+impl Transform for InferRelations {
+    type Value = Config;
+    type Error = String;
+
+    fn transform(&self, mut config: Self::Value) -> Valid<Self::Value, Self::Error> {
+        let endpoints = self.endpoints(&config);
+        let input_types = config.input_types();
+
+        let mut relations = vec![];
+        for (type_name, ty) in config.types.iter() {
+            if config.is_root_operation_type(type_name) || input_types.contains(type_name) {
+                continue;
+            }
+
+            for (field_name, field) in ty.fields.iter() {
+                if field.type_of.is_list() || !config.is_scalar(field.type_of.name()) {
+                    continue;
+                }
+                let Some(prefix) = foreign_key_prefix(field_name) else {
+                    continue;
+                };
+                let Some(endpoint) = endpoints.get(&prefix) else {
+                    continue;
+                };
+                if ty.fields.contains_key(&prefix)
+                    || ty.added_fields.iter().any(|f| f.name == prefix)
+                {
+                    continue;
+                }
+
+                let http = Http {
+                    url: endpoint.http.url.clone(),
+                    headers: endpoint.http.headers.clone(),
+                    query: vec![URLQuery {
+                        key: "id".to_string(),
+                        value: format!("{{{{.value.{}}}}}", field_name),
+                        skip_empty: None,
+                    }],
+                    batch_key: vec!["id".to_string()],
+                    ..Default::default()
+                };
+                let relation = Field {
+                    type_of: Type::from(endpoint.type_name.to_string()),
+                    resolvers: Resolver::Http(http).into(),
+                    ..Default::default()
+                };
+
+                relations.push((type_name.clone(), prefix, relation));
+            }
+        }
+
+        // the relations are resolved with `batchKey`, which requires batching
+        if !relations.is_empty() && config.upstream.get_delay() < 1 {
+            let batch = config.upstream.batch.take().unwrap_or_default();
+            config.upstream.batch = Some(Batch { delay: BATCH_DELAY, ..batch });
+        }
+
+        for (type_name, field_name, field) in relations {
+            if let Some(ty) = config.types.get_mut(&type_name) {
+                ty.fields.insert(field_name, field);
+            }
+        }
+
+        Valid::succeed(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tailcall_valid::Validator;
+
+    use super::InferRelations;
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::{ConfigModule, Resolver, URLQuery};
+    use crate::core::generator::{FromJsonGenerator, NameGenerator, RequestSample};
+    use crate::core::transform::TransformerOps;
+
+    #[test]
+    fn test_singularize() {
+        assert_eq!(super::singularize("users").as_deref(), Some("user"));
+        assert_eq!(
+            super::singularize("categories").as_deref(),
+            Some("category")
+        );
+        assert_eq!(super::singularize("addresses").as_deref(), Some("address"));
+        assert_eq!(super::singularize("data"), None);
+    }
+
+    #[test]
+    fn test_post_user_relation() {
+        let samples = [
+            RequestSample::new(
+                "http://jsonplaceholder.typicode.com/posts".parse().unwrap(),
+                json!([{"id": 1, "userId": 1, "title": "Hello"}]),
+                "posts".to_string(),
+            ),
+            RequestSample::new(
+                "http://jsonplaceholder.typicode.com/users".parse().unwrap(),
+                json!([{"id": 1, "name": "Leanne Graham"}]),
+                "users".to_string(),
+            ),
+        ];
+
+        let config = FromJsonGenerator::new(&samples, &NameGenerator::new("T"), "Query", &None)
+            .pipe(InferRelations)
+            .generate()
+            .to_result()
+            .unwrap();
+
+        let query = config.find_type("Query").unwrap();
+        let post = query.fields.get("posts").unwrap().type_of.name();
+        let user = query.fields.get("users").unwrap().type_of.name();
+
+        let relation = config.find_type(post).unwrap().fields.get("user").unwrap();
+        assert_eq!(relation.type_of.name(), user);
+        assert!(relation.type_of.is_nullable() && !relation.type_of.is_list());
+
+        let [Resolver::Http(http)] = relation.resolvers.0.as_slice() else {
+            panic!("expected a single @http resolver");
+        };
+        assert_eq!(http.url, "http://jsonplaceholder.typicode.com/users");
+        assert_eq!(
+            http.query,
+            vec![URLQuery {
+                key: "id".to_string(),
+                value: "{{.value.userId}}".to_string(),
+                skip_empty: None
+            }]
+        );
+        assert_eq!(http.batch_key, vec!["id".to_string()]);
+
+        // the users have no foreign keys, so they're left as they are
+        assert_eq!(config.find_type(user).unwrap().fields.len(), 2);
+
+        // the batched relation needs the upstream requests to be batched
+        assert!(config.upstream.get_delay() >= 1);
+        Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+    }
+}
@@ -1,8 +1,10 @@
 mod ambiguous_type;
 mod flatten_single_field;
 mod improve_type_names;
+mod infer_relations;
 mod merge_types;
 mod nested_unions;
+mod prefix_types;
 mod preset;
 mod rename_types;
 mod required;
@@ -13,8 +15,10 @@ mod union_input_type;
 pub use ambiguous_type::{AmbiguousType, Resolution};
 pub use flatten_single_field::FlattenSingleField;
 pub use improve_type_names::ImproveTypeNames;
+pub use infer_relations::InferRelations;
 pub use merge_types::TypeMerger;
 pub use nested_unions::NestedUnions;
+pub use prefix_types::PrefixTypes;
 pub use preset::Preset;
 pub use rename_types::RenameTypes;
 pub use required::Required;
@@ -0,0 +1,132 @@
+use tailcall_valid::Valid;
+
+use super::RenameTypes;
+use crate::core::config::Config;
+use crate::core::Transform;
+
+/// A transformer that prefixes the names of all the types, enums and unions
+/// with a namespace, so configs that define the same type names can be merged
+/// together. Root operation types and scalars keep their names.
+pub struct PrefixTypes(String);
+
+impl PrefixTypes {
+    pub fn new(prefix: impl ToString) -> Self {
+        Self(prefix.to_string())
+    }
+}
+
+impl Transform for PrefixTypes {
+    type Value = Config;
+    type Error = String;
+
+    fn transform(&self, config: Self::Value) -> Valid<Self::Value, Self::Error> {
+        let types = config
+            .types
+            .keys()
+            .filter(|name| !config.is_root_operation_type(name) && !config.is_scalar(name));
+
+        let suggested_names = types
+            .chain(config.enums.keys())
+            .chain(config.unions.keys())
+            .map(|name| (name.clone(), format!("{}{}", self.0, name)))
+            .collect::<Vec<_>>();
+
+        RenameTypes::new(suggested_names.into_iter()).transform(config)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tailcall_valid::Validator;
+
+    use super::PrefixTypes;
+    use crate::core::config::Config;
+    use crate::core::transform::Transform;
+
+    #[test]
+    fn test_prefix_types() {
+        let sdl = r#"
+            schema {
+                query: Query
+            }
+            scalar Url
+            enum Role {
+                ADMIN
+                USER
+            }
+            interface Node {
+                id: ID!
+            }
+            type User implements Node {
+                id: ID!
+                role: Role
+                website: Url
+                posts(filter: PostFilter): [Post]
+            }
+            input PostFilter {
+                title: String
+            }
+            type Post {
+                id: ID!
+            }
+            union Entity = User | Post
+            type Query {
+                users: [User] @http(url: "http://jsonplaceholder.typicode.com/users")
+                entities: [Entity] @http(url: "http://jsonplaceholder.typicode.com/entities")
+            }
+        "#;
+        let config = Config::from_sdl(sdl).to_result().unwrap();
+
+        let cfg = PrefixTypes::new("Users_")
+            .transform(config)
+            .to_result()
+            .unwrap();
+
+        let mut types = cfg.types.keys().cloned().collect::<Vec<_>>();
+        types.sort();
+        assert_eq!(
+            types,
+            vec![
+                "Query",
+                "Url",
+                "Users_Node",
+                "Users_Post",
+                "Users_PostFilter",
+                "Users_User"
+            ]
+        );
+        assert!(cfg.enums.contains_key("Users_Role"));
+        assert!(cfg.unions.contains_key("Users_Entity"));
+        assert_eq!(cfg.schema.query.as_deref(), Some("Query"));
+
+        let query = cfg.types.get("Query").unwrap();
+        assert_eq!(
+            query.fields.get("users").unwrap().type_of.name(),
+            "Users_User"
+        );
+        assert_eq!(
+            query.fields.get("entities").unwrap().type_of.name(),
+            "Users_Entity"
+        );
+
+        let user = cfg.types.get("Users_User").unwrap();
+        assert!(user.implements.contains("Users_Node"));
+        assert_eq!(user.fields.get("id").unwrap().type_of.name(), "ID");
+        assert_eq!(
+            user.fields.get("role").unwrap().type_of.name(),
+            "Users_Role"
+        );
+        assert_eq!(user.fields.get("website").unwrap().type_of.name(), "Url");
+
+        let posts = user.fields.get("posts").unwrap();
+        assert_eq!(posts.type_of.name(), "Users_Post");
+        assert_eq!(
+            posts.args.get("filter").unwrap().type_of.name(),
+            "Users_PostFilter"
+        );
+
+        let entity = cfg.unions.get("Users_Entity").unwrap();
+        assert!(entity.types.contains("Users_User"));
+        assert!(entity.types.contains("Users_Post"));
+    }
+}
@@ -26,48 +26,76 @@ impl Transform for RenameTypes {
 
     fn transform(&self, config: Self::Value) -> Valid<Self::Value, Self::Error> {
         let mut config = config;
-        let mut lookup = IndexMap::new();
+        let is_defined = |config: &Config, name: &str| {
+            config.types.contains_key(name)
+                || config.enums.contains_key(name)
+                || config.unions.contains_key(name)
+        };
 
-        // Ensure all types exist in the configuration
+        // Ensure all types exist in the configuration and the suggested names
+        // don't collide with other types.
         Valid::from_iter(self.0.iter(), |(existing_name, suggested_name)| {
-            if config.types.contains_key(existing_name)
-                || config.enums.contains_key(existing_name)
-                || config.unions.contains_key(existing_name)
-            {
-                // handle for the types.
-                if let Some(type_info) = config.types.remove(existing_name) {
-                    config.types.insert(suggested_name.to_string(), type_info);
-                    lookup.insert(existing_name.clone(), suggested_name.clone());
-
-                    // edge case where type is of operation type.
-                    if config.schema.query == Some(existing_name.clone()) {
-                        config.schema.query = Some(suggested_name.clone());
-                    } else if config.schema.mutation == Some(existing_name.clone()) {
-                        config.schema.mutation = Some(suggested_name.clone());
-                    }
-                }
-
-                // handle for the enums.
-                if let Some(type_info) = config.enums.remove(existing_name) {
-                    config.enums.insert(suggested_name.to_string(), type_info);
-                    lookup.insert(existing_name.clone(), suggested_name.clone());
-                }
+            if !is_defined(&config, existing_name) {
+                return Valid::fail(format!(
+                    "Type '{}' not found in configuration.",
+                    existing_name
+                ));
+            }
 
-                // handle for the union.
-                if let Some(type_info) = config.unions.remove(existing_name) {
-                    config.unions.insert(suggested_name.to_string(), type_info);
-                    lookup.insert(existing_name.clone(), suggested_name.clone());
-                }
+            let is_duplicate = self.0.iter().any(|(other_name, other_suggested_name)| {
+                other_name != existing_name
+                    && other_suggested_name == suggested_name
+                    && is_defined(&config, other_name)
+            });
+            let is_taken =
+                is_defined(&config, suggested_name) && !self.0.contains_key(suggested_name);
 
-                Valid::succeed(())
-            } else {
+            if is_duplicate || is_taken {
                 Valid::fail(format!(
-                    "Type '{}' not found in configuration.",
-                    existing_name
+                    "Cannot rename type '{}' to '{}' because the name is already taken.",
+                    existing_name, suggested_name
                 ))
+            } else {
+                Valid::succeed(())
             }
         })
         .map(|_| {
+            // remove all the renamed types first, so types can swap their names
+            let mut renamed = Vec::new();
+            for (existing_name, suggested_name) in self.0.iter() {
+                let type_info = config.types.remove(existing_name);
+                let enum_info = config.enums.remove(existing_name);
+                let union_info = config.unions.remove(existing_name);
+                renamed.push((suggested_name, type_info, enum_info, union_info));
+            }
+
+            for (suggested_name, type_info, enum_info, union_info) in renamed {
+                if let Some(type_info) = type_info {
+                    config.types.insert(suggested_name.to_string(), type_info);
+                }
+                if let Some(enum_info) = enum_info {
+                    config.enums.insert(suggested_name.to_string(), enum_info);
+                }
+                if let Some(union_info) = union_info {
+                    config.unions.insert(suggested_name.to_string(), union_info);
+                }
+            }
+
+            // edge case where type is of operation type.
+            let lookup = &self.0;
+            for operation in [
+                &mut config.schema.query,
+                &mut config.schema.mutation,
+                &mut config.schema.subscription,
+            ] {
+                if let Some(suggested_name) = operation.as_ref().and_then(|name| lookup.get(name)) {
+                    *operation = Some(suggested_name.clone());
+                }
+            }
+
+            lookup
+        })
+        .map(|lookup| {
             for type_ in config.types.values_mut() {
                 for field_ in type_.fields.values_mut() {
                     // replace type of field.
@@ -258,6 +286,117 @@ mod test {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_rename_updates_all_references() {
+        let sdl = r#"
+            schema {
+                query: Query
+                subscription: Subscription
+            }
+            input GenInput {
+                id: ID
+            }
+            type GenUser {
+                id: ID
+            }
+            union GenResult = GenUser | Post
+            type Post {
+                id: ID
+                author: GenUser
+            }
+            type Query {
+                user(input: GenInput): GenUser @http(url: "http://jsonplaceholder.typicode.com/users")
+                search: [GenResult] @http(url: "http://jsonplaceholder.typicode.com/search")
+            }
+            type Subscription {
+                users: [GenUser] @http(url: "http://jsonplaceholder.typicode.com/users")
+            }
+        "#;
+        let config = Config::from_sdl(sdl).to_result().unwrap();
+
+        let cfg = RenameTypes::new(
+            hashmap! {
+                "GenInput" => "UserInput",
+                "GenUser" => "User",
+                "GenResult" => "SearchResult",
+                "Subscription" => "UserSubscription"
+            }
+            .iter(),
+        )
+        .transform(config)
+        .to_result()
+        .unwrap();
+
+        let query = cfg.types.get("Query").unwrap();
+        let user = query.fields.get("user").unwrap();
+        assert_eq!(user.type_of.name(), "User");
+        assert_eq!(user.args.get("input").unwrap().type_of.name(), "UserInput");
+        assert_eq!(
+            query.fields.get("search").unwrap().type_of.name(),
+            "SearchResult"
+        );
+
+        let author = cfg.types.get("Post").unwrap().fields.get("author").unwrap();
+        assert_eq!(author.type_of.name(), "User");
+
+        let union_ = cfg.unions.get("SearchResult").unwrap();
+        assert!(union_.types.contains("User"));
+        assert!(!union_.types.contains("GenUser"));
+
+        assert_eq!(cfg.schema.subscription.as_deref(), Some("UserSubscription"));
+        assert!(!cfg.types.contains_key("GenUser"));
+        assert!(!cfg.types.contains_key("GenInput"));
+    }
+
+    #[test]
+    fn test_should_raise_error_when_name_is_taken() {
+        let sdl = r#"
+            schema {
+                query: Query
+            }
+            type A {
+                id: ID
+            }
+            type B {
+                id: ID
+            }
+            type C {
+                id: ID
+            }
+            type Query {
+                a: A @http(url: "http://jsonplaceholder.typicode.com/a")
+                b: B @http(url: "http://jsonplaceholder.typicode.com/b")
+                c: C @http(url: "http://jsonplaceholder.typicode.com/c")
+            }
+        "#;
+        let config = Config::from_sdl(sdl).to_result().unwrap();
+
+        let actual = RenameTypes::new(hashmap! {"A" => "B"}.iter())
+            .transform(config.clone())
+            .to_result();
+        let expected = Err(ValidationError::new(
+            "Cannot rename type 'A' to 'B' because the name is already taken.".to_string(),
+        ));
+        assert_eq!(actual, expected);
+
+        let mut suggested_names = IndexMap::new();
+        suggested_names.insert("A", "User");
+        suggested_names.insert("C", "User");
+        let actual = RenameTypes::new(suggested_names.iter())
+            .transform(config.clone())
+            .to_result();
+        assert!(actual.is_err());
+
+        // swapping the names is allowed
+        let cfg = RenameTypes::new(hashmap! {"A" => "B", "B" => "A"}.iter())
+            .transform(config)
+            .to_result()
+            .unwrap();
+        let query = cfg.types.get("Query").unwrap();
+        assert_eq!(query.fields.get("a").unwrap().type_of.name(), "B");
+        assert_eq!(query.fields.get("b").unwrap().type_of.name(), "A");
+    }
+
     #[test]
     fn test_inferface_rename() {
         let sdl = r#"
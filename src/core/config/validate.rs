@@ -0,0 +1,277 @@
+use std::collections::HashSet;
+
+use tailcall_valid::{Valid, Validator};
+
+use super::Config;
+use crate::core::scalar::Scalar;
+
+impl Config {
+    /// Runs a quick structural validation of the config that is independent of
+    /// the blueprint compilation and collects all the mistakes it finds:
+    /// schema roots and type references that point to missing types, object
+    /// types without fields and enums that define the same value twice.
+    ///
+    /// NOTE: types without fields are custom scalars in the config, so only the
+    /// root types and the types implementing interfaces are reported as empty.
+    pub fn validate(&self) -> Valid<(), String> {
+        self.validate_schema()
+            .and(self.validate_types())
+            .and(self.validate_unions())
+            .and(self.validate_enums())
+    }
+
+    fn validate_schema(&self) -> Valid<(), String> {
+        let roots = [
+            ("query", &self.schema.query),
+            ("mutation", &self.schema.mutation),
+            ("subscription", &self.schema.subscription),
+        ];
+
+        Valid::from_iter(roots, |(operation, root)| match root {
+            Some(name) if !self.types.contains_key(name) => {
+                Valid::fail(format!("Root type {} is not defined", name)).trace(operation)
+            }
+            _ => Valid::succeed(()),
+        })
+        .trace("schema")
+        .unit()
+    }
+
+    fn validate_types(&self) -> Valid<(), String> {
+        Valid::from_iter(self.types.iter(), |(type_name, type_)| {
+            let is_empty = type_.fields.is_empty()
+                && (self.is_root_operation_type(type_name) || !type_.implements.is_empty());
+
+            Valid::<(), String>::fail(format!("Type {} has no fields", type_name))
+                .when(|| is_empty)
+                .and(
+                    Valid::from_iter(type_.implements.iter(), |interface| {
+                        if self.types.contains_key(interface) {
+                            Valid::succeed(())
+                        } else {
+                            Valid::fail(format!("Interface {} is not defined", interface))
+                        }
+                    })
+                    .unit(),
+                )
+                .and(
+                    Valid::from_iter(type_.fields.iter(), |(field_name, field)| {
+                        self.validate_type_ref(field.type_of.name())
+                            .and(
+                                Valid::from_iter(field.args.iter(), |(arg_name, arg)| {
+                                    self.validate_type_ref(arg.type_of.name()).trace(arg_name)
+                                })
+                                .unit(),
+                            )
+                            .trace(field_name)
+                    })
+                    .unit(),
+                )
+                .trace(type_name)
+        })
+        .unit()
+    }
+
+    fn validate_unions(&self) -> Valid<(), String> {
+        Valid::from_iter(self.unions.iter(), |(union_name, union_)| {
+            Valid::from_iter(union_.types.iter(), |type_name| {
+                if self.types.contains_key(type_name) {
+                    Valid::succeed(())
+                } else {
+                    Valid::fail(format!("Type {} is not defined", type_name))
+                }
+            })
+            .trace(union_name)
+        })
+        .unit()
+    }
+
+    fn validate_enums(&self) -> Valid<(), String> {
+        Valid::from_iter(self.enums.iter(), |(enum_name, enum_)| {
+            let mut names = HashSet::new();
+            let duplicates = enum_
+                .variants
+                .iter()
+                .flat_map(|variant| {
+                    std::iter::once(&variant.name)
+                        .chain(variant.alias.iter().flat_map(|alias| alias.options.iter()))
+                })
+                .filter(|name| !names.insert(*name))
+                .collect::<Vec<_>>();
+
+            Valid::from_iter(duplicates, |name| {
+                Valid::<(), String>::fail(format!("Enum value {} is defined more than once", name))
+            })
+            .trace(enum_name)
+        })
+        .unit()
+    }
+
+    fn validate_type_ref(&self, name: &str) -> Valid<(), String> {
+        if self.contains(name) || Scalar::is_predefined(name) {
+            Valid::succeed(())
+        } else {
+            Valid::fail(format!("Type {} is not defined", name))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tailcall_valid::Validator;
+
+    use crate::core::config::{Config, Field, Type};
+
+    fn errors(config: &Config) -> Vec<(String, Vec<String>)> {
+        config
+            .validate()
+            .to_result()
+            .unwrap_err()
+            .as_vec()
+            .iter()
+            .map(|cause| (cause.message.clone(), cause.trace.iter().cloned().collect()))
+            .collect()
+    }
+
+    fn error(message: &str, trace: &[&str]) -> (String, Vec<String>) {
+        (
+            message.to_string(),
+            trace.iter().map(|s| s.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_valid_config() {
+        let config = Config::from_sdl(
+            r#"
+            schema {
+              query: Query
+            }
+
+            scalar Cursor
+
+            enum Status {
+              ACTIVE
+              INACTIVE
+            }
+
+            type Query {
+              users(after: Cursor, status: Status): [User] @http(url: "http://example.com/users")
+            }
+
+            type User {
+              id: ID!
+              status: Status
+            }
+            "#,
+        )
+        .to_result()
+        .unwrap();
+
+        assert!(config.validate().is_succeed());
+    }
+
+    #[test]
+    fn test_undefined_type_references() {
+        let config = Config::from_sdl(
+            r#"
+            schema {
+              query: Query
+            }
+
+            union Result = User | Post
+
+            type Query {
+              user(filter: UserFilter): User @http(url: "http://example.com/user")
+              posts: [Post] @http(url: "http://example.com/posts")
+            }
+
+            type User implements Node {
+              id: ID!
+            }
+            "#,
+        )
+        .to_result()
+        .unwrap();
+
+        assert_eq!(
+            errors(&config),
+            vec![
+                error("Type Post is not defined", &["Query", "posts"]),
+                error(
+                    "Type UserFilter is not defined",
+                    &["Query", "user", "filter"]
+                ),
+                error("Interface Node is not defined", &["User"]),
+                error("Type Post is not defined", &["Result"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_object_types() {
+        let config = Config::default().query("Query").types(vec![
+            ("Query", Type::default()),
+            ("Node", Type::default().fields(vec![("id", Field::id())])),
+            (
+                "User",
+                Type {
+                    implements: ["Node".to_string()].into(),
+                    ..Default::default()
+                },
+            ),
+            ("Cursor", Type::default()),
+        ]);
+
+        assert_eq!(
+            errors(&config),
+            vec![
+                error("Type Query has no fields", &["Query"]),
+                error("Type User has no fields", &["User"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_schema_roots() {
+        let mut config = Config::default().query("Query").types(vec![(
+            "Mutation",
+            Type::default().fields(vec![("a", Field::int())]),
+        )]);
+        config.schema.subscription = Some("Subscription".to_string());
+
+        assert_eq!(
+            errors(&config),
+            vec![
+                error("Root type Query is not defined", &["schema", "query"]),
+                error(
+                    "Root type Subscription is not defined",
+                    &["schema", "subscription"]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_enum_values() {
+        let config = Config::from_sdl(
+            r#"
+            enum Status {
+              ACTIVE
+              DISABLED @alias(options: ["INACTIVE", "ACTIVE"])
+              INACTIVE
+            }
+            "#,
+        )
+        .to_result()
+        .unwrap();
+
+        assert_eq!(
+            errors(&config),
+            vec![
+                error("Enum value ACTIVE is defined more than once", &["Status"]),
+                error("Enum value INACTIVE is defined more than once", &["Status"]),
+            ]
+        );
+    }
+}
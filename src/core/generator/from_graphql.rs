@@ -0,0 +1,530 @@
+use std::fmt::Write;
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use tailcall_valid::Validator;
+
+use crate::core::config::{Config, GraphQL, KeyValue, Resolver};
+
+/// The query that fetches the schema of a GraphQL upstream, its response is
+/// turned into a config by [`from_graphql`].
+pub const INTROSPECTION_QUERY: &str = r#"
+query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    types { ...FullType }
+  }
+}
+
+fragment FullType on __Type {
+  kind
+  name
+  description
+  fields(includeDeprecated: true) {
+    name
+    description
+    args { ...InputValue }
+    type { ...TypeRef }
+  }
+  inputFields { ...InputValue }
+  interfaces { ...TypeRef }
+  enumValues(includeDeprecated: true) { name description }
+  possibleTypes { ...TypeRef }
+}
+
+fragment InputValue on __InputValue {
+  name
+  description
+  type { ...TypeRef }
+  defaultValue
+}
+
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType {
+    kind
+    name
+    ofType {
+      kind
+      name
+      ofType {
+        kind
+        name
+        ofType {
+          kind
+          name
+          ofType {
+            kind
+            name
+            ofType { kind name ofType { kind name } }
+          }
+        }
+      }
+    }
+  }
+}
+"#;
+
+const BUILTIN_SCALARS: &[&str] = &["String", "Int", "Float", "Boolean", "ID"];
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Schema {
+    query_type: Option<NamedType>,
+    mutation_type: Option<NamedType>,
+    types: Vec<FullType>,
+}
+
+#[derive(Deserialize)]
+struct NamedType {
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FullType {
+    kind: String,
+    name: String,
+    description: Option<String>,
+    fields: Option<Vec<FieldDefinition>>,
+    input_fields: Option<Vec<InputValue>>,
+    interfaces: Option<Vec<TypeRef>>,
+    enum_values: Option<Vec<EnumValue>>,
+    possible_types: Option<Vec<TypeRef>>,
+}
+
+#[derive(Deserialize)]
+struct FieldDefinition {
+    name: String,
+    description: Option<String>,
+    #[serde(default)]
+    args: Vec<InputValue>,
+    #[serde(rename = "type")]
+    type_of: TypeRef,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InputValue {
+    name: String,
+    description: Option<String>,
+    #[serde(rename = "type")]
+    type_of: TypeRef,
+    default_value: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct EnumValue {
+    name: String,
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TypeRef {
+    kind: String,
+    name: Option<String>,
+    of_type: Option<Box<TypeRef>>,
+}
+
+/// Names the root types of the upstream the way the generated config expects
+struct Names<'a> {
+    upstream_query: Option<&'a str>,
+    upstream_mutation: Option<&'a str>,
+    query: &'a str,
+    mutation: Option<&'a str>,
+}
+
+impl Names<'_> {
+    fn get<'a>(&'a self, name: &'a str) -> &'a str {
+        if Some(name) == self.upstream_query {
+            self.query
+        } else if Some(name) == self.upstream_mutation {
+            self.mutation.unwrap_or(name)
+        } else {
+            name
+        }
+    }
+
+    fn type_ref(&self, type_ref: &TypeRef) -> Result<String> {
+        match (type_ref.kind.as_str(), &type_ref.of_type, &type_ref.name) {
+            ("NON_NULL", Some(of_type), _) => Ok(format!("{}!", self.type_ref(of_type)?)),
+            ("LIST", Some(of_type), _) => Ok(format!("[{}]", self.type_ref(of_type)?)),
+            (_, _, Some(name)) => Ok(self.get(name).to_string()),
+            _ => Err(anyhow!("Invalid type reference of kind {}", type_ref.kind)),
+        }
+    }
+}
+
+/// Generates the config of an upstream GraphQL server from its response to the
+/// [`INTROSPECTION_QUERY`]. The types of the upstream are copied as they are,
+/// and every root field is resolved with `@graphQL` from the upstream.
+pub fn from_graphql(
+    introspection: &Value,
+    url: &str,
+    query: &str,
+    mutation: Option<&str>,
+) -> Result<Config> {
+    let schema = introspection
+        .get("data")
+        .unwrap_or(introspection)
+        .get("__schema")
+        .ok_or_else(|| anyhow!("The introspection response has no __schema"))?;
+    let schema = Schema::deserialize(schema)?;
+
+    let names = Names {
+        upstream_query: schema.query_type.as_ref().map(|t| t.name.as_str()),
+        upstream_mutation: schema.mutation_type.as_ref().map(|t| t.name.as_str()),
+        query,
+        mutation,
+    };
+
+    let sdl = to_sdl(&schema, &names)?;
+    let mut config = Config::from_sdl(&sdl).to_result()?;
+
+    let root_types = [schema.query_type.as_ref(), schema.mutation_type.as_ref()];
+    for root_type in root_types.into_iter().flatten() {
+        let Some(type_of) = config.types.get_mut(names.get(&root_type.name)) else {
+            continue;
+        };
+
+        for (name, field) in type_of.fields.iter_mut() {
+            let args = field
+                .args
+                .keys()
+                .map(|arg| KeyValue { key: arg.clone(), value: format!("{{{{.args.{}}}}}", arg) })
+                .collect::<Vec<_>>();
+
+            field.resolvers = Resolver::Graphql(GraphQL {
+                args: (!args.is_empty()).then_some(args),
+                url: url.to_string(),
+                name: name.clone(),
+                ..Default::default()
+            })
+            .into();
+        }
+    }
+
+    Ok(config)
+}
+
+fn to_sdl(schema: &Schema, names: &Names) -> Result<String> {
+    let mut sdl = String::new();
+
+    writeln!(sdl, "schema {{")?;
+    if let Some(query_type) = &schema.query_type {
+        writeln!(sdl, "  query: {}", names.get(&query_type.name))?;
+    }
+    if let Some(mutation_type) = &schema.mutation_type {
+        writeln!(sdl, "  mutation: {}", names.get(&mutation_type.name))?;
+    }
+    writeln!(sdl, "}}")?;
+
+    let types = schema
+        .types
+        .iter()
+        .filter(|t| !t.name.starts_with("__") && !BUILTIN_SCALARS.contains(&t.name.as_str()));
+
+    for type_of in types {
+        let name = names.get(&type_of.name);
+        writeln!(sdl)?;
+        write_description(&mut sdl, &type_of.description, "")?;
+
+        match type_of.kind.as_str() {
+            "SCALAR" => writeln!(sdl, "scalar {}", name)?,
+            "UNION" => {
+                let types = type_of
+                    .possible_types
+                    .iter()
+                    .flatten()
+                    .map(|t| names.type_ref(t))
+                    .collect::<Result<Vec<_>>>()?;
+                writeln!(sdl, "union {} = {}", name, types.join(" | "))?;
+            }
+            "ENUM" => {
+                writeln!(sdl, "enum {} {{", name)?;
+                for value in type_of.enum_values.iter().flatten() {
+                    write_description(&mut sdl, &value.description, "  ")?;
+                    writeln!(sdl, "  {}", value.name)?;
+                }
+                writeln!(sdl, "}}")?;
+            }
+            "INPUT_OBJECT" => {
+                writeln!(sdl, "input {} {{", name)?;
+                for field in type_of.input_fields.iter().flatten() {
+                    write_description(&mut sdl, &field.description, "  ")?;
+                    writeln!(sdl, "  {}", input_value(field, names)?)?;
+                }
+                writeln!(sdl, "}}")?;
+            }
+            "OBJECT" | "INTERFACE" => {
+                let keyword = if type_of.kind == "OBJECT" {
+                    "type"
+                } else {
+                    "interface"
+                };
+                let interfaces = type_of
+                    .interfaces
+                    .iter()
+                    .flatten()
+                    .map(|t| names.type_ref(t))
+                    .collect::<Result<Vec<_>>>()?;
+
+                write!(sdl, "{} {}", keyword, name)?;
+                if !interfaces.is_empty() {
+                    write!(sdl, " implements {}", interfaces.join(" & "))?;
+                }
+                writeln!(sdl, " {{")?;
+                for field in type_of.fields.iter().flatten() {
+                    write_description(&mut sdl, &field.description, "  ")?;
+                    write!(sdl, "  {}", field.name)?;
+                    if !field.args.is_empty() {
+                        let args = field
+                            .args
+                            .iter()
+                            .map(|arg| input_value(arg, names))
+                            .collect::<Result<Vec<_>>>()?;
+                        write!(sdl, "({})", args.join(", "))?;
+                    }
+                    writeln!(sdl, ": {}", names.type_ref(&field.type_of)?)?;
+                }
+                writeln!(sdl, "}}")?;
+            }
+            kind => return Err(anyhow!("Unknown kind {} of type {}", kind, type_of.name)),
+        }
+    }
+
+    Ok(sdl)
+}
+
+fn input_value(value: &InputValue, names: &Names) -> Result<String> {
+    let type_of = names.type_ref(&value.type_of)?;
+    Ok(match &value.default_value {
+        Some(default_value) => format!("{}: {} = {}", value.name, type_of, default_value),
+        None => format!("{}: {}", value.name, type_of),
+    })
+}
+
+fn write_description(sdl: &mut String, description: &Option<String>, indent: &str) -> Result<()> {
+    if let Some(description) = description.as_ref().filter(|d| !d.is_empty()) {
+        let description = description.replace(r#"""""#, r#"\""""#);
+        writeln!(sdl, r#"{indent}"""{description}""""#)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tailcall_valid::Validator;
+
+    use super::from_graphql;
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::{Config, ConfigModule};
+
+    fn type_ref(kind: &str, name: &str) -> serde_json::Value {
+        json!({"kind": kind, "name": name, "ofType": null})
+    }
+
+    fn non_null(of_type: serde_json::Value) -> serde_json::Value {
+        json!({"kind": "NON_NULL", "name": null, "ofType": of_type})
+    }
+
+    fn list(of_type: serde_json::Value) -> serde_json::Value {
+        json!({"kind": "LIST", "name": null, "ofType": of_type})
+    }
+
+    fn introspection() -> serde_json::Value {
+        json!({
+            "data": {
+                "__schema": {
+                    "queryType": {"name": "Root"},
+                    "mutationType": {"name": "Mutation"},
+                    "types": [
+                        {
+                            "kind": "OBJECT",
+                            "name": "Root",
+                            "description": null,
+                            "fields": [
+                                {
+                                    "name": "user",
+                                    "description": "Finds a user by id",
+                                    "args": [{
+                                        "name": "id",
+                                        "description": null,
+                                        "type": non_null(type_ref("SCALAR", "ID")),
+                                        "defaultValue": null
+                                    }],
+                                    "type": type_ref("OBJECT", "User")
+                                },
+                                {
+                                    "name": "users",
+                                    "description": null,
+                                    "args": [{
+                                        "name": "first",
+                                        "description": null,
+                                        "type": type_ref("SCALAR", "Int"),
+                                        "defaultValue": "10"
+                                    }],
+                                    "type": non_null(list(non_null(type_ref("OBJECT", "User"))))
+                                }
+                            ],
+                            "inputFields": null,
+                            "interfaces": [],
+                            "enumValues": null,
+                            "possibleTypes": null
+                        },
+                        {
+                            "kind": "OBJECT",
+                            "name": "Mutation",
+                            "description": null,
+                            "fields": [{
+                                "name": "createUser",
+                                "description": null,
+                                "args": [{
+                                    "name": "input",
+                                    "description": null,
+                                    "type": non_null(type_ref("INPUT_OBJECT", "UserInput")),
+                                    "defaultValue": null
+                                }],
+                                "type": type_ref("OBJECT", "User")
+                            }],
+                            "inputFields": null,
+                            "interfaces": [],
+                            "enumValues": null,
+                            "possibleTypes": null
+                        },
+                        {
+                            "kind": "OBJECT",
+                            "name": "User",
+                            "description": "A registered user",
+                            "fields": [
+                                {
+                                    "name": "id",
+                                    "description": null,
+                                    "args": [],
+                                    "type": non_null(type_ref("SCALAR", "ID"))
+                                },
+                                {
+                                    "name": "name",
+                                    "description": null,
+                                    "args": [],
+                                    "type": type_ref("SCALAR", "String")
+                                },
+                                {
+                                    "name": "role",
+                                    "description": null,
+                                    "args": [],
+                                    "type": type_ref("ENUM", "Role")
+                                }
+                            ],
+                            "inputFields": null,
+                            "interfaces": [],
+                            "enumValues": null,
+                            "possibleTypes": null
+                        },
+                        {
+                            "kind": "INPUT_OBJECT",
+                            "name": "UserInput",
+                            "description": null,
+                            "fields": null,
+                            "inputFields": [{
+                                "name": "name",
+                                "description": null,
+                                "type": non_null(type_ref("SCALAR", "String")),
+                                "defaultValue": null
+                            }],
+                            "interfaces": null,
+                            "enumValues": null,
+                            "possibleTypes": null
+                        },
+                        {
+                            "kind": "ENUM",
+                            "name": "Role",
+                            "description": null,
+                            "fields": null,
+                            "inputFields": null,
+                            "interfaces": null,
+                            "enumValues": [
+                                {"name": "ADMIN", "description": null},
+                                {"name": "MEMBER", "description": null}
+                            ],
+                            "possibleTypes": null
+                        },
+                        {
+                            "kind": "SCALAR",
+                            "name": "String",
+                            "description": null,
+                            "fields": null,
+                            "inputFields": null,
+                            "interfaces": null,
+                            "enumValues": null,
+                            "possibleTypes": null
+                        },
+                        {
+                            "kind": "OBJECT",
+                            "name": "__Schema",
+                            "description": null,
+                            "fields": [],
+                            "inputFields": null,
+                            "interfaces": [],
+                            "enumValues": null,
+                            "possibleTypes": null
+                        }
+                    ]
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_from_graphql() {
+        let url = "http://upstream/graphql";
+        let config = from_graphql(&introspection(), url, "Query", None).unwrap();
+
+        let expected = Config::from_sdl(
+            r#"
+            schema {
+              query: Query
+              mutation: Mutation
+            }
+
+            type Query {
+              "Finds a user by id"
+              user(id: ID!): User @graphQL(url: "http://upstream/graphql", name: "user", args: [{key: "id", value: "{{.args.id}}"}])
+              users(first: Int = 10): [User!]! @graphQL(url: "http://upstream/graphql", name: "users", args: [{key: "first", value: "{{.args.first}}"}])
+            }
+
+            type Mutation {
+              createUser(input: UserInput!): User @graphQL(url: "http://upstream/graphql", name: "createUser", args: [{key: "input", value: "{{.args.input}}"}])
+            }
+
+            "A registered user"
+            type User {
+              id: ID!
+              name: String
+              role: Role
+            }
+
+            input UserInput {
+              name: String!
+            }
+
+            enum Role {
+              ADMIN
+              MEMBER
+            }
+            "#,
+        )
+        .to_result()
+        .unwrap();
+
+        pretty_assertions::assert_eq!(config.to_sdl(), expected.to_sdl());
+
+        // the generated config is a valid config that resolves from the upstream
+        Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+    }
+}
@@ -207,12 +207,13 @@ impl Context {
 
             let variants_with_comments = variants_with_comments
                 .into_iter()
-                .map(|v| Variant { name: v, alias: None })
+                .map(|v| Variant { name: v, alias: None, upstream_value: None })
                 .collect();
 
-            self.config
-                .enums
-                .insert(type_name, Enum { variants: variants_with_comments, doc });
+            self.config.enums.insert(
+                type_name,
+                Enum { variants: variants_with_comments, doc, unmapped: None },
+            );
         }
         self
     }
@@ -355,6 +356,7 @@ impl Context {
                          * from proto file */
                         doc: None,
                         modify: None,
+                        omit: None,
                         default_value: None,
                     };
 
@@ -376,6 +378,7 @@ impl Context {
                     dedupe: None,
                     select: None,
                     on_response_body: None,
+                    web: None,
                 })
                 .into();
 
@@ -7,6 +7,7 @@ use serde_json::Value;
 use tailcall_valid::Validator;
 use url::Url;
 
+use super::from_graphql::from_graphql;
 use super::from_proto::from_proto;
 use super::proto::connect_rpc::ConnectRPC;
 use super::{FromJsonGenerator, NameGenerator, RequestSample, PREFIX};
@@ -49,6 +50,10 @@ pub enum Input {
         schema: String,
         source: config::Source,
     },
+    GraphQL {
+        url: String,
+        introspection: Value,
+    },
 }
 
 impl Default for Generator {
@@ -145,6 +150,11 @@ impl Generator {
                     };
                     config = config.merge_right(proto_config);
                 }
+                Input::GraphQL { url, introspection } => {
+                    let graphql_config =
+                        from_graphql(introspection, url, &self.query, self.mutation.as_deref())?;
+                    config = config.merge_right(graphql_config);
+                }
             }
         }
 
@@ -1,3 +1,4 @@
+mod from_graphql;
 mod from_json;
 mod from_proto;
 mod generator;
@@ -5,6 +6,7 @@ mod graphql_type;
 mod json;
 mod proto;
 
+pub use from_graphql::{from_graphql, INTROSPECTION_QUERY};
 pub use from_json::{FromJsonGenerator, RequestSample};
 pub use generator::{Generator, Input};
 
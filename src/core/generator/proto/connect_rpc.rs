@@ -85,6 +85,7 @@ mod tests {
             dedupe: Default::default(),
             select: Default::default(),
             on_response_body: Default::default(),
+            web: Default::default(),
         };
 
         let http = Http::from(grpc);
@@ -105,6 +106,7 @@ mod tests {
             dedupe: Default::default(),
             select: Default::default(),
             on_response_body: Default::default(),
+            web: Default::default(),
         };
 
         let http = Http::from(grpc);
@@ -123,6 +125,7 @@ mod tests {
             dedupe: Default::default(),
             select: Default::default(),
             on_response_body: Default::default(),
+            web: Default::default(),
         };
 
         let http = Http::from(grpc);
@@ -150,6 +153,7 @@ mod tests {
             dedupe: Some(true),
             select: Some(Value::String("select_value".to_string())),
             on_response_body: Some("on_response_body_value".to_string()),
+            web: None,
         };
 
         let http = Http::from(grpc);
@@ -1,10 +1,13 @@
 use std::collections::HashMap;
+use std::ops::Deref;
 use std::str::from_utf8;
 use std::sync::Arc;
 use std::time::Duration;
 
 use async_graphql::async_trait;
 use async_graphql::futures_util::future::join_all;
+use async_graphql_value::{ConstValue, Name};
+use indexmap::IndexMap;
 
 use crate::core::config::Batch;
 use crate::core::data_loader::{DataLoader, Loader};
@@ -14,11 +17,17 @@ use crate::core::runtime::TargetRuntime;
 pub struct GraphqlDataLoader {
     pub runtime: TargetRuntime,
     pub batch: bool,
+    pub merge: bool,
 }
 
 impl GraphqlDataLoader {
     pub fn new(runtime: TargetRuntime, batch: bool) -> Self {
-        GraphqlDataLoader { runtime, batch }
+        GraphqlDataLoader { runtime, batch, merge: false }
+    }
+
+    /// Merges the requests into a single query, see `GraphQL::merge`
+    pub fn merge(self, merge: bool) -> Self {
+        Self { merge, ..self }
     }
 
     pub fn into_data_loader(
@@ -41,7 +50,21 @@ impl Loader<DataLoaderRequest> for GraphqlDataLoader {
         &self,
         keys: &[DataLoaderRequest],
     ) -> async_graphql::Result<HashMap<DataLoaderRequest, Self::Value>, Self::Error> {
-        if self.batch {
+        if self.merge {
+            let results = merge_groups(keys).into_iter().map(|group| async move {
+                let merged_req = create_merged_request(&group);
+                let result = self.runtime.http.execute(merged_req).await?.to_json()?;
+                anyhow::Ok(split_merged_response(result, &group))
+            });
+
+            #[allow(clippy::mutable_key_type)]
+            let mut hashmap = HashMap::new();
+            for result in join_all(results).await {
+                hashmap.extend(result?);
+            }
+
+            Ok(hashmap)
+        } else if self.batch {
             let batched_req = create_batched_request(keys);
             let result = self.runtime.http.execute(batched_req).await?.to_json();
             let hashmap = extract_responses(result, keys);
@@ -116,6 +139,127 @@ fn extract_responses(
     hashmap
 }
 
+/// Groups the requests that can be merged into a single query, i.e. the ones
+/// with the same headers. The requests are ordered by their fields, so that
+/// the same fields always produce the same query.
+fn merge_groups(keys: &[DataLoaderRequest]) -> Vec<Vec<&DataLoaderRequest>> {
+    let mut keys = keys.iter().collect::<Vec<_>>();
+    keys.sort_by(|a, b| a.batching_value().cmp(&b.batching_value()));
+
+    let mut groups: Vec<Vec<&DataLoaderRequest>> = vec![];
+    for key in keys {
+        match groups
+            .iter_mut()
+            .find(|group| group[0].deref().headers() == key.deref().headers())
+        {
+            Some(group) => group.push(key),
+            None => groups.push(vec![key]),
+        }
+    }
+    groups
+}
+
+fn create_merged_request(group: &[&DataLoaderRequest]) -> reqwest::Request {
+    let fields = group
+        .iter()
+        .enumerate()
+        .map(|(i, req)| {
+            let field = req.batching_value().map(String::as_str).unwrap_or_default();
+            format!("{}: {}", alias(i), field)
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut merged_req = group[0].to_request();
+    merged_req.body_mut().replace(reqwest::Body::from(format!(
+        r#"{{ "query": "query {{ {} }}" }}"#,
+        fields
+    )));
+    merged_req
+}
+
+fn alias(i: usize) -> String {
+    format!("f{}", i)
+}
+
+/// Splits the response of the merged query into the responses of the
+/// requests: every request gets the data of its alias under the name of its
+/// field, along with the errors of the field and the errors without a path.
+#[allow(clippy::mutable_key_type)]
+fn split_merged_response(
+    res: Response<ConstValue>,
+    group: &[&DataLoaderRequest],
+) -> HashMap<DataLoaderRequest, Response<ConstValue>> {
+    let (data, errors) = match &res.body {
+        ConstValue::Object(body) => (body.get("data"), body.get("errors")),
+        _ => (None, None),
+    };
+    let errors = match errors {
+        Some(ConstValue::List(errors)) => errors.as_slice(),
+        _ => &[],
+    };
+
+    group
+        .iter()
+        .enumerate()
+        .map(|(i, request)| {
+            let alias = alias(i);
+            let field = request.batching_value().map(String::as_str).unwrap_or_default();
+            let name = Name::new(field_name(field));
+
+            let value = match data {
+                Some(ConstValue::Object(data)) => data.get(alias.as_str()).cloned(),
+                _ => None,
+            };
+            let errors = errors
+                .iter()
+                .filter_map(|error| {
+                    let ConstValue::Object(error) = error else {
+                        return None;
+                    };
+                    match error.get("path") {
+                        Some(ConstValue::List(path)) => {
+                            if !matches!(path.first(), Some(ConstValue::String(first)) if *first == alias) {
+                                return None;
+                            }
+                            let mut path = path.clone();
+                            path[0] = ConstValue::String(name.to_string());
+                            let mut error = error.clone();
+                            error.insert(Name::new("path"), ConstValue::List(path));
+                            Some(ConstValue::Object(error))
+                        }
+                        _ => Some(ConstValue::Object(error.clone())),
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let mut body = IndexMap::new();
+            body.insert(
+                Name::new("data"),
+                ConstValue::Object(IndexMap::from([(name, value.unwrap_or_default())])),
+            );
+            if !errors.is_empty() {
+                body.insert(Name::new("errors"), ConstValue::List(errors));
+            }
+
+            let response = Response {
+                status: res.status,
+                headers: res.headers.clone(),
+                body: ConstValue::Object(body),
+            };
+            ((*request).clone(), response)
+        })
+        .collect()
+}
+
+/// Returns the name of the root field, e.g. `user` for `user(id: 1) { name }`
+fn field_name(field: &str) -> &str {
+    field
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .next()
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeSet;
@@ -148,4 +292,51 @@ mod tests {
         let body = collect_request_bodies(&[dl_req1, dl_req2, dl_req3]);
         assert_eq!(body, "[a,b,c]");
     }
+
+    #[test]
+    fn test_merge_requests() {
+        let url = Url::parse("http://example.com").unwrap();
+        let request = |field: &str| {
+            let mut req = reqwest::Request::new(reqwest::Method::POST, url.clone());
+            req.body_mut()
+                .replace(reqwest::Body::from(field.to_string()));
+            DataLoaderRequest::new(req, BTreeSet::new()).with_batching_value(Some(field.into()))
+        };
+        let keys = [
+            request("user(id: 1) { name }"),
+            request("post(id: 1) { title }"),
+        ];
+
+        let groups = merge_groups(&keys);
+        assert_eq!(groups.len(), 1);
+
+        let merged_req = create_merged_request(&groups[0]);
+        let body = merged_req.body().and_then(|body| body.as_bytes()).unwrap();
+        assert_eq!(
+            from_utf8(body).unwrap(),
+            r#"{ "query": "query { f0: post(id: 1) { title } f1: user(id: 1) { name } }" }"#
+        );
+
+        let res = Response {
+            body: ConstValue::from_json(serde_json::json!({
+                "data": {"f0": {"title": "Hello"}, "f1": null},
+                "errors": [{"message": "Not found", "path": ["f1"]}]
+            }))
+            .unwrap(),
+            ..Default::default()
+        };
+        let responses = split_merged_response(res, &groups[0]);
+
+        assert_eq!(
+            responses[&keys[1]].body.clone().into_json().unwrap(),
+            serde_json::json!({"data": {"post": {"title": "Hello"}}})
+        );
+        assert_eq!(
+            responses[&keys[0]].body.clone().into_json().unwrap(),
+            serde_json::json!({
+                "data": {"user": null},
+                "errors": [{"message": "Not found", "path": ["user"]}]
+            })
+        );
+    }
 }
@@ -1,6 +1,7 @@
 #![allow(clippy::too_many_arguments)]
 
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 
 use derive_setters::Setters;
@@ -14,8 +15,8 @@ use crate::core::helpers::headers::MustacheHeaders;
 use crate::core::http::Method::POST;
 use crate::core::ir::model::{CacheKey, IoId};
 use crate::core::ir::{GraphQLOperationContext, RelatedFields};
-use crate::core::mustache::Mustache;
-use crate::core::path::{PathGraphql, PathString};
+use crate::core::mustache::{Mustache, Segment};
+use crate::core::path::{PathGraphql, PathString, PathValue, ValueString};
 
 /// Represents a GraphQL selection that can either be resolved or unresolved.
 #[derive(Debug, Clone)]
@@ -53,6 +54,8 @@ pub struct RequestTemplate {
     pub operation_type: GraphQLOperationType,
     pub operation_name: String,
     pub operation_arguments: Option<Vec<(String, Mustache)>>,
+    /// The arguments sent as variables, mapped to the types of the variables
+    pub variables: HashMap<String, String>,
     pub headers: MustacheHeaders,
     pub related_fields: RelatedFields,
     pub selection: Option<Selection>,
@@ -90,7 +93,7 @@ impl RequestTemplate {
         req
     }
 
-    pub fn to_request<C: PathGraphql + HasHeaders + GraphQLOperationContext>(
+    pub fn to_request<C: PathGraphql + PathValue + HasHeaders + GraphQLOperationContext>(
         &self,
         ctx: &C,
     ) -> anyhow::Result<reqwest::Request> {
@@ -100,7 +103,7 @@ impl RequestTemplate {
         Ok(req)
     }
 
-    fn set_body<C: PathGraphql + HasHeaders + GraphQLOperationContext>(
+    fn set_body<C: PathGraphql + PathValue + HasHeaders + GraphQLOperationContext>(
         &self,
         mut req: reqwest::Request,
         ctx: &C,
@@ -110,12 +113,45 @@ impl RequestTemplate {
         req
     }
 
-    fn render_graphql_query<C: PathGraphql + HasHeaders + GraphQLOperationContext>(
+    fn render_graphql_query<C: PathGraphql + PathValue + HasHeaders + GraphQLOperationContext>(
         &self,
         ctx: &C,
     ) -> String {
         let operation_type = &self.operation_type;
+        let (field, definitions, variables) = self.render_operation(ctx);
+
+        let query = if definitions.is_empty() {
+            format!(r#"{{ "query": "{operation_type} {{ {field} }}" }}"#)
+        } else {
+            let definitions = definitions.join(", ");
+            let variables = serde_json::Value::Object(variables);
+            format!(
+                r#"{{ "query": "{operation_type}({definitions}) {{ {field} }}", "variables": {variables} }}"#
+            )
+        };
+        info!("Query {} ", query);
+        query
+    }
+
+    /// Renders the requested root field alone, to be merged with the fields of
+    /// the other requests into a single query, see `GraphQL::merge`.
+    pub fn render_field<C: PathGraphql + PathValue + HasHeaders + GraphQLOperationContext>(
+        &self,
+        ctx: &C,
+    ) -> String {
+        self.render_operation(ctx).0
+    }
 
+    /// Renders the root field with its args, directives and selection set,
+    /// along with the definitions and the values of the variables it uses.
+    fn render_operation<C: PathGraphql + PathValue + HasHeaders + GraphQLOperationContext>(
+        &self,
+        ctx: &C,
+    ) -> (
+        String,
+        Vec<String>,
+        serde_json::Map<String, serde_json::Value>,
+    ) {
         let selection_set = match &self.selection {
             Some(Selection::Resolved(s)) => Cow::Borrowed(s),
             Some(Selection::UnResolved(u)) => Cow::Owned(u.to_string()),
@@ -123,11 +159,20 @@ impl RequestTemplate {
         };
 
         let mut operation = Cow::Borrowed(&self.operation_name);
+        let mut definitions = vec![];
+        let mut variables = serde_json::Map::new();
 
         if let Some(args) = &self.operation_arguments {
             let args = args
                 .iter()
                 .filter_map(|(k, v)| {
+                    if let Some(type_of) = self.variables.get(k) {
+                        let value = variable_value(v, ctx)?;
+                        definitions.push(format!("${}: {}", k, type_of));
+                        variables.insert(k.clone(), value);
+                        return Some(format!("{}: ${}", k, k));
+                    }
+
                     let value = v.render_graphql(ctx);
                     if value.is_empty() {
                         None
@@ -156,10 +201,11 @@ impl RequestTemplate {
             }
         }
 
-        let query =
-            format!(r#"{{ "query": "{operation_type} {{ {operation} {selection_set} }}" }}"#);
-        info!("Query {} ", query);
-        query
+        (
+            format!("{operation} {selection_set}"),
+            definitions,
+            variables,
+        )
     }
 
     pub fn new(
@@ -186,13 +232,30 @@ impl RequestTemplate {
             operation_name: operation_name.to_owned(),
             operation_arguments,
             headers,
+            variables: HashMap::new(),
             related_fields,
             selection: None,
         })
     }
 }
 
-impl<Ctx: PathGraphql + HasHeaders + GraphQLOperationContext> CacheKey<Ctx> for RequestTemplate {
+/// Returns the JSON value of an argument sent as a variable, or `None` if the
+/// value isn't available, in which case the argument is omitted just like the
+/// inlined ones.
+fn variable_value(mustache: &Mustache, ctx: &impl PathValue) -> Option<serde_json::Value> {
+    let [Segment::Expression(parts)] = mustache.segments().as_slice() else {
+        return None;
+    };
+
+    match ctx.raw_value(parts)? {
+        ValueString::Value(value) => value.into_owned().into_json().ok(),
+        ValueString::String(value) => Some(serde_json::Value::String(value.into_owned())),
+    }
+}
+
+impl<Ctx: PathGraphql + PathValue + HasHeaders + GraphQLOperationContext> CacheKey<Ctx>
+    for RequestTemplate
+{
     fn cache_key(&self, ctx: &Ctx) -> Option<IoId> {
         let mut hasher = TailcallHasher::default();
         let graphql_query = self.render_graphql_query(ctx);
@@ -203,7 +266,8 @@ impl<Ctx: PathGraphql + HasHeaders + GraphQLOperationContext> CacheKey<Ctx> for
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use std::borrow::Cow;
+    use std::collections::{HashMap, HashSet};
 
     use async_graphql::Value;
     use http::header::HeaderMap;
@@ -217,7 +281,7 @@ mod tests {
     use crate::core::ir::model::CacheKey;
     use crate::core::ir::GraphQLOperationContext;
     use crate::core::json::JsonLike;
-    use crate::core::path::PathGraphql;
+    use crate::core::path::{PathGraphql, PathValue, ValueString};
 
     struct Context {
         pub value: Value,
@@ -230,6 +294,14 @@ mod tests {
         }
     }
 
+    impl PathValue for Context {
+        fn raw_value<'a, T: AsRef<str>>(&'a self, path: &[T]) -> Option<ValueString<'a>> {
+            self.value
+                .get_path(path)
+                .map(|v| ValueString::Value(Cow::Borrowed(v)))
+        }
+    }
+
     impl HasHeaders for Context {
         fn headers(&self) -> &HeaderMap {
             &self.headers
@@ -314,6 +386,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_query_with_variables() {
+        let tmpl = RequestTemplate::new(
+            "http://localhost:3000".to_string(),
+            &GraphQLOperationType::Mutation,
+            "create",
+            Some(
+                serde_json::from_str(
+                    r#"[{"key": "id", "value": "{{foo.bar}}"}, {"key": "struct", "value": "{{foo}}"}, {"key": "limit", "value": "10"}]"#,
+                )
+                .unwrap(),
+            )
+            .as_ref(),
+            vec![],
+            RelatedFields::default(),
+        )
+        .unwrap()
+        .variables(HashMap::from([
+            ("id".to_string(), "ID!".to_string()),
+            ("struct".to_string(), "Foo".to_string()),
+        ]));
+        let ctx = Context {
+            value: Value::from_json(json!({
+              "foo": {
+                "bar": "say \"hi\"",
+                "header": "abc"
+              }
+            }))
+            .unwrap(),
+            headers: Default::default(),
+        };
+
+        let req = tmpl.to_request(&ctx).unwrap();
+        let body = req.body().unwrap().as_bytes().unwrap().to_owned();
+
+        assert_eq!(
+            std::str::from_utf8(&body).unwrap(),
+            r#"{ "query": "mutation($id: ID!, $struct: Foo) { create(id: $id, struct: $struct, limit: 10) { a,b,c } }", "variables": {"id":"say \"hi\"","struct":{"bar":"say \"hi\"","header":"abc"}} }"#
+        );
+    }
+
     fn create_gql_request_template_and_ctx(json: serde_json::Value) -> (RequestTemplate, Context) {
         let value = Value::from_json(json).unwrap();
 
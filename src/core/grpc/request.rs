@@ -1,15 +1,29 @@
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use http::header::{HeaderName, HeaderValue, CONTENT_TYPE};
 use http::{HeaderMap, Method};
+use hyper::body::Bytes;
 use reqwest::Request;
 use url::Url;
 
 use super::protobuf::ProtobufOperation;
+use crate::core::config::GrpcWeb;
 use crate::core::http::Response;
 use crate::core::runtime::TargetRuntime;
 
 pub static GRPC_STATUS: &str = "grpc-status";
+pub static GRPC_WEB_HEADER: HeaderName = HeaderName::from_static("x-grpc-web");
+
+/// The flag of the frame that holds the trailers in a gRPC-Web response
+const TRAILERS_FLAG: u8 = 0x80;
 
 pub fn create_grpc_request(url: Url, headers: HeaderMap, body: Vec<u8>) -> Request {
+    let body = match grpc_web(&headers) {
+        Some(GrpcWeb::Text) => BASE64_STANDARD.encode(body).into_bytes(),
+        _ => body,
+    };
+
     let mut req = Request::new(Method::POST, url);
     req.headers_mut().extend(headers.clone());
     req.body_mut().replace(body.into());
@@ -22,7 +36,11 @@ pub async fn execute_grpc_request(
     operation: &ProtobufOperation,
     request: Request,
 ) -> Result<Response<async_graphql::Value>> {
-    let response = runtime.http2_only.execute(request).await?;
+    let response = match grpc_web(request.headers()) {
+        // gRPC-Web proxies usually don't speak HTTP/2
+        Some(web) => decode_grpc_web(runtime.http.execute(request).await?, web)?,
+        None => runtime.http2_only.execute(request).await?,
+    };
 
     let grpc_status = response
         .headers
@@ -39,13 +57,79 @@ pub async fn execute_grpc_request(
     bail!("Failed to execute request");
 }
 
+/// Returns the gRPC-Web framing of the request from its content-type, `None`
+/// for plain gRPC.
+fn grpc_web(headers: &HeaderMap) -> Option<GrpcWeb> {
+    let content_type = headers.get(CONTENT_TYPE)?.to_str().ok()?;
+
+    if content_type.starts_with("application/grpc-web-text") {
+        Some(GrpcWeb::Text)
+    } else if content_type.starts_with("application/grpc-web") {
+        Some(GrpcWeb::Binary)
+    } else {
+        None
+    }
+}
+
+/// Converts a gRPC-Web response into the shape of a gRPC one: the message
+/// frames are kept as the body and the trailers, which gRPC-Web sends as the
+/// last frame of the body, are moved to the headers.
+fn decode_grpc_web(mut response: Response<Bytes>, web: GrpcWeb) -> Result<Response<Bytes>> {
+    let body = match web {
+        GrpcWeb::Binary => response.body.to_vec(),
+        // every chunk of the stream is padded separately, so the groups of four
+        // characters are decoded one by one
+        GrpcWeb::Text => response
+            .body
+            .chunks(4)
+            .map(|chunk| BASE64_STANDARD.decode(chunk))
+            .collect::<Result<Vec<_>, _>>()
+            .context("Invalid base64 in the gRPC-Web response")?
+            .concat(),
+    };
+
+    let mut message = Vec::new();
+    let mut rest = body.as_slice();
+    while !rest.is_empty() {
+        let (flag, len) = match rest {
+            [flag, a, b, c, d, ..] => (*flag, u32::from_be_bytes([*a, *b, *c, *d]) as usize),
+            _ => bail!("Invalid frame in the gRPC-Web response"),
+        };
+        let frame = rest
+            .get(..5 + len)
+            .context("Invalid frame in the gRPC-Web response")?;
+
+        if flag & TRAILERS_FLAG == 0 {
+            message.extend_from_slice(frame);
+        } else {
+            let trailers = std::str::from_utf8(&frame[5..])?;
+            for (name, value) in trailers
+                .split("\r\n")
+                .filter_map(|line| line.split_once(':'))
+            {
+                response.headers.insert(
+                    HeaderName::from_bytes(name.trim().as_bytes())?,
+                    HeaderValue::from_str(value.trim())?,
+                );
+            }
+        }
+
+        rest = &rest[5 + len..];
+    }
+
+    response.body = message.into();
+    Ok(response)
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
     use anyhow::Result;
     use async_trait::async_trait;
-    use http::header::HeaderMap;
+    use base64::prelude::BASE64_STANDARD;
+    use base64::Engine;
+    use http::header::{HeaderMap, CONTENT_TYPE};
     use http::{Method, StatusCode};
     use hyper::body::Bytes;
     use reqwest::Request;
@@ -54,8 +138,9 @@ mod tests {
     use tonic::{Code, Status};
 
     use crate::core::blueprint::GrpcMethod;
+    use crate::core::config::GrpcWeb;
     use crate::core::grpc::protobuf::{ProtobufOperation, ProtobufSet};
-    use crate::core::grpc::request::execute_grpc_request;
+    use crate::core::grpc::request::{create_grpc_request, execute_grpc_request};
     use crate::core::http::Response;
     use crate::core::ir::Error;
     use crate::core::runtime::TargetRuntime;
@@ -188,6 +273,93 @@ mod tests {
         Ok(())
     }
 
+    struct GrpcWebHttp {
+        web: GrpcWeb,
+        trailers: &'static str,
+    }
+
+    #[async_trait]
+    impl HttpIO for GrpcWebHttp {
+        async fn execute(&self, request: Request) -> Result<Response<Bytes>> {
+            let content_type = request.headers().get(CONTENT_TYPE).unwrap();
+            let body = request.body().and_then(|body| body.as_bytes()).unwrap();
+            let input = b"\0\0\0\0\x06\n\x04test";
+            match self.web {
+                GrpcWeb::Binary => {
+                    assert_eq!(content_type, "application/grpc-web+proto");
+                    assert_eq!(body, input);
+                }
+                GrpcWeb::Text => {
+                    assert_eq!(content_type, "application/grpc-web-text+proto");
+                    assert_eq!(body, BASE64_STANDARD.encode(input).as_bytes());
+                }
+            }
+
+            let message = b"\0\0\0\0\x0e\n\x0ctest message";
+            let mut trailers = vec![0x80];
+            trailers.extend((self.trailers.len() as u32).to_be_bytes());
+            trailers.extend(self.trailers.as_bytes());
+
+            let body = match self.web {
+                GrpcWeb::Binary => [message.as_slice(), trailers.as_slice()].concat(),
+                // the frames are encoded separately, as streamed by the proxies
+                GrpcWeb::Text => format!(
+                    "{}{}",
+                    BASE64_STANDARD.encode(message),
+                    BASE64_STANDARD.encode(trailers)
+                )
+                .into_bytes(),
+            };
+
+            Ok(Response {
+                status: StatusCode::OK,
+                headers: HeaderMap::new(),
+                body: body.into(),
+            })
+        }
+    }
+
+    async fn execute_grpc_web(web: GrpcWeb, trailers: &'static str) -> Result<serde_json::Value> {
+        let (mut runtime, operation, _) =
+            prepare_args(TestHttp { scenario: TestScenario::Error }).await?;
+        runtime.http = Arc::new(GrpcWebHttp { web, trailers });
+
+        let content_type = match web {
+            GrpcWeb::Binary => "application/grpc-web+proto",
+            GrpcWeb::Text => "application/grpc-web-text+proto",
+        };
+        let headers = HeaderMap::from_iter([(CONTENT_TYPE, content_type.parse()?)]);
+        let request = create_grpc_request(
+            "http://example.com".parse()?,
+            headers,
+            operation.convert_input(r#"{"name": "test"}"#)?,
+        );
+
+        let response = execute_grpc_request(&runtime, &operation, request).await?;
+        Ok(serde_json::to_value(response.body)?)
+    }
+
+    #[tokio::test]
+    async fn test_grpc_web_request() -> Result<()> {
+        for web in [GrpcWeb::Binary, GrpcWeb::Text] {
+            let actual = execute_grpc_web(web, "grpc-status: 0\r\ngrpc-message: \r\n").await?;
+            assert_eq!(actual, json!({"message": "test message"}), "for {web:?}");
+
+            let error = execute_grpc_web(web, "grpc-status: 3\r\ngrpc-message: invalid\r\n")
+                .await
+                .unwrap_err();
+            match error.downcast_ref::<Error>() {
+                Some(Error::GRPC { grpc_code, grpc_status_message, .. }) => {
+                    assert_eq!(*grpc_code, Code::InvalidArgument as i32);
+                    assert_eq!(grpc_status_message, "invalid");
+                }
+                _ => panic!("Expected GRPCError for {web:?}"),
+            }
+        }
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_grpc_request_error() -> Result<()> {
         let test_http = TestHttp { scenario: TestScenario::Error };
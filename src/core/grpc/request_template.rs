@@ -6,8 +6,8 @@ use http::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use tailcall_hasher::TailcallHasher;
 use url::Url;
 
-use super::request::create_grpc_request;
-use crate::core::config::GraphQLOperationType;
+use super::request::{create_grpc_request, GRPC_WEB_HEADER};
+use crate::core::config::{GraphQLOperationType, GrpcWeb};
 use crate::core::grpc::protobuf::ProtobufOperation;
 use crate::core::has_headers::HasHeaders;
 use crate::core::helpers::headers::MustacheHeaders;
@@ -16,6 +16,9 @@ use crate::core::mustache::Mustache;
 use crate::core::path::PathString;
 
 static GRPC_MIME_TYPE: HeaderValue = HeaderValue::from_static("application/grpc");
+static GRPC_WEB_MIME_TYPE: HeaderValue = HeaderValue::from_static("application/grpc-web+proto");
+static GRPC_WEB_TEXT_MIME_TYPE: HeaderValue =
+    HeaderValue::from_static("application/grpc-web-text+proto");
 
 #[derive(Setters, Debug, Clone)]
 pub struct RequestTemplate {
@@ -24,6 +27,7 @@ pub struct RequestTemplate {
     pub body: Option<RequestBody>,
     pub operation: ProtobufOperation,
     pub operation_type: GraphQLOperationType,
+    pub web: Option<GrpcWeb>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Setters)]
@@ -67,7 +71,15 @@ impl RequestTemplate {
     fn create_headers<C: PathString>(&self, ctx: &C) -> HeaderMap {
         let mut header_map = HeaderMap::new();
 
-        header_map.insert(CONTENT_TYPE, GRPC_MIME_TYPE.to_owned());
+        let content_type = match self.web {
+            None => &GRPC_MIME_TYPE,
+            Some(GrpcWeb::Binary) => &GRPC_WEB_MIME_TYPE,
+            Some(GrpcWeb::Text) => &GRPC_WEB_TEXT_MIME_TYPE,
+        };
+        header_map.insert(CONTENT_TYPE, content_type.to_owned());
+        if self.web.is_some() {
+            header_map.insert(GRPC_WEB_HEADER, HeaderValue::from_static("1"));
+        }
 
         for (k, v) in &self.headers {
             if let Ok(header_value) = HeaderValue::from_str(&v.render(ctx)) {
@@ -135,16 +147,18 @@ mod tests {
     use http::header::{HeaderMap, HeaderName, HeaderValue};
     use http::Method;
     use pretty_assertions::assert_eq;
+    use serde_json::json;
     use tailcall_fixtures::protobuf;
+    use tailcall_valid::Validator;
 
     use super::{RequestBody, RequestTemplate};
-    use crate::core::blueprint::GrpcMethod;
+    use crate::core::blueprint::{Blueprint, Definition, GrpcMethod};
     use crate::core::config::reader::ConfigReader;
     use crate::core::config::{
         Config, Field, GraphQLOperationType, Grpc, Link, LinkType, Resolver, Type,
     };
     use crate::core::grpc::protobuf::{ProtobufOperation, ProtobufSet};
-    use crate::core::ir::model::CacheKey;
+    use crate::core::ir::model::{CacheKey, IO, IR};
     use crate::core::mustache::Mustache;
 
     async fn get_protobuf_op() -> ProtobufOperation {
@@ -227,6 +241,7 @@ mod tests {
             operation: get_protobuf_op().await,
             body: None,
             operation_type: GraphQLOperationType::Query,
+            web: None,
         };
         let ctx = Context::default();
         let rendered = tmpl.render(&ctx).unwrap();
@@ -264,6 +279,7 @@ mod tests {
                 value: Default::default(),
             }),
             operation_type: GraphQLOperationType::Query,
+            web: None,
         };
         let ctx = Context::default();
         let rendered = tmpl.render(&ctx).unwrap();
@@ -284,6 +300,7 @@ mod tests {
                 value: Default::default(),
             }),
             operation_type: GraphQLOperationType::Query,
+            web: None,
         }
     }
 
@@ -310,4 +327,58 @@ mod tests {
 
         assert_eq!(arr.len(), tmpl_set.len());
     }
+
+    #[tokio::test]
+    async fn request_with_templated_body() {
+        let sdl = format!(
+            r#"
+            schema @link(src: "{}", type: Protobuf) {{
+              query: Query
+            }}
+
+            type Query {{
+              hello(name: String!): HelloReply
+                @grpc(url: "http://localhost:50051", method: "greetings.Greeter.SayHello", body: {{name: "Hello {{{{.args.name}}}}"}})
+              helloArgs(name: String!): HelloReply
+                @grpc(url: "http://localhost:50051", method: "greetings.Greeter.SayHello", body: "{{{{.args}}}}")
+            }}
+
+            type HelloReply {{
+              message: String
+            }}
+            "#,
+            protobuf::GREETINGS
+        );
+        let config = Config::from_sdl(&sdl).to_result().unwrap();
+        let runtime = crate::core::runtime::test::init(None);
+        let config_module = ConfigReader::init(runtime)
+            .resolve(config, None)
+            .await
+            .unwrap();
+        let blueprint = Blueprint::try_from(&config_module).unwrap();
+        let ctx = Context::default().value(json!({"args": {"name": "test"}}));
+
+        let body = |field: &str| {
+            let resolver = blueprint.definitions.iter().find_map(|def| match def {
+                Definition::Object(def) if def.name == "Query" => def
+                    .fields
+                    .iter()
+                    .find(|def| def.name == field)
+                    .and_then(|def| def.resolver.as_ref()),
+                _ => None,
+            });
+            let Some(IR::IO(IO::Grpc { req_template, .. })) = resolver else {
+                panic!("expected a @grpc resolver on {field}");
+            };
+            let req = req_template.render(&ctx).unwrap().to_request().unwrap();
+
+            req.body()
+                .and_then(|body| body.as_bytes())
+                .unwrap()
+                .to_vec()
+        };
+
+        assert_eq!(body("hello"), b"\0\0\0\0\x0c\n\x0aHello test");
+        assert_eq!(body("helloArgs"), b"\0\0\0\0\x06\n\x04test");
+    }
 }
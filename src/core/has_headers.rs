@@ -4,10 +4,19 @@ use crate::core::ir::{EvalContext, ResolverContextLike};
 
 pub trait HasHeaders {
     fn headers(&self) -> &HeaderMap;
+
+    /// The incoming headers that resolvers can pick with `forwardHeaders`.
+    fn forwarded_headers(&self) -> &HeaderMap {
+        self.headers()
+    }
 }
 
 impl<Ctx: ResolverContextLike> HasHeaders for EvalContext<'_, Ctx> {
     fn headers(&self) -> &HeaderMap {
         self.headers()
     }
+
+    fn forwarded_headers(&self) -> &HeaderMap {
+        self.forwarded_headers()
+    }
 }
@@ -0,0 +1,124 @@
+use std::net::IpAddr;
+use std::time::Instant;
+
+use chrono::{DateTime, FixedOffset, Local};
+use hyper::body::HttpBody;
+use hyper::header::{self, HeaderMap, HeaderName};
+use hyper::{Request, Response};
+
+/// The tracing target of the access log lines.
+pub const ACCESS_LOG_TARGET: &str = "tailcall::access_log";
+
+/// Collects what's known about a request when it arrives, to print it as a
+/// line of the access log once the response is ready.
+pub struct AccessLog {
+    remote_addr: String,
+    request_line: String,
+    referer: String,
+    user_agent: String,
+    time: DateTime<FixedOffset>,
+    start: Instant,
+}
+
+impl AccessLog {
+    pub fn new<B>(req: &Request<B>, client_ip: Option<IpAddr>) -> Self {
+        let remote_addr = client_ip
+            .map(|ip| ip.to_string())
+            .unwrap_or_else(|| "-".to_string());
+
+        let path = req
+            .uri()
+            .path_and_query()
+            .map(|path| path.as_str())
+            .unwrap_or("/");
+
+        Self {
+            remote_addr,
+            request_line: format!("{} {} {:?}", req.method(), path, req.version()),
+            referer: header_value(req.headers(), &header::REFERER),
+            user_agent: header_value(req.headers(), &header::USER_AGENT),
+            time: Local::now().fixed_offset(),
+            start: Instant::now(),
+        }
+    }
+
+    /// Formats the request and its response in the Apache Combined Log Format,
+    /// followed by the time it took to respond in milliseconds.
+    pub fn to_line<B: HttpBody>(&self, response: &Response<B>) -> String {
+        let bytes = response
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string())
+            .or_else(|| response.body().size_hint().exact().map(|n| n.to_string()))
+            .unwrap_or_else(|| "-".to_string());
+
+        format!(
+            "{} - - [{}] \"{}\" {} {} \"{}\" \"{}\" {}",
+            self.remote_addr,
+            self.time.format("%d/%b/%Y:%H:%M:%S %z"),
+            escape(&self.request_line),
+            response.status().as_u16(),
+            bytes,
+            escape(&self.referer),
+            escape(&self.user_agent),
+            self.start.elapsed().as_millis()
+        )
+    }
+}
+
+fn header_value(headers: &HeaderMap, name: &HeaderName) -> String {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("-")
+        .to_string()
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+    use hyper::{Body, Request, Response, StatusCode};
+
+    use super::AccessLog;
+
+    #[test]
+    fn test_combined_log_format() {
+        let req = Request::post("http://localhost:8000/graphql?op=users")
+            .header("user-agent", "curl/8.4.0 \"test\"")
+            .body(Body::empty())
+            .unwrap();
+
+        let mut log = AccessLog::new(&req, "10.0.0.1".parse().ok());
+        log.time = DateTime::parse_from_rfc3339("2024-10-10T13:55:36-07:00").unwrap();
+
+        let response = Response::builder()
+            .status(StatusCode::OK)
+            .body(Body::from(r#"{"data":{}}"#))
+            .unwrap();
+        let line = log.to_line(&response);
+        let (line, duration) = line.rsplit_once(' ').unwrap();
+
+        assert_eq!(
+            line,
+            r#"10.0.0.1 - - [10/Oct/2024:13:55:36 -0700] "POST /graphql?op=users HTTP/1.1" 200 11 "-" "curl/8.4.0 \"test\"""#
+        );
+        assert!(duration.parse::<u128>().is_ok());
+    }
+
+    #[test]
+    fn test_unknown_remote_addr() {
+        let req = Request::get("http://localhost:8000/health")
+            .body(Body::empty())
+            .unwrap();
+
+        let line = AccessLog::new(&req, None).to_line(&Response::new(Body::empty()));
+
+        assert!(line.starts_with("- - - ["));
+        assert!(line.contains(r#""GET /health HTTP/1.1" 200 0 "-" "-""#));
+    }
+}
@@ -0,0 +1,152 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use url::Url;
+
+use crate::core::HttpIO;
+
+/// The base URLs of the replicas of an upstream, which the calls of a field
+/// are spread across in a round-robin.
+#[derive(Debug)]
+pub struct BaseUrls {
+    urls: Vec<Url>,
+    next: AtomicUsize,
+}
+
+impl BaseUrls {
+    pub fn new(urls: Vec<Url>) -> Self {
+        Self { urls, next: AtomicUsize::new(0) }
+    }
+
+    /// Points the url to the next base URL whose origin is available. When
+    /// none is, the next one is used anyway, so the call fails as it would
+    /// without the balancing.
+    pub fn select(&self, url: &mut Url, http: &dyn HttpIO) {
+        if self.urls.is_empty() {
+            return;
+        }
+
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let base = (0..self.urls.len())
+            .map(|i| &self.urls[(start + i) % self.urls.len()])
+            .find(|base| http.is_available(&base.origin().ascii_serialization()))
+            .unwrap_or(&self.urls[start % self.urls.len()]);
+
+        // the base URLs are validated to be http urls with a host, so the
+        // parts can always be set
+        let _ = url.set_scheme(base.scheme());
+        let _ = url.set_host(base.host_str());
+        let _ = url.set_port(base.port());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use hyper::body::Bytes;
+
+    use super::*;
+    use crate::core::config::{CircuitBreaker, RequestLogging};
+    use crate::core::http::{CircuitBreakerHttp, LoggingHttp, Response};
+
+    /// Fails every call to the hosts named `down`
+    struct Upstream;
+
+    #[async_trait::async_trait]
+    impl HttpIO for Upstream {
+        async fn execute(&self, request: reqwest::Request) -> anyhow::Result<Response<Bytes>> {
+            let status = if request.url().host_str() == Some("down") {
+                reqwest::StatusCode::SERVICE_UNAVAILABLE
+            } else {
+                reqwest::StatusCode::OK
+            };
+
+            Ok(Response { status, headers: Default::default(), body: Bytes::new() })
+        }
+    }
+
+    fn base_urls(urls: &[&str]) -> BaseUrls {
+        BaseUrls::new(urls.iter().map(|url| url.parse().unwrap()).collect())
+    }
+
+    fn select(base_urls: &BaseUrls, http: &dyn HttpIO) -> Url {
+        let mut url: Url = "http://upstream/users?id=1".parse().unwrap();
+        base_urls.select(&mut url, http);
+        url
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let base_urls = base_urls(&["http://a:8000", "https://b", "http://c"]);
+        let mut counts = HashMap::new();
+
+        for _ in 0..9 {
+            let url = select(&base_urls, &Upstream);
+            assert_eq!(url.path(), "/users");
+            assert_eq!(url.query(), Some("id=1"));
+            *counts
+                .entry(url.origin().ascii_serialization())
+                .or_insert(0) += 1;
+        }
+
+        assert_eq!(
+            counts,
+            HashMap::from([
+                ("http://a:8000".to_string(), 3),
+                ("https://b".to_string(), 3),
+                ("http://c".to_string(), 3),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_open_breaker_is_skipped() {
+        let config = CircuitBreaker { failure_threshold: 1, open_duration: 60_000 };
+        let http = CircuitBreakerHttp::new(Arc::new(Upstream), &config);
+        let base_urls = base_urls(&["http://up", "http://down"]);
+
+        let request = reqwest::Request::new(reqwest::Method::GET, "http://down".parse().unwrap());
+        http.execute(request).await.unwrap();
+
+        for _ in 0..4 {
+            assert_eq!(select(&base_urls, &http).as_str(), "http://up/users?id=1");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_open_breaker_is_skipped_through_wrappers() {
+        let config = CircuitBreaker { failure_threshold: 1, open_duration: 60_000 };
+        let breaker = Arc::new(CircuitBreakerHttp::new(Arc::new(Upstream), &config));
+        let http = LoggingHttp::new(breaker.clone(), &RequestLogging::default());
+        let base_urls = base_urls(&["http://up", "http://down"]);
+
+        let request = reqwest::Request::new(reqwest::Method::GET, "http://down".parse().unwrap());
+        breaker.execute(request).await.unwrap();
+
+        for _ in 0..4 {
+            assert_eq!(select(&base_urls, &http).as_str(), "http://up/users?id=1");
+        }
+    }
+
+    #[test]
+    fn test_all_unavailable() {
+        struct Down;
+
+        #[async_trait::async_trait]
+        impl HttpIO for Down {
+            async fn execute(&self, _: reqwest::Request) -> anyhow::Result<Response<Bytes>> {
+                unimplemented!()
+            }
+
+            fn is_available(&self, _origin: &str) -> bool {
+                false
+            }
+        }
+
+        let base_urls = base_urls(&["http://a", "http://b"]);
+
+        assert_eq!(select(&base_urls, &Down).host_str(), Some("a"));
+        assert_eq!(select(&base_urls, &Down).host_str(), Some("b"));
+    }
+}
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use hyper::body::Bytes;
+
+/// Post-processes the serialized body of an `@http` request before it's sent
+/// to the upstream, e.g. to order the keys of the JSON canonically, to add a
+/// signature or to wrap the body in an envelope. Transformers are registered
+/// on the runtime with `TargetRuntime::add_body_transformer` and run in the
+/// order they were added.
+pub trait BodyTransformer: Send + Sync + 'static {
+    /// Returns the body to send in place of `body`. The request is the one the
+    /// body belongs to, with its URL and headers already rendered.
+    fn transform(&self, request: &reqwest::Request, body: Bytes) -> anyhow::Result<Bytes>;
+}
+
+/// Runs the transformers over the body of the request. Requests without a
+/// body are left untouched.
+pub fn transform_body(
+    transformers: &[Arc<dyn BodyTransformer>],
+    request: &mut reqwest::Request,
+) -> anyhow::Result<()> {
+    if transformers.is_empty() {
+        return Ok(());
+    }
+
+    let Some(body) = request.body().and_then(|body| body.as_bytes()) else {
+        return Ok(());
+    };
+
+    let mut body = Bytes::copy_from_slice(body);
+    for transformer in transformers {
+        body = transformer.transform(request, body)?;
+    }
+    request.body_mut().replace(body.into());
+
+    Ok(())
+}
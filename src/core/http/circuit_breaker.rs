@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use hyper::body::Bytes;
+
+use super::{BodyStream, Response};
+use crate::core::config::CircuitBreaker;
+use crate::core::ir::Error;
+use crate::core::HttpIO;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum State {
+    Closed { failures: usize },
+    Open { until: Instant },
+    HalfOpen { until: Instant },
+}
+
+/// Wraps an [HttpIO] with a circuit breaker per upstream origin. After
+/// `failure_threshold` consecutive failures the breaker opens and the calls to
+/// that upstream fail immediately with [Error::UpstreamUnavailable]. Once
+/// `open_duration` elapses a single probe request is let through, which closes
+/// the breaker on success and opens it again on failure.
+pub struct CircuitBreakerHttp {
+    http: Arc<dyn HttpIO>,
+    failure_threshold: usize,
+    open_duration: Duration,
+    states: Mutex<HashMap<String, State>>,
+}
+
+impl CircuitBreakerHttp {
+    pub fn new(http: Arc<dyn HttpIO>, config: &CircuitBreaker) -> Self {
+        Self {
+            http,
+            failure_threshold: config.failure_threshold,
+            open_duration: Duration::from_millis(config.open_duration),
+            states: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Checks whether a request to the upstream can be made, moving an expired
+    /// open breaker to the half-open state.
+    fn try_acquire(&self, upstream: &str) -> bool {
+        let mut states = self.states.lock().unwrap();
+        let state = states
+            .entry(upstream.to_string())
+            .or_insert(State::Closed { failures: 0 });
+
+        match *state {
+            State::Closed { .. } => true,
+            State::Open { until } | State::HalfOpen { until } if Instant::now() >= until => {
+                // another probe is let through only if this one doesn't complete in time
+                *state = State::HalfOpen { until: Instant::now() + self.open_duration };
+                true
+            }
+            State::Open { .. } | State::HalfOpen { .. } => false,
+        }
+    }
+
+    fn record(&self, upstream: &str, is_success: bool) {
+        let mut states = self.states.lock().unwrap();
+        let state = states
+            .entry(upstream.to_string())
+            .or_insert(State::Closed { failures: 0 });
+
+        *state = match *state {
+            _ if is_success => State::Closed { failures: 0 },
+            State::Closed { failures } if failures + 1 < self.failure_threshold => {
+                State::Closed { failures: failures + 1 }
+            }
+            _ => State::Open { until: Instant::now() + self.open_duration },
+        };
+    }
+}
+
+/// Whether the response shows a healthy upstream. Client errors are caused by
+/// the request and don't indicate a faulty upstream.
+fn is_success<Body>(response: &anyhow::Result<Response<Body>>) -> bool {
+    match response {
+        Ok(response) => !response.status.is_server_error(),
+        Err(err) => {
+            matches!(err.downcast_ref::<Error>(), Some(Error::HTTP { status, .. }) if *status < 500)
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpIO for CircuitBreakerHttp {
+    async fn execute(&self, request: reqwest::Request) -> anyhow::Result<Response<Bytes>> {
+        let upstream = request.url().origin().ascii_serialization();
+        if !self.try_acquire(&upstream) {
+            return Err(Error::UpstreamUnavailable(upstream).into());
+        }
+
+        let response = self.http.execute(request).await;
+        self.record(&upstream, is_success(&response));
+
+        response
+    }
+
+    async fn execute_stream(
+        &self,
+        request: reqwest::Request,
+    ) -> anyhow::Result<Response<BodyStream>> {
+        let upstream = request.url().origin().ascii_serialization();
+        if !self.try_acquire(&upstream) {
+            return Err(Error::UpstreamUnavailable(upstream).into());
+        }
+
+        // only the status is known when the body starts to arrive
+        let response = self.http.execute_stream(request).await;
+        self.record(&upstream, is_success(&response));
+
+        response
+    }
+
+    fn is_available(&self, origin: &str) -> bool {
+        let is_let_through = match self.states.lock().unwrap().get(origin) {
+            Some(State::Open { until } | State::HalfOpen { until }) => Instant::now() >= *until,
+            _ => true,
+        };
+
+        is_let_through && self.http.is_available(origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use hyper::body::Bytes;
+
+    use super::CircuitBreakerHttp;
+    use crate::core::config::CircuitBreaker;
+    use crate::core::http::Response;
+    use crate::core::ir::Error;
+    use crate::core::HttpIO;
+
+    #[derive(Default)]
+    struct FlakyHttp {
+        hits: AtomicUsize,
+        is_down: AtomicBool,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpIO for FlakyHttp {
+        async fn execute(&self, _request: reqwest::Request) -> anyhow::Result<Response<Bytes>> {
+            self.hits.fetch_add(1, Ordering::SeqCst);
+            let status = if self.is_down.load(Ordering::SeqCst) {
+                reqwest::StatusCode::SERVICE_UNAVAILABLE
+            } else {
+                reqwest::StatusCode::OK
+            };
+
+            Ok(Response { status, headers: Default::default(), body: Bytes::new() })
+        }
+    }
+
+    fn request(url: &str) -> reqwest::Request {
+        reqwest::Request::new(reqwest::Method::GET, url.parse().unwrap())
+    }
+
+    fn setup() -> (Arc<FlakyHttp>, CircuitBreakerHttp) {
+        let http = Arc::new(FlakyHttp::default());
+        http.is_down.store(true, Ordering::SeqCst);
+        let config = CircuitBreaker { failure_threshold: 3, open_duration: 50 };
+        let breaker = CircuitBreakerHttp::new(http.clone(), &config);
+
+        (http, breaker)
+    }
+
+    fn is_unavailable(result: anyhow::Result<Response<Bytes>>) -> bool {
+        matches!(
+            result.map_err(|e| e.downcast::<Error>()),
+            Err(Ok(Error::UpstreamUnavailable(_)))
+        )
+    }
+
+    #[tokio::test]
+    async fn test_opens_after_consecutive_failures() {
+        let (http, breaker) = setup();
+
+        for _ in 0..3 {
+            let response = breaker.execute(request("http://upstream/posts")).await;
+            assert!(response.unwrap().status.is_server_error());
+        }
+
+        assert!(is_unavailable(
+            breaker.execute(request("http://upstream/posts")).await
+        ));
+        assert!(is_unavailable(
+            breaker.execute(request("http://upstream/users")).await
+        ));
+        assert_eq!(http.hits.load(Ordering::SeqCst), 3);
+
+        // breakers are kept per upstream origin
+        let response = breaker.execute(request("http://other/posts")).await;
+        assert!(response.unwrap().status.is_server_error());
+        assert_eq!(http.hits.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_half_open_probe() {
+        let (http, breaker) = setup();
+
+        for _ in 0..3 {
+            breaker
+                .execute(request("http://upstream/posts"))
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(60)).await;
+
+        // a failed probe opens the breaker again
+        breaker
+            .execute(request("http://upstream/posts"))
+            .await
+            .unwrap();
+        assert!(is_unavailable(
+            breaker.execute(request("http://upstream/posts")).await
+        ));
+        assert_eq!(http.hits.load(Ordering::SeqCst), 4);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        http.is_down.store(false, Ordering::SeqCst);
+
+        // a successful probe closes the breaker
+        for _ in 0..2 {
+            let response = breaker.execute(request("http://upstream/posts")).await;
+            assert!(response.unwrap().status.is_success());
+        }
+        assert_eq!(http.hits.load(Ordering::SeqCst), 6);
+    }
+}
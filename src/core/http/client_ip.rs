@@ -0,0 +1,139 @@
+use std::net::{IpAddr, SocketAddr};
+
+use http::header::{HeaderMap, FORWARDED};
+
+/// Finds the IP of the client that made the request, given the IP of the
+/// connected peer and the number of proxies in front of the server.
+///
+/// Each proxy appends the address it received the request from to the
+/// `Forwarded` (or `X-Forwarded-For`) header, so the client is the address
+/// that precedes the trusted proxies in the chain. Anything before it could
+/// have been sent by the client itself and is ignored.
+pub fn client_ip(
+    headers: &HeaderMap,
+    peer: Option<IpAddr>,
+    trusted_proxies: usize,
+) -> Option<IpAddr> {
+    if trusted_proxies == 0 {
+        return peer;
+    }
+
+    let forwarded = forwarded_for(headers);
+    let hops = forwarded.len() + 1;
+    let index = hops.saturating_sub(trusted_proxies + 1);
+
+    if index == forwarded.len() {
+        peer
+    } else {
+        forwarded[index]
+    }
+}
+
+/// Reads the addresses of the `Forwarded` header, or the `X-Forwarded-For`
+/// header when there's none, in the order they were added. An address that
+/// can't be parsed, e.g. an obfuscated identifier, is kept as `None` so it
+/// still counts as a hop.
+fn forwarded_for(headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+    let forwarded = headers.get_all(FORWARDED).iter().collect::<Vec<_>>();
+
+    if forwarded.is_empty() {
+        headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|node| parse_node(node.trim()))
+            .collect()
+    } else {
+        forwarded
+            .into_iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|element| {
+                element.split(';').find_map(|pair| {
+                    let (key, value) = pair.trim().split_once('=')?;
+                    key.eq_ignore_ascii_case("for")
+                        .then(|| parse_node(value.trim().trim_matches('"')))
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parses an address that may come with a port, e.g. `192.0.2.43:47011` or
+/// `[2001:db8:cafe::17]:4711`.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    node.parse::<IpAddr>()
+        .ok()
+        .or_else(|| node.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+        .or_else(|| {
+            node.strip_prefix('[')
+                .and_then(|node| node.strip_suffix(']'))
+                .and_then(|node| node.parse().ok())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use http::header::HeaderMap;
+
+    use super::client_ip;
+
+    fn ip(value: &str) -> Option<IpAddr> {
+        Some(value.parse().unwrap())
+    }
+
+    fn headers(name: &'static str, value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_direct_connection() {
+        let headers = headers("x-forwarded-for", "203.0.113.7");
+
+        assert_eq!(client_ip(&headers, ip("10.0.0.1"), 0), ip("10.0.0.1"));
+        assert_eq!(
+            client_ip(&HeaderMap::new(), ip("10.0.0.1"), 0),
+            ip("10.0.0.1")
+        );
+        assert_eq!(client_ip(&HeaderMap::new(), None, 0), None);
+    }
+
+    #[test]
+    fn test_forwarded_chain() {
+        // the client prepended a spoofed address, and two proxies forwarded it
+        let headers = headers("x-forwarded-for", "1.2.3.4, 203.0.113.7, 10.0.0.2");
+
+        assert_eq!(client_ip(&headers, ip("10.0.0.1"), 1), ip("10.0.0.2"));
+        assert_eq!(client_ip(&headers, ip("10.0.0.1"), 2), ip("203.0.113.7"));
+        assert_eq!(client_ip(&headers, None, 2), ip("203.0.113.7"));
+        assert_eq!(client_ip(&headers, ip("10.0.0.1"), 5), ip("1.2.3.4"));
+        assert_eq!(
+            client_ip(&HeaderMap::new(), ip("10.0.0.1"), 1),
+            ip("10.0.0.1")
+        );
+    }
+
+    #[test]
+    fn test_forwarded_header() {
+        let mut headers = headers(
+            "forwarded",
+            r#"for=1.2.3.4, for="[2001:db8:cafe::17]:4711";proto=https"#,
+        );
+        headers.append(
+            "forwarded",
+            "for=192.0.2.43:47011;by=10.0.0.2".parse().unwrap(),
+        );
+        headers.insert("x-forwarded-for", "5.6.7.8".parse().unwrap());
+
+        assert_eq!(client_ip(&headers, ip("10.0.0.1"), 1), ip("192.0.2.43"));
+        assert_eq!(
+            client_ip(&headers, ip("10.0.0.1"), 2),
+            ip("2001:db8:cafe::17")
+        );
+    }
+}
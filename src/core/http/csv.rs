@@ -0,0 +1,152 @@
+use async_graphql_value::{ConstValue, Name};
+use indexmap::IndexMap;
+
+/// Converts a CSV document to a list with an object per row. The fields are
+/// named after the header row, or `column1`, `column2` and so on when there's
+/// no header. Fields can be quoted with `"`, with `""` standing for a quote
+/// inside a quoted field. Empty fields become `null`, fields that are a JSON
+/// number or boolean become a number or boolean and blank lines are skipped.
+pub fn from_csv(body: &[u8], delimiter: char, header: bool) -> anyhow::Result<ConstValue> {
+    let mut rows = parse_rows(std::str::from_utf8(body)?, delimiter)?.into_iter();
+
+    let names = if header {
+        rows.next().unwrap_or_default()
+    } else {
+        vec![]
+    };
+
+    let values = rows
+        .enumerate()
+        .map(|(index, row)| {
+            if header && row.len() > names.len() {
+                anyhow::bail!("CSV row {} has more fields than the header", index + 1);
+            }
+
+            let mut object = IndexMap::new();
+            for (position, field) in row.iter().enumerate() {
+                let name = match names.get(position) {
+                    Some(name) => name.clone(),
+                    None => format!("column{}", position + 1),
+                };
+                let value = if field.is_empty() {
+                    ConstValue::Null
+                } else {
+                    to_scalar(field)
+                };
+                object.insert(Name::new(name), value);
+            }
+            for name in names.iter().skip(row.len()) {
+                object.insert(Name::new(name), ConstValue::Null);
+            }
+
+            Ok(ConstValue::Object(object))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok(ConstValue::List(values))
+}
+
+fn parse_rows(text: &str, delimiter: char) -> anyhow::Result<Vec<Vec<String>>> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut field = String::new();
+    let mut is_quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if is_quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => is_quoted = false,
+                c => field.push(c),
+            }
+        } else {
+            match c {
+                '"' if field.is_empty() => is_quoted = true,
+                '\r' if chars.peek() == Some(&'\n') => {}
+                '\n' => {
+                    row.push(std::mem::take(&mut field));
+                    rows.push(std::mem::take(&mut row));
+                }
+                c if c == delimiter => row.push(std::mem::take(&mut field)),
+                c => field.push(c),
+            }
+        }
+    }
+
+    if is_quoted {
+        anyhow::bail!("CSV has an unterminated quoted field");
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    Ok(rows
+        .into_iter()
+        .filter(|row| !matches!(row.as_slice(), [field] if field.is_empty()))
+        .collect())
+}
+
+/// Converts a text to a boolean or a number when it's the JSON representation
+/// of one, and to a string otherwise.
+fn to_scalar(text: &str) -> ConstValue {
+    match text {
+        "true" => ConstValue::Boolean(true),
+        "false" => ConstValue::Boolean(false),
+        _ => match text.parse::<serde_json::Number>() {
+            Ok(number) => ConstValue::Number(number),
+            Err(_) => ConstValue::String(text.to_string()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql_value::ConstValue;
+    use serde_json::json;
+
+    use super::from_csv;
+
+    #[test]
+    fn test_from_csv() {
+        let csv = "id,name,city,active\r\n\
+                   1,Leanne Graham,Gwenborough,true\r\n\
+                   2,\"Howell, Ervin\",\"Wisokyburgh \"\"East\"\"\",false\r\n\
+                   \r\n\
+                   3,Clementine Bauch,,\n";
+
+        let actual = from_csv(csv.as_bytes(), ',', true).unwrap();
+        let expected = ConstValue::from_json(json!([
+            {"id": 1, "name": "Leanne Graham", "city": "Gwenborough", "active": true},
+            {"id": 2, "name": "Howell, Ervin", "city": "Wisokyburgh \"East\"", "active": false},
+            {"id": 3, "name": "Clementine Bauch", "city": null, "active": null}
+        ]))
+        .unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_from_csv_without_header() {
+        let csv = "1;Leanne Graham\n2;Ervin Howell";
+
+        let actual = from_csv(csv.as_bytes(), ';', false).unwrap();
+        let expected = ConstValue::from_json(json!([
+            {"column1": 1, "column2": "Leanne Graham"},
+            {"column1": 2, "column2": "Ervin Howell"}
+        ]))
+        .unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_from_csv_invalid() {
+        assert!(from_csv(b"id,name\n1,\"Leanne", ',', true).is_err());
+        assert!(from_csv(b"id\n1,Leanne", ',', true).is_err());
+    }
+}
@@ -9,9 +9,9 @@ use tailcall_valid::Validator;
 
 use super::transformations::{BodyBatching, QueryBatching};
 use crate::core::config::group_by::GroupBy;
-use crate::core::config::Batch;
+use crate::core::config::{Batch, StatusAction};
 use crate::core::data_loader::{DataLoader, Loader};
-use crate::core::http::{DataLoaderRequest, Response};
+use crate::core::http::{DataLoaderRequest, Response, ResponseDecoder};
 use crate::core::json::JsonLike;
 use crate::core::runtime::TargetRuntime;
 use crate::core::transform::TransformerOps;
@@ -40,10 +40,20 @@ pub struct HttpDataLoader {
     pub runtime: TargetRuntime,
     pub group_by: Option<GroupBy>,
     is_list: bool,
+    response_decoder: ResponseDecoder,
 }
 impl HttpDataLoader {
     pub fn new(runtime: TargetRuntime, group_by: Option<GroupBy>, is_list: bool) -> Self {
-        HttpDataLoader { runtime, group_by, is_list }
+        HttpDataLoader {
+            runtime,
+            group_by,
+            is_list,
+            response_decoder: ResponseDecoder::default(),
+        }
+    }
+
+    pub fn response_decoder(self, response_decoder: ResponseDecoder) -> Self {
+        Self { response_decoder, ..self }
     }
 
     pub fn to_data_loader(self, batch: Batch) -> DataLoader<DataLoaderRequest, HttpDataLoader> {
@@ -93,7 +103,7 @@ impl Loader<DataLoaderRequest> for HttpDataLoader {
                     .http
                     .execute(base_request)
                     .await?
-                    .to_json::<ConstValue>()?;
+                    .to_value(self.response_decoder)?;
 
                 // Create a response HashMap
                 #[allow(clippy::mutable_key_type)]
@@ -112,32 +122,34 @@ impl Loader<DataLoaderRequest> for HttpDataLoader {
                     get_body_value_single
                 };
 
-                // For each request and insert its corresponding value
-                if base_dl_request.method() == reqwest::Method::GET {
-                    for dl_req in dl_requests.iter() {
-                        let url = dl_req.url();
-                        let query_set: HashMap<_, _> = url.query_pairs().collect();
-                        let id = query_set.get(query_name).ok_or(anyhow::anyhow!(
-                            "Unable to find key {} in query params",
-                            query_name
-                        ))?;
-
-                        // Clone the response and set the body
-                        let body = data_extractor(&response_map, id);
-                        let res = res.clone().body(body);
-
-                        hashmap.insert(dl_req.clone(), res);
-                    }
-                } else {
-                    for dl_req in dl_requests.into_iter() {
-                        let body_key = dl_req.batching_value().ok_or(anyhow::anyhow!(
-                            "Unable to find batching value in the body for data loader request {}",
-                            dl_req.url().as_str()
-                        ))?;
-                        let extracted_value = data_extractor(&response_map, body_key);
-                        let res = res.clone().body(extracted_value);
-                        hashmap.insert(dl_req.clone(), res);
-                    }
+                // For each request and insert its corresponding value. The requests without
+                // a key or without an item in the response are resolved on their own
+                // according to `on_missing`, so they don't fail the rest of the batch.
+                let on_missing = group_by.on_missing();
+                let is_get = base_dl_request.method() == reqwest::Method::GET;
+                for dl_req in dl_requests.iter() {
+                    let id = if is_get {
+                        dl_req
+                            .url()
+                            .query_pairs()
+                            .find(|(key, _)| key == query_name)
+                            .map(|(_, value)| value.into_owned())
+                    } else {
+                        dl_req.batching_value().cloned()
+                    };
+
+                    let id = id.filter(|id| response_map.contains_key(id));
+                    let body = match id {
+                        Some(id) => data_extractor(&response_map, &id),
+                        None if on_missing == StatusAction::Null && self.is_list => {
+                            ConstValue::List(vec![])
+                        }
+                        None if on_missing == StatusAction::Null => ConstValue::Null,
+                        None => continue,
+                    };
+
+                    // Clone the response and set the body
+                    hashmap.insert(dl_req.clone(), res.clone().body(body));
                 }
 
                 Ok(hashmap)
@@ -156,7 +168,7 @@ impl Loader<DataLoaderRequest> for HttpDataLoader {
             #[allow(clippy::mutable_key_type)]
             let mut hashmap = HashMap::with_capacity(results.len());
             for (key, value) in results {
-                hashmap.insert(key, value?.to_json()?);
+                hashmap.insert(key, value?.to_value(self.response_decoder)?);
             }
 
             Ok(hashmap)
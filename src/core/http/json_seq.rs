@@ -0,0 +1,153 @@
+use async_graphql_value::ConstValue;
+use futures_util::stream::{self, Stream, StreamExt};
+use hyper::body::Bytes;
+
+use super::BodyStream;
+
+/// The record separator that starts every JSON text of a sequence
+const RS: u8 = 0x1e;
+
+/// Converts a JSON text sequence (RFC 7464), i.e. JSON texts that each start
+/// with a record separator and end with a line feed, to a list of its values.
+pub fn from_json_seq(body: &[u8]) -> anyhow::Result<ConstValue> {
+    let values = JsonSeqDecoder::default().decode(body, true)?;
+    Ok(ConstValue::List(values))
+}
+
+/// Decodes the values of a JSON text sequence as the chunks of the body
+/// arrive, so every value is available as soon as its text is complete.
+pub fn json_seq_stream(body: BodyStream) -> impl Stream<Item = anyhow::Result<ConstValue>> + Send {
+    let state = Some((body, JsonSeqDecoder::default()));
+    stream::unfold(state, |state| async move { next_values(state?).await }).flat_map(stream::iter)
+}
+
+type State = (BodyStream, JsonSeqDecoder);
+
+/// Reads the body until some values are complete and returns them along with
+/// the state to read the next ones, which is dropped at the end of the body or
+/// after an error.
+async fn next_values(
+    (mut body, mut decoder): State,
+) -> Option<(Vec<anyhow::Result<ConstValue>>, Option<State>)> {
+    loop {
+        let (chunk, is_last) = match body.next().await {
+            Some(Ok(chunk)) => (chunk, false),
+            Some(Err(err)) => return Some((vec![Err(err)], None)),
+            None => (Bytes::new(), true),
+        };
+
+        match decoder.decode(&chunk, is_last) {
+            Ok(values) if values.is_empty() && !is_last => continue,
+            Ok(values) => {
+                let values = values.into_iter().map(Ok).collect();
+                return Some((values, (!is_last).then_some((body, decoder))));
+            }
+            Err(err) => return Some((vec![Err(err)], None)),
+        }
+    }
+}
+
+/// Buffers the part of the sequence whose JSON text isn't complete yet.
+#[derive(Default)]
+struct JsonSeqDecoder {
+    buf: Vec<u8>,
+}
+
+impl JsonSeqDecoder {
+    /// Appends a chunk of the body and returns the values that it completes.
+    fn decode(&mut self, chunk: &[u8], is_last: bool) -> anyhow::Result<Vec<ConstValue>> {
+        // the separators are only meaningful to recover from a malformed text,
+        // so they're parsed as whitespace
+        self.buf.extend(
+            chunk
+                .iter()
+                .map(|&byte| if byte == RS { b' ' } else { byte }),
+        );
+
+        let mut values = vec![];
+        let mut consumed = 0;
+        let mut texts = serde_json::Deserializer::from_slice(&self.buf).into_iter::<ConstValue>();
+        loop {
+            match texts.next() {
+                // a number at the end of the chunk may go on in the next one
+                Some(Ok(ConstValue::Number(_)))
+                    if !is_last && texts.byte_offset() == self.buf.len() =>
+                {
+                    break
+                }
+                Some(Ok(value)) => {
+                    consumed = texts.byte_offset();
+                    values.push(value);
+                }
+                Some(Err(err)) if err.is_eof() && !is_last => break,
+                Some(Err(err)) => return Err(err.into()),
+                None => break,
+            }
+        }
+
+        self.buf.drain(..consumed);
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn to_json(value: ConstValue) -> serde_json::Value {
+        value.into_json().unwrap()
+    }
+
+    #[test]
+    fn test_from_json_seq() {
+        let body = b"\x1e{\"id\":1}\n\x1e{\"id\":2}\n\x1e[3]\n\x1e4\n";
+        let value = from_json_seq(body).unwrap();
+        assert_eq!(to_json(value), json!([{"id": 1}, {"id": 2}, [3], 4]));
+
+        assert_eq!(to_json(from_json_seq(b"").unwrap()), json!([]));
+        assert!(from_json_seq(b"\x1e{\"id\":1}\n\x1e{\"id\"").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_json_seq_stream() {
+        let chunks = [
+            "\x1e{\"id\"",
+            ":1}\n\x1e12",
+            "3\n\x1e{\"id\":2}\n\x1e{\"id\":3}",
+            "\n",
+        ];
+        let body = stream::iter(chunks.map(|chunk| Ok(Bytes::from(chunk)))).boxed();
+
+        let values = json_seq_stream(body)
+            .map(|value| to_json(value.unwrap()))
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(
+            values,
+            vec![
+                json!({"id": 1}),
+                json!(123),
+                json!({"id": 2}),
+                json!({"id": 3})
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_json_seq_stream_truncated() {
+        let chunks = ["\x1e{\"id\":1}\n", "\x1e{\"id\":"];
+        let body = stream::iter(chunks.map(|chunk| Ok(Bytes::from(chunk)))).boxed();
+
+        let values = json_seq_stream(body).collect::<Vec<_>>().await;
+
+        assert_eq!(values.len(), 2);
+        assert_eq!(
+            to_json(values[0].as_ref().unwrap().clone()),
+            json!({"id": 1})
+        );
+        assert!(values[1].is_err());
+    }
+}
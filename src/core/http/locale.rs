@@ -0,0 +1,74 @@
+use http::header::{HeaderMap, ACCEPT_LANGUAGE};
+
+/// Finds the locale preferred by the client in the `Accept-Language` header,
+/// i.e. the one with the highest quality, or the first of them on a tie. The
+/// wildcard and the locales refused with `q=0` are skipped.
+pub fn locale(headers: &HeaderMap) -> Option<String> {
+    let mut best: Option<(&str, f32)> = None;
+
+    let ranges = headers
+        .get_all(ACCEPT_LANGUAGE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','));
+
+    for range in ranges {
+        let mut parts = range.split(';').map(str::trim);
+        let tag = parts.next().unwrap_or_default();
+        let quality = match parts.find_map(|param| param.strip_prefix("q=")) {
+            Some(quality) => quality.parse::<f32>().unwrap_or(0.0),
+            None => 1.0,
+        };
+
+        if tag.is_empty() || tag == "*" || quality <= 0.0 {
+            continue;
+        }
+
+        if !best.is_some_and(|(_, best)| quality <= best) {
+            best = Some((tag, quality));
+        }
+    }
+
+    best.map(|(tag, _)| tag.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use http::header::HeaderMap;
+
+    use super::locale;
+
+    fn accept_language(value: &'static str) -> Option<String> {
+        let mut headers = HeaderMap::new();
+        headers.insert("accept-language", value.parse().unwrap());
+        locale(&headers)
+    }
+
+    #[test]
+    fn test_highest_quality() {
+        assert_eq!(accept_language("fr-CA,fr;q=0.9"), Some("fr-CA".to_string()));
+        assert_eq!(
+            accept_language("en;q=0.5, de;q=0.8, fr;q=0.7"),
+            Some("de".to_string())
+        );
+        assert_eq!(accept_language("en;q=0.8, de;q=1"), Some("de".to_string()));
+    }
+
+    #[test]
+    fn test_first_on_tie() {
+        assert_eq!(accept_language("da, en-GB"), Some("da".to_string()));
+        assert_eq!(
+            accept_language("it;q=0.5, es;q=0.5"),
+            Some("it".to_string())
+        );
+    }
+
+    #[test]
+    fn test_skipped_ranges() {
+        assert_eq!(accept_language("*, en;q=0.1"), Some("en".to_string()));
+        assert_eq!(accept_language("fr;q=0, en;q=0.1"), Some("en".to_string()));
+        assert_eq!(accept_language("fr;q=0"), None);
+        assert_eq!(accept_language(""), None);
+        assert_eq!(locale(&HeaderMap::new()), None);
+    }
+}
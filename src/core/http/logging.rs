@@ -0,0 +1,284 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+
+use http::HeaderMap;
+use hyper::body::Bytes;
+use tracing::Level;
+
+use super::{BodyStream, Response};
+use crate::core::config::RequestLogging;
+use crate::core::HttpIO;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Wraps an [HttpIO] and logs the outgoing upstream requests and their
+/// responses at the debug level. The values of the headers and of the JSON
+/// body fields whose names match one of the `redact` patterns are masked. Does
+/// nothing besides the level check when the debug level is disabled.
+pub struct LoggingHttp {
+    http: Arc<dyn HttpIO>,
+    redact: Vec<String>,
+    body: bool,
+}
+
+impl LoggingHttp {
+    pub fn new(http: Arc<dyn HttpIO>, config: &RequestLogging) -> Self {
+        Self {
+            http,
+            redact: config.redact.iter().map(|p| p.to_lowercase()).collect(),
+            body: config.body,
+        }
+    }
+
+    /// Executes the request and logs it along with its response, `body` gives
+    /// the part of the response body that can be logged.
+    async fn log<Body, Fut>(
+        &self,
+        request: reqwest::Request,
+        execute: impl FnOnce(reqwest::Request) -> Fut,
+        body: impl Fn(&Body) -> Option<&[u8]>,
+    ) -> anyhow::Result<Response<Body>>
+    where
+        Fut: Future<Output = anyhow::Result<Response<Body>>>,
+    {
+        if !tracing::enabled!(Level::DEBUG) {
+            return execute(request).await;
+        }
+
+        let method = request.method().clone();
+        let url = request.url().clone();
+        tracing::debug!(
+            method = %method,
+            url = %url,
+            headers = %self.format_headers(request.headers()),
+            body = %self.format_body(request.body().and_then(|body| body.as_bytes())),
+            "upstream request"
+        );
+
+        let start = Instant::now();
+        let response = execute(request).await;
+        let elapsed = start.elapsed().as_millis() as u64;
+
+        match &response {
+            Ok(response) => tracing::debug!(
+                method = %method,
+                url = %url,
+                status = response.status.as_u16(),
+                elapsed_ms = elapsed,
+                headers = %self.format_headers(&response.headers),
+                body = %self.format_body(body(&response.body)),
+                "upstream response"
+            ),
+            Err(err) => tracing::debug!(
+                method = %method,
+                url = %url,
+                elapsed_ms = elapsed,
+                error = %err,
+                "upstream request failed"
+            ),
+        }
+
+        response
+    }
+
+    fn is_redacted(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.redact.iter().any(|pattern| matches(pattern, &name))
+    }
+
+    fn format_headers(&self, headers: &HeaderMap) -> String {
+        headers
+            .iter()
+            .map(|(name, value)| {
+                let value = if self.is_redacted(name.as_str()) {
+                    REDACTED
+                } else {
+                    value.to_str().unwrap_or_default()
+                };
+                format!("{}: {}", name, value)
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn format_body(&self, body: Option<&[u8]>) -> String {
+        let Some(body) = body.filter(|_| self.body) else {
+            return String::new();
+        };
+
+        match serde_json::from_slice::<serde_json::Value>(body) {
+            Ok(mut value) => {
+                self.redact_json(&mut value);
+                value.to_string()
+            }
+            Err(_) => String::from_utf8_lossy(body).to_string(),
+        }
+    }
+
+    fn redact_json(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if self.is_redacted(key) {
+                        *value = serde_json::Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact_json(value);
+                    }
+                }
+            }
+            serde_json::Value::Array(values) => {
+                values.iter_mut().for_each(|value| self.redact_json(value))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Matches a lowercase name against a lowercase pattern where `*` stands for
+/// any sequence of characters.
+fn matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = name.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let parts = parts.collect::<Vec<_>>();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
+}
+
+#[async_trait::async_trait]
+impl HttpIO for LoggingHttp {
+    async fn execute(&self, request: reqwest::Request) -> anyhow::Result<Response<Bytes>> {
+        self.log(
+            request,
+            |request| self.http.execute(request),
+            |body| Some(&body[..]),
+        )
+        .await
+    }
+
+    async fn execute_stream(
+        &self,
+        request: reqwest::Request,
+    ) -> anyhow::Result<Response<BodyStream>> {
+        // the body is still being received, so it isn't logged
+        self.log(
+            request,
+            |request| self.http.execute_stream(request),
+            |_: &BodyStream| None,
+        )
+        .await
+    }
+
+    fn is_available(&self, origin: &str) -> bool {
+        self.http.is_available(origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    use hyper::body::Bytes;
+    use tracing_subscriber::fmt::MakeWriter;
+
+    use super::{matches, LoggingHttp};
+    use crate::core::config::RequestLogging;
+    use crate::core::http::Response;
+    use crate::core::HttpIO;
+
+    #[derive(Clone, Default)]
+    struct Logs(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Logs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Logs {
+        type Writer = Logs;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    struct EchoHttp;
+
+    #[async_trait::async_trait]
+    impl HttpIO for EchoHttp {
+        async fn execute(&self, request: reqwest::Request) -> anyhow::Result<Response<Bytes>> {
+            Ok(Response {
+                status: reqwest::StatusCode::OK,
+                headers: request.headers().clone(),
+                body: Bytes::from(r#"{"id":1,"accessToken":"abc"}"#),
+            })
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(matches("authorization", "authorization"));
+        assert!(!matches("authorization", "x-authorization"));
+        assert!(matches("*token*", "x-api-token"));
+        assert!(matches("*token*", "token"));
+        assert!(matches("x-*-key", "x-api-key"));
+        assert!(!matches("x-*-key", "x-api-keys"));
+    }
+
+    #[tokio::test]
+    async fn test_redacted_headers_are_masked() {
+        let logs = Logs::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(logs.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let config = RequestLogging {
+            redact: ["authorization".to_string(), "*token*".to_string()].into(),
+            body: true,
+        };
+        let http = LoggingHttp::new(Arc::new(EchoHttp), &config);
+
+        let mut request = reqwest::Request::new(
+            reqwest::Method::GET,
+            "http://upstream/users".parse().unwrap(),
+        );
+        let headers = request.headers_mut();
+        headers.insert("Authorization", "Bearer secret".parse().unwrap());
+        headers.insert("x-api-token", "secret".parse().unwrap());
+        headers.insert("x-request-id", "42".parse().unwrap());
+        http.execute(request).await.unwrap();
+
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        assert!(logs.contains("upstream request"));
+        assert!(logs.contains("upstream response"));
+        assert!(logs.contains("http://upstream/users"));
+        assert!(logs.contains("status=200"));
+        assert!(logs.contains("authorization: [REDACTED]"));
+        assert!(logs.contains("x-api-token: [REDACTED]"));
+        assert!(logs.contains("x-request-id: 42"));
+        assert!(logs.contains(r#""accessToken":"[REDACTED]""#));
+        assert!(!logs.contains("secret"));
+        assert!(!logs.contains("abc"));
+    }
+}
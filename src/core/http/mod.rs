@@ -1,17 +1,35 @@
+pub use access_log::ACCESS_LOG_TARGET;
+pub use base_urls::BaseUrls;
+pub use body_transformer::{transform_body, BodyTransformer};
 pub use cache::*;
+pub use circuit_breaker::CircuitBreakerHttp;
+pub use client_ip::client_ip;
 pub use data_loader::*;
 pub use data_loader_request::*;
-use http::HeaderValue;
+use http::{HeaderName, HeaderValue};
+pub use json_seq::json_seq_stream;
+pub use locale::locale;
+pub use logging::LoggingHttp;
 pub use method::Method;
 pub use query_encoder::QueryEncoder;
 pub use request_context::RequestContext;
 pub use request_handler::{handle_request, API_URL_PREFIX};
 pub use request_template::RequestTemplate;
 pub use response::*;
+pub use xml::XmlTypes;
 
+mod access_log;
+mod base_urls;
+mod body_transformer;
 mod cache;
+mod circuit_breaker;
+mod client_ip;
+mod csv;
 mod data_loader;
 mod data_loader_request;
+mod json_seq;
+mod locale;
+mod logging;
 mod method;
 mod query_encoder;
 mod request_context;
@@ -21,6 +39,8 @@ mod response;
 pub mod showcase;
 mod telemetry;
 mod transformations;
+mod xml;
 
 pub static TAILCALL_HTTPS_ORIGIN: HeaderValue = HeaderValue::from_static("https://tailcall.run");
 pub static TAILCALL_HTTP_ORIGIN: HeaderValue = HeaderValue::from_static("http://tailcall.run");
+pub static X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
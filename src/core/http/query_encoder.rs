@@ -1,5 +1,14 @@
+use std::borrow::Cow;
+
 use crate::core::path::ValueString;
 
+/// Characters that can't appear as is in the key or the value of a query
+/// parameter.
+pub const QUERY_RESERVED: &[u8] = b" \"#%&+<=>`";
+
+/// Characters that can't appear as is in a segment of the path.
+pub const PATH_RESERVED: &[u8] = b" \"#%/<>?`{}";
+
 /// Defines different strategies for encoding query parameters.
 #[derive(Default, Debug, Clone)]
 pub enum QueryEncoder {
@@ -16,7 +25,9 @@ impl QueryEncoder {
         if let Some(value) = raw_value {
             match &value {
                 ValueString::Value(val) => self.encode_const_value(key, val),
-                ValueString::String(val) => format!("{}={}", key, val),
+                ValueString::String(val) => {
+                    format!("{}={}", key, percent_encode(val, QUERY_RESERVED))
+                }
             }
         } else {
             key.to_owned()
@@ -26,8 +37,11 @@ impl QueryEncoder {
         match self {
             QueryEncoder::CommaSeparated => match value {
                 async_graphql::Value::List(list) if !list.is_empty() => {
-                    let encoded_values: Vec<String> =
-                        list.iter().filter_map(convert_value).collect();
+                    let encoded_values: Vec<String> = list
+                        .iter()
+                        .filter_map(convert_value)
+                        .map(|val| percent_encode(&val, QUERY_RESERVED).into_owned())
+                        .collect();
 
                     if encoded_values.is_empty() {
                         key.to_string()
@@ -36,7 +50,7 @@ impl QueryEncoder {
                     }
                 }
                 _ => convert_value(value)
-                    .map(|val| format!("{}={}", key, val))
+                    .map(|val| format!("{}={}", key, percent_encode(&val, QUERY_RESERVED)))
                     .unwrap_or(key.to_string()),
             },
             QueryEncoder::RepeatedKey => match value {
@@ -52,7 +66,7 @@ impl QueryEncoder {
                     }
                 }
                 _ => convert_value(value)
-                    .map(|val| format!("{}={}", key, val))
+                    .map(|val| format!("{}={}", key, percent_encode(&val, QUERY_RESERVED)))
                     .unwrap_or(key.to_string()),
             },
         }
@@ -69,6 +83,25 @@ pub fn convert_value(value: &async_graphql::Value) -> Option<String> {
     }
 }
 
+/// Percent-encodes the reserved characters, the control characters and the
+/// non-ASCII characters of the value.
+pub fn percent_encode<'a>(value: &'a str, reserved: &[u8]) -> Cow<'a, str> {
+    let is_encoded = |byte: u8| !byte.is_ascii_graphic() || reserved.contains(&byte);
+    if !value.bytes().any(is_encoded) {
+        return Cow::Borrowed(value);
+    }
+
+    let mut encoded = String::with_capacity(value.len() * 3);
+    for byte in value.bytes() {
+        if is_encoded(byte) {
+            encoded.push_str(&format!("%{:02X}", byte));
+        } else {
+            encoded.push(byte as char);
+        }
+    }
+    Cow::Owned(encoded)
+}
+
 #[cfg(test)]
 mod tests {
     use std::borrow::Cow;
@@ -253,4 +286,26 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_encode_reserved_characters() {
+        let encoder = QueryEncoder::CommaSeparated;
+        let values = Value::List(vec![
+            Value::String("a/b?c".to_string()),
+            Value::String("x&y=z".to_string()),
+        ]);
+        let arg_raw_value = Some(ValueString::Value(Cow::Borrowed(&values)));
+
+        let actual = encoder.encode("key", arg_raw_value);
+        let expected = "key=a/b?c,x%26y%3Dz".to_string();
+
+        assert_eq!(actual, expected);
+
+        let raw_value = Some(ValueString::String("50% off #1".into()));
+
+        let actual = encoder.encode("key", raw_value);
+        let expected = "key=50%25%20off%20%231".to_string();
+
+        assert_eq!(actual, expected);
+    }
 }
@@ -1,18 +1,23 @@
+use std::net::IpAddr;
 use std::num::NonZeroU64;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use async_graphql_value::ConstValue;
 use cache_control::{Cachability, CacheControl};
 use derive_setters::Setters;
 use http::header::{HeaderMap, HeaderName, HeaderValue};
+use http::{Method, Uri};
+use hyper::body::Bytes;
+use tokio::sync::Semaphore;
 
 use crate::core::app_context::AppContext;
 use crate::core::blueprint::{Server, Upstream};
 use crate::core::data_loader::{DataLoader, DedupeResult};
 use crate::core::graphql::GraphqlDataLoader;
 use crate::core::grpc::data_loader::GrpcDataLoader;
-use crate::core::http::{DataLoaderRequest, HttpDataLoader};
+use crate::core::http::{DataLoaderRequest, HttpDataLoader, Response};
 use crate::core::ir::model::IoId;
 use crate::core::ir::Error;
 use crate::core::runtime::TargetRuntime;
@@ -27,6 +32,9 @@ pub struct RequestContext {
     // A subset of all the headers received in the GraphQL Request that will be sent to the
     // upstream.
     pub allowed_headers: HeaderMap,
+    // The headers received in the GraphQL Request that specific resolvers forward
+    // to their upstream with `forwardHeaders`.
+    pub forwarded_headers: HeaderMap,
     pub http_data_loaders: Arc<Vec<DataLoader<DataLoaderRequest, HttpDataLoader>>>,
     pub gql_data_loaders: Arc<Vec<DataLoader<DataLoaderRequest, GraphqlDataLoader>>>,
     pub grpc_data_loaders: Arc<Vec<DataLoader<grpc::DataLoaderRequest, GrpcDataLoader>>>,
@@ -35,6 +43,22 @@ pub struct RequestContext {
     pub runtime: TargetRuntime,
     pub cache: DedupeResult<IoId, ConstValue, Error>,
     pub dedupe_handler: Arc<DedupeResult<IoId, ConstValue, Error>>,
+    // Responses of the upstream requests shared by the fields of the operation
+    // when `coalesce` is enabled
+    pub coalesced_requests: DedupeResult<DataLoaderRequest, Response<Bytes>, Error>,
+    // Limits the number of resolver calls executed concurrently across requests
+    pub concurrency_limiter: Option<Arc<Semaphore>>,
+    // IP of the client that made the request, when it's known
+    pub client_ip: Option<IpAddr>,
+    // Method and URI of the request received by the server
+    pub request_method: Option<Method>,
+    pub request_uri: Option<Uri>,
+    // Locale preferred by the client in `Accept-Language`
+    pub locale: Option<String>,
+    // Id of the request, sent as `X-Request-Id` to the upstream and the client
+    pub request_id: Option<HeaderValue>,
+    // Instant by which the operation has to complete, when it has a timeout
+    pub deadline: Arc<Mutex<Option<Instant>>>,
 }
 
 impl RequestContext {
@@ -52,7 +76,16 @@ impl RequestContext {
             runtime: target_runtime,
             cache: DedupeResult::new(true),
             dedupe_handler: Arc::new(DedupeResult::new(false)),
+            coalesced_requests: DedupeResult::new(true),
             allowed_headers: HeaderMap::new(),
+            forwarded_headers: HeaderMap::new(),
+            concurrency_limiter: None,
+            client_ip: None,
+            request_method: None,
+            request_uri: None,
+            locale: None,
+            request_id: None,
+            deadline: Arc::new(Mutex::new(None)),
         }
     }
     fn set_min_max_age_conc(&self, min_max_age: i32) {
@@ -148,6 +181,24 @@ impl RequestContext {
         self.runtime.cache.set(key, value, ttl).await
     }
 
+    /// Sets the instant by which the operation has to complete. The earliest
+    /// deadline is kept when the operations of a batch share the context.
+    pub fn set_deadline(&self, deadline: Instant) {
+        let mut current = self.deadline.lock().unwrap();
+        match *current {
+            Some(earliest) if earliest <= deadline => {}
+            _ => *current = Some(deadline),
+        }
+    }
+
+    /// Returns the time left before the deadline of the operation, if any.
+    pub fn remaining_budget(&self) -> Option<Duration> {
+        self.deadline
+            .lock()
+            .unwrap()
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
     pub fn is_batching_enabled(&self) -> bool {
         self.upstream.is_batching_enabled()
     }
@@ -193,6 +244,7 @@ impl From<&AppContext> for RequestContext {
             x_response_headers: Arc::new(Mutex::new(HeaderMap::new())),
             cookie_headers,
             allowed_headers: HeaderMap::new(),
+            forwarded_headers: HeaderMap::new(),
             http_data_loaders: app_ctx.http_data_loaders.clone(),
             gql_data_loaders: app_ctx.gql_data_loaders.clone(),
             grpc_data_loaders: app_ctx.grpc_data_loaders.clone(),
@@ -201,6 +253,14 @@ impl From<&AppContext> for RequestContext {
             runtime: app_ctx.runtime.clone(),
             cache: DedupeResult::new(true),
             dedupe_handler: app_ctx.dedupe_handler.clone(),
+            coalesced_requests: DedupeResult::new(true),
+            concurrency_limiter: app_ctx.concurrency_limiter.clone(),
+            client_ip: None,
+            request_method: None,
+            request_uri: None,
+            locale: None,
+            request_id: None,
+            deadline: Arc::new(Mutex::new(None)),
         }
     }
 }
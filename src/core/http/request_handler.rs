@@ -1,4 +1,5 @@
 use std::collections::BTreeSet;
+use std::net::{IpAddr, SocketAddr};
 use std::ops::Deref;
 use std::sync::Arc;
 
@@ -15,13 +16,19 @@ use serde::de::DeserializeOwned;
 use tracing::Instrument;
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
+use super::access_log::{AccessLog, ACCESS_LOG_TARGET};
 use super::request_context::RequestContext;
 use super::telemetry::{get_response_status_code, RequestCounter};
-use super::{showcase, telemetry, TAILCALL_HTTPS_ORIGIN, TAILCALL_HTTP_ORIGIN};
+use super::{
+    client_ip, locale, showcase, telemetry, TAILCALL_HTTPS_ORIGIN, TAILCALL_HTTP_ORIGIN,
+    X_REQUEST_ID,
+};
 use crate::core::app_context::AppContext;
-use crate::core::async_graphql_hyper::{GraphQLRequestLike, GraphQLResponse};
+use crate::core::async_graphql_hyper::{accepts_multipart, GraphQLRequestLike, GraphQLResponse};
 use crate::core::blueprint::telemetry::TelemetryExporter;
+use crate::core::blueprint::{Cors, Server};
 use crate::core::config::{PrometheusExporter, PrometheusFormat};
+use crate::core::ir::{EmptyResolverContext, EvalContext};
 use crate::core::jit::JITExecutor;
 
 pub const API_URL_PREFIX: &str = "/api";
@@ -48,16 +55,57 @@ fn prometheus_metrics(prometheus_exporter: &PrometheusExporter) -> Result<Respon
         .body(Body::from(buffer))?)
 }
 
-fn not_found() -> Result<Response<Body>> {
-    Ok(Response::builder()
-        .status(StatusCode::NOT_FOUND)
-        .body(Body::empty())?)
+fn not_found(server: &Server) -> Result<Response<Body>> {
+    match &server.not_found {
+        Some(not_found) => {
+            let mut response = Response::new(Body::from(not_found.body.clone()));
+            *response.status_mut() = not_found.status;
+            response.headers_mut().extend(not_found.headers.clone());
+            Ok(response)
+        }
+        None => Ok(Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty())?),
+    }
 }
 
 fn create_request_context(req: &Request<Body>, app_ctx: &AppContext) -> RequestContext {
-    let allowed_headers =
-        create_allowed_headers(req.headers(), &app_ctx.blueprint.upstream.allowed_headers);
-    RequestContext::from(app_ctx).allowed_headers(allowed_headers)
+    let upstream = &app_ctx.blueprint.upstream;
+    let allowed_headers = create_allowed_headers(req.headers(), &upstream.allowed_headers);
+    let forwarded_headers = create_allowed_headers(req.headers(), &upstream.forwarded_headers);
+    RequestContext::from(app_ctx)
+        .allowed_headers(allowed_headers)
+        .forwarded_headers(forwarded_headers)
+        .client_ip(get_client_ip(req, app_ctx))
+        .request_method(Some(req.method().clone()))
+        .request_uri(Some(req.uri().clone()))
+        .locale(locale(req.headers()))
+        .request_id(get_request_id(req, app_ctx))
+}
+
+/// Reuses the id sent by the client in `X-Request-Id`, or generates one, when
+/// `@server(headers: {requestId: true})` is set.
+fn get_request_id(req: &Request<Body>, app_ctx: &AppContext) -> Option<HeaderValue> {
+    if !app_ctx.blueprint.server.enable_request_id {
+        return None;
+    }
+
+    req.headers()
+        .get(&X_REQUEST_ID)
+        .filter(|id| !id.is_empty())
+        .cloned()
+        .or_else(|| HeaderValue::from_str(&uuid::Uuid::new_v4().to_string()).ok())
+}
+
+/// Finds the IP of the client, trusting the forwarding headers only as far as
+/// the number of proxies configured in `@server(trustedProxies: ...)`.
+fn get_client_ip(req: &Request<Body>, app_ctx: &AppContext) -> Option<IpAddr> {
+    let peer = req.extensions().get::<SocketAddr>().map(|addr| addr.ip());
+    client_ip(
+        req.headers(),
+        peer,
+        app_ctx.blueprint.server.trusted_proxies,
+    )
 }
 
 pub fn update_response_headers(
@@ -71,6 +119,21 @@ pub fn update_response_headers(
             .extend(app_ctx.blueprint.server.response_headers.clone());
     }
 
+    // Add templated response headers, skipping the ones that render to nothing
+    let templates = &app_ctx.blueprint.server.response_header_templates;
+    if !templates.is_empty() {
+        let ctx = EvalContext::new(req_ctx, &EmptyResolverContext {});
+        for (name, template) in templates {
+            let value = template.render(&ctx);
+            if value.is_empty() {
+                continue;
+            }
+            if let Ok(value) = HeaderValue::from_str(&value) {
+                resp.headers_mut().insert(name.clone(), value);
+            }
+        }
+    }
+
     // Insert Cookie Headers
     if let Some(ref cookie_headers) = req_ctx.cookie_headers {
         let cookie_headers = cookie_headers.lock().unwrap();
@@ -79,6 +142,11 @@ pub fn update_response_headers(
 
     // Insert Experimental Headers
     req_ctx.extend_x_headers(resp.headers_mut());
+
+    if let Some(ref request_id) = req_ctx.request_id {
+        resp.headers_mut()
+            .insert(X_REQUEST_ID.clone(), request_id.clone());
+    }
 }
 
 #[tracing::instrument(skip_all, fields(otel.name = "graphQL", otel.kind = ?SpanKind::Server))]
@@ -120,7 +188,8 @@ async fn execute_query<T: DeserializeOwned + GraphQLRequestLike>(
     req: Parts,
 ) -> anyhow::Result<Response<Body>> {
     let operation_id = request.operation_id(&req.headers);
-    let exec = JITExecutor::new(app_ctx.clone(), req_ctx.clone(), operation_id);
+    let exec = JITExecutor::new(app_ctx.clone(), req_ctx.clone(), operation_id)
+        .multipart(accepts_multipart(&req.headers));
     let mut response = request
         .execute_with_jit(exec)
         .await
@@ -129,6 +198,8 @@ async fn execute_query<T: DeserializeOwned + GraphQLRequestLike>(
             req_ctx.get_min_max_age().unwrap_or(0),
             req_ctx.is_cache_public().unwrap_or(true),
         )
+        .set_format(app_ctx.blueprint.server.response_format)
+        .set_compression(app_ctx.blueprint.server.compression, &req.headers)
         .into_response()?;
 
     update_response_headers(&mut response, req_ctx, app_ctx);
@@ -180,14 +251,24 @@ async fn handle_origin_tailcall<T: DeserializeOwned + GraphQLRequestLike>(
     }
 }
 
+/// Picks the CORS rules for the requested path: the REST endpoints can have
+/// their own rules, and otherwise the global ones apply.
+fn cors_rules<'a>(path: &str, server: &'a Server) -> Option<&'a Cors> {
+    if path.starts_with(API_URL_PREFIX) {
+        server.rest_cors.as_ref().or(server.cors.as_ref())
+    } else {
+        server.cors.as_ref()
+    }
+}
+
 async fn handle_request_with_cors<T: DeserializeOwned + GraphQLRequestLike>(
     req: Request<Body>,
     app_ctx: Arc<AppContext>,
     request_counter: &mut RequestCounter,
 ) -> Result<Response<Body>> {
     // Safe to call `.unwrap()` because this method will only be called when
-    // `cors` is `Some`
-    let cors = app_ctx.blueprint.server.cors.as_ref().unwrap();
+    // there are CORS rules for the route
+    let cors = cors_rules(req.uri().path(), &app_ctx.blueprint.server).unwrap();
     let (parts, body) = req.into_parts();
     let origin = parts.headers.get(&header::ORIGIN);
 
@@ -199,7 +280,7 @@ async fn handle_request_with_cors<T: DeserializeOwned + GraphQLRequestLike>(
     headers.extend(cors.allow_origin_to_header(origin));
     headers.extend(cors.allow_credentials_to_header());
     headers.extend(cors.allow_private_network_to_header(&parts));
-    headers.extend(cors.vary_to_header());
+    headers.extend(cors.vary_to_header(parts.method == Method::OPTIONS));
 
     // Return results immediately upon preflight request
     if parts.method == Method::OPTIONS {
@@ -269,7 +350,7 @@ async fn handle_rest_apis(
         .await;
     }
 
-    not_found()
+    not_found(&app_ctx.blueprint.server)
 }
 
 async fn handle_request_inner<T: DeserializeOwned + GraphQLRequestLike>(
@@ -318,9 +399,9 @@ async fn handle_request_inner<T: DeserializeOwned + GraphQLRequestLike>(
                     return prometheus_metrics(prometheus);
                 }
             };
-            not_found()
+            not_found(&app_ctx.blueprint.server)
         }
-        _ => not_found(),
+        _ => not_found(&app_ctx.blueprint.server),
     }
 }
 
@@ -340,8 +421,13 @@ pub async fn handle_request<T: DeserializeOwned + GraphQLRequestLike>(
 ) -> Result<Response<Body>> {
     telemetry::propagate_context(&req);
     let mut req_counter = RequestCounter::new(&app_ctx.blueprint.telemetry, &req);
+    let access_log = app_ctx
+        .blueprint
+        .server
+        .enable_access_log
+        .then(|| AccessLog::new(&req, get_client_ip(&req, &app_ctx)));
 
-    let response = if app_ctx.blueprint.server.cors.is_some() {
+    let response = if cors_rules(req.uri().path(), &app_ctx.blueprint.server).is_some() {
         handle_request_with_cors::<T>(req, app_ctx, &mut req_counter).await
     } else if let Some(origin) = req.headers().get(&header::ORIGIN) {
         if origin == TAILCALL_HTTPS_ORIGIN || origin == TAILCALL_HTTP_ORIGIN {
@@ -357,6 +443,10 @@ pub async fn handle_request<T: DeserializeOwned + GraphQLRequestLike>(
     if let Ok(response) = &response {
         let status = get_response_status_code(response);
         tracing::Span::current().set_attribute(status.key, status.value);
+
+        if let Some(access_log) = access_log {
+            tracing::info!(target: ACCESS_LOG_TARGET, "{}", access_log.to_line(response));
+        }
     };
 
     response
@@ -429,6 +519,394 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_custom_not_found() -> anyhow::Result<()> {
+        let config = Config::from_sdl(
+            r#"
+            schema
+              @server(notFound: {status: 410, contentType: "text/html", body: "<h1>Gone</h1>"}) {
+              query: Query
+            }
+
+            type Query {
+              greet: String @expr(body: "Hello World!")
+            }
+            "#,
+        )
+        .to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let app_ctx = Arc::new(AppContext::new(
+            blueprint,
+            init(None),
+            EndpointSet::default(),
+        ));
+
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("http://localhost:8000/unknown".to_string())
+            .body(Body::empty())?;
+
+        let resp = handle_request::<GraphQLRequest>(req, app_ctx).await?;
+
+        assert_eq!(resp.status(), StatusCode::GONE);
+        assert_eq!(resp.headers().get(CONTENT_TYPE).unwrap(), "text/html");
+        let body = hyper::body::to_bytes(resp.into_body()).await?;
+        assert_eq!(body, "<h1>Gone</h1>");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_not_found_redirect() -> anyhow::Result<()> {
+        let config = Config::from_sdl(
+            r#"
+            schema @server(notFound: {redirect: "https://tailcall.run"}) {
+              query: Query
+            }
+
+            type Query {
+              greet: String @expr(body: "Hello World!")
+            }
+            "#,
+        )
+        .to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let app_ctx = Arc::new(AppContext::new(
+            blueprint,
+            init(None),
+            EndpointSet::default(),
+        ));
+
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost:8000/unknown".to_string())
+            .body(Body::empty())?;
+
+        let resp = handle_request::<GraphQLRequest>(req, app_ctx).await?;
+
+        assert_eq!(resp.status(), StatusCode::FOUND);
+        assert_eq!(
+            resp.headers().get(header::LOCATION).unwrap(),
+            "https://tailcall.run"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_templated_response_headers() -> anyhow::Result<()> {
+        let config = Config::from_sdl(
+            r#"
+            schema
+              @server(headers: {custom: [{key: "x-correlation-id", value: "{{.headers.x-request-id}}"}, {key: "x-missing", value: "{{.headers.x-missing}}"}]})
+              @upstream(allowedHeaders: ["x-request-id"]) {
+              query: Query
+            }
+
+            type Query {
+              greet: String @expr(body: "Hello World!")
+            }
+            "#,
+        )
+        .to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let app_ctx = Arc::new(AppContext::new(
+            blueprint,
+            init(None),
+            EndpointSet::default(),
+        ));
+
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost:8000/graphql".to_string())
+            .header("Content-Type", "application/json")
+            .header("x-request-id", "abc-123")
+            .body(Body::from(r#"{"query": "{ greet }"}"#))?;
+
+        let resp = handle_request::<GraphQLRequest>(req, app_ctx).await?;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get("x-correlation-id").unwrap(), "abc-123");
+        assert!(resp.headers().get("x-missing").is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_request_id() -> anyhow::Result<()> {
+        let config = Config::from_sdl(
+            r#"
+            schema @server(headers: {requestId: true}) {
+              query: Query
+            }
+
+            type Query {
+              greet: String @expr(body: "Hello World!")
+            }
+            "#,
+        )
+        .to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let app_ctx = Arc::new(AppContext::new(
+            blueprint,
+            init(None),
+            EndpointSet::default(),
+        ));
+
+        let request = || {
+            Request::builder()
+                .method(Method::POST)
+                .uri("http://localhost:8000/graphql".to_string())
+                .header("Content-Type", "application/json")
+        };
+
+        let req = request().body(Body::from(r#"{"query": "{ greet }"}"#))?;
+        let resp = handle_request::<GraphQLRequest>(req, app_ctx.clone()).await?;
+        let request_id = resp.headers().get("x-request-id").unwrap().to_str()?;
+        assert!(uuid::Uuid::parse_str(request_id).is_ok());
+
+        let req = request()
+            .header("X-Request-Id", "abc-123")
+            .body(Body::from(r#"{"query": "{ greet }"}"#))?;
+        let resp = handle_request::<GraphQLRequest>(req, app_ctx).await?;
+        assert_eq!(resp.headers().get("x-request-id").unwrap(), "abc-123");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deferred_fragment() -> anyhow::Result<()> {
+        let config = Config::from_sdl(
+            r#"
+            schema {
+              query: Query
+            }
+
+            type Query {
+              greet: String @expr(body: "Hello World!")
+              slow: String @expr(body: "Hello Defer!")
+            }
+            "#,
+        )
+        .to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let app_ctx = Arc::new(AppContext::new(
+            blueprint,
+            init(None),
+            EndpointSet::default(),
+        ));
+
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost:8000/graphql".to_string())
+            .header("Content-Type", "application/json")
+            .header(
+                "Accept",
+                "multipart/mixed; deferSpec=20220824, application/json",
+            )
+            .body(Body::from(
+                r#"{"query": "{ greet ... @defer(label: \"slow\") { slow } }"}"#,
+            ))?;
+
+        let resp = handle_request::<GraphQLRequest>(req, app_ctx).await?;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("content-type").unwrap(),
+            r#"multipart/mixed; boundary="-"; deferSpec=20220824"#
+        );
+        let body = hyper::body::to_bytes(resp.into_body()).await?;
+        assert_eq!(
+            String::from_utf8(body.to_vec())?,
+            [
+                "\r\n---\r\nContent-Type: application/json; charset=utf-8\r\n\r\n",
+                r#"{"data":{"greet":"Hello World!"},"hasNext":true}"#,
+                "\r\n---\r\nContent-Type: application/json; charset=utf-8\r\n\r\n",
+                r#"{"incremental":[{"data":{"slow":"Hello Defer!"},"path":[],"label":"slow"}],"hasNext":false}"#,
+                "\r\n-----\r\n",
+            ]
+            .concat()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deferred_fragment_without_multipart() -> anyhow::Result<()> {
+        let config = Config::from_sdl(
+            r#"
+            schema {
+              query: Query
+            }
+
+            type Query {
+              greet: String @expr(body: "Hello World!")
+              slow: String @expr(body: "Hello Defer!")
+            }
+            "#,
+        )
+        .to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let app_ctx = Arc::new(AppContext::new(
+            blueprint,
+            init(None),
+            EndpointSet::default(),
+        ));
+
+        // the client doesn't accept multipart, so everything is sent at once
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost:8000/graphql".to_string())
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json")
+            .body(Body::from(
+                r#"{"query": "{ greet ... @defer(label: \"slow\") { slow } }"}"#,
+            ))?;
+
+        let resp = handle_request::<GraphQLRequest>(req, app_ctx).await?;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("content-type").unwrap(),
+            "application/json"
+        );
+        let body = hyper::body::to_bytes(resp.into_body()).await?;
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&body)?,
+            serde_json::json!({"data": {"greet": "Hello World!", "slow": "Hello Defer!"}})
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_streamed_list() -> anyhow::Result<()> {
+        let config = Config::from_sdl(
+            r#"
+            schema {
+              query: Query
+            }
+
+            type Query {
+              numbers: [Int] @expr(body: [1, 2, 3])
+            }
+            "#,
+        )
+        .to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let app_ctx = Arc::new(AppContext::new(
+            blueprint,
+            init(None),
+            EndpointSet::default(),
+        ));
+
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("http://localhost:8000/graphql".to_string())
+            .header("Content-Type", "application/json")
+            .header("Accept", "multipart/mixed")
+            .body(Body::from(
+                r#"{"query": "{ numbers @stream(initialCount: 1) }"}"#,
+            ))?;
+
+        let resp = handle_request::<GraphQLRequest>(req, app_ctx).await?;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(resp.into_body()).await?;
+        let body = String::from_utf8(body.to_vec())?;
+        let payloads = body
+            .strip_suffix("\r\n-----\r\n")
+            .unwrap()
+            .split("\r\n---\r\nContent-Type: application/json; charset=utf-8\r\n\r\n")
+            .skip(1)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            payloads,
+            vec![
+                r#"{"data":{"numbers":[1]},"hasNext":true}"#,
+                r#"{"incremental":[{"items":[2],"path":["numbers",1]}],"hasNext":true}"#,
+                r#"{"incremental":[{"items":[3],"path":["numbers",2]}],"hasNext":true}"#,
+                r#"{"hasNext":false}"#,
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rest_cors() -> anyhow::Result<()> {
+        let config = Config::from_sdl(
+            r#"
+            schema
+              @server(
+                headers: {
+                  cors: {allowOrigins: ["https://app.example.com"], allowMethods: [POST]}
+                  restCors: {allowOrigins: ["https://rest.example.com"], allowMethods: [GET]}
+                }
+              ) {
+              query: Query
+            }
+
+            type Query {
+              greet: String @expr(body: "Hello World!")
+            }
+            "#,
+        )
+        .to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let app_ctx = Arc::new(AppContext::new(
+            blueprint,
+            init(None),
+            EndpointSet::default(),
+        ));
+
+        let preflight = |path: &str, origin: &str| {
+            Request::builder()
+                .method(Method::OPTIONS)
+                .uri(format!("http://localhost:8000{}", path))
+                .header("Origin", origin)
+                .header("Access-Control-Request-Method", "GET")
+                .body(Body::empty())
+        };
+
+        let resp = handle_request::<GraphQLRequest>(
+            preflight("/api/greet", "https://rest.example.com")?,
+            app_ctx.clone(),
+        )
+        .await?;
+        assert_eq!(
+            resp.headers().get("access-control-allow-origin").unwrap(),
+            "https://rest.example.com"
+        );
+        assert_eq!(
+            resp.headers().get("access-control-allow-methods").unwrap(),
+            "GET"
+        );
+
+        let resp = handle_request::<GraphQLRequest>(
+            preflight("/api/greet", "https://app.example.com")?,
+            app_ctx.clone(),
+        )
+        .await?;
+        assert!(resp.headers().get("access-control-allow-origin").is_none());
+
+        let resp = handle_request::<GraphQLRequest>(
+            preflight("/graphql", "https://app.example.com")?,
+            app_ctx,
+        )
+        .await?;
+        assert_eq!(
+            resp.headers().get("access-control-allow-origin").unwrap(),
+            "https://app.example.com"
+        );
+        assert_eq!(
+            resp.headers().get("access-control-allow-methods").unwrap(),
+            "POST"
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_create_allowed_headers() {
         use std::collections::BTreeSet;
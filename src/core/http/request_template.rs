@@ -1,12 +1,14 @@
 use std::borrow::Cow;
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
 use derive_setters::Setters;
-use http::header::{HeaderMap, HeaderValue};
+use http::header::{HeaderMap, HeaderName, HeaderValue};
 use tailcall_hasher::TailcallHasher;
 use url::Url;
 
-use super::query_encoder::QueryEncoder;
+use super::query_encoder::{percent_encode, QueryEncoder, PATH_RESERVED, QUERY_RESERVED};
+use super::{ResponseDecoder, XmlTypes};
 use crate::core::config::Encoding;
 use crate::core::endpoint::Endpoint;
 use crate::core::has_headers::HasHeaders;
@@ -26,10 +28,14 @@ pub struct RequestTemplate {
     pub query: Vec<Query>,
     pub method: reqwest::Method,
     pub headers: MustacheHeaders,
+    pub forward_headers: Vec<HeaderName>,
     pub body_path: Option<Mustache>,
     pub endpoint: Endpoint,
     pub encoding: Encoding,
     pub query_encoder: QueryEncoder,
+    pub response_decoder: ResponseDecoder,
+    /// Converts the text of an XML response to the types of the field
+    pub xml_types: Option<Arc<XmlTypes>>,
 }
 
 #[derive(Setters, Debug, Clone)]
@@ -43,7 +49,7 @@ impl RequestTemplate {
     /// Creates a URL for the context
     /// Fills in all the mustache templates with required values.
     fn create_url<C: PathString + PathValue>(&self, ctx: &C) -> anyhow::Result<Url> {
-        let mut url = url::Url::parse(self.root_url.render(ctx).as_str())?;
+        let mut url = url::Url::parse(self.render_url(ctx).as_str())?;
         if self.query.is_empty() && self.root_url.is_const() {
             return Ok(url);
         }
@@ -68,7 +74,14 @@ impl RequestTemplate {
             .query_pairs()
             .filter_map(|(k, v)| if v.is_empty() { None } else { Some((k, v)) });
 
-        let qp_string = base_qp.map(|(k, v)| format!("{}={}", k, v));
+        // the pairs are decoded, so they're encoded again
+        let qp_string = base_qp.map(|(k, v)| {
+            format!(
+                "{}={}",
+                percent_encode(&k, QUERY_RESERVED),
+                percent_encode(&v, QUERY_RESERVED)
+            )
+        });
         let qp_string = qp_string.chain(extra_qp).fold("".to_string(), |str, item| {
             if str.is_empty() {
                 item
@@ -88,6 +101,34 @@ impl RequestTemplate {
         }
     }
 
+    /// Renders the root URL, percent-encoding the values substituted into the
+    /// path and the query, so that e.g. an id containing `/` stays a single
+    /// segment of the path. Values substituted before the path, e.g. a base
+    /// URL from the environment, are inserted as is.
+    fn render_url<C: PathString>(&self, ctx: &C) -> String {
+        let mut url = String::new();
+        for segment in self.root_url.segments() {
+            match segment {
+                Segment::Literal(text) => url.push_str(text),
+                Segment::Expression(parts) => {
+                    let value = ctx.path_string(parts).unwrap_or_default();
+                    let in_path = url
+                        .split_once("://")
+                        .is_some_and(|(_, rest)| rest.contains('/'));
+
+                    if url.contains('?') {
+                        url.push_str(&percent_encode(&value, QUERY_RESERVED));
+                    } else if in_path {
+                        url.push_str(&percent_encode(&value, PATH_RESERVED));
+                    } else {
+                        url.push_str(&value);
+                    }
+                }
+            }
+        }
+        url
+    }
+
     /// Checks if the template has any mustache templates or not
     /// Returns true if there are not templates
     pub fn is_const(&self) -> bool {
@@ -98,7 +139,7 @@ impl RequestTemplate {
     }
 
     /// Creates a HeaderMap for the context
-    fn create_headers<C: PathString>(&self, ctx: &C) -> HeaderMap {
+    fn create_headers<C: PathString + HasHeaders>(&self, ctx: &C) -> HeaderMap {
         let mut header_map = HeaderMap::new();
 
         for (k, v) in &self.headers {
@@ -107,6 +148,17 @@ impl RequestTemplate {
             }
         }
 
+        let forwarded = ctx.forwarded_headers();
+        for name in &self.forward_headers {
+            let mut values = forwarded.get_all(name).iter();
+            if let Some(first) = values.next() {
+                header_map.insert(name, first.clone());
+                for value in values {
+                    header_map.append(name, value.clone());
+                }
+            }
+        }
+
         header_map
     }
 
@@ -192,10 +244,13 @@ impl RequestTemplate {
             query: Default::default(),
             method: reqwest::Method::GET,
             headers: Default::default(),
+            forward_headers: Default::default(),
             body_path: Default::default(),
             endpoint: Endpoint::new(root_url.to_string()),
             encoding: Default::default(),
             query_encoder: Default::default(),
+            response_decoder: Default::default(),
+            xml_types: Default::default(),
         })
     }
 
@@ -243,10 +298,13 @@ impl TryFrom<Endpoint> for RequestTemplate {
             query,
             method,
             headers,
+            forward_headers: Default::default(),
             body_path: body,
             endpoint,
             encoding,
             query_encoder: Default::default(),
+            response_decoder: Default::default(),
+            xml_types: Default::default(),
         })
     }
 }
@@ -268,6 +326,14 @@ impl<Ctx: PathString + HasHeaders + PathValue> CacheKey<Ctx> for RequestTemplate
             value.hash(state);
         }
 
+        let forwarded = ctx.forwarded_headers();
+        for name in self.forward_headers.iter() {
+            for value in forwarded.get_all(name) {
+                name.hash(state);
+                value.hash(state);
+            }
+        }
+
         if let Some(body) = self.body_path.as_ref() {
             body.render(ctx).hash(state)
         }
@@ -579,6 +645,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_url_template_encoding() {
+        let query = vec![Query {
+            key: "id".to_string(),
+            value: Mustache::parse("{{id}}"),
+            skip_empty: false,
+        }];
+        let tmpl = RequestTemplate::new("http://localhost:3000/users/{{id}}?from={{id}}")
+            .unwrap()
+            .query(query);
+        let ctx = Context::default().value(json!({
+          "id": "a/b?c&d=1"
+        }));
+        let request_wrapper = tmpl.to_request(&ctx).unwrap();
+        let req = request_wrapper.request();
+        assert_eq!(
+            req.url().to_string(),
+            "http://localhost:3000/users/a%2Fb%3Fc&d=1?from=a/b?c%26d%3D1&id=a/b?c%26d%3D1"
+        );
+    }
+
     #[test]
     fn test_headers() {
         let headers = vec![
@@ -708,6 +795,31 @@ mod tests {
         assert_eq!(body, "baz");
     }
 
+    #[test]
+    fn test_body_encoding_application_x_www_form_urlencoded() {
+        let tmpl = RequestTemplate::form_encoded_url("http://localhost:3000")
+            .unwrap()
+            .method(reqwest::Method::POST)
+            .body_path(Some(Mustache::parse(
+                r#"{"age":{{foo.age}},"name":"{{foo.name}}"}"#,
+            )));
+        let ctx = Context::default().value(json!({
+          "foo": {
+            "age": 30,
+            "name": "John Doe & Co"
+          }
+        }));
+        let request_wrapper = tmpl.to_request(&ctx).unwrap();
+        let req = request_wrapper.request();
+        assert_eq!(
+            req.headers().get("Content-Type").unwrap(),
+            "application/x-www-form-urlencoded"
+        );
+
+        let body = tmpl.to_body(&ctx).unwrap();
+        assert_eq!(body, "age=30&name=John+Doe+%26+Co");
+    }
+
     mod endpoint {
         use http::header::HeaderMap;
         use serde_json::json;
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use async_graphql_value::{ConstValue, Name};
 use derive_setters::Setters;
+use futures_util::stream::{self, BoxStream, StreamExt};
 use hyper::body::Bytes;
 use hyper::Body;
 use indexmap::IndexMap;
@@ -8,6 +9,9 @@ use prost::Message;
 use tonic::Status;
 use tonic_types::Status as GrpcStatus;
 
+use super::csv::from_csv;
+use super::json_seq::from_json_seq;
+use super::xml::from_xml;
 use crate::core::grpc::protobuf::ProtobufOperation;
 use crate::core::ir::Error;
 
@@ -18,6 +22,9 @@ pub struct Response<Body> {
     pub body: Body,
 }
 
+/// The body of a response as its chunks are received.
+pub type BodyStream = BoxStream<'static, Result<Bytes>>;
+
 // Trait to convert a serde_json_borrow::Value to a ConstValue.
 // serde_json_borrow::Value is a borrowed version of serde_json::Value.
 // It has a limited lifetime tied to the input JSON, making it more
@@ -48,6 +55,19 @@ impl FromValue for ConstValue {
     }
 }
 
+/// Parses the body of an upstream response to a [ConstValue].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResponseDecoder {
+    #[default]
+    Json,
+    Xml,
+    Csv {
+        delimiter: char,
+        header: bool,
+    },
+    JsonSeq,
+}
+
 impl Response<Bytes> {
     pub async fn from_reqwest(resp: reqwest::Response) -> Result<Self> {
         let status = resp.status();
@@ -87,6 +107,18 @@ impl Response<Bytes> {
         Ok(Response { status: self.status, headers: self.headers, body })
     }
 
+    /// Parses the body according to the response format of the upstream.
+    pub fn to_value(self, decoder: ResponseDecoder) -> Result<Response<ConstValue>> {
+        let body = match decoder {
+            ResponseDecoder::Json => return self.to_json(),
+            _ if self.body.is_empty() => return self.to_json(),
+            ResponseDecoder::Xml => from_xml(&self.body)?,
+            ResponseDecoder::Csv { delimiter, header } => from_csv(&self.body, delimiter, header)?,
+            ResponseDecoder::JsonSeq => from_json_seq(&self.body)?,
+        };
+        Ok(Response { status: self.status, headers: self.headers, body })
+    }
+
     pub fn to_grpc_value(
         self,
         operation: &ProtobufOperation,
@@ -147,6 +179,27 @@ impl Response<Bytes> {
         anyhow::Error::new(error)
     }
 
+    /// Creates the error for a response with a 4xx/5xx status. Up to
+    /// `body_size` bytes of the body are kept to help diagnose the failure.
+    pub fn to_http_error(&self, body_size: usize) -> anyhow::Error {
+        let body = (body_size > 0).then(|| {
+            let end = self.body.len().min(body_size);
+            String::from_utf8_lossy(&self.body[..end]).into_owned()
+        });
+
+        anyhow::Error::new(Error::HTTP { status: self.status.as_u16(), body })
+    }
+
+    /// Turns the received body into a stream of a single chunk.
+    pub fn into_stream(self) -> Response<BodyStream> {
+        let body = self.body;
+        Response {
+            status: self.status,
+            headers: self.headers,
+            body: stream::once(async move { Ok(body) }).boxed(),
+        }
+    }
+
     pub fn to_resp_string(self) -> Result<Response<String>> {
         Ok(Response::<String> {
             body: String::from_utf8(self.body.to_vec())?,
@@ -164,3 +217,40 @@ impl From<Response<Bytes>> for http::Response<Body> {
         response
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use hyper::body::Bytes;
+
+    use super::Response;
+    use crate::core::ir::Error;
+
+    fn http_error(body_size: usize) -> Error {
+        let response = Response {
+            status: reqwest::StatusCode::UNPROCESSABLE_ENTITY,
+            headers: Default::default(),
+            body: Bytes::from_static(b"name is invalid"),
+        };
+
+        response.to_http_error(body_size).downcast().unwrap()
+    }
+
+    #[test]
+    fn test_http_error_body_is_capped() {
+        let Error::HTTP { status, body } = http_error(4) else {
+            panic!("expected an HTTP error");
+        };
+
+        assert_eq!(status, 422);
+        assert_eq!(body.as_deref(), Some("name"));
+    }
+
+    #[test]
+    fn test_http_error_body_is_redacted() {
+        let Error::HTTP { body, .. } = http_error(0) else {
+            panic!("expected an HTTP error");
+        };
+
+        assert_eq!(body, None);
+    }
+}
@@ -0,0 +1,339 @@
+use std::collections::BTreeMap;
+
+use async_graphql_value::{ConstValue, Name};
+use indexmap::IndexMap;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::core::config::Config;
+use crate::core::Type;
+
+/// The field that holds the text of an element that also has attributes or
+/// child elements.
+const TEXT_FIELD: &str = "_text";
+
+/// Converts an XML document to the equivalent JSON value:
+///
+/// * the root element becomes the value itself;
+/// * an element without attributes and child elements becomes its text, or
+///   `null` when it's empty;
+/// * any other element becomes an object with its attributes and child elements
+///   as fields, in document order, and its text in the `_text` field;
+/// * elements and attributes with the same name within an element are collected
+///   into a list;
+/// * text is kept as a string, [XmlTypes] converts it to the types of the
+///   fields;
+/// * namespace prefixes are dropped, namespace declarations, comments and
+///   processing instructions are ignored.
+pub fn from_xml(body: &[u8]) -> anyhow::Result<ConstValue> {
+    let mut reader = Reader::from_reader(body);
+    reader.trim_text(true);
+
+    let mut stack: Vec<Element> = vec![];
+    let mut root = None;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(start) => stack.push(Element::new(&start)?),
+            Event::Empty(start) => Element::new(&start)?.close(&mut stack, &mut root),
+            Event::End(_) => match stack.pop() {
+                Some(element) => element.close(&mut stack, &mut root),
+                None => anyhow::bail!("Unexpected closing tag in XML"),
+            },
+            Event::Text(text) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&text.unescape()?);
+                }
+            }
+            Event::CData(data) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&String::from_utf8_lossy(&data));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    root.ok_or_else(|| anyhow::anyhow!("XML document has no root element"))
+}
+
+struct Element {
+    name: String,
+    fields: IndexMap<Name, ConstValue>,
+    text: String,
+}
+
+impl Element {
+    fn new(start: &BytesStart) -> anyhow::Result<Self> {
+        let mut element = Element {
+            name: String::from_utf8_lossy(start.local_name().as_ref()).to_string(),
+            fields: IndexMap::new(),
+            text: String::new(),
+        };
+
+        for attr in start.attributes() {
+            let attr = attr?;
+            if attr.key.as_namespace_binding().is_some() {
+                continue;
+            }
+            let name = String::from_utf8_lossy(attr.key.local_name().as_ref()).to_string();
+            let value = ConstValue::String(attr.unescape_value()?.to_string());
+            element.insert(name, value);
+        }
+
+        Ok(element)
+    }
+
+    fn insert(&mut self, name: String, value: ConstValue) {
+        match self.fields.get_mut(name.as_str()) {
+            Some(ConstValue::List(values)) => values.push(value),
+            Some(existing) => {
+                let first = std::mem::take(existing);
+                *existing = ConstValue::List(vec![first, value]);
+            }
+            None => {
+                self.fields.insert(Name::new(name), value);
+            }
+        }
+    }
+
+    /// Adds the element to its parent, or makes it the root of the document.
+    fn close(self, stack: &mut [Element], root: &mut Option<ConstValue>) {
+        let name = self.name.clone();
+        let value = self.into_value();
+        match stack.last_mut() {
+            Some(parent) => parent.insert(name, value),
+            None => *root = Some(value),
+        }
+    }
+
+    fn into_value(mut self) -> ConstValue {
+        if self.fields.is_empty() {
+            if self.text.is_empty() {
+                ConstValue::Null
+            } else {
+                ConstValue::String(self.text)
+            }
+        } else {
+            if !self.text.is_empty() {
+                self.fields
+                    .insert(Name::new(TEXT_FIELD), ConstValue::String(self.text));
+            }
+            ConstValue::Object(self.fields)
+        }
+    }
+}
+
+/// The types of the fields an XML response is read into, so the text of the
+/// elements is converted to the scalars of the fields, e.g. `<id>1</id>`
+/// becomes a number on an `Int` field while `<zip>01234</zip>` stays a string
+/// on a `String` field. The elements of a list field become a list even when
+/// they appear once. Text that can't be converted is left as a string.
+#[derive(Clone, Debug)]
+pub struct XmlTypes {
+    root: Type,
+    /// The types of the fields of every object type reachable from the root
+    objects: BTreeMap<String, BTreeMap<String, Type>>,
+}
+
+impl XmlTypes {
+    pub fn new(root: &Type, config: &Config) -> Self {
+        let mut objects = BTreeMap::new();
+        let mut pending = vec![root.name().to_string()];
+
+        while let Some(type_name) = pending.pop() {
+            if objects.contains_key(&type_name) {
+                continue;
+            }
+            let Some(type_of) = config.find_type(&type_name) else {
+                continue;
+            };
+
+            let fields = type_of
+                .fields
+                .iter()
+                .map(|(name, field)| (name.clone(), field.type_of.clone()))
+                .collect::<BTreeMap<_, _>>();
+            pending.extend(fields.values().map(|type_of| type_of.name().to_string()));
+            objects.insert(type_name, fields);
+        }
+
+        Self { root: root.clone(), objects }
+    }
+
+    pub fn coerce(&self, value: ConstValue) -> ConstValue {
+        self.coerce_to(value, &self.root)
+    }
+
+    fn coerce_to(&self, value: ConstValue, type_of: &Type) -> ConstValue {
+        match (type_of, value) {
+            (_, ConstValue::Null) => ConstValue::Null,
+            (Type::List { of_type, .. }, ConstValue::List(items)) => ConstValue::List(
+                items
+                    .into_iter()
+                    .map(|item| self.coerce_to(item, of_type))
+                    .collect(),
+            ),
+            (Type::List { of_type, .. }, value) => {
+                ConstValue::List(vec![self.coerce_to(value, of_type)])
+            }
+            (Type::Named { name, .. }, ConstValue::Object(mut object)) => {
+                if let Some(fields) = self.objects.get(name) {
+                    for (field_name, value) in object.iter_mut() {
+                        if let Some(type_of) = fields.get(field_name.as_str()) {
+                            *value = self.coerce_to(std::mem::take(value), type_of);
+                        }
+                    }
+                }
+                ConstValue::Object(object)
+            }
+            (Type::Named { name, .. }, ConstValue::String(text)) => to_scalar(name, text),
+            (_, value) => value,
+        }
+    }
+}
+
+/// Converts the text to the scalar, when it's a number or a boolean.
+fn to_scalar(type_name: &str, text: String) -> ConstValue {
+    let value = match type_name {
+        "Int" | "Int8" | "Int16" | "Int32" | "UInt8" | "UInt16" | "UInt32" => text
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .map(|number| ConstValue::Number(number.into())),
+        "Float" => text
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(ConstValue::Number),
+        "Boolean" => text.trim().parse::<bool>().ok().map(ConstValue::Boolean),
+        _ => None,
+    };
+
+    value.unwrap_or(ConstValue::String(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql_value::ConstValue;
+    use serde_json::json;
+    use tailcall_valid::Validator;
+
+    use super::{from_xml, XmlTypes};
+    use crate::core::config::Config;
+    use crate::core::Type;
+
+    #[test]
+    fn test_from_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+              <soap:Body>
+                <!-- users of the account -->
+                <users count="2">
+                  <user id="1" active="true">
+                    <name>Leanne &amp; Co</name>
+                    <zip>01234</zip>
+                    <email/>
+                    <tag>admin</tag>
+                    <tag>editor</tag>
+                  </user>
+                  <user id="2" active="false">
+                    <name><![CDATA[<Ervin>]]></name>
+                    <balance currency="EUR">12.5</balance>
+                  </user>
+                </users>
+              </soap:Body>
+            </soap:Envelope>"#;
+
+        let actual = from_xml(xml.as_bytes()).unwrap();
+        let expected = ConstValue::from_json(json!({
+            "Body": {
+                "users": {
+                    "count": "2",
+                    "user": [
+                        {
+                            "id": "1",
+                            "active": "true",
+                            "name": "Leanne & Co",
+                            "zip": "01234",
+                            "email": null,
+                            "tag": ["admin", "editor"]
+                        },
+                        {
+                            "id": "2",
+                            "active": "false",
+                            "name": "<Ervin>",
+                            "balance": {"currency": "EUR", "_text": "12.5"}
+                        }
+                    ]
+                }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_xml_types() {
+        let config = Config::from_sdl(
+            r#"
+            type Query {
+              users: Users @http(url: "http://upstream/users", responseFormat: Xml)
+            }
+
+            type Users {
+              count: Int
+              user: [User]
+            }
+
+            type User {
+              id: Int!
+              active: Boolean
+              zip: String
+              phone: String
+              score: Float
+              tag: [String]
+            }
+            "#,
+        )
+        .to_result()
+        .unwrap();
+        let xml = r#"
+            <users count="1">
+              <user id="1" active="true">
+                <zip>01234</zip>
+                <phone>1e5</phone>
+                <score>1e5</score>
+                <tag>admin</tag>
+              </user>
+            </users>"#;
+
+        let types = XmlTypes::new(&Type::from("Users".to_string()), &config);
+        let actual = types.coerce(from_xml(xml.as_bytes()).unwrap());
+        let expected = ConstValue::from_json(json!({
+            "count": 1,
+            "user": [
+                {
+                    "id": 1,
+                    "active": true,
+                    "zip": "01234",
+                    "phone": "1e5",
+                    "score": 100000.0,
+                    "tag": ["admin"]
+                }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_from_xml_invalid() {
+        assert!(from_xml(b"<user><name>Leanne</user>").is_err());
+        assert!(from_xml(b"").is_err());
+    }
+}
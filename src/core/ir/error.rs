@@ -35,6 +35,15 @@ pub enum Error {
 
     #[from(ignore)]
     Entity(String),
+
+    #[from(ignore)]
+    UpstreamUnavailable(String),
+
+    #[from(ignore)]
+    HTTP {
+        status: u16,
+        body: Option<String>,
+    },
 }
 
 impl Display for Error {
@@ -67,7 +76,11 @@ impl From<Error> for Errata {
             }
             Error::Worker(err) => Errata::new("Worker Error").description(err.to_string()),
             Error::Cache(err) => Errata::new("Cache Error").description(err.to_string()),
-            Error::Entity(message) => Errata::new("Entity Resolver Error").description(message)
+            Error::Entity(message) => Errata::new("Entity Resolver Error").description(message),
+            Error::UpstreamUnavailable(upstream) => Errata::new("Upstream Unavailable")
+                .description(format!("circuit breaker is open for `{upstream}`")),
+            Error::HTTP { status, body: _ } => Errata::new("HTTP Error")
+                .description(format!("upstream responded with status {status}"))
         }
     }
 }
@@ -87,6 +100,15 @@ impl ErrorExtensions for Error {
                 e.set("grpcStatusMessage", grpc_status_message);
                 e.set("grpcStatusDetails", grpc_status_details.clone());
             }
+            if let Error::UpstreamUnavailable(_) = self {
+                e.set("code", "UPSTREAM_UNAVAILABLE");
+            }
+            if let Error::HTTP { status, body } = self {
+                e.set("upstreamStatus", *status);
+                if let Some(body) = body {
+                    e.set("upstreamBody", body);
+                }
+            }
         })
     }
 }
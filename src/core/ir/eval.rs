@@ -10,6 +10,8 @@ use super::eval_io::eval_io;
 use super::model::{Cache, CacheKey, Map, IR};
 use super::{Error, EvalContext, ResolverContextLike, TypedValue};
 use crate::core::auth::verify::{AuthVerifier, Verify};
+use crate::core::cache::metrics::CACHE_METRICS;
+use crate::core::config::{JoinConflict, UnmappedAction};
 use crate::core::json::{JsonLike, JsonObjectLike};
 use crate::core::merge_right::MergeRight;
 use crate::core::serde_value_ext::ValueExt;
@@ -44,13 +46,16 @@ impl IR {
                     expr.eval(ctx).await
                 }
                 IR::IO(io) => eval_io(io, ctx).await,
-                IR::Cache(Cache { max_age, io }) => {
+                IR::Cache(Cache { max_age, io, label }) => {
                     let io = io.deref();
-                    let key = io.cache_key(ctx);
+                    // skipped calls aren't cached since the predicate depends on the request
+                    let key = io.is_enabled(ctx).then(|| io.cache_key(ctx)).flatten();
                     if let Some(key) = key {
                         if let Some(val) = ctx.request_ctx.runtime.cache.get(&key).await? {
+                            CACHE_METRICS.hit(label);
                             Ok(val)
                         } else {
+                            CACHE_METRICS.miss(label);
                             let val = eval_io(io, ctx).await?;
                             ctx.request_ctx
                                 .runtime
@@ -63,24 +68,25 @@ impl IR {
                         eval_io(io, ctx).await
                     }
                 }
-                IR::Map(Map { input, map }) => {
+                IR::Map(Map { input, map, unmapped }) => {
                     fn recursive_map_enum(
                         val: Result<ConstValue, Error>,
                         map: &HashMap<String, String>,
+                        unmapped: UnmappedAction,
                     ) -> Result<ConstValue, Error> {
                         match val? {
                             ConstValue::Null => Ok(ConstValue::Null),
-                            ConstValue::String(key) => {
-                                if let Some(value) = map.get(&key) {
-                                    Ok(ConstValue::String(value.to_owned()))
-                                } else {
+                            ConstValue::String(key) => match (map.get(&key), unmapped) {
+                                (Some(value), _) => Ok(ConstValue::String(value.to_owned())),
+                                (None, UnmappedAction::PassThrough) => Ok(ConstValue::String(key)),
+                                (None, UnmappedAction::Error) => {
                                     Err(Error::ExprEval(format!("Can't find mapped key: {}.", key)))
                                 }
-                            }
+                            },
                             ConstValue::List(vec) => {
                                 let vec = vec
                                     .into_iter()
-                                    .map(|value| recursive_map_enum(Ok(value), map))
+                                    .map(|value| recursive_map_enum(Ok(value), map, unmapped))
                                     .collect::<Result<Vec<_>, _>>()?;
                                 Ok(ConstValue::List(vec))
                             }
@@ -89,7 +95,7 @@ impl IR {
                             )),
                         }
                     }
-                    recursive_map_enum(input.eval(ctx).await, map)
+                    recursive_map_enum(input.eval(ctx).await, map, *unmapped)
                 }
                 IR::Pipe(first, second) => {
                     let args = first.eval(&mut ctx.clone()).await?;
@@ -114,6 +120,18 @@ impl IR {
                         .reduce(|acc, result| acc.merge_right(result))
                         .unwrap_or_default())
                 }
+                IR::Join(conflict, vec) => {
+                    let results: Vec<_> = join_all(vec.iter().map(|ir| {
+                        let mut ctx = ctx.clone();
+
+                        async move { ir.eval(&mut ctx).await }
+                    }))
+                    .await
+                    .into_iter()
+                    .collect::<Result<_, _>>()?;
+
+                    join_objects(results, *conflict)
+                }
                 IR::Discriminate(discriminator, expr) => expr
                     .eval(ctx)
                     .await
@@ -170,6 +188,49 @@ impl IR {
     }
 }
 
+/// Merges the objects returned by the resolvers of a `@join` field shallowly,
+/// in the order of the resolvers. `null` results are skipped.
+fn join_objects(values: Vec<ConstValue>, conflict: JoinConflict) -> Result<ConstValue, Error> {
+    let mut joined = IndexMap::new();
+    let mut is_null = true;
+
+    for value in values {
+        let object = match value {
+            ConstValue::Null => continue,
+            ConstValue::Object(object) => object,
+            _ => return Err(Error::ExprEval("@join can only merge objects".to_string())),
+        };
+        is_null = false;
+
+        for (key, value) in object {
+            match joined.get(&key) {
+                Some(existing) if *existing != value => match conflict {
+                    JoinConflict::LeftWins => {}
+                    JoinConflict::RightWins => {
+                        joined.insert(key, value);
+                    }
+                    JoinConflict::Error => {
+                        return Err(Error::ExprEval(format!(
+                            "Conflicting values for field `{}` in @join",
+                            key
+                        )));
+                    }
+                },
+                Some(_) => {}
+                None => {
+                    joined.insert(key, value);
+                }
+            }
+        }
+    }
+
+    if is_null {
+        Ok(ConstValue::Null)
+    } else {
+        Ok(ConstValue::Object(joined))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -237,4 +298,148 @@ mod tests {
             assert_eq!(actual, expected);
         }
     }
+
+    mod join {
+        use serde_json::json;
+
+        use super::*;
+        use crate::core::blueprint::{Blueprint, DynamicValue};
+        use crate::core::http::RequestContext;
+        use crate::core::ir::EmptyResolverContext;
+
+        async fn join(conflict: JoinConflict) -> Result<ConstValue, Error> {
+            let values = [
+                json!({"id": 1, "name": "Leanne", "address": {"city": "Gwenborough"}}),
+                json!(null),
+                json!({"id": 1, "name": "Bret", "address": {"zip": "92998"}}),
+            ];
+            let ir = IR::Join(
+                conflict,
+                values
+                    .into_iter()
+                    .map(|value| {
+                        IR::Dynamic(DynamicValue::Value(ConstValue::from_json(value).unwrap()))
+                    })
+                    .collect(),
+            );
+            let runtime = crate::cli::runtime::init(&Blueprint::default());
+            let req_ctx = RequestContext::new(runtime);
+            let res_ctx = EmptyResolverContext {};
+            let mut eval_ctx = EvalContext::new(&req_ctx, &res_ctx);
+
+            ir.eval(&mut eval_ctx).await
+        }
+
+        #[tokio::test]
+        async fn test_conflict_policies() {
+            let actual = join(JoinConflict::RightWins).await.unwrap();
+            let expected = ConstValue::from_json(
+                json!({"id": 1, "name": "Bret", "address": {"zip": "92998"}}),
+            )
+            .unwrap();
+            assert_eq!(actual, expected);
+
+            let actual = join(JoinConflict::LeftWins).await.unwrap();
+            let expected = ConstValue::from_json(
+                json!({"id": 1, "name": "Leanne", "address": {"city": "Gwenborough"}}),
+            )
+            .unwrap();
+            assert_eq!(actual, expected);
+
+            let actual = join(JoinConflict::Error).await.unwrap_err();
+            assert!(matches!(
+                actual,
+                Error::ExprEval(message) if message == "Conflicting values for field `name` in @join"
+            ));
+        }
+    }
+
+    mod cache {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        use hyper::body::Bytes;
+        use serde_json::json;
+        use tailcall_valid::Validator;
+
+        use super::*;
+        use crate::core::blueprint::{Blueprint, Definition};
+        use crate::core::cache::metrics::CacheLabel;
+        use crate::core::config::{Config, ConfigModule};
+        use crate::core::http::{RequestContext, Response};
+        use crate::core::ir::EmptyResolverContext;
+        use crate::core::HttpIO;
+
+        const CONFIG: &str = r#"
+            schema @server {
+              query: Query
+            }
+
+            type Query {
+              cachedUser: User @http(url: "http://upstream/user") @cache(maxAge: 60000)
+            }
+
+            type User {
+              id: Int
+            }
+        "#;
+
+        /// Counts the calls made to the upstream
+        #[derive(Default)]
+        struct CountingHttp {
+            calls: AtomicUsize,
+        }
+
+        #[async_trait::async_trait]
+        impl HttpIO for CountingHttp {
+            async fn execute(&self, _request: reqwest::Request) -> anyhow::Result<Response<Bytes>> {
+                self.calls.fetch_add(1, Ordering::SeqCst);
+
+                Ok(Response {
+                    status: reqwest::StatusCode::OK,
+                    headers: Default::default(),
+                    body: Bytes::from(json!({"id": 1}).to_string()),
+                })
+            }
+        }
+
+        #[tokio::test]
+        async fn test_cache_metrics() {
+            let config = Config::from_sdl(CONFIG).to_result().unwrap();
+            let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+            let ir = blueprint
+                .definitions
+                .iter()
+                .find_map(|definition| match definition {
+                    Definition::Object(object) if object.name == "Query" => object
+                        .fields
+                        .iter()
+                        .find(|field| field.name == "cachedUser")?
+                        .resolver
+                        .clone(),
+                    _ => None,
+                })
+                .unwrap();
+
+            let http = Arc::new(CountingHttp::default());
+            let mut runtime = crate::core::runtime::test::init(None);
+            runtime.http = http.clone();
+
+            // every request shares the cache of the runtime
+            for _ in 0..2 {
+                let req_ctx = RequestContext::new(runtime.clone());
+                let res_ctx = EmptyResolverContext {};
+                let mut eval_ctx = EvalContext::new(&req_ctx, &res_ctx);
+
+                let actual = ir.eval(&mut eval_ctx).await.unwrap();
+                assert_eq!(actual, ConstValue::from_json(json!({"id": 1})).unwrap());
+            }
+
+            assert_eq!(http.calls.load(Ordering::SeqCst), 1);
+            assert_eq!(
+                CACHE_METRICS.get(&CacheLabel::new("Query", "cachedUser")),
+                (1, 1)
+            );
+        }
+    }
 }
@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::collections::BTreeMap;
+use std::net::IpAddr;
 use std::sync::Arc;
 
 use async_graphql::{ServerError, Value};
@@ -79,10 +80,20 @@ impl<'a, Ctx: ResolverContextLike> EvalContext<'a, Ctx> {
         }
     }
 
+    /// Returns the value at the path in the ancestor of the value at the given
+    /// level, see [ResolverContextLike::parent].
+    pub fn path_parent<T: AsRef<str>>(&self, level: usize, path: &[T]) -> Option<Cow<'a, Value>> {
+        get_path_value(self.graphql_ctx.parent(level)?, path).map(Cow::Borrowed)
+    }
+
     pub fn headers(&self) -> &HeaderMap {
         &self.request_ctx.allowed_headers
     }
 
+    pub fn forwarded_headers(&self) -> &HeaderMap {
+        &self.request_ctx.forwarded_headers
+    }
+
     pub fn header(&self, key: &str) -> Option<&str> {
         let value = self.headers().get(key)?;
 
@@ -103,6 +114,33 @@ impl<'a, Ctx: ResolverContextLike> EvalContext<'a, Ctx> {
         &self.request_ctx.server.vars
     }
 
+    pub fn client_ip(&self) -> Option<IpAddr> {
+        self.request_ctx.client_ip
+    }
+
+    pub fn request_method(&self) -> Option<&str> {
+        self.request_ctx
+            .request_method
+            .as_ref()
+            .map(|method| method.as_str())
+    }
+
+    pub fn request_path(&self) -> Option<&str> {
+        self.request_ctx.request_uri.as_ref().map(|uri| uri.path())
+    }
+
+    pub fn request_locale(&self) -> Option<&str> {
+        self.request_ctx.locale.as_deref()
+    }
+
+    pub fn request_query(&self, key: &str) -> Option<Cow<'_, str>> {
+        let query = self.request_ctx.request_uri.as_ref()?.query()?;
+
+        url::form_urlencoded::parse(query.as_bytes())
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value)
+    }
+
     pub fn add_error(&self, error: ServerError) {
         self.graphql_ctx.add_error(error)
     }
@@ -1,6 +1,8 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_graphql::from_value;
+use reqwest::header::HeaderMap;
 use reqwest::Request;
 use tailcall_valid::Validator;
 
@@ -12,7 +14,8 @@ use crate::core::grpc::protobuf::ProtobufOperation;
 use crate::core::grpc::request::execute_grpc_request;
 use crate::core::grpc::request_template::RenderedRequestTemplate;
 use crate::core::http::{
-    cache_policy, DataLoaderRequest, HttpDataLoader, RequestTemplate, Response,
+    cache_policy, transform_body, BaseUrls, DataLoaderRequest, HttpDataLoader, RequestTemplate,
+    Response, ResponseDecoder, X_REQUEST_ID,
 };
 use crate::core::ir::Error;
 use crate::core::json::JsonLike;
@@ -47,6 +50,8 @@ pub struct EvalHttp<'a, 'ctx, Context: ResolverContextLike + Sync> {
     evaluation_ctx: &'ctx EvalContext<'a, Context>,
     data_loader: Option<&'a DataLoader<DataLoaderRequest, HttpDataLoader>>,
     request_template: &'a http::RequestTemplate,
+    coalesce: bool,
+    base_urls: Option<&'a BaseUrls>,
 }
 
 impl<'a, 'ctx, Context: ResolverContextLike + Sync> EvalHttp<'a, 'ctx, Context> {
@@ -66,11 +71,42 @@ impl<'a, 'ctx, Context: ResolverContextLike + Sync> EvalHttp<'a, 'ctx, Context>
             None
         };
 
-        Self { evaluation_ctx, data_loader, request_template }
+        Self {
+            evaluation_ctx,
+            data_loader,
+            request_template,
+            coalesce: true,
+            base_urls: None,
+        }
+    }
+
+    /// Allows the request to share the response of an identical request when
+    /// the upstream coalesces them. Disabled for the fields that opt out of
+    /// deduplication, since their calls may have side effects.
+    pub fn coalesce(mut self, coalesce: bool) -> Self {
+        self.coalesce = coalesce;
+        self
+    }
+
+    /// Spreads the calls across the base URLs of the upstream replicas.
+    pub fn base_urls(mut self, base_urls: Option<&'a BaseUrls>) -> Self {
+        self.base_urls = base_urls;
+        self
     }
 
     pub fn init_request(&self) -> Result<DynamicRequest<String>, Error> {
-        let inner = self.request_template.to_request(self.evaluation_ctx)?;
+        let mut inner = self.request_template.to_request(self.evaluation_ctx)?;
+        let request = inner.request_mut();
+        if let Some(base_urls) = self.base_urls {
+            let http = self.evaluation_ctx.request_ctx.runtime.http.as_ref();
+            base_urls.select(request.url_mut(), http);
+        }
+        transform_body(
+            &self.evaluation_ctx.request_ctx.runtime.body_transformers,
+            request,
+        )?;
+        set_request_id(self.evaluation_ctx, request.headers_mut());
+        set_timeout(self.evaluation_ctx, request);
         Ok(inner)
     }
 
@@ -80,11 +116,21 @@ impl<'a, 'ctx, Context: ResolverContextLike + Sync> EvalHttp<'a, 'ctx, Context>
     ) -> Result<Response<async_graphql::Value>, Error> {
         let ctx = &self.evaluation_ctx;
         let dl = &self.data_loader;
-        let response = if dl.is_some() {
-            execute_request_with_dl(ctx, req, self.data_loader).await?
+        let mut response = if dl.is_some() {
+            // the loader leaves out the requests that are configured to fail
+            // when the batched response has no item for them
+            execute_request_with_dl(ctx, req, self.data_loader)
+                .await?
+                .ok_or_else(|| {
+                    Error::IO("The batched response has no item for the request".to_string())
+                })?
         } else {
-            execute_raw_request(ctx, req).await?
+            let decoder = self.request_template.response_decoder;
+            execute_raw_request(ctx, req, decoder, self.coalesce).await?
         };
+        if let Some(xml_types) = &self.request_template.xml_types {
+            response.body = xml_types.coerce(response.body);
+        }
 
         if ctx.request_ctx.server.get_enable_http_validation() {
             self.request_template
@@ -152,7 +198,7 @@ pub async fn execute_request_with_dl<
     ctx: &EvalContext<'ctx, Ctx>,
     req: DynamicRequest<String>,
     data_loader: Option<&DataLoader<DataLoaderRequest, Dl>>,
-) -> Result<Response<async_graphql::Value>, Error> {
+) -> Result<Option<Response<async_graphql::Value>>, Error> {
     let headers = ctx
         .request_ctx
         .upstream
@@ -165,12 +211,31 @@ pub async fn execute_request_with_dl<
     let endpoint_key =
         crate::core::http::DataLoaderRequest::new(req, headers).with_batching_value(batching_value);
 
-    Ok(data_loader
+    data_loader
         .unwrap()
         .load_one(endpoint_key)
         .await
-        .map_err(Error::from)?
-        .unwrap_or_default())
+        .map_err(Error::from)
+}
+
+/// Sends the id of the request being resolved to the upstream, so that the
+/// calls can be correlated with it.
+pub fn set_request_id<Ctx: ResolverContextLike>(
+    ctx: &EvalContext<'_, Ctx>,
+    headers: &mut HeaderMap,
+) {
+    if let Some(request_id) = ctx.request_ctx.request_id.as_ref() {
+        headers.insert(X_REQUEST_ID.clone(), request_id.clone());
+    }
+}
+
+/// Caps the timeout of the upstream call at the budget left to the operation,
+/// so that a chain of calls can't collectively exceed the operation timeout.
+pub fn set_timeout<Ctx: ResolverContextLike>(ctx: &EvalContext<'_, Ctx>, request: &mut Request) {
+    if let Some(remaining) = ctx.request_ctx.remaining_budget() {
+        let timeout = Duration::from_secs(ctx.request_ctx.upstream.timeout);
+        *request.timeout_mut() = Some(timeout.min(remaining));
+    }
 }
 
 pub fn set_headers<Ctx: ResolverContextLike>(
@@ -212,17 +277,35 @@ fn set_cookie_headers<Ctx: ResolverContextLike>(
 pub async fn execute_raw_request<Ctx: ResolverContextLike>(
     ctx: &EvalContext<'_, Ctx>,
     req: DynamicRequest<String>,
+    decoder: ResponseDecoder,
+    coalesce: bool,
 ) -> Result<Response<async_graphql::Value>, Error> {
-    let response = ctx
-        .request_ctx
-        .runtime
-        .http
-        .execute(req.into_request())
-        .await
-        .map_err(Error::from)?
-        .to_json()?;
+    let req = req.into_request();
+    let client = &ctx.request_ctx.runtime.http;
+
+    let response = if coalesce && is_coalesced(ctx, &req) {
+        // every header is part of the key, so only the identical requests share
+        // the response
+        let headers = req.headers().keys().map(|name| name.to_string()).collect();
+        let key = DataLoaderRequest::new(req, headers);
 
-    Ok(response)
+        ctx.request_ctx
+            .coalesced_requests
+            .dedupe(&key, || async {
+                client.execute(key.to_request()).await.map_err(Error::from)
+            })
+            .await?
+    } else {
+        client.execute(req).await.map_err(Error::from)?
+    };
+
+    Ok(response.to_value(decoder)?)
+}
+
+/// Checks if the request can share the response of an identical request made
+/// by another field of the same query.
+fn is_coalesced<Ctx: ResolverContextLike>(ctx: &EvalContext<'_, Ctx>, req: &Request) -> bool {
+    ctx.request_ctx.upstream.coalesce && ctx.is_query() && req.method() == reqwest::Method::GET
 }
 
 pub async fn execute_raw_grpc_request<Ctx: ResolverContextLike>(
@@ -282,3 +365,106 @@ pub fn parse_graphql_response<Ctx: ResolverContextLike>(
         .map(|v| v.to_owned())
         .unwrap_or_default())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    use hyper::body::Bytes;
+
+    use super::EvalHttp;
+    use crate::core::http::{BodyTransformer, RequestContext, RequestTemplate, Response};
+    use crate::core::ir::{EmptyResolverContext, EvalContext};
+    use crate::core::mustache::Mustache;
+    use crate::core::HttpIO;
+
+    struct Envelope;
+
+    impl BodyTransformer for Envelope {
+        fn transform(&self, request: &reqwest::Request, body: Bytes) -> anyhow::Result<Bytes> {
+            let mut envelope =
+                format!(r#"{{"path":"{}","payload":"#, request.url().path()).into_bytes();
+            envelope.extend_from_slice(&body);
+            envelope.push(b'}');
+            Ok(envelope.into())
+        }
+    }
+
+    #[derive(Default)]
+    struct Upstream {
+        body: Mutex<Option<Bytes>>,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpIO for Upstream {
+        async fn execute(&self, request: reqwest::Request) -> anyhow::Result<Response<Bytes>> {
+            let body = request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map(Bytes::copy_from_slice);
+            *self.body.lock().unwrap() = body;
+
+            Ok(Response {
+                status: reqwest::StatusCode::OK,
+                headers: Default::default(),
+                body: Bytes::from_static(b"{}"),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_envelope() {
+        let upstream = Arc::new(Upstream::default());
+        let mut runtime = crate::core::runtime::test::init(None);
+        runtime.http = upstream.clone();
+        runtime.add_body_transformer(Envelope);
+
+        let req_ctx = RequestContext::new(runtime);
+        let res_ctx = EmptyResolverContext {};
+        let eval_ctx = EvalContext::new(&req_ctx, &res_ctx);
+        let template = RequestTemplate::new("http://localhost:3000/users")
+            .unwrap()
+            .method(reqwest::Method::POST)
+            .body_path(Some(Mustache::parse(r#"{"name":"foo"}"#)));
+
+        let eval_http = EvalHttp::new(&eval_ctx, &template, &None);
+        let request = eval_http.init_request().unwrap();
+        eval_http.execute(request).await.unwrap();
+
+        let actual = upstream.body.lock().unwrap().clone().unwrap();
+        assert_eq!(actual, r#"{"path":"/users","payload":{"name":"foo"}}"#);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_capped_at_remaining_budget() {
+        let req_ctx = RequestContext::new(crate::core::runtime::test::init(None));
+        let res_ctx = EmptyResolverContext {};
+        let eval_ctx = EvalContext::new(&req_ctx, &res_ctx);
+        let template = RequestTemplate::new("http://localhost:3000/users").unwrap();
+        let eval_http = EvalHttp::new(&eval_ctx, &template, &None);
+
+        // without a deadline the client timeout of the upstream applies
+        let request = eval_http.init_request().unwrap();
+        assert_eq!(request.request().timeout(), None);
+
+        req_ctx.set_deadline(Instant::now() + Duration::from_millis(300));
+        let first = *eval_http
+            .init_request()
+            .unwrap()
+            .request()
+            .timeout()
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let second = *eval_http
+            .init_request()
+            .unwrap()
+            .request()
+            .timeout()
+            .unwrap();
+
+        assert!(first <= Duration::from_millis(300));
+        assert!(second <= Duration::from_millis(100));
+        assert!(second < first);
+    }
+}
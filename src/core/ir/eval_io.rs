@@ -1,23 +1,31 @@
 use async_graphql_value::ConstValue;
+use futures_util::stream::{BoxStream, StreamExt};
+use tokio::sync::OwnedSemaphorePermit;
 
 use super::eval_http::{
     execute_grpc_request_with_dl, execute_raw_grpc_request, execute_raw_request,
-    execute_request_with_dl, parse_graphql_response, set_headers, EvalHttp, WorkerContext,
+    execute_request_with_dl, parse_graphql_response, set_headers, set_request_id, set_timeout,
+    EvalHttp, WorkerContext,
 };
 use super::model::{CacheKey, IO};
 use super::{DynamicRequest, EvalContext, ResolverContextLike};
-use crate::core::config::GraphQLOperationType;
+use crate::core::config::{GraphQLOperationType, StatusAction};
 use crate::core::data_loader::DataLoader;
 use crate::core::graphql::GraphqlDataLoader;
 use crate::core::grpc;
 use crate::core::grpc::data_loader::GrpcDataLoader;
-use crate::core::http::DataLoaderRequest;
+use crate::core::http::{json_seq_stream, DataLoaderRequest, ResponseDecoder};
 use crate::core::ir::Error;
 
 pub async fn eval_io<Ctx>(io: &IO, ctx: &mut EvalContext<'_, Ctx>) -> Result<ConstValue, Error>
 where
     Ctx: ResolverContextLike + Sync,
 {
+    // skip the call altogether when its `when` predicate doesn't hold
+    if !io.is_enabled(ctx) {
+        return Ok(ConstValue::Null);
+    }
+
     // Note: Handled the case separately for performance reasons. It avoids cache
     // key generation when it's not required
     let dedupe = io.dedupe();
@@ -40,44 +48,121 @@ where
     }
 }
 
+/// Makes the `@http` call of the IO and returns the values of the JSON text
+/// sequence it responds with as they arrive. `None` is returned when the call
+/// is skipped by its `when` predicate.
+pub async fn eval_json_seq<Ctx>(
+    io: &IO,
+    ctx: &EvalContext<'_, Ctx>,
+) -> Result<Option<BoxStream<'static, anyhow::Result<ConstValue>>>, Error>
+where
+    Ctx: ResolverContextLike + Sync,
+{
+    let IO::Http { req_template, dl_id, base_urls, .. } = io else {
+        return Err(Error::IO("Not an @http call".to_string()));
+    };
+    if !io.is_enabled(ctx) {
+        return Ok(None);
+    }
+
+    let request = EvalHttp::new(ctx, req_template, dl_id)
+        .base_urls(base_urls.as_deref())
+        .init_request()?;
+    let _permit = acquire_permit(ctx).await?;
+    let response = ctx
+        .request_ctx
+        .runtime
+        .http
+        .execute_stream(request.into_request())
+        .await?;
+
+    Ok(Some(json_seq_stream(response.body).boxed()))
+}
+
+/// Waits for a permit of the upstream concurrency limit, if one is set. The
+/// permit is held only while the upstream call is made, so the evaluation of
+/// the rest of the resolvers isn't limited.
+async fn acquire_permit<Ctx>(
+    ctx: &EvalContext<'_, Ctx>,
+) -> Result<Option<OwnedSemaphorePermit>, Error>
+where
+    Ctx: ResolverContextLike + Sync,
+{
+    match &ctx.request_ctx.concurrency_limiter {
+        Some(limiter) => limiter
+            .clone()
+            .acquire_owned()
+            .await
+            .map(Some)
+            .map_err(|e| Error::IO(e.to_string())),
+        None => Ok(None),
+    }
+}
+
 async fn eval_io_inner<Ctx>(io: &IO, ctx: &mut EvalContext<'_, Ctx>) -> Result<ConstValue, Error>
 where
     Ctx: ResolverContextLike + Sync,
 {
+    // bounds the fan-out on the upstream
+    let _permit = acquire_permit(ctx).await?;
+
     match io {
-        IO::Http { req_template, dl_id, hook, .. } => {
+        IO::Http { req_template, dl_id, hook, on_status, dedupe, base_urls, .. } => {
             let event_worker = &ctx.request_ctx.runtime.cmd_worker;
             let js_worker = &ctx.request_ctx.runtime.worker;
-            let eval_http = EvalHttp::new(ctx, req_template, dl_id);
+            let eval_http = EvalHttp::new(ctx, req_template, dl_id)
+                .coalesce(*dedupe != Some(false))
+                .base_urls(base_urls.as_deref());
             let request = eval_http.init_request()?;
             let response = match (&event_worker, js_worker, hook) {
                 (Some(worker), Some(js_worker), Some(hook)) => {
                     let worker_ctx = WorkerContext::new(worker, js_worker, hook);
-                    eval_http.execute_with_worker(request, worker_ctx).await?
+                    eval_http.execute_with_worker(request, worker_ctx).await
                 }
-                _ => eval_http.execute(request).await?,
+                _ => eval_http.execute(request).await,
             };
 
-            Ok(response.body)
+            // apply the actions configured for the status of the upstream response
+            match response {
+                Ok(response) => match on_status.get(&response.status.as_u16()) {
+                    Some(StatusAction::Error) => {
+                        Err(Error::HTTP { status: response.status.as_u16(), body: None })
+                    }
+                    Some(StatusAction::Null) => Ok(ConstValue::Null),
+                    None => Ok(response.body),
+                },
+                Err(Error::HTTP { status, .. })
+                    if on_status.get(&status) == Some(&StatusAction::Null) =>
+                {
+                    Ok(ConstValue::Null)
+                }
+                Err(err) => Err(err),
+            }
         }
-        IO::GraphQL { req_template, field_name, dl_id, .. } => {
-            let req = req_template.to_request(ctx)?;
-            let request = DynamicRequest::new(req);
+        IO::GraphQL { req_template, field_name, merge, dl_id, .. } => {
+            let mut req = req_template.to_request(ctx)?;
+            set_request_id(ctx, req.headers_mut());
+            set_timeout(ctx, &mut req);
+            let request = DynamicRequest::new(req)
+                .with_batching_value(merge.then(|| req_template.render_field(ctx)));
             let res = if ctx.request_ctx.upstream.batch.is_some()
                 && matches!(req_template.operation_type, GraphQLOperationType::Query)
             {
                 let data_loader: Option<&DataLoader<DataLoaderRequest, GraphqlDataLoader>> =
                     dl_id.and_then(|dl| ctx.request_ctx.gql_data_loaders.get(dl.as_usize()));
-                execute_request_with_dl(ctx, request, data_loader).await?
+                execute_request_with_dl(ctx, request, data_loader)
+                    .await?
+                    .unwrap_or_default()
             } else {
-                execute_raw_request(ctx, request).await?
+                execute_raw_request(ctx, request, ResponseDecoder::Json, true).await?
             };
 
             set_headers(ctx, &res);
             parse_graphql_response(ctx, res, field_name)
         }
         IO::Grpc { req_template, dl_id, hook, .. } => {
-            let rendered = req_template.render(ctx)?;
+            let mut rendered = req_template.render(ctx)?;
+            set_request_id(ctx, &mut rendered.headers);
             let worker = &ctx.request_ctx.runtime.worker;
 
             let res = if ctx.request_ctx.upstream.batch.is_some() &&
@@ -88,7 +173,8 @@ where
                     dl_id.and_then(|index| ctx.request_ctx.grpc_data_loaders.get(index.as_usize()));
                 execute_grpc_request_with_dl(ctx, rendered, data_loader).await?
             } else {
-                let req = rendered.to_request()?;
+                let mut req = rendered.to_request()?;
+                set_timeout(ctx, &mut req);
                 execute_raw_grpc_request(ctx, req, &req_template.operation).await?
             };
 
@@ -14,6 +14,7 @@ use std::ops::Deref;
 pub use discriminator::*;
 pub use error::*;
 pub use eval_context::EvalContext;
+pub use eval_io::eval_json_seq;
 pub(crate) use request::DynamicRequest;
 pub use resolver_context_like::{
     EmptyResolverContext, ResolverContext, ResolverContextLike, SelectionField,
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::num::NonZeroU64;
+use std::sync::Arc;
 
 use async_graphql::Value;
 use strum_macros::Display;
@@ -8,8 +9,13 @@ use strum_macros::Display;
 use super::discriminator::Discriminator;
 use super::{EvalContext, ResolverContextLike};
 use crate::core::blueprint::{Auth, DynamicValue};
+use crate::core::cache::metrics::CacheLabel;
 use crate::core::config::group_by::GroupBy;
+use crate::core::config::{JoinConflict, StatusAction, UnmappedAction};
 use crate::core::graphql::{self};
+use crate::core::http::BaseUrls;
+use crate::core::mustache::Mustache;
+use crate::core::path::PathString;
 use crate::core::worker_hooks::WorkerHooks;
 use crate::core::{grpc, http};
 
@@ -27,6 +33,9 @@ pub enum IR {
     Pipe(Box<IR>, Box<IR>),
     /// Merges the result of multiple IRs together
     Merge(Vec<IR>),
+    /// Merges the objects returned by multiple IRs shallowly, resolving the
+    /// fields they have in common with the conflict policy of `@join`
+    Join(JoinConflict, Vec<IR>),
     Discriminate(Discriminator, Box<IR>),
     /// Apollo Federation _entities resolver
     Entity(HashMap<String, IR>),
@@ -39,6 +48,7 @@ pub struct Map {
     pub input: Box<IR>,
     // accept key return value instead of
     pub map: HashMap<String, String>,
+    pub unmapped: UnmappedAction,
 }
 
 #[derive(Clone, Debug, strum_macros::Display)]
@@ -48,13 +58,19 @@ pub enum IO {
         group_by: Option<GroupBy>,
         dl_id: Option<DataLoaderId>,
         is_list: bool,
-        dedupe: bool,
+        // unset follows the defaults of the upstream, `false` opts the field
+        // out of every kind of deduplication
+        dedupe: Option<bool>,
         hook: Option<WorkerHooks>,
+        when: Option<Mustache>,
+        on_status: HashMap<u16, StatusAction>,
+        base_urls: Option<Arc<BaseUrls>>,
     },
     GraphQL {
         req_template: graphql::RequestTemplate,
         field_name: String,
         batch: bool,
+        merge: bool,
         dl_id: Option<DataLoaderId>,
         dedupe: bool,
     },
@@ -73,12 +89,86 @@ pub enum IO {
 impl IO {
     pub fn dedupe(&self) -> bool {
         match self {
-            IO::Http { dedupe, .. } => *dedupe,
+            IO::Http { dedupe, .. } => dedupe.unwrap_or_default(),
             IO::GraphQL { dedupe, .. } => *dedupe,
             IO::Grpc { dedupe, .. } => *dedupe,
             IO::Js { .. } => false,
         }
     }
+
+    /// Evaluates the `when` predicate of the IO. The call is made only if the
+    /// predicate is absent or renders to a truthy value.
+    pub fn is_enabled(&self, ctx: &impl PathString) -> bool {
+        match self {
+            IO::Http { when: Some(when), .. } => {
+                !matches!(when.render(ctx).trim(), "" | "false" | "0" | "null")
+            }
+            _ => true,
+        }
+    }
+
+    /// Checks if the request refers to the value of the parent field or its
+    /// ancestors, i.e. the call can't be made before the parent is resolved.
+    pub fn reads_parent(&self) -> bool {
+        match self {
+            IO::Http { req_template, group_by, when, .. } => {
+                group_by.is_some()
+                    || reads_parent(&req_template.root_url)
+                    || req_template
+                        .query
+                        .iter()
+                        .any(|query| reads_parent(&query.value))
+                    || req_template
+                        .headers
+                        .iter()
+                        .any(|(_, value)| reads_parent(value))
+                    || req_template.body_path.as_ref().is_some_and(reads_parent)
+                    || when.as_ref().is_some_and(reads_parent)
+            }
+            IO::GraphQL { req_template, .. } => {
+                req_template
+                    .headers
+                    .iter()
+                    .any(|(_, value)| reads_parent(value))
+                    || req_template
+                        .operation_arguments
+                        .iter()
+                        .flatten()
+                        .any(|(_, value)| reads_parent(value))
+            }
+            IO::Grpc { req_template, group_by, .. } => {
+                group_by.is_some()
+                    || reads_parent(&req_template.url)
+                    || req_template
+                        .headers
+                        .iter()
+                        .any(|(_, value)| reads_parent(value))
+                    || req_template
+                        .body
+                        .as_ref()
+                        .and_then(|body| body.mustache.as_ref())
+                        .is_some_and(reads_parent)
+            }
+            IO::Js { .. } => true,
+        }
+    }
+}
+
+/// Checks if the template refers to the value of the parent or its ancestors
+fn reads_parent(mustache: &Mustache) -> bool {
+    mustache
+        .expression_segments()
+        .iter()
+        .any(|parts| matches!(parts.first().map(String::as_str), Some("value" | "parent")))
+}
+
+fn dynamic_reads_parent<A>(value: &DynamicValue<A>) -> bool {
+    match value {
+        DynamicValue::Value(_) => false,
+        DynamicValue::Mustache(mustache) => reads_parent(mustache),
+        DynamicValue::Object(obj) => obj.values().any(dynamic_reads_parent),
+        DynamicValue::Array(arr) => arr.iter().any(dynamic_reads_parent),
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -115,6 +205,7 @@ pub trait CacheKey<Ctx> {
 pub struct Cache {
     pub max_age: NonZeroU64,
     pub io: Box<IO>,
+    pub label: CacheLabel,
 }
 
 impl Cache {
@@ -122,9 +213,14 @@ impl Cache {
     /// Wraps an expression with the cache primitive.
     /// Performance DFS on the cache on the expression and identifies all the IO
     /// nodes. Then wraps each IO node with the cache primitive.
-    pub fn wrap(max_age: NonZeroU64, expr: IR) -> IR {
+    /// The label identifies the field in the cache metrics.
+    pub fn wrap(max_age: NonZeroU64, label: CacheLabel, expr: IR) -> IR {
         expr.modify(&mut move |expr| match expr {
-            IR::IO(io) => Some(IR::Cache(Cache { max_age, io: Box::new(io.to_owned()) })),
+            IR::IO(io) => Some(IR::Cache(Cache {
+                max_age,
+                io: Box::new(io.to_owned()),
+                label: label.clone(),
+            })),
             _ => None,
         })
     }
@@ -153,6 +249,22 @@ impl IR {
         }
     }
 
+    /// Checks if the IR may read the value of the parent field. Errs on the
+    /// side of caution for the IRs that can't be inspected.
+    pub fn reads_parent(&self) -> bool {
+        match self {
+            IR::Dynamic(value) => dynamic_reads_parent(value),
+            IR::IO(io) => io.reads_parent(),
+            IR::Cache(cache) => cache.io.reads_parent(),
+            IR::Path(ir, _) | IR::Protect(_, ir) | IR::Discriminate(_, ir) => ir.reads_parent(),
+            IR::Map(map) => map.input.reads_parent(),
+            IR::Pipe(ir, ir1) => ir.reads_parent() || ir1.reads_parent(),
+            IR::Merge(vec) | IR::Join(_, vec) => vec.iter().any(IR::reads_parent),
+            IR::ContextPath(_) | IR::Entity(_) => true,
+            IR::Service(_) => false,
+        }
+    }
+
     pub fn pipe(self, next: Self) -> Self {
         IR::Pipe(Box::new(self), Box::new(next))
     }
@@ -178,17 +290,17 @@ impl IR {
                     IR::ContextPath(path) => IR::ContextPath(path),
                     IR::Dynamic(_) => expr,
                     IR::IO(_) => expr,
-                    IR::Cache(Cache { io, max_age }) => {
+                    IR::Cache(Cache { io, max_age, label }) => {
                         let expr = *IR::IO(*io).modify_box(modifier);
                         match expr {
-                            IR::IO(io) => IR::Cache(Cache { io: Box::new(io), max_age }),
+                            IR::IO(io) => IR::Cache(Cache { io: Box::new(io), max_age, label }),
                             expr => expr,
                         }
                     }
                     IR::Path(expr, path) => IR::Path(expr.modify_box(modifier), path),
                     IR::Protect(auth, expr) => IR::Protect(auth, expr.modify_box(modifier)),
-                    IR::Map(Map { input, map }) => {
-                        IR::Map(Map { input: input.modify_box(modifier), map })
+                    IR::Map(Map { input, map, unmapped }) => {
+                        IR::Map(Map { input: input.modify_box(modifier), map, unmapped })
                     }
                     IR::Discriminate(discriminator, expr) => {
                         IR::Discriminate(discriminator, expr.modify_box(modifier))
@@ -202,6 +314,10 @@ impl IR {
                     IR::Merge(vec) => {
                         IR::Merge(vec.into_iter().map(|ir| ir.modify(modifier)).collect())
                     }
+                    IR::Join(conflict, vec) => IR::Join(
+                        conflict,
+                        vec.into_iter().map(|ir| ir.modify(modifier)).collect(),
+                    ),
                 }
             }
         }
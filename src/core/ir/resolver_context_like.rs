@@ -7,6 +7,11 @@ use indexmap::IndexMap;
 
 pub trait ResolverContextLike: Clone {
     fn value(&self) -> Option<&Value>;
+    /// Returns the ancestor of the value at the given level, where level `0`
+    /// is the object that the value belongs to.
+    fn parent(&self, _level: usize) -> Option<&Value> {
+        None
+    }
     fn args(&self) -> Option<&IndexMap<Name, Value>>;
     fn field(&self) -> Option<SelectionField>;
     fn is_query(&self) -> bool;
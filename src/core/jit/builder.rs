@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ops::Deref;
 use std::sync::Arc;
 
@@ -69,6 +69,7 @@ pub struct Builder<'a> {
     pub arg_id: Counter<usize>,
     pub field_id: Counter<usize>,
     pub document: &'a ExecutableDocument,
+    pub max_selections_per_level: Option<usize>,
 }
 
 // TODO: make generic over Value (Input) type
@@ -81,9 +82,30 @@ impl<'a> Builder<'a> {
             index,
             arg_id: Counter::default(),
             field_id: Counter::default(),
+            max_selections_per_level: blueprint.server.max_selections_per_level,
         }
     }
 
+    /// Checks that none of the selection sets requests more fields than
+    /// allowed, to reject the operations that multiply the work with aliases.
+    fn check_selections(&self, name: &str, fields: &[Field<Value>]) -> Result<(), BuildError> {
+        let Some(limit) = self.max_selections_per_level else {
+            return Ok(());
+        };
+
+        if fields.len() > limit {
+            return Err(BuildError::TooManySelections {
+                name: name.to_string(),
+                count: fields.len(),
+                limit,
+            });
+        }
+
+        fields
+            .iter()
+            .try_for_each(|field| self.check_selections(&field.output_name, &field.selection))
+    }
+
     #[inline(always)]
     fn include(
         &self,
@@ -125,6 +147,54 @@ impl<'a> Builder<'a> {
         }
     }
 
+    /// Reads the `@defer` directive of a fragment. The fragment is delivered
+    /// with the rest of the response when the directive is disabled with
+    /// `if: false`.
+    #[inline(always)]
+    fn defer(&self, directives: &[Positioned<Directive>]) -> Option<Defer> {
+        let directive = &directives
+            .iter()
+            .find(|d| d.node.name.node.as_str() == "defer")?
+            .node;
+
+        if let Some(Value::Boolean(false)) = directive.get_argument("if").map(|pos| &pos.node) {
+            return None;
+        }
+
+        let label = match directive.get_argument("label").map(|pos| &pos.node) {
+            Some(Value::String(label)) => Some(label.clone()),
+            _ => None,
+        };
+
+        Some(Defer { label })
+    }
+
+    /// Reads the `@stream` directive of a list field. The whole list is
+    /// delivered with the rest of the response when the directive is disabled
+    /// with `if: false`.
+    #[inline(always)]
+    fn stream(&self, directives: &[Positioned<Directive>]) -> Option<Stream> {
+        let directive = &directives
+            .iter()
+            .find(|d| d.node.name.node.as_str() == "stream")?
+            .node;
+
+        if let Some(Value::Boolean(false)) = directive.get_argument("if").map(|pos| &pos.node) {
+            return None;
+        }
+
+        let label = match directive.get_argument("label").map(|pos| &pos.node) {
+            Some(Value::String(label)) => Some(label.clone()),
+            _ => None,
+        };
+        let initial_count = match directive.get_argument("initialCount").map(|pos| &pos.node) {
+            Some(Value::Number(count)) => count.as_u64().unwrap_or_default() as usize,
+            _ => 0,
+        };
+
+        Some(Stream { label, initial_count })
+    }
+
     #[allow(clippy::too_many_arguments)]
     #[inline(always)]
     fn iter(
@@ -133,6 +203,8 @@ impl<'a> Builder<'a> {
         selection: &SelectionSet,
         type_condition: &str,
         fragments: &HashMap<&str, &FragmentDefinition>,
+        is_root: bool,
+        defer: Option<&Defer>,
     ) -> Vec<Field<Value>> {
         let mut fields = vec![];
 
@@ -149,7 +221,9 @@ impl<'a> Builder<'a> {
                     let mut directives = Vec::with_capacity(gql_field.directives.len());
                     for directive in &gql_field.directives {
                         let directive = &directive.node;
-                        if directive.name.node == "skip" || directive.name.node == "include" {
+                        // incremental delivery is handled by the executor and isn't
+                        // forwarded to the upstream
+                        if matches!(directive.name.node.as_str(), "skip" | "include" | "stream") {
                             continue;
                         }
                         let arguments = directive
@@ -189,6 +263,8 @@ impl<'a> Builder<'a> {
                                     type_of,
                                     value: request_args.get(arg_name).cloned(),
                                     default_value,
+                                    constraint: arg_value.constraint.clone(),
+                                    context_default: arg_value.context_default.clone(),
                                 });
                             }
                         }
@@ -206,17 +282,28 @@ impl<'a> Builder<'a> {
                             &gql_field.selection_set.node,
                             type_of.name(),
                             fragments,
+                            false,
+                            None,
                         );
 
-                        let ir = match field_def {
-                            QueryField::Field((field_def, _)) => field_def.resolver.clone(),
-                            _ => None,
+                        let (ir, prefetch) = match field_def {
+                            QueryField::Field((field_def, _)) => {
+                                (field_def.resolver.clone(), field_def.prefetch)
+                            }
+                            _ => (None, false),
+                        };
+
+                        // only the root fields are streamed
+                        let stream = if is_root && type_of.is_list() {
+                            self.stream(&gql_field.directives)
+                        } else {
+                            None
                         };
 
                         let scalar = if self.index.type_is_scalar(type_of.name()) {
                             Some(
-                                scalar::Scalar::find(type_of.name())
-                                    .cloned()
+                                self.index
+                                    .get_scalar(type_of.name())
                                     .unwrap_or(scalar::Scalar::Empty),
                             )
                         } else {
@@ -244,6 +331,9 @@ impl<'a> Builder<'a> {
                             pos: selection.pos.into(),
                             directives,
                             scalar,
+                            defer: defer.cloned(),
+                            stream,
+                            prefetch,
                         };
 
                         fields.push(field);
@@ -254,7 +344,9 @@ impl<'a> Builder<'a> {
                             output_name: field_name.to_string(),
                             ir: None,
                             type_of: Type::Named { name: "String".to_owned(), non_null: true },
-                            type_condition: None,
+                            // keeps the `__typename` of a fragment out of the values of
+                            // the other types
+                            type_condition: Some(type_condition.to_string()),
                             skip,
                             include,
                             args: Vec::new(),
@@ -264,6 +356,9 @@ impl<'a> Builder<'a> {
                             directives,
                             is_enum: false,
                             scalar: Some(scalar::Scalar::Empty),
+                            defer: defer.cloned(),
+                            stream: None,
+                            prefetch: false,
                         };
 
                         fields.push(typename_field);
@@ -273,11 +368,17 @@ impl<'a> Builder<'a> {
                     if let Some(fragment) =
                         fragments.get(fragment_spread.fragment_name.node.as_str())
                     {
+                        // only the fragments on the root fields are deferred
+                        let fragment_defer = is_root
+                            .then(|| self.defer(&fragment_spread.directives))
+                            .flatten();
                         fields.extend(self.iter(
                             Some(fragment.type_condition.node.on.node.as_str()),
                             &fragment.selection_set.node,
                             fragment.type_condition.node.on.node.as_str(),
                             fragments,
+                            is_root,
+                            fragment_defer.as_ref().or(defer),
                         ));
                     }
                 }
@@ -287,11 +388,15 @@ impl<'a> Builder<'a> {
                         .as_ref()
                         .map(|cond| cond.node.on.node.as_str())
                         .unwrap_or(type_condition);
+                    let fragment_defer =
+                        is_root.then(|| self.defer(&fragment.directives)).flatten();
                     fields.extend(self.iter(
                         Some(type_of),
                         &fragment.selection_set.node,
                         type_of,
                         fragments,
+                        is_root,
+                        fragment_defer.as_ref().or(defer),
                     ));
                 }
             }
@@ -299,6 +404,134 @@ impl<'a> Builder<'a> {
 
         fields
     }
+    /// Checks if the root selection requests the `__schema` or `__type` meta
+    /// fields, including the ones selected through fragments. Such fields are
+    /// resolved by the introspection of the schema.
+    fn has_introspection_field(
+        &self,
+        selection: &SelectionSet,
+        fragments: &HashMap<&str, &FragmentDefinition>,
+    ) -> bool {
+        selection
+            .items
+            .iter()
+            .any(|selection| match &selection.node {
+                Selection::Field(Positioned { node: gql_field, .. }) => {
+                    matches!(gql_field.name.node.as_str(), "__schema" | "__type")
+                }
+                Selection::FragmentSpread(Positioned { node: fragment_spread, .. }) => fragments
+                    .get(fragment_spread.fragment_name.node.as_str())
+                    .is_some_and(|fragment| {
+                        self.has_introspection_field(&fragment.selection_set.node, fragments)
+                    }),
+                Selection::InlineFragment(Positioned { node: fragment, .. }) => {
+                    self.has_introspection_field(&fragment.selection_set.node, fragments)
+                }
+            })
+    }
+
+    /// Checks that `@defer` is used only on the fragments of the root fields of
+    /// a query and `@stream` only on the root fields of a query, since the
+    /// incremental payloads are delivered as a part of the root and the fields
+    /// of a mutation are executed in order.
+    fn check_incremental(
+        &self,
+        selection: &SelectionSet,
+        fragments: &HashMap<&str, &FragmentDefinition>,
+        is_root: bool,
+    ) -> Result<(), BuildError> {
+        selection
+            .items
+            .iter()
+            .try_for_each(|selection| match &selection.node {
+                Selection::Field(field) => {
+                    if !is_root && self.stream(&field.node.directives).is_some() {
+                        return Err(BuildError::UnsupportedStream);
+                    }
+                    self.check_incremental(&field.node.selection_set.node, fragments, false)
+                }
+                Selection::FragmentSpread(spread) => {
+                    if !is_root && self.defer(&spread.node.directives).is_some() {
+                        return Err(BuildError::UnsupportedDefer);
+                    }
+                    match fragments.get(spread.node.fragment_name.node.as_str()) {
+                        Some(fragment) => {
+                            self.check_incremental(&fragment.selection_set.node, fragments, is_root)
+                        }
+                        None => Ok(()),
+                    }
+                }
+                Selection::InlineFragment(fragment) => {
+                    if !is_root && self.defer(&fragment.node.directives).is_some() {
+                        return Err(BuildError::UnsupportedDefer);
+                    }
+                    self.check_incremental(&fragment.node.selection_set.node, fragments, is_root)
+                }
+            })
+    }
+
+    /// Checks that no fragment spreads itself, directly or through other
+    /// fragments, since such fragments can't be expanded.
+    fn check_fragment_cycles(
+        &self,
+        fragments: &HashMap<&str, &FragmentDefinition>,
+    ) -> Result<(), BuildError> {
+        fn spreads<'b>(selection: &'b SelectionSet, out: &mut Vec<&'b str>) {
+            for selection in selection.items.iter() {
+                match &selection.node {
+                    Selection::Field(field) => spreads(&field.node.selection_set.node, out),
+                    Selection::FragmentSpread(spread) => {
+                        out.push(spread.node.fragment_name.node.as_str())
+                    }
+                    Selection::InlineFragment(fragment) => {
+                        spreads(&fragment.node.selection_set.node, out)
+                    }
+                }
+            }
+        }
+
+        fn visit<'b>(
+            name: &'b str,
+            fragments: &HashMap<&'b str, &'b FragmentDefinition>,
+            visited: &mut HashSet<&'b str>,
+            path: &mut Vec<&'b str>,
+        ) -> Result<(), BuildError> {
+            if let Some(start) = path.iter().position(|fragment| *fragment == name) {
+                let cycle = path[start..]
+                    .iter()
+                    .chain(std::iter::once(&name))
+                    .map(|fragment| fragment.to_string())
+                    .collect();
+                return Err(BuildError::FragmentCycle { cycle });
+            }
+
+            if !visited.insert(name) {
+                return Ok(());
+            }
+
+            if let Some(fragment) = fragments.get(name) {
+                let mut names = vec![];
+                spreads(&fragment.selection_set.node, &mut names);
+
+                path.push(name);
+                for name in names {
+                    visit(name, fragments, visited, path)?;
+                }
+                path.pop();
+            }
+
+            Ok(())
+        }
+
+        let mut names = fragments.keys().copied().collect::<Vec<_>>();
+        names.sort();
+
+        let mut visited = HashSet::new();
+        names
+            .into_iter()
+            .try_for_each(|name| visit(name, fragments, &mut visited, &mut vec![]))
+    }
+
     #[inline(always)]
     fn get_type(&self, ty: OperationType) -> Option<&str> {
         match ty {
@@ -343,21 +576,45 @@ impl<'a> Builder<'a> {
             fragments.insert(name.as_str(), &fragment.node);
         }
 
+        self.check_fragment_cycles(&fragments)?;
+
         let operation = self.get_operation(operation_name)?;
+        self.check_incremental(
+            &operation.selection_set.node,
+            &fragments,
+            operation.ty == OperationType::Query,
+        )?;
 
         let name = self
             .get_type(operation.ty)
             .ok_or(BuildError::RootOperationTypeNotDefined { operation: operation.ty })?;
-        let fields = self.iter(None, &operation.selection_set.node, name, &fragments);
-
-        let is_introspection_query = operation.selection_set.node.items.iter().any(|f| {
-            if let Selection::Field(Positioned { node: gql_field, .. }) = &f.node {
-                let query = gql_field.name.node.as_str();
-                query.contains("__schema") || query.contains("__type")
-            } else {
-                false
-            }
-        });
+        let fields = self.iter(
+            None,
+            &operation.selection_set.node,
+            name,
+            &fragments,
+            operation.ty == OperationType::Query,
+            None,
+        );
+
+        self.check_selections(name, &fields)?;
+
+        let is_introspection_query =
+            self.has_introspection_field(&operation.selection_set.node, &fragments);
+
+        let variables = operation
+            .variable_definitions
+            .iter()
+            .map(|definition| VariableDefinition {
+                name: definition.node.name.node.to_string(),
+                type_of: Type::from(&definition.node.var_type.node),
+                default_value: definition
+                    .node
+                    .default_value
+                    .as_ref()
+                    .map(|value| value.node.clone()),
+            })
+            .collect();
 
         let plan = OperationPlan::new(
             name,
@@ -367,12 +624,13 @@ impl<'a> Builder<'a> {
             is_introspection_query,
             Some(self.index.get_interfaces()),
         );
-        Ok(plan)
+        Ok(OperationPlan { variables, ..plan })
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use async_graphql_value::ConstValue;
     use pretty_assertions::assert_eq;
     use tailcall_valid::Validator;
 
@@ -580,6 +838,127 @@ mod tests {
         insta::assert_debug_snapshot!(plan.selection);
     }
 
+    #[test]
+    fn test_variable_definitions() {
+        let plan = plan(
+            r#"
+            query ($id: ID!, $postId: ID = 1) {
+              user(id: $id) { id }
+              post(id: $postId) { id }
+            }
+        "#,
+        );
+
+        let variables = plan
+            .variables
+            .iter()
+            .map(|var| {
+                (
+                    var.name.as_str(),
+                    var.type_of.clone(),
+                    var.default_value.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            variables,
+            vec![
+                ("id", Type::from("ID".to_string()).into_required(), None),
+                (
+                    "postId",
+                    Type::from("ID".to_string()),
+                    Some(ConstValue::from(1))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_max_selections_per_level() {
+        let config = Config::from_sdl(CONFIG).to_result().unwrap();
+        let mut blueprint = Blueprint::try_from(&config.into()).unwrap();
+        blueprint.server.max_selections_per_level = Some(3);
+        let build = |query: &str| {
+            let document = async_graphql::parser::parse_query(query).unwrap();
+            Builder::new(&blueprint, &document).build(None)
+        };
+
+        assert!(build("{ a: posts { id } b: posts { id } c: posts { id title body } }").is_ok());
+        assert_eq!(
+            build("{ a: posts { id } b: posts { id } c: posts { id } d: posts { id } }")
+                .unwrap_err(),
+            BuildError::TooManySelections { name: "Query".to_string(), count: 4, limit: 3 }
+        );
+        assert_eq!(
+            build("{ posts { a: id b: id c: id d: id } }").unwrap_err(),
+            BuildError::TooManySelections { name: "posts".to_string(), count: 4, limit: 3 }
+        );
+    }
+
+    #[test]
+    fn test_fragment_cycle() {
+        let config = Config::from_sdl(CONFIG).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&config.into()).unwrap();
+        let document = async_graphql::parser::parse_query(
+            r#"
+            query {
+              posts { ...PostFields }
+            }
+            fragment PostFields on Post { id user { ...UserFields } }
+            fragment UserFields on User { id posts { ...PostFields } }
+        "#,
+        )
+        .unwrap();
+
+        let error = Builder::new(&blueprint, &document).build(None).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Fragment cycle detected: PostFields -> UserFields -> PostFields"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_incremental() {
+        let config = Config::from_sdl(CONFIG).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&config.into()).unwrap();
+
+        for query in [
+            "query { posts { id ... @defer { user { id } } } }",
+            "query { posts { ...PostUser } } fragment PostUser on Post { user { ... @defer { id } } }",
+            "mutation { ... @defer { createUser(user: {id: 1}) { id } } }",
+        ] {
+            let document = async_graphql::parser::parse_query(query).unwrap();
+            let error = Builder::new(&blueprint, &document).build(None).unwrap_err();
+
+            assert_eq!(error, BuildError::UnsupportedDefer);
+        }
+
+        for query in [
+            "query { posts { id user @stream { id } } }",
+            "query { posts { ...PostUser } } fragment PostUser on Post { user @stream(initialCount: 1) { id } }",
+            "mutation { createUser(user: {id: 1}) @stream { id } }",
+        ] {
+            let document = async_graphql::parser::parse_query(query).unwrap();
+            let error = Builder::new(&blueprint, &document).build(None).unwrap_err();
+
+            assert_eq!(error, BuildError::UnsupportedStream);
+        }
+
+        // the fragments on the root fields of a query are deferred
+        let deferred = plan("query { posts { id } ... @defer { users { id } } }");
+        assert!(deferred.is_incremental());
+
+        // and the root fields of a query, also in fragments, are streamed
+        let streamed = plan("query { ... on Query { posts @stream(initialCount: 1) { id } } }");
+        assert!(streamed.is_incremental());
+
+        // and disabled `@defer` is allowed anywhere
+        let disabled = plan("query { posts { ... @defer(if: false) { id } } }");
+        assert!(!disabled.is_incremental());
+    }
+
     #[test]
     fn test_condition() {
         // cases:
@@ -0,0 +1,255 @@
+//! Coercion of the variables provided with the request to the types declared
+//! by the operation, see the
+//! [spec](https://spec.graphql.org/October2021/#sec-Coercing-Variable-Values).
+//!
+//! Every problem is reported with the path to the offending value, e.g.
+//! `$id: expected Int, got String` or `$filter.ids[1]: expected ID!, got
+//! Boolean`.
+
+use async_graphql::Name;
+use async_graphql_value::ConstValue;
+
+use super::{ResolveInputError, VariableDefinition, Variables};
+use crate::core::blueprint::Index;
+use crate::core::Type;
+
+/// Checks the variables against their declarations and fills in the default
+/// values of the variables that weren't provided.
+pub fn coerce_variables(
+    index: &Index,
+    definitions: &[VariableDefinition],
+    mut variables: Variables<ConstValue>,
+) -> Result<Variables<ConstValue>, ResolveInputError> {
+    for definition in definitions {
+        let path = format!("${}", definition.name);
+        let value = match variables.get(&definition.name) {
+            Some(value) => value.clone(),
+            None => match &definition.default_value {
+                Some(value) => value.clone(),
+                None if !definition.type_of.is_nullable() => {
+                    return Err(not_provided(path, "variable", &definition.type_of))
+                }
+                None => continue,
+            },
+        };
+
+        let value = coerce(index, &definition.type_of, value, &path)?;
+        variables.insert(definition.name.clone(), value);
+    }
+
+    Ok(variables)
+}
+
+fn coerce(
+    index: &Index,
+    type_of: &Type,
+    value: ConstValue,
+    path: &str,
+) -> Result<ConstValue, ResolveInputError> {
+    if value.is_null() {
+        return if type_of.is_nullable() {
+            Ok(value)
+        } else {
+            Err(unexpected(path, type_of, &value))
+        };
+    }
+
+    match type_of {
+        Type::List { of_type, .. } => match value {
+            ConstValue::List(items) => items
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| coerce(index, of_type, item, &format!("{}[{}]", path, i)))
+                .collect::<Result<_, _>>()
+                .map(ConstValue::List),
+            // a single value is accepted as a list with one item
+            value => coerce(index, of_type, value, path).map(|item| ConstValue::List(vec![item])),
+        },
+        Type::Named { name, .. } => {
+            if let Some(def) = index.get_input_type_definition(name) {
+                let ConstValue::Object(mut fields) = value else {
+                    return Err(unexpected(path, type_of, &value));
+                };
+
+                for field in &def.fields {
+                    let path = format!("{}.{}", path, field.name);
+                    let key = Name::new(&field.name);
+                    match fields.get(&key) {
+                        Some(value) => {
+                            let value = coerce(index, &field.of_type, value.clone(), &path)?;
+                            fields.insert(key, value);
+                        }
+                        // the default value is supplied later along with the
+                        // defaults of the arguments
+                        None if field.default_value.is_some() || field.of_type.is_nullable() => {}
+                        None => return Err(not_provided(path, "field", &field.of_type)),
+                    }
+                }
+
+                return Ok(ConstValue::Object(fields));
+            }
+
+            if is_valid(index, name, &value) {
+                Ok(value)
+            } else {
+                Err(unexpected(path, type_of, &value))
+            }
+        }
+    }
+}
+
+fn is_valid(index: &Index, type_name: &str, value: &ConstValue) -> bool {
+    match (type_name, value) {
+        ("Int", ConstValue::Number(n)) => n.as_i64().is_some_and(|n| i32::try_from(n).is_ok()),
+        ("Float", ConstValue::Number(_)) => true,
+        ("String", ConstValue::String(_)) => true,
+        ("Boolean", ConstValue::Boolean(_)) => true,
+        ("ID", ConstValue::String(_)) => true,
+        ("ID", ConstValue::Number(n)) => n.is_i64() || n.is_u64(),
+        ("Int" | "Float" | "String" | "Boolean" | "ID", _) => false,
+        (_, ConstValue::String(variant) | ConstValue::Enum(variant))
+            if index.type_is_enum(type_name) =>
+        {
+            index.validate_enum_value(type_name, variant)
+        }
+        _ if index.type_is_enum(type_name) => false,
+        _ => match index.get_scalar(type_name) {
+            Some(scalar) => scalar.validate(value),
+            None => true,
+        },
+    }
+}
+
+fn unexpected(path: &str, type_of: &Type, value: &ConstValue) -> ResolveInputError {
+    ResolveInputError::InvalidVariable {
+        path: path.to_string(),
+        message: format!("expected {:?}, got {}", type_of, kind(value)),
+    }
+}
+
+fn not_provided(path: String, what: &str, type_of: &Type) -> ResolveInputError {
+    ResolveInputError::InvalidVariable {
+        path,
+        message: format!("required {} of type {:?} was not provided", what, type_of),
+    }
+}
+
+fn kind(value: &ConstValue) -> &'static str {
+    match value {
+        ConstValue::Null => "null",
+        ConstValue::Number(n) if n.is_f64() => "Float",
+        ConstValue::Number(_) => "Int",
+        ConstValue::String(_) => "String",
+        ConstValue::Boolean(_) => "Boolean",
+        ConstValue::Binary(_) => "Binary",
+        ConstValue::Enum(_) => "Enum",
+        ConstValue::List(_) => "List",
+        ConstValue::Object(_) => "Object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql_value::ConstValue;
+    use serde_json::json;
+    use tailcall_valid::Validator;
+
+    use super::coerce_variables;
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::{Config, ConfigModule};
+    use crate::core::jit::{Request, ResolveInputError, Variables};
+
+    const CONFIG: &str = r#"
+        schema {
+          query: Query
+        }
+
+        input Filter {
+          ids: [ID!]
+          status: Status!
+        }
+
+        enum Status {
+          ACTIVE
+          INACTIVE
+        }
+
+        type Query {
+          user(id: Int!): String @expr(body: "{{.args.id}}")
+          users(filter: Filter, limit: Int): [String] @expr(body: [])
+        }
+    "#;
+
+    fn coerce(query: &str, variables: serde_json::Value) -> Result<serde_json::Value, String> {
+        let config = Config::from_sdl(CONFIG).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let plan = Request::<ConstValue>::new(query)
+            .create_plan(&blueprint)
+            .unwrap();
+        let variables = serde_json::from_value::<Variables<ConstValue>>(variables).unwrap();
+
+        coerce_variables(&plan.index, &plan.variables, variables)
+            .map(|variables| json!(variables.into_hashmap()))
+            .map_err(|err: ResolveInputError| err.to_string())
+    }
+
+    #[test]
+    fn test_valid_variables() {
+        let actual = coerce(
+            "query ($id: Int!, $filter: Filter, $limit: Int = 10) { user(id: $id) users(filter: $filter, limit: $limit) }",
+            json!({"id": 1, "filter": {"ids": 2, "status": "ACTIVE"}}),
+        );
+
+        assert_eq!(
+            actual,
+            Ok(json!({"id": 1, "filter": {"ids": [2], "status": "ACTIVE"}, "limit": 10}))
+        );
+    }
+
+    #[test]
+    fn test_wrong_type() {
+        let actual = coerce("query ($id: Int!) { user(id: $id) }", json!({"id": "1"}));
+
+        assert_eq!(actual, Err("$id: expected Int!, got String".to_string()));
+    }
+
+    #[test]
+    fn test_missing_required_variable() {
+        let actual = coerce("query ($id: Int!) { user(id: $id) }", json!({}));
+
+        assert_eq!(
+            actual,
+            Err("$id: required variable of type Int! was not provided".to_string())
+        );
+
+        let actual = coerce("query ($id: Int!) { user(id: $id) }", json!({"id": null}));
+
+        assert_eq!(actual, Err("$id: expected Int!, got null".to_string()));
+    }
+
+    #[test]
+    fn test_nested_path() {
+        let query = "query ($filter: Filter) { users(filter: $filter) }";
+
+        let actual = coerce(
+            query,
+            json!({"filter": {"ids": ["1", true], "status": "ACTIVE"}}),
+        );
+        assert_eq!(
+            actual,
+            Err("$filter.ids[1]: expected ID!, got Boolean".to_string())
+        );
+
+        let actual = coerce(query, json!({"filter": {"status": "DELETED"}}));
+        assert_eq!(
+            actual,
+            Err("$filter.status: expected Status!, got String".to_string())
+        );
+
+        let actual = coerce(query, json!({"filter": {}}));
+        assert_eq!(
+            actual,
+            Err("$filter.status: required field of type Status! was not provided".to_string())
+        );
+    }
+}
@@ -7,6 +7,7 @@ use indexmap::IndexMap;
 use super::error::*;
 use super::{Field, OperationPlan, Positioned};
 use crate::core::ir::{ResolverContextLike, SelectionField};
+use crate::core::json::JsonLike;
 
 #[derive(Debug)]
 pub struct RequestContext<'a, Input> {
@@ -33,6 +34,8 @@ impl<'a, Input> RequestContext<'a, Input> {
 #[derive(Debug, Clone)]
 pub struct Context<'a, Input, Output> {
     value: Option<&'a Output>,
+    /// The ancestors of the value, the nearest one first
+    parents: Vec<&'a Output>,
     args: Option<indexmap::IndexMap<Name, Input>>,
     // TODO: remove the args, since they're already present inside the fields and add support for
     // default values.
@@ -41,7 +44,13 @@ pub struct Context<'a, Input, Output> {
 }
 impl<'a, Input: Clone, Output> Context<'a, Input, Output> {
     pub fn new(field: &'a Field<Input>, request: &'a RequestContext<Input>) -> Self {
-        Self { request, value: None, args: Self::build_args(field), field }
+        Self {
+            request,
+            value: None,
+            parents: vec![],
+            args: Self::build_args(field),
+            field,
+        }
     }
 
     pub fn with_value(&self, value: &'a Output) -> Self {
@@ -50,15 +59,21 @@ impl<'a, Input: Clone, Output> Context<'a, Input, Output> {
             // TODO: no need to build again?
             args: Self::build_args(self.field),
             value: Some(value),
+            parents: self.parents.clone(),
             field: self.field,
         }
     }
 
+    /// Creates the context of the nested field, the current value becomes the
+    /// nearest ancestor of the nested one.
     pub fn with_value_and_field(&self, value: &'a Output, field: &'a Field<Input>) -> Self {
+        let parents = self.value.into_iter().chain(self.parents.iter().copied());
+
         Self {
             request: self.request,
             args: Self::build_args(field),
             value: Some(value),
+            parents: parents.collect(),
             field,
         }
     }
@@ -67,6 +82,10 @@ impl<'a, Input: Clone, Output> Context<'a, Input, Output> {
         self.value
     }
 
+    pub fn parents(&self) -> &[&'a Output] {
+        &self.parents
+    }
+
     pub fn field(&self) -> &Field<Input> {
         self.field
     }
@@ -85,11 +104,53 @@ impl<'a, Input: Clone, Output> Context<'a, Input, Output> {
     }
 }
 
+impl<'a, Input: Clone, Output: for<'b> JsonLike<'b>> Context<'a, Input, Output> {
+    /// Calls the function with the context of every item of the list value.
+    /// The values of the list field are nested the same way as the values of
+    /// its ancestors, so every ancestor is narrowed down to the item the
+    /// value descends from.
+    pub fn for_each_item(&self, f: &mut impl FnMut(Self)) {
+        if let Some(value) = self.value {
+            for_each_item(value, self.parents.clone(), &mut |value, parents| {
+                f(Self { parents, ..self.with_value(value) })
+            });
+        }
+    }
+}
+
+fn for_each_item<'a, Output: for<'b> JsonLike<'b>>(
+    value: &'a Output,
+    parents: Vec<&'a Output>,
+    f: &mut impl FnMut(&'a Output, Vec<&'a Output>),
+) {
+    match value.as_array() {
+        Some(items) => {
+            for (i, item) in items.iter().enumerate() {
+                let parents = parents
+                    .iter()
+                    .map(|&parent| {
+                        parent
+                            .as_array()
+                            .and_then(|parents| parents.get(i))
+                            .unwrap_or(parent)
+                    })
+                    .collect();
+                for_each_item(item, parents, f);
+            }
+        }
+        None => f(value, parents),
+    }
+}
+
 impl ResolverContextLike for Context<'_, ConstValue, ConstValue> {
     fn value(&self) -> Option<&ConstValue> {
         self.value
     }
 
+    fn parent(&self, level: usize) -> Option<&ConstValue> {
+        self.parents.get(level).copied()
+    }
+
     // TODO: make generic over type of stored values
     fn args(&self) -> Option<&indexmap::IndexMap<Name, ConstValue>> {
         self.args.as_ref()
@@ -14,6 +14,18 @@ pub enum BuildError {
     OperationNotFound(String),
     #[error("Operation name required in request")]
     OperationNameRequired,
+    #[error("@defer is supported only on the fragments of the root fields of a query")]
+    UnsupportedDefer,
+    #[error("@stream is supported only on the root fields of a query")]
+    UnsupportedStream,
+    #[error("Fragment cycle detected: {}", .cycle.join(" -> "))]
+    FragmentCycle { cycle: Vec<String> },
+    #[error("Selection set of `{name}` has {count} fields, more than the limit of {limit}")]
+    TooManySelections {
+        name: String,
+        count: usize,
+        limit: usize,
+    },
 }
 
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
@@ -26,6 +38,28 @@ pub enum ResolveInputError {
         arg_name: String,
         field_name: String,
     },
+    #[error("Value `{value}` of argument `{arg_name}` for field `{field_name}` is not a valid variant of enum `{type_of}`")]
+    InvalidEnumValue {
+        value: String,
+        type_of: String,
+        arg_name: String,
+        field_name: String,
+    },
+    #[error("Value `{value}` of argument `{arg_name}` for field `{field_name}` is not a valid `{type_of}`")]
+    InvalidScalarValue {
+        value: String,
+        type_of: String,
+        arg_name: String,
+        field_name: String,
+    },
+    #[error("Argument `{arg_name}` for field `{field_name}` {message}")]
+    ConstraintViolation {
+        message: String,
+        arg_name: String,
+        field_name: String,
+    },
+    #[error("{path}: {message}")]
+    InvalidVariable { path: String, message: String },
 }
 
 #[derive(Error, Debug, Clone)]
@@ -38,6 +72,8 @@ pub enum ValidationError {
     EnumInvalid { type_of: String },
     #[error("internal: non-null types require a return value")]
     ValueRequired,
+    #[error(r#"internal: invalid value for type "{type_of}""#)]
+    TypeMismatch { type_of: String },
 }
 
 #[derive(Debug, Clone, Error)]
@@ -52,6 +88,8 @@ pub enum Error {
     Validation(#[from] ValidationError),
     #[error("{0}")]
     ServerError(async_graphql::ServerError),
+    #[error("The {0} timed out")]
+    Timeout(OperationType),
     #[error("Unexpected error")]
     Unknown,
 }
@@ -64,7 +102,9 @@ impl ErrorExtensions for Error {
             Error::IR(error) => error.extend(),
             Error::Validation(error) => error.extend(),
             Error::ServerError(error) => error.extend(),
-            Error::Unknown => super::graphql_error::Error::new(self.to_string()),
+            Error::Timeout(_) | Error::Unknown => {
+                super::graphql_error::Error::new(self.to_string())
+            }
         }
     }
 }
@@ -3,7 +3,7 @@ use std::mem;
 use std::sync::{Arc, Mutex};
 
 use derive_getters::Getters;
-use futures_util::future::join_all;
+use futures_util::future::{join, join_all};
 
 use super::context::{Context, RequestContext};
 use super::{OperationPlan, Positioned, Response, Store};
@@ -11,7 +11,7 @@ use crate::core::ir::model::IR;
 use crate::core::ir::TypedValue;
 use crate::core::jit;
 use crate::core::jit::synth::Synth;
-use crate::core::json::{JsonLike, JsonLikeList};
+use crate::core::json::{JsonLike, JsonLikeList, JsonObjectLike};
 
 type SharedStore<Output, Error> = Arc<Mutex<Store<Result<Output, Positioned<Error>>>>>;
 
@@ -34,7 +34,7 @@ where
     }
 
     pub async fn store(&self) -> Store<Result<Value, Positioned<jit::Error>>> {
-        let store = Arc::new(Mutex::new(Store::new()));
+        let store = Arc::new(Mutex::new(Store::with_capacity(self.ctx.plan().size())));
         let mut ctx = ExecutorInner::new(store.clone(), &self.exec, &self.ctx);
         ctx.init().await;
 
@@ -47,6 +47,7 @@ where
         Output: JsonLike<'a> + Default,
     {
         let mut response = Response::new(synth.synthesize());
+        response.add_errors(synth.take_errors());
         response.add_errors(self.ctx.errors().clone());
         response
     }
@@ -87,27 +88,67 @@ where
         &'b self,
         ctx: &'b Context<'b, Input, Output>,
         value: &'b Output,
+        prefetched: bool,
     ) -> Result<(), Error> {
         let field = ctx.field();
         // TODO: Validate if the value is an Object
         // Has to be an Object, we don't do anything while executing if its a Scalar
-        join_all(field.iter().map(|child| {
-            let ctx = ctx.with_value_and_field(value, child);
-            async move { self.execute(&ctx).await }
-        }))
+        join_all(
+            field
+                .iter()
+                .filter(|child| !(prefetched && child.prefetch))
+                .map(|child| {
+                    let ctx = ctx.with_value_and_field(value, child);
+                    async move { self.execute(&ctx).await }
+                }),
+        )
         .await;
 
         Ok(())
     }
 
+    /// Executes the children marked with `@prefetch`, their resolvers don't
+    /// read the value of the field so they're given an empty object instead.
+    async fn prefetch<'b>(&'b self, ctx: &'b Context<'b, Input, Output>, placeholder: &'b Output) {
+        join_all(
+            ctx.field()
+                .iter()
+                .filter(|child| child.prefetch)
+                .map(|child| {
+                    let ctx = ctx.with_value_and_field(placeholder, child);
+                    async move { self.execute(&ctx).await }
+                }),
+        )
+        .await;
+    }
+
     async fn execute<'b>(&'b self, ctx: &'b Context<'b, Input, Output>) -> Result<(), Error> {
         let field = ctx.field();
 
         if let Some(ir) = &field.ir {
-            let result = self.ir_exec.execute(ir, ctx).await;
+            // the results are stored once for every field, so the children can
+            // be prefetched only if the field resolves to a single object. The
+            // children of a mutation field read what the mutation has changed,
+            // so they're never prefetched.
+            let prefetched = self.request.plan().is_query()
+                && !field.type_of.is_list()
+                && !ctx.value().is_some_and(|value| value.as_array().is_some())
+                && field.iter().any(|child| child.prefetch);
+
+            let result = if prefetched {
+                let placeholder = Output::object(JsonObjectLike::new());
+                let (result, _) = join(
+                    self.ir_exec.execute(ir, ctx),
+                    self.prefetch(ctx, &placeholder),
+                )
+                .await;
+                result
+            } else {
+                self.ir_exec.execute(ir, ctx).await
+            };
 
             if let Ok(value) = &result {
-                self.iter_field(ctx, value).await?;
+                self.iter_field(ctx, value, prefetched).await?;
             }
 
             let mut store = self.store.lock().unwrap();
@@ -134,7 +175,7 @@ where
                 None => Output::null(),
             };
 
-            self.iter_field(ctx, &value).await?;
+            self.iter_field(ctx, &value, false).await?;
         }
 
         Ok(())
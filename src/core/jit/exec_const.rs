@@ -1,17 +1,32 @@
-use std::sync::Arc;
+use std::borrow::Cow;
+use std::future::Future;
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
+use async_graphql::parser::types::OperationType;
 use async_graphql_value::{ConstValue, Value};
-use futures_util::future::join_all;
+use futures_util::future::{self, join_all};
+use futures_util::stream::{self, BoxStream, FuturesOrdered, StreamExt};
+use futures_util::Stream;
+use serde::Serialize;
 use tailcall_valid::Validator;
 
+use super::coerce::coerce_variables;
 use super::context::Context;
 use super::exec::{Executor, IRExecutor};
 use super::graphql_error::GraphQLError;
-use super::{transform, AnyResponse, BuildError, Error, OperationPlan, Request, Response, Result};
+use super::{
+    transform, AnyResponse, BuildError, Error, Field, IncrementalResult, InitialPayload,
+    OperationPlan, PathSegment, Request, Response, Result, Stream as StreamDirective,
+    SubsequentPayload, Variables,
+};
 use crate::core::app_context::AppContext;
-use crate::core::http::RequestContext;
-use crate::core::ir::model::IR;
-use crate::core::ir::{self, EmptyResolverContext, EvalContext};
+use crate::core::blueprint::DynamicValue;
+use crate::core::config::NullPropagation;
+use crate::core::http::{RequestContext, ResponseDecoder};
+use crate::core::ir::model::{IO, IR};
+use crate::core::ir::{self, EmptyResolverContext, EvalContext, ResolverContextLike};
 use crate::core::jit::synth::Synth;
 use crate::core::jit::transform::InputResolver;
 use crate::core::json::{JsonLike, JsonLikeList};
@@ -34,67 +49,263 @@ impl ConstValueExecutor {
         Ok(Self::from(plan))
     }
 
-    pub async fn execute<'a>(
+    pub async fn execute(
         self,
         app_ctx: &Arc<AppContext>,
         req_ctx: &RequestContext,
         request: Request<ConstValue>,
     ) -> AnyResponse<Vec<u8>> {
+        self.execute_with(app_ctx, req_ctx, request, &[], |response| response.into())
+            .await
+    }
+
+    /// Executes the plan and returns the chunks of the JSON of the response
+    /// as it's resolved, the entry of every root field in the `data` is sent
+    /// as soon as the field and the fields before it are resolved. The root
+    /// fields of a query are resolved concurrently and those of a mutation one
+    /// after another. The errors and the extensions follow the `data`. Since
+    /// the fields already sent can't be taken back, nothing is sent until the
+    /// last non-nullable root field is resolved, so that its failure can
+    /// still null the whole `data`.
+    pub fn execute_chunked(
+        self,
+        app_ctx: Arc<AppContext>,
+        req_ctx: Arc<RequestContext>,
+        request: Request<ConstValue>,
+        deadline: Option<Instant>,
+    ) -> impl Stream<Item = Vec<u8>> + Send + 'static {
+        let operation_type = self.plan.operation_type();
+
+        stream::once(async move {
+            let prepared = within(deadline, self.prepare(&req_ctx, &request))
+                .await
+                .unwrap_or_else(|| Err(vec![timeout_error(operation_type)]));
+            let (plan, variables) = match prepared {
+                Ok(prepared) => prepared,
+                Err(errors) => {
+                    let response = Response::<serde_json::Value>::default().with_errors(errors);
+                    return stream::once(future::ready(to_payload(&response))).boxed();
+                }
+            };
+
+            let is_query = plan.is_query();
+            // with `Isolate` a failed field never nulls the whole `data`
+            let held = match req_ctx.server.null_propagation {
+                NullPropagation::Isolate => 0,
+                NullPropagation::Strict => plan
+                    .selection
+                    .iter()
+                    .rposition(|field| !field.type_of.is_nullable())
+                    .map_or(0, |i| i + 1),
+            };
+            let fields = plan.split_root_fields().into_iter().map(move |plan| {
+                let (app_ctx, req_ctx, request, variables) = (
+                    app_ctx.clone(),
+                    req_ctx.clone(),
+                    request.clone(),
+                    variables.clone(),
+                );
+                async move {
+                    let names = plan
+                        .selection
+                        .iter()
+                        .map(|field| field.output_name.clone())
+                        .collect::<Vec<_>>();
+                    let response = Self::resolve(
+                        &plan,
+                        variables,
+                        &app_ctx,
+                        &req_ctx,
+                        request,
+                        &[],
+                        to_json_response,
+                    )
+                    .await;
+                    (names, response)
+                }
+            });
+            let fields = if is_query {
+                FuturesOrdered::from_iter(fields).boxed()
+            } else {
+                stream::iter(fields).then(|field| field).boxed()
+            };
+
+            ChunkedWriter::new(fields, held, deadline, operation_type)
+                .into_stream()
+                .boxed()
+        })
+        .flatten()
+    }
+
+    /// Executes the plan that defers or streams some of its root fields. The
+    /// initial payload contains the response without the deferred fields and
+    /// with the streamed lists cut down to their initial count, the nested
+    /// fields of the remaining items aren't resolved yet. It's followed by a
+    /// payload for every remaining item of the streamed lists, resolved one
+    /// by one only after the previous payload was consumed, and then by a
+    /// payload for every deferred fragment, executed the same way. The
+    /// streamed lists whose upstream responds with a JSON text sequence are
+    /// read item by item, so every item is sent as soon as it arrives.
+    pub fn execute_incremental(
+        self,
+        app_ctx: Arc<AppContext>,
+        req_ctx: Arc<RequestContext>,
+        request: Request<ConstValue>,
+    ) -> impl Stream<Item = Vec<u8>> + Send + 'static {
+        let (initial, deferred) = self.plan.split_deferred();
+        let count = deferred.len();
+        let streamed = initial
+            .selection
+            .iter()
+            .filter_map(StreamedField::try_new)
+            .map(Arc::new)
+            .collect::<Vec<_>>();
+        let has_streamed = !streamed.is_empty();
+
+        let initial_payload = {
+            let (app_ctx, req_ctx, request) = (app_ctx.clone(), req_ctx.clone(), request.clone());
+            let (plan, streamed) = (initial.clone(), streamed.clone());
+            async move {
+                Self::from(plan)
+                    .execute_with(&app_ctx, &req_ctx, request, &streamed, |response| {
+                        let has_next = has_streamed || count > 0;
+                        to_payload(&InitialPayload {
+                            response: to_json_response(response),
+                            has_next,
+                        })
+                    })
+                    .await
+            }
+        };
+
+        let items = {
+            let (app_ctx, req_ctx, request) = (app_ctx.clone(), req_ctx.clone(), request.clone());
+            stream::iter(streamed)
+                .flat_map(move |streamed| streamed.payloads(&initial, &app_ctx, &req_ctx, &request))
+        };
+
+        // the end of a list is known only after its last item was sent
+        let end = (has_streamed && count == 0)
+            .then(|| to_payload(&SubsequentPayload::<()> { incremental: vec![], has_next: false }));
+
+        let subsequent =
+            stream::iter(deferred.into_iter().enumerate()).then(move |(i, (defer, plan))| {
+                let (app_ctx, req_ctx, request) =
+                    (app_ctx.clone(), req_ctx.clone(), request.clone());
+                async move {
+                    Self::from(plan)
+                        .execute_with(&app_ctx, &req_ctx, request, &[], |response| {
+                            to_payload(&SubsequentPayload {
+                                incremental: vec![IncrementalResult::Defer {
+                                    response,
+                                    path: vec![],
+                                    label: defer.label,
+                                }],
+                                has_next: i + 1 < count,
+                            })
+                        })
+                        .await
+                }
+            });
+
+        stream::once(initial_payload)
+            .chain(items)
+            .chain(stream::iter(end))
+            .chain(subsequent)
+    }
+
+    async fn execute_with<T>(
+        self,
+        app_ctx: &Arc<AppContext>,
+        req_ctx: &RequestContext,
+        request: Request<ConstValue>,
+        streamed: &[Arc<StreamedField>],
+        into: impl for<'v> FnOnce(Response<serde_json_borrow::Value<'v>>) -> T,
+    ) -> T {
+        match self.prepare(req_ctx, &request).await {
+            Ok((plan, variables)) => {
+                Self::resolve(&plan, variables, app_ctx, req_ctx, request, streamed, into).await
+            }
+            Err(errors) => into(Response::default().with_errors(errors)),
+        }
+    }
+
+    /// Runs the `before` chain and resolves the variables and the arguments
+    /// of the plan, it fails with the errors of the request as a whole.
+    async fn prepare(
+        self,
+        req_ctx: &RequestContext,
+        request: &Request<ConstValue>,
+    ) -> std::result::Result<(OperationPlan<ConstValue>, Variables<ConstValue>), Vec<GraphQLError>>
+    {
         // Run all the IRs in the before chain
         if let Some(ir) = &self.plan.before {
             let mut eval_context = EvalContext::new(req_ctx, &EmptyResolverContext {});
-            match ir.eval(&mut eval_context).await {
-                Ok(_) => (),
-                Err(err) => {
-                    let resp: Response<ConstValue> = Response::default();
-                    return resp
-                        .with_errors(vec![GraphQLError::new(err.to_string(), None)])
-                        .into();
-                }
+            if let Err(err) = ir.eval(&mut eval_context).await {
+                return Err(vec![GraphQLError::new(err.to_string(), None)]);
             }
         }
 
-        let is_introspection_query =
-            req_ctx.server.get_enable_introspection() && self.plan.is_introspection_query;
-        let variables = &request.variables;
+        // Check the variables against the types declared by the operation
+        let variables = coerce_variables(
+            &self.plan.index,
+            &self.plan.variables,
+            request.variables.clone(),
+        )
+        .map_err(|err| vec![GraphQLError::new(BuildError::from(err).to_string(), None)])?;
 
         // Attempt to skip unnecessary fields
-        let Ok(plan) = transform::Skip::new(variables)
+        let Ok(plan) = transform::Skip::new(&variables)
             .transform(self.plan)
             .to_result()
         else {
-            let resp: Response<ConstValue> = Response::default();
             // this shouldn't actually ever happen
-            return resp
-                .with_errors(vec![GraphQLError::new(Error::Unknown.to_string(), None)])
-                .into();
+            return Err(vec![GraphQLError::new(Error::Unknown.to_string(), None)]);
         };
 
+        // Supply the arguments omitted by the client from the request context
+        let eval_context = EvalContext::new(req_ctx, &EmptyResolverContext {});
+        let plan = transform::ContextDefault::new(&eval_context)
+            .transform(plan)
+            .to_result()
+            .map_err(|err| {
+                err.as_vec()
+                    .iter()
+                    .map(|cause| {
+                        GraphQLError::new(BuildError::from(cause.message.clone()).to_string(), None)
+                    })
+                    .collect::<Vec<_>>()
+            })?;
+
         // Attempt to replace variables in the plan with the actual values
-        // TODO: operation from [ExecutableDocument] could contain definitions for
-        // default values of arguments. That info should be passed to
-        // [InputResolver] to resolve defaults properly
-        let result = InputResolver::new(plan).resolve_input(variables);
-
-        let plan = match result {
-            Ok(plan) => plan,
-            Err(err) => {
-                let resp: Response<ConstValue> = Response::default();
-                return resp
-                    .with_errors(vec![GraphQLError::new(
-                        BuildError::from(err).to_string(),
-                        None,
-                    )])
-                    .into();
-            }
-        };
+        // NOTE: the default values of the omitted variables are already
+        // supplied by the coercion above
+        let plan = InputResolver::new(plan)
+            .resolve_input(&variables)
+            .map_err(|err| vec![GraphQLError::new(BuildError::from(err).to_string(), None)])?;
+
+        Ok((plan, variables))
+    }
+
+    /// Resolves the fields of the prepared plan and builds the response.
+    async fn resolve<T>(
+        plan: &OperationPlan<ConstValue>,
+        variables: Variables<ConstValue>,
+        app_ctx: &Arc<AppContext>,
+        req_ctx: &RequestContext,
+        request: Request<ConstValue>,
+        streamed: &[Arc<StreamedField>],
+        into: impl for<'v> FnOnce(Response<serde_json_borrow::Value<'v>>) -> T,
+    ) -> T {
+        let is_introspection_query =
+            req_ctx.server.get_enable_introspection() && plan.is_introspection_query;
 
-        let exec = ConstValueExec::new(&plan, req_ctx);
-        // PERF: remove this particular clone?
-        let vars = request.variables.clone();
-        let exe = Executor::new(&plan, exec);
+        let exec = ConstValueExec::new(plan, req_ctx, streamed);
+        let exe = Executor::new(plan, exec);
         let store = exe.store().await;
-        let synth = Synth::new(&plan, store, vars);
+        let synth = Synth::new(plan, store, variables)
+            .strict(req_ctx.server.get_enable_strict_response_validation())
+            .isolate_errors(req_ctx.server.null_propagation == NullPropagation::Isolate);
 
         let resp: Response<serde_json_borrow::Value> = exe.execute(&synth).await;
 
@@ -102,21 +313,362 @@ impl ConstValueExecutor {
             let async_req = async_graphql::Request::from(request).only_introspection();
             let async_resp = app_ctx.execute(async_req).await;
 
-            resp.merge_with(&async_resp).into()
+            into(resp.merge_with(&async_resp))
+        } else {
+            into(resp)
+        }
+    }
+}
+
+/// Writes the JSON of a response as its root fields are resolved, see
+/// [ConstValueExecutor::execute_chunked].
+struct ChunkedWriter {
+    /// The responses of the root fields in the order of the query, along
+    /// with the names of the fields.
+    fields: BoxStream<'static, (Vec<String>, Response<serde_json::Value>)>,
+    /// The number of the leading root fields that are written before anything
+    /// is sent, i.e. up to the last non-nullable one
+    held: usize,
+    /// The number of the root fields received so far
+    received: usize,
+    deadline: Option<Instant>,
+    operation_type: OperationType,
+    /// The JSON written but not sent yet
+    pending: Vec<u8>,
+    errors: Vec<GraphQLError>,
+    extensions: Vec<(String, serde_json::Value)>,
+    /// Whether an entry of the `data` was written already
+    started: bool,
+    /// Whether a non-nullable root field failed and nulled the `data`
+    nulled: bool,
+}
+
+impl ChunkedWriter {
+    fn new(
+        fields: BoxStream<'static, (Vec<String>, Response<serde_json::Value>)>,
+        held: usize,
+        deadline: Option<Instant>,
+        operation_type: OperationType,
+    ) -> Self {
+        Self {
+            fields,
+            held,
+            received: 0,
+            deadline,
+            operation_type,
+            pending: br#"{"data":{"#.to_vec(),
+            errors: vec![],
+            extensions: vec![],
+            started: false,
+            nulled: false,
+        }
+    }
+
+    fn into_stream(self) -> impl Stream<Item = Vec<u8>> + Send + 'static {
+        stream::unfold(Some(self), |writer| async move {
+            let mut writer = writer?;
+            loop {
+                let next = match within(writer.deadline, writer.fields.next()).await {
+                    Some(next) => next,
+                    None => {
+                        // the fields that aren't resolved yet are left out
+                        writer.errors.push(timeout_error(writer.operation_type));
+                        None
+                    }
+                };
+
+                match next {
+                    Some((names, response)) => {
+                        writer.write(names, response);
+                        if !writer.is_held() {
+                            return Some((mem::take(&mut writer.pending), Some(writer)));
+                        }
+                    }
+                    None => return Some((writer.end(), None)),
+                }
+            }
+        })
+    }
+
+    /// Whether the written JSON is kept until a non-nullable root field
+    /// that's still to come is resolved, or for good once it failed.
+    fn is_held(&self) -> bool {
+        self.nulled || self.received < self.held
+    }
+
+    /// Writes the entries of the root fields of a response and keeps its
+    /// errors and extensions for the end.
+    fn write(&mut self, names: Vec<String>, response: Response<serde_json::Value>) {
+        let is_held = self.is_held();
+        self.received += 1;
+        self.errors.extend(response.errors);
+        self.extensions.extend(response.extensions);
+
+        let entries = match response.data {
+            serde_json::Value::Object(map) => map.into_iter().collect::<Vec<_>>(),
+            // the error of a non-nullable field nulls the whole `data` as long
+            // as nothing was sent yet
+            _ if is_held => {
+                self.nulled = true;
+                return;
+            }
+            // otherwise it nulls only the field itself
+            _ => names
+                .into_iter()
+                .map(|name| (name, serde_json::Value::Null))
+                .collect(),
+        };
+
+        for (name, value) in entries {
+            if mem::replace(&mut self.started, true) {
+                self.pending.push(b',');
+            }
+            // writing into a vector doesn't fail
+            serde_json::to_writer(&mut self.pending, &name).unwrap_or_default();
+            self.pending.push(b':');
+            serde_json::to_writer(&mut self.pending, &value).unwrap_or_default();
+        }
+    }
+
+    /// Closes the `data` and writes the errors and the extensions, the
+    /// `data` is `null` if it's still held, i.e. a non-nullable root field
+    /// failed or wasn't resolved by the deadline.
+    fn end(self) -> Vec<u8> {
+        let mut chunk = if self.is_held() {
+            br#"{"data":null"#.to_vec()
         } else {
-            resp.into()
+            let mut chunk = self.pending;
+            chunk.push(b'}');
+            chunk
+        };
+        let rest = Response::<serde_json::Value> {
+            errors: self.errors,
+            extensions: self.extensions,
+            ..Default::default()
+        };
+        if let Ok(serde_json::Value::Object(rest)) = serde_json::to_value(rest) {
+            for (key, value) in rest.into_iter().filter(|(key, _)| key != "data") {
+                chunk.push(b',');
+                serde_json::to_writer(&mut chunk, &key).unwrap_or_default();
+                chunk.push(b':');
+                serde_json::to_writer(&mut chunk, &value).unwrap_or_default();
+            }
+        }
+        chunk.push(b'}');
+
+        chunk
+    }
+}
+
+/// Awaits the future until the deadline, returns `None` once it's passed.
+async fn within<F: Future>(deadline: Option<Instant>, task: F) -> Option<F::Output> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline.into(), task).await.ok(),
+        None => Some(task.await),
+    }
+}
+
+fn timeout_error(operation_type: OperationType) -> GraphQLError {
+    GraphQLError::new(Error::Timeout(operation_type).to_string(), None)
+}
+
+fn to_payload(payload: &impl Serialize) -> Vec<u8> {
+    serde_json::to_vec(payload).unwrap_or_default()
+}
+
+fn to_json_response(response: Response<serde_json_borrow::Value>) -> Response<serde_json::Value> {
+    let to_json =
+        |value: &serde_json_borrow::Value| serde_json::to_value(value).unwrap_or_default();
+
+    Response {
+        data: to_json(&response.data),
+        errors: response.errors,
+        extensions: response
+            .extensions
+            .iter()
+            .map(|(key, value)| (key.clone(), to_json(value)))
+            .collect(),
+        cache_control: response.cache_control,
+    }
+}
+
+/// A root field marked with `@stream`. Only its initial items are resolved
+/// while the initial payload is executed, the remaining items are resolved
+/// one by one once that payload was sent. The fields whose upstream responds
+/// with a JSON text sequence are read item by item as well.
+struct StreamedField {
+    field: Field<Value>,
+    stream: StreamDirective,
+    is_json_seq: bool,
+    remaining: Mutex<Option<BoxStream<'static, anyhow::Result<ConstValue>>>>,
+}
+
+impl StreamedField {
+    /// The field is read as the upstream sends the items when it's resolved
+    /// by a single `@http` call to a JSON text sequence whose response isn't
+    /// processed as a whole.
+    fn try_new(field: &Field<Value>) -> Option<Self> {
+        let stream = field.stream.clone()?;
+        let is_json_seq = matches!(
+            &field.ir,
+            Some(IR::IO(IO::Http { req_template, group_by: None, hook: None, on_status, .. }))
+                if req_template.response_decoder == ResponseDecoder::JsonSeq && on_status.is_empty()
+        );
+
+        Some(Self {
+            field: field.clone(),
+            stream,
+            is_json_seq,
+            remaining: Mutex::new(None),
+        })
+    }
+
+    /// Makes the upstream call and reads the initial items, the rest of the
+    /// sequence is kept for the subsequent payloads.
+    async fn open<Ctx: ResolverContextLike + Sync>(
+        &self,
+        io: &IO,
+        ctx: &EvalContext<'_, Ctx>,
+    ) -> Result<ConstValue> {
+        let Some(mut items) = ir::eval_json_seq(io, ctx).await? else {
+            return Ok(ConstValue::Null);
+        };
+
+        let mut initial = Vec::with_capacity(self.stream.initial_count);
+        while initial.len() < self.stream.initial_count {
+            match items.next().await {
+                Some(item) => initial.push(item.map_err(ir::Error::from)?),
+                None => break,
+            }
+        }
+        *self.remaining.lock().unwrap() = Some(items);
+
+        Ok(ConstValue::List(initial))
+    }
+
+    /// Cuts the resolved list down to its initial count, the remaining items
+    /// are kept for the subsequent payloads.
+    fn split(&self, value: ConstValue) -> ConstValue {
+        match value {
+            ConstValue::List(mut items) => {
+                let remaining = items.split_off(self.stream.initial_count.min(items.len()));
+                *self.remaining.lock().unwrap() =
+                    Some(stream::iter(remaining.into_iter().map(Ok)).boxed());
+
+                ConstValue::List(items)
+            }
+            value => value,
         }
     }
+
+    /// Executes the selection of the field for every remaining item as soon
+    /// as it's read, each into a payload.
+    fn payloads(
+        self: Arc<Self>,
+        plan: &OperationPlan<Value>,
+        app_ctx: &Arc<AppContext>,
+        req_ctx: &Arc<RequestContext>,
+        request: &Request<ConstValue>,
+    ) -> impl Stream<Item = Vec<u8>> + Send + 'static {
+        let remaining = self.remaining.lock().unwrap().take();
+        let (plan, app_ctx, req_ctx, request) = (
+            plan.clone(),
+            app_ctx.clone(),
+            req_ctx.clone(),
+            request.clone(),
+        );
+
+        remaining
+            .unwrap_or_else(|| stream::empty().boxed())
+            .enumerate()
+            .then(move |(i, item)| {
+                let streamed = self.clone();
+                let (plan, app_ctx, req_ctx, request) = (
+                    plan.clone(),
+                    app_ctx.clone(),
+                    req_ctx.clone(),
+                    request.clone(),
+                );
+                async move {
+                    let index = streamed.stream.initial_count + i;
+                    let item = match item {
+                        Ok(item) => item,
+                        Err(err) => {
+                            let error =
+                                GraphQLError::new(err.to_string(), Some(streamed.field.pos));
+                            return streamed.item_payload(index, None, vec![error]);
+                        }
+                    };
+
+                    // the item is resolved as a list of its own, so the nested
+                    // fields are executed as for the items of the initial list
+                    let list = ConstValue::List(vec![item]);
+                    let field = Field {
+                        ir: Some(IR::Dynamic(DynamicValue::Value(list))),
+                        stream: None,
+                        ..streamed.field.clone()
+                    };
+                    let plan = OperationPlan {
+                        selection: vec![field],
+                        is_introspection_query: false,
+                        ..plan
+                    };
+
+                    ConstValueExecutor::from(plan)
+                        .execute_with(&app_ctx, &req_ctx, request, &[], |response| {
+                            let mut response = to_json_response(response);
+                            let item = response
+                                .data
+                                .get_mut(&streamed.field.output_name)
+                                .and_then(|list| list.as_array_mut()?.pop());
+                            for error in &mut response.errors {
+                                if let Some(segment @ PathSegment::Index(_)) = error.path.get_mut(1)
+                                {
+                                    *segment = PathSegment::Index(index);
+                                }
+                            }
+
+                            streamed.item_payload(index, item, response.errors)
+                        })
+                        .await
+                }
+            })
+    }
+
+    fn item_payload(
+        &self,
+        index: usize,
+        item: Option<serde_json::Value>,
+        errors: Vec<GraphQLError>,
+    ) -> Vec<u8> {
+        to_payload(&SubsequentPayload {
+            incremental: vec![IncrementalResult::Stream {
+                items: item.into_iter().collect(),
+                path: vec![
+                    PathSegment::Field(Cow::Owned(self.field.output_name.clone())),
+                    PathSegment::Index(index),
+                ],
+                label: self.stream.label.clone(),
+                errors,
+            }],
+            has_next: true,
+        })
+    }
 }
 
 struct ConstValueExec<'a> {
     plan: &'a OperationPlan<ConstValue>,
     req_context: &'a RequestContext,
+    streamed: &'a [Arc<StreamedField>],
 }
 
 impl<'a> ConstValueExec<'a> {
-    pub fn new(plan: &'a OperationPlan<ConstValue>, req_context: &'a RequestContext) -> Self {
-        Self { req_context, plan }
+    pub fn new(
+        plan: &'a OperationPlan<ConstValue>,
+        req_context: &'a RequestContext,
+        streamed: &'a [Arc<StreamedField>],
+    ) -> Self {
+        Self { req_context, plan, streamed }
     }
 
     async fn call(
@@ -152,17 +704,35 @@ impl IRExecutor for ConstValueExec<'_> {
     ) -> Result<Self::Output> {
         let field = ctx.field();
 
+        // only the initial items of a streamed list are resolved along with
+        // the rest of the response
+        let streamed = self
+            .streamed
+            .iter()
+            .find(|streamed| streamed.field.id == field.id);
+        if let Some(streamed) = streamed {
+            return match ir {
+                IR::IO(io) if streamed.is_json_seq => {
+                    let eval_ctx = EvalContext::new(self.req_context, ctx);
+                    streamed.open(io, &eval_ctx).await
+                }
+                _ => Ok(streamed.split(self.call(ctx, ir).await?)),
+            };
+        }
+
         match ctx.value() {
             // TODO: check that field is expected list and it's a list of the required deepness
             Some(value) if value.as_array().is_some() => {
                 let mut tasks = Vec::new();
 
                 // collect the async tasks first before creating the final result
-                value.for_each(&mut |value| {
+                ctx.for_each_item(&mut |ctx| {
                     // execute the resolver only for fields that are related to current value
                     // for fragments on union/interface
-                    if self.plan.field_is_part_of_value(field, value) {
-                        let ctx = ctx.with_value(value);
+                    let is_part_of_value = ctx
+                        .value()
+                        .is_some_and(|value| self.plan.field_is_part_of_value(field, value));
+                    if is_part_of_value {
                         tasks.push(async move { self.call(&ctx, ir).await })
                     }
                 });
@@ -191,3 +761,495 @@ impl IRExecutor for ConstValueExec<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use futures_util::stream::{self, BoxStream, StreamExt};
+    use futures_util::FutureExt;
+    use hyper::body::Bytes;
+    use serde_json::json;
+    use tailcall_valid::Validator;
+    use tokio::sync::mpsc;
+
+    use super::ConstValueExecutor;
+    use crate::core::app_context::AppContext;
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::{Config, ConfigModule};
+    use crate::core::http::{BodyStream, RequestContext, Response};
+    use crate::core::jit::Request;
+    use crate::core::rest::EndpointSet;
+    use crate::core::HttpIO;
+
+    const CONFIG: &str = r#"
+        schema @server @upstream(concurrency: 5) {
+          query: Query
+        }
+
+        type Query {
+          items: [Item] @http(url: "http://upstream/items")
+        }
+
+        type Item {
+          id: Int
+          detail: Detail @http(url: "http://upstream/items/{{.value.id}}")
+        }
+
+        type Detail {
+          title: String
+        }
+    "#;
+
+    /// Serves a list of 100 items and tracks the maximum number of requests
+    /// for their details that are in flight at the same time.
+    #[derive(Default)]
+    struct CountingHttp {
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpIO for CountingHttp {
+        async fn execute(&self, request: reqwest::Request) -> anyhow::Result<Response<Bytes>> {
+            let body = if request.url().path() == "/items" {
+                json!((1..=100).map(|id| json!({"id": id})).collect::<Vec<_>>())
+            } else {
+                let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                json!({"title": "item"})
+            };
+
+            Ok(Response {
+                status: reqwest::StatusCode::OK,
+                headers: Default::default(),
+                body: Bytes::from(body.to_string()),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit() {
+        let config = Config::from_sdl(CONFIG).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let http = Arc::new(CountingHttp::default());
+        let mut runtime = crate::core::runtime::test::init(None);
+        runtime.http = http.clone();
+        let app_ctx = Arc::new(AppContext::new(blueprint, runtime, EndpointSet::default()));
+        let req_ctx = RequestContext::from(app_ctx.as_ref());
+
+        let request = Request::new("query { items { id detail { title } } }");
+        let executor = ConstValueExecutor::try_new(&request, &app_ctx).unwrap();
+        let response = executor.execute(&app_ctx, &req_ctx, request).await;
+        let response: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+
+        let items = response["data"]["items"].as_array().unwrap();
+        assert_eq!(items.len(), 100);
+        assert!(items.iter().all(|item| item["detail"]["title"] == "item"));
+        assert_eq!(http.max_in_flight.load(Ordering::SeqCst), 5);
+    }
+
+    const PREFETCH_CONFIG: &str = r#"
+        schema @server {
+          query: Query
+          mutation: Mutation
+        }
+
+        type Query {
+          user: User @http(url: "http://upstream/user")
+        }
+
+        type Mutation {
+          updateUser: User @http(url: "http://upstream/user", method: POST)
+        }
+
+        type User {
+          name: String
+          stats: Stats @http(url: "http://upstream/stats") @prefetch
+        }
+
+        type Stats {
+          count: Int
+        }
+    "#;
+
+    /// Records when the requests start and complete, the user is served with
+    /// a delay.
+    #[derive(Default)]
+    struct RecordingHttp {
+        events: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpIO for RecordingHttp {
+        async fn execute(&self, request: reqwest::Request) -> anyhow::Result<Response<Bytes>> {
+            let path = request.url().path().to_string();
+            self.events.lock().unwrap().push(format!("start {}", path));
+
+            let body = if path == "/user" {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                json!({"name": "foo"})
+            } else {
+                json!({"count": 1})
+            };
+
+            self.events.lock().unwrap().push(format!("end {}", path));
+
+            Ok(Response {
+                status: reqwest::StatusCode::OK,
+                headers: Default::default(),
+                body: Bytes::from(body.to_string()),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prefetch() {
+        let config = Config::from_sdl(PREFETCH_CONFIG).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let http = Arc::new(RecordingHttp::default());
+        let mut runtime = crate::core::runtime::test::init(None);
+        runtime.http = http.clone();
+        let app_ctx = Arc::new(AppContext::new(blueprint, runtime, EndpointSet::default()));
+        let req_ctx = RequestContext::from(app_ctx.as_ref());
+
+        let request = Request::new("query { user { name stats { count } } }");
+        let executor = ConstValueExecutor::try_new(&request, &app_ctx).unwrap();
+        let response = executor.execute(&app_ctx, &req_ctx, request).await;
+        let response: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+
+        assert_eq!(
+            response,
+            json!({"data": {"user": {"name": "foo", "stats": {"count": 1}}}})
+        );
+        // the stats are fetched while the user is still being fetched
+        assert_eq!(
+            *http.events.lock().unwrap(),
+            vec!["start /user", "start /stats", "end /stats", "end /user"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_mutation() {
+        let config = Config::from_sdl(PREFETCH_CONFIG).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let http = Arc::new(RecordingHttp::default());
+        let mut runtime = crate::core::runtime::test::init(None);
+        runtime.http = http.clone();
+        let app_ctx = Arc::new(AppContext::new(blueprint, runtime, EndpointSet::default()));
+        let req_ctx = RequestContext::from(app_ctx.as_ref());
+
+        let request = Request::new("mutation { updateUser { name stats { count } } }");
+        let executor = ConstValueExecutor::try_new(&request, &app_ctx).unwrap();
+        let response = executor.execute(&app_ctx, &req_ctx, request).await;
+        let response: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+
+        assert_eq!(
+            response,
+            json!({"data": {"updateUser": {"name": "foo", "stats": {"count": 1}}}})
+        );
+        // the stats are fetched only once the mutation is done
+        assert_eq!(
+            *http.events.lock().unwrap(),
+            vec!["start /user", "end /user", "start /stats", "end /stats"]
+        );
+    }
+
+    /// Serves a list of 3 items and records the paths of the requests
+    #[derive(Default)]
+    struct RecordingPathsHttp {
+        paths: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpIO for RecordingPathsHttp {
+        async fn execute(&self, request: reqwest::Request) -> anyhow::Result<Response<Bytes>> {
+            let path = request.url().path().to_string();
+            self.paths.lock().unwrap().push(path.clone());
+
+            let body = match path.strip_prefix("/items/") {
+                Some(id) => json!({"title": format!("item {}", id)}),
+                None => json!([{"id": 1}, {"id": 2}, {"id": 3}]),
+            };
+
+            Ok(Response {
+                status: reqwest::StatusCode::OK,
+                headers: Default::default(),
+                body: Bytes::from(body.to_string()),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream() {
+        let config = Config::from_sdl(CONFIG).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let http = Arc::new(RecordingPathsHttp::default());
+        let mut runtime = crate::core::runtime::test::init(None);
+        runtime.http = http.clone();
+        let app_ctx = Arc::new(AppContext::new(blueprint, runtime, EndpointSet::default()));
+        let req_ctx = Arc::new(RequestContext::from(app_ctx.as_ref()));
+
+        let request =
+            Request::new("query { items @stream(initialCount: 1) { id detail { title } } }");
+        let executor = ConstValueExecutor::try_new(&request, &app_ctx).unwrap();
+        let mut payloads = executor
+            .execute_incremental(app_ctx, req_ctx, request)
+            .boxed();
+
+        assert_eq!(
+            next(&mut payloads).await,
+            json!({"data": {"items": [{"id": 1, "detail": {"title": "item 1"}}]}, "hasNext": true})
+        );
+        // the nested fields of the remaining items are resolved only once
+        // the previous payload was sent
+        assert_eq!(*http.paths.lock().unwrap(), vec!["/items", "/items/1"]);
+
+        assert_eq!(
+            next(&mut payloads).await,
+            json!({
+                "incremental": [{"items": [{"id": 2, "detail": {"title": "item 2"}}], "path": ["items", 1]}],
+                "hasNext": true
+            })
+        );
+        assert_eq!(
+            *http.paths.lock().unwrap(),
+            vec!["/items", "/items/1", "/items/2"]
+        );
+
+        assert_eq!(
+            next(&mut payloads).await,
+            json!({
+                "incremental": [{"items": [{"id": 3, "detail": {"title": "item 3"}}], "path": ["items", 2]}],
+                "hasNext": true
+            })
+        );
+        assert_eq!(next(&mut payloads).await, json!({"hasNext": false}));
+        assert!(payloads.next().await.is_none());
+    }
+
+    const CHUNKED_CONFIG: &str = r#"
+        schema @server {
+          query: Query
+        }
+
+        type Query {
+          fast: Item @http(url: "http://upstream/fast")
+          slow: Item @http(url: "http://upstream/slow")
+          required: Item! @http(url: "http://upstream/slow")
+          broken: Item! @http(url: "http://upstream/broken")
+        }
+
+        type Item {
+          id: Int
+        }
+    "#;
+
+    /// Serves the slow item only once the test lets it through and fails the
+    /// broken one
+    #[derive(Default)]
+    struct SlowHttp {
+        slow: tokio::sync::Notify,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpIO for SlowHttp {
+        async fn execute(&self, request: reqwest::Request) -> anyhow::Result<Response<Bytes>> {
+            let body = match request.url().path() {
+                "/slow" => {
+                    self.slow.notified().await;
+                    json!({"id": 2})
+                }
+                "/broken" => anyhow::bail!("the upstream is broken"),
+                _ => json!({"id": 1}),
+            };
+
+            Ok(Response {
+                status: reqwest::StatusCode::OK,
+                headers: Default::default(),
+                body: Bytes::from(body.to_string()),
+            })
+        }
+    }
+
+    fn chunked(
+        http: Arc<SlowHttp>,
+        query: &str,
+        deadline: Option<std::time::Instant>,
+    ) -> BoxStream<'static, Vec<u8>> {
+        let config = Config::from_sdl(CHUNKED_CONFIG).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let mut runtime = crate::core::runtime::test::init(None);
+        runtime.http = http;
+        let app_ctx = Arc::new(AppContext::new(blueprint, runtime, EndpointSet::default()));
+        let req_ctx = Arc::new(RequestContext::from(app_ctx.as_ref()));
+
+        let request = Request::new(query);
+        let executor = ConstValueExecutor::try_new(&request, &app_ctx).unwrap();
+        executor
+            .execute_chunked(app_ctx, req_ctx, request, deadline)
+            .boxed()
+    }
+
+    #[tokio::test]
+    async fn test_chunked() {
+        let http = Arc::new(SlowHttp::default());
+        let mut chunks = chunked(http.clone(), "query { fast { id } slow { id } }", None);
+
+        // the resolved field is sent while the slow one is still resolved
+        let sent = chunks.next().await.unwrap();
+        assert_eq!(
+            String::from_utf8(sent).unwrap(),
+            r#"{"data":{"fast":{"id":1}"#
+        );
+        assert!(chunks.next().now_or_never().is_none());
+
+        http.slow.notify_one();
+        let rest = chunks.collect::<Vec<_>>().await.concat();
+        assert_eq!(String::from_utf8(rest).unwrap(), r#","slow":{"id":2}}}"#);
+    }
+
+    #[tokio::test]
+    async fn test_chunked_timeout() {
+        let deadline = std::time::Instant::now() + Duration::from_millis(50);
+        let chunks = chunked(
+            Arc::new(SlowHttp::default()),
+            "query { fast { id } slow { id } }",
+            Some(deadline),
+        );
+
+        // the fields that aren't resolved by the deadline are left out
+        let response = chunks.collect::<Vec<_>>().await.concat();
+        assert_eq!(
+            String::from_utf8(response).unwrap(),
+            r#"{"data":{"fast":{"id":1}},"errors":[{"message":"The query timed out"}]}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chunked_holds_until_non_nullable() {
+        let http = Arc::new(SlowHttp::default());
+        let mut chunks = chunked(http.clone(), "query { fast { id } required { id } }", None);
+
+        // nothing is sent while the non-nullable field is still resolved
+        let held = tokio::time::timeout(Duration::from_millis(50), chunks.next()).await;
+        assert!(held.is_err());
+
+        http.slow.notify_one();
+        let response = chunks.collect::<Vec<_>>().await.concat();
+        assert_eq!(
+            String::from_utf8(response).unwrap(),
+            r#"{"data":{"fast":{"id":1},"required":{"id":2}}}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chunked_non_nullable_error() {
+        let chunks = chunked(
+            Arc::new(SlowHttp::default()),
+            "query { fast { id } broken { id } }",
+            None,
+        );
+
+        // the failed non-nullable field nulls the whole data
+        let response = chunks.collect::<Vec<_>>().await.concat();
+        let response: serde_json::Value = serde_json::from_slice(&response).unwrap();
+        assert_eq!(response["data"], serde_json::Value::Null);
+        assert_eq!(response["errors"].as_array().unwrap().len(), 1);
+    }
+
+    const JSON_SEQ_CONFIG: &str = r#"
+        schema @server {
+          query: Query
+        }
+
+        type Query {
+          items: [Item] @http(url: "http://upstream/items", responseFormat: JsonSeq)
+        }
+
+        type Item {
+          id: Int
+        }
+    "#;
+
+    /// Responds with a JSON text sequence whose chunks are sent by the test
+    struct JsonSeqHttp {
+        chunks: Mutex<Option<mpsc::UnboundedReceiver<Bytes>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpIO for JsonSeqHttp {
+        async fn execute(&self, _request: reqwest::Request) -> anyhow::Result<Response<Bytes>> {
+            unimplemented!("the items are streamed")
+        }
+
+        async fn execute_stream(
+            &self,
+            _request: reqwest::Request,
+        ) -> anyhow::Result<Response<BodyStream>> {
+            let chunks = self.chunks.lock().unwrap().take().unwrap();
+            let body = stream::unfold(chunks, |mut chunks| async move {
+                Some((Ok(chunks.recv().await?), chunks))
+            });
+
+            Ok(Response {
+                status: reqwest::StatusCode::OK,
+                headers: Default::default(),
+                body: body.boxed(),
+            })
+        }
+    }
+
+    async fn next(payloads: &mut BoxStream<'_, Vec<u8>>) -> serde_json::Value {
+        serde_json::from_slice(&payloads.next().await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_json_seq_stream() {
+        let config = Config::from_sdl(JSON_SEQ_CONFIG).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let (sender, chunks) = mpsc::unbounded_channel();
+        let mut runtime = crate::core::runtime::test::init(None);
+        runtime.http = Arc::new(JsonSeqHttp { chunks: Mutex::new(Some(chunks)) });
+        let app_ctx = Arc::new(AppContext::new(blueprint, runtime, EndpointSet::default()));
+        let req_ctx = Arc::new(RequestContext::from(app_ctx.as_ref()));
+
+        let request = Request::new("query { items @stream(initialCount: 1) { id } }");
+        let executor = ConstValueExecutor::try_new(&request, &app_ctx).unwrap();
+        let mut payloads = executor
+            .execute_incremental(app_ctx, req_ctx, request)
+            .boxed();
+
+        // every payload is sent while the upstream is still sending the rest
+        sender.send(Bytes::from("\x1e{\"id\":1}\n")).unwrap();
+        assert_eq!(
+            next(&mut payloads).await,
+            json!({"data": {"items": [{"id": 1}]}, "hasNext": true})
+        );
+        assert!(payloads.next().now_or_never().is_none());
+
+        sender
+            .send(Bytes::from("\x1e{\"id\":2}\n\x1e{\"id\""))
+            .unwrap();
+        assert_eq!(
+            next(&mut payloads).await,
+            json!({"incremental": [{"items": [{"id": 2}], "path": ["items", 1]}], "hasNext": true})
+        );
+        assert!(payloads.next().now_or_never().is_none());
+
+        sender.send(Bytes::from(":3}\n")).unwrap();
+        assert_eq!(
+            next(&mut payloads).await,
+            json!({"incremental": [{"items": [{"id": 3}], "path": ["items", 2]}], "hasNext": true})
+        );
+
+        drop(sender);
+        assert_eq!(next(&mut payloads).await, json!({"hasNext": false}));
+        assert!(payloads.next().await.is_none());
+    }
+}
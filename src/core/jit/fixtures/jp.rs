@@ -113,6 +113,14 @@ impl<'a, Value: Deserialize<'a> + Clone + 'a + JsonLike<'a> + std::fmt::Debug> J
         JP { test_data, plan, vars }
     }
 
+    /// Repeats the posts of the test data until there are `count` of them, to
+    /// synthesize large lists.
+    pub fn with_posts(mut self, count: usize) -> Self {
+        let posts = self.test_data.posts.iter().cycle().take(count).cloned();
+        self.test_data.posts = posts.collect();
+        self
+    }
+
     pub fn synth(&'a self) -> Synth<'a, Value> {
         let ProcessedTestData { posts, users } = self.test_data.to_processed();
         let vars = self.vars.clone();
@@ -1,25 +1,27 @@
 use std::collections::BTreeMap;
 use std::future::Future;
-use std::hash::{Hash, Hasher};
 use std::sync::Arc;
+use std::time::Instant;
 
 use async_graphql::{BatchRequest, Value};
 use async_graphql_value::{ConstValue, Extensions};
-use futures_util::stream::FuturesOrdered;
-use futures_util::StreamExt;
-use tailcall_hasher::TailcallHasher;
+use futures_util::stream::{self, BoxStream, FuturesOrdered};
+use futures_util::{future, StreamExt};
 
+use super::graphql_error::GraphQLError;
 use super::{AnyResponse, BatchResponse, Response};
 use crate::core::app_context::AppContext;
 use crate::core::async_graphql_hyper::OperationId;
 use crate::core::http::RequestContext;
-use crate::core::jit::{self, ConstValueExecutor, OPHash, Pos, Positioned};
+use crate::core::jit::{self, ConstValueExecutor, OPHash, OperationPlan, Pos, Positioned};
 
 #[derive(Clone)]
 pub struct JITExecutor {
     app_ctx: Arc<AppContext>,
     req_ctx: Arc<RequestContext>,
     operation_id: OperationId,
+    /// Set when the client accepts an incremental delivery response
+    multipart: bool,
 }
 
 impl JITExecutor {
@@ -28,7 +30,13 @@ impl JITExecutor {
         req_ctx: Arc<RequestContext>,
         operation_id: OperationId,
     ) -> Self {
-        Self { app_ctx, req_ctx, operation_id }
+        Self { app_ctx, req_ctx, operation_id, multipart: false }
+    }
+
+    /// Allows the deferred and streamed fields to be delivered incrementally,
+    /// otherwise they're delivered with the rest of the response.
+    pub fn multipart(self, multipart: bool) -> Self {
+        Self { multipart, ..self }
     }
 
     #[inline(always)]
@@ -63,10 +71,18 @@ impl JITExecutor {
 
     #[inline(always)]
     fn req_hash(request: &async_graphql::Request) -> OPHash {
-        let mut hasher = TailcallHasher::default();
-        request.query.hash(&mut hasher);
+        jit::normalize::hash_operation(&request.query, request.operation_name.as_deref())
+    }
 
-        OPHash::new(hasher.finish())
+    /// Returns the cached plan of the operation or builds a new one
+    fn plan(
+        &self,
+        hash: &OPHash,
+        request: &jit::Request<ConstValue>,
+    ) -> jit::Result<OperationPlan<async_graphql_value::Value>> {
+        self.app_ctx
+            .operation_plans
+            .get_or_build(hash, || request.create_plan(&self.app_ctx.blueprint))
     }
 }
 
@@ -75,8 +91,6 @@ impl JITExecutor {
         &self,
         request: async_graphql::Request,
     ) -> impl Future<Output = AnyResponse<Vec<u8>>> + Send + '_ {
-        // TODO: hash considering only the query itself ignoring specified operation and
-        // variables that could differ for the same query
         let hash = Self::req_hash(&request);
 
         async move {
@@ -85,30 +99,44 @@ impl JITExecutor {
             }
 
             let jit_request = jit::Request::from(request);
-            let exec = if let Some(op) = self.app_ctx.operation_plans.get(&hash) {
-                ConstValueExecutor::from(op.value().clone())
-            } else {
-                let exec = match ConstValueExecutor::try_new(&jit_request, &self.app_ctx) {
-                    Ok(exec) => exec,
-                    Err(error) => {
-                        return Response::<async_graphql::Value>::default()
-                            .with_errors(vec![Positioned::new(error, Pos::default())])
-                            .into()
-                    }
-                };
-                self.app_ctx
-                    .operation_plans
-                    .insert(hash.clone(), exec.plan.clone());
-                exec
+            let exec = match self.plan(&hash, &jit_request) {
+                Ok(plan) => ConstValueExecutor::from(plan),
+                Err(error) => {
+                    return Response::<async_graphql::Value>::default()
+                        .with_errors(vec![Positioned::new(error, Pos::default())])
+                        .into()
+                }
             };
 
             let is_const = exec.plan.is_const;
             let is_protected = exec.plan.is_protected;
+            let operation_type = exec.plan.operation_type();
 
-            let response = if exec.plan.can_dedupe() {
-                self.dedupe_and_exec(exec, jit_request).await
-            } else {
-                self.exec(exec, jit_request).await
+            let timeout = self.app_ctx.blueprint.server.get_timeout(operation_type);
+            if let Some(duration) = timeout {
+                // the upstream calls of the operation share its budget
+                self.req_ctx.set_deadline(Instant::now() + duration);
+            }
+
+            let execution = async move {
+                if exec.plan.can_dedupe() {
+                    self.dedupe_and_exec(exec, jit_request).await
+                } else {
+                    self.exec(exec, jit_request).await
+                }
+            };
+
+            let response = match timeout {
+                Some(duration) => match tokio::time::timeout(duration, execution).await {
+                    Ok(response) => response,
+                    Err(_) => {
+                        let error = jit::Error::Timeout(operation_type);
+                        return Response::<async_graphql::Value>::default()
+                            .with_errors(vec![GraphQLError::new(error.to_string(), None)])
+                            .into();
+                    }
+                },
+                None => execution.await,
             };
 
             // Cache the response if it's constant and not wrapped with protected.
@@ -122,6 +150,86 @@ impl JITExecutor {
         }
     }
 
+    /// Checks if the responses are sent chunk by chunk, see
+    /// [JITExecutor::execute_chunked].
+    pub fn is_chunked(&self) -> bool {
+        self.app_ctx.blueprint.server.enable_response_streaming
+    }
+
+    /// Executes the request like [JITExecutor::execute], except that the JSON
+    /// of the response is sent as its root fields are resolved, see
+    /// [ConstValueExecutor::execute_chunked]. Such responses are neither
+    /// deduplicated nor cached, since they're never buffered in full.
+    pub fn execute_chunked(&self, request: async_graphql::Request) -> BoxStream<'static, Vec<u8>> {
+        let hash = Self::req_hash(&request);
+        let jit_request = jit::Request::from(request);
+        let exec = match self.plan(&hash, &jit_request) {
+            Ok(plan) => ConstValueExecutor::from(plan),
+            Err(error) => {
+                let response: AnyResponse<Vec<u8>> = Response::<async_graphql::Value>::default()
+                    .with_errors(vec![Positioned::new(error, Pos::default())])
+                    .into();
+                return stream::once(future::ready(response.body.as_ref().clone())).boxed();
+            }
+        };
+
+        let operation_type = exec.plan.operation_type();
+        let timeout = self.app_ctx.blueprint.server.get_timeout(operation_type);
+        let deadline = timeout.map(|duration| Instant::now() + duration);
+        if let Some(deadline) = deadline {
+            self.req_ctx.set_deadline(deadline);
+        }
+
+        exec.execute_chunked(
+            self.app_ctx.clone(),
+            self.req_ctx.clone(),
+            jit_request,
+            deadline,
+        )
+        .boxed()
+    }
+
+    /// Checks if the request defers or streams some of its root fields and
+    /// the client accepts it, such requests are executed with
+    /// [JITExecutor::execute_incremental].
+    pub fn is_incremental(&self, request: &async_graphql::Request) -> bool {
+        // skip building the plan for the requests that can't defer anything
+        if !self.multipart
+            || !request.query.contains("@defer") && !request.query.contains("@stream")
+        {
+            return false;
+        }
+
+        let mut jit_request = jit::Request::new(&request.query);
+        jit_request.operation_name = request.operation_name.clone();
+
+        self.plan(&Self::req_hash(request), &jit_request)
+            .map(|plan| plan.is_incremental())
+            .unwrap_or(false)
+    }
+
+    /// Executes the request that defers or streams some of its root fields
+    /// and returns the payloads of the response in the order of delivery.
+    pub fn execute_incremental(
+        &self,
+        request: async_graphql::Request,
+    ) -> BoxStream<'static, Vec<u8>> {
+        let hash = Self::req_hash(&request);
+        let jit_request = jit::Request::from(request);
+
+        match self.plan(&hash, &jit_request) {
+            Ok(plan) => ConstValueExecutor::from(plan)
+                .execute_incremental(self.app_ctx.clone(), self.req_ctx.clone(), jit_request)
+                .boxed(),
+            Err(error) => {
+                let response: AnyResponse<Vec<u8>> = Response::<async_graphql::Value>::default()
+                    .with_errors(vec![Positioned::new(error, Pos::default())])
+                    .into();
+                stream::once(future::ready(response.body.as_ref().clone())).boxed()
+            }
+        }
+    }
+
     /// Execute a GraphQL batch query.
     pub async fn execute_batch(&self, batch_request: BatchRequest) -> BatchResponse<Vec<u8>> {
         match batch_request {
@@ -154,3 +262,148 @@ impl From<jit::Request<Value>> for async_graphql::Request {
         request
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use http::HeaderMap;
+    use tailcall_valid::Validator;
+
+    use super::JITExecutor;
+    use crate::core::app_context::AppContext;
+    use crate::core::async_graphql_hyper::{GraphQLRequest, GraphQLRequestLike};
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::{Config, ConfigModule};
+    use crate::core::http::RequestContext;
+    use crate::core::rest::EndpointSet;
+
+    const CONFIG: &str = r#"
+        schema {
+          query: Query
+        }
+
+        type Query {
+          echo(value: Int): Int @expr(body: "{{.args.value}}")
+        }
+    "#;
+
+    fn app_ctx() -> Arc<AppContext> {
+        let config = Config::from_sdl(CONFIG).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let runtime = crate::core::runtime::test::init(None);
+        Arc::new(AppContext::new(blueprint, runtime, EndpointSet::default()))
+    }
+
+    async fn execute(app_ctx: &Arc<AppContext>, request: async_graphql::Request) -> String {
+        let request = GraphQLRequest(request);
+        let operation_id = request.operation_id(&HeaderMap::new());
+        let req_ctx = Arc::new(RequestContext::from(app_ctx.as_ref()));
+        let executor = JITExecutor::new(app_ctx.clone(), req_ctx, operation_id);
+        let response = executor.execute(request.0).await;
+
+        String::from_utf8(response.body.as_ref().clone()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_plan_is_reused() {
+        let app_ctx = app_ctx();
+        let query = "query ($value: Int) { echo(value: $value) }";
+
+        for value in [1, 2] {
+            let request = async_graphql::Request::new(query).variables(
+                async_graphql::Variables::from_json(serde_json::json!({"value": value})),
+            );
+            let response = execute(&app_ctx, request).await;
+
+            assert_eq!(response, format!(r#"{{"data":{{"echo":{}}}}}"#, value));
+        }
+
+        assert_eq!(app_ctx.operation_plans.builds(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_per_operation_type() {
+        let server = httpmock::MockServer::start();
+        server.mock(|when, then| {
+            when.path("/slow");
+            then.status(200)
+                .body("1")
+                .delay(std::time::Duration::from_millis(300));
+        });
+
+        let config = Config::from_sdl(&format!(
+            r#"
+            schema @server(queryTimeout: 50, mutationTimeout: 5000) {{
+              query: Query
+              mutation: Mutation
+            }}
+
+            type Query {{
+              slow: Int @http(url: "{0}/slow")
+            }}
+
+            type Mutation {{
+              slow: Int @http(url: "{0}/slow", method: POST)
+            }}
+            "#,
+            server.base_url()
+        ))
+        .to_result()
+        .unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let runtime = crate::core::runtime::test::init(None);
+        let app_ctx = Arc::new(AppContext::new(blueprint, runtime, EndpointSet::default()));
+
+        let response = execute(&app_ctx, async_graphql::Request::new("mutation { slow }")).await;
+        assert_eq!(response, r#"{"data":{"slow":1}}"#);
+
+        let response = execute(&app_ctx, async_graphql::Request::new("query { slow }")).await;
+        assert_eq!(
+            response,
+            r#"{"data":null,"errors":[{"message":"The query timed out"}]}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_chunked() {
+        use futures_util::StreamExt;
+
+        let app_ctx = app_ctx();
+        let chunked = |query: &str| {
+            let req_ctx = Arc::new(RequestContext::from(app_ctx.as_ref()));
+            let request = GraphQLRequest(async_graphql::Request::new(query));
+            let operation_id = request.operation_id(&HeaderMap::new());
+            let executor = JITExecutor::new(app_ctx.clone(), req_ctx, operation_id);
+            executor.execute_chunked(request.0).collect::<Vec<_>>()
+        };
+
+        let chunks = chunked("{ a: echo(value: 1) b: echo(value: 2) }").await;
+        assert_eq!(
+            String::from_utf8(chunks.concat()).unwrap(),
+            r#"{"data":{"a":1,"b":2}}"#
+        );
+
+        // the errors of the request as a whole are sent the usual way
+        let chunks = chunked("{ echo(").await;
+        assert_eq!(
+            String::from_utf8(chunks.concat()).unwrap(),
+            execute(&app_ctx, async_graphql::Request::new("{ echo(")).await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_plan_per_operation() {
+        let app_ctx = app_ctx();
+        let query = "query One { echo(value: 1) } query Two { echo(value: 2) }";
+
+        for (operation, value) in [("One", 1), ("Two", 2), ("One", 1)] {
+            let request = async_graphql::Request::new(query).operation_name(operation);
+            let response = execute(&app_ctx, request).await;
+
+            assert_eq!(response, format!(r#"{{"data":{{"echo":{}}}}}"#, value));
+        }
+
+        assert_eq!(app_ctx.operation_plans.builds(), 2);
+    }
+}
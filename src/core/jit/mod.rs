@@ -1,6 +1,9 @@
+mod coerce;
 mod exec;
 pub mod graphql_error;
 mod model;
+pub mod normalize;
+mod plan_cache;
 mod store;
 mod synth;
 mod transform;
@@ -23,5 +26,6 @@ pub use error::*;
 pub use exec_const::*;
 pub use graphql_executor::*;
 pub use model::*;
+pub use plan_cache::*;
 pub use request::*;
 pub use response::*;
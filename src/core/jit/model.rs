@@ -10,10 +10,11 @@ use async_graphql_value::ConstValue;
 use serde::{Deserialize, Serialize};
 
 use super::Error;
-use crate::core::blueprint::Index;
+use crate::core::blueprint::{Constraint, Index};
 use crate::core::ir::model::IR;
 use crate::core::ir::TypedValue;
 use crate::core::json::{JsonLike, JsonLikeOwned};
+use crate::core::mustache::Mustache;
 use crate::core::path::PathString;
 use crate::core::scalar::Scalar;
 
@@ -102,6 +103,8 @@ pub struct Arg<Input> {
     pub type_of: crate::core::Type,
     pub value: Option<Input>,
     pub default_value: Option<Input>,
+    pub constraint: Option<Constraint>,
+    pub context_default: Option<Mustache>,
 }
 
 impl<Input: Display> Display for Arg<Input> {
@@ -131,6 +134,8 @@ impl<Input> Arg<Input> {
             type_of: self.type_of,
             value: self.value.map(map).transpose()?,
             default_value: self.default_value.map(map).transpose()?,
+            constraint: self.constraint,
+            context_default: self.context_default,
         })
     }
 }
@@ -192,6 +197,29 @@ pub struct Field<Input> {
     pub directives: Vec<Directive<Input>>,
     pub is_enum: bool,
     pub scalar: Option<Scalar>,
+    /// Set for the root fields selected inside a fragment marked with
+    /// `@defer`, they are delivered after the rest of the response
+    pub defer: Option<Defer>,
+    /// Set for the root list fields marked with `@stream`, their items after
+    /// the initial count are delivered one by one after the initial response
+    pub stream: Option<Stream>,
+    /// Set for the fields marked with `@prefetch`, their resolver is executed
+    /// along with the resolver of the parent field
+    pub prefetch: bool,
+}
+
+/// Describes a fragment marked with the `@defer` directive
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Defer {
+    pub label: Option<String>,
+}
+
+/// Describes a list field marked with the `@stream` directive
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stream {
+    pub label: Option<String>,
+    /// Number of items delivered with the initial response
+    pub initial_count: usize,
 }
 
 pub struct DFS<'a, Input> {
@@ -262,6 +290,9 @@ impl<Input> Field<Input> {
                 .collect::<Result<_, _>>()?,
             is_enum: self.is_enum,
             scalar: self.scalar,
+            defer: self.defer,
+            stream: self.stream,
+            prefetch: self.prefetch,
         })
     }
 }
@@ -290,6 +321,15 @@ impl<Input: Debug> Debug for Field<Input> {
             debug_struct.field("include", &self.include);
         }
         debug_struct.field("directives", &self.directives);
+        if self.defer.is_some() {
+            debug_struct.field("defer", &self.defer);
+        }
+        if self.stream.is_some() {
+            debug_struct.field("stream", &self.stream);
+        }
+        if self.prefetch {
+            debug_struct.field("prefetch", &self.prefetch);
+        }
 
         debug_struct.finish()
     }
@@ -304,6 +344,14 @@ impl OPHash {
     }
 }
 
+/// A variable declared by the operation
+#[derive(Debug, Clone)]
+pub struct VariableDefinition {
+    pub name: String,
+    pub type_of: crate::core::Type,
+    pub default_value: Option<ConstValue>,
+}
+
 #[derive(Debug, Clone)]
 pub struct OperationPlan<Input> {
     pub root_name: String,
@@ -318,6 +366,7 @@ pub struct OperationPlan<Input> {
     pub selection: Vec<Field<Input>>,
     pub before: Option<IR>,
     pub interfaces: Option<HashSet<String>>,
+    pub variables: Vec<VariableDefinition>,
 }
 
 impl<Input> OperationPlan<Input> {
@@ -343,6 +392,7 @@ impl<Input> OperationPlan<Input> {
             min_cache_ttl: self.min_cache_ttl,
             before: self.before,
             interfaces: None,
+            variables: self.variables,
         })
     }
 }
@@ -372,6 +422,7 @@ impl<Input> OperationPlan<Input> {
             min_cache_ttl: None,
             before: Default::default(),
             interfaces,
+            variables: Vec::new(),
         }
     }
 
@@ -442,6 +493,68 @@ impl<Input> OperationPlan<Input> {
     pub fn can_dedupe(&self) -> bool {
         self.is_query() && (self.is_dedupe || self.is_const || self.min_cache_ttl.is_some())
     }
+
+    /// Check if any of the root fields is deferred or streamed
+    pub fn is_incremental(&self) -> bool {
+        self.selection
+            .iter()
+            .any(|field| field.defer.is_some() || field.stream.is_some())
+    }
+
+    /// Splits the plan into a plan for every root field in the order they
+    /// appear in the query, the plan without any, e.g. that only introspects
+    /// the schema, is kept as it is.
+    pub fn split_root_fields(&self) -> Vec<Self>
+    where
+        Input: Clone,
+    {
+        if self.selection.is_empty() {
+            return vec![self.clone()];
+        }
+
+        self.selection
+            .iter()
+            .enumerate()
+            .map(|(i, field)| Self {
+                selection: vec![field.clone()],
+                // introspection is resolved along with the first field
+                is_introspection_query: self.is_introspection_query && i == 0,
+                ..self.clone()
+            })
+            .collect()
+    }
+
+    /// Splits the plan into the plan for the initial response and the plans
+    /// for every deferred fragment in the order they appear in the query.
+    pub fn split_deferred(&self) -> (Self, Vec<(Defer, Self)>)
+    where
+        Input: Clone,
+    {
+        let mut initial = vec![];
+        let mut deferred: Vec<(Defer, Vec<Field<Input>>)> = vec![];
+
+        for field in &self.selection {
+            match &field.defer {
+                None => initial.push(field.clone()),
+                Some(defer) => match deferred.iter_mut().find(|(d, _)| d == defer) {
+                    Some((_, fields)) => fields.push(field.clone()),
+                    None => deferred.push((defer.clone(), vec![field.clone()])),
+                },
+            }
+        }
+
+        let initial = Self { selection: initial, ..self.clone() };
+        let deferred = deferred
+            .into_iter()
+            .map(|(defer, selection)| {
+                // introspection is resolved along with the initial response
+                let plan = Self { selection, is_introspection_query: false, ..self.clone() };
+                (defer, plan)
+            })
+            .collect();
+
+        (initial, deferred)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -0,0 +1,233 @@
+//! Canonical representation of incoming queries.
+//!
+//! Two queries that differ only in insignificant tokens (whitespace, commas
+//! and comments) share the same normalized form and hence the same
+//! [`OPHash`]. The order of selections is preserved since it defines the
+//! shape of the response.
+
+use std::hash::{Hash, Hasher};
+
+use tailcall_hasher::TailcallHasher;
+
+use super::{OPHash, Result};
+
+/// Validates the query with the async-graphql parser and returns its
+/// normalized representation.
+pub fn normalize(query: &str) -> Result<String> {
+    async_graphql::parser::parse_query(query)?;
+
+    Ok(minify(query))
+}
+
+/// Validates the query and returns a stable hash of its normalized
+/// representation.
+pub fn hash(query: &str) -> Result<OPHash> {
+    async_graphql::parser::parse_query(query)?;
+
+    Ok(hash_unchecked(query))
+}
+
+/// Hashes the significant tokens of the query without validating it first.
+///
+/// NOTE: This is hot code and doesn't allocate.
+pub fn hash_unchecked(query: &str) -> OPHash {
+    let mut hasher = TailcallHasher::default();
+    for token in Tokens::new(query) {
+        token.hash(&mut hasher);
+    }
+
+    OPHash::new(hasher.finish())
+}
+
+/// Hashes the query together with the name of the operation to execute, since
+/// every operation of the document has its own plan.
+pub fn hash_operation(query: &str, operation_name: Option<&str>) -> OPHash {
+    let mut hasher = TailcallHasher::default();
+    for token in Tokens::new(query) {
+        token.hash(&mut hasher);
+    }
+    operation_name.hash(&mut hasher);
+
+    OPHash::new(hasher.finish())
+}
+
+/// Joins the significant tokens of the query, keeping a single space only
+/// where two adjacent tokens would otherwise merge.
+fn minify(query: &str) -> String {
+    let mut out = String::with_capacity(query.len());
+    let mut last = None;
+
+    for token in Tokens::new(query) {
+        let first = token.as_bytes()[0];
+        if last.is_some_and(is_word) && (is_word(first) || first == b'-') {
+            out.push(' ');
+        }
+        out.push_str(token);
+        last = token.as_bytes().last().copied();
+    }
+
+    out
+}
+
+fn is_word(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+/// Iterator over the significant lexical tokens of a GraphQL document.
+struct Tokens<'a> {
+    query: &'a str,
+    pos: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(query: &'a str) -> Self {
+        Self { query, pos: 0 }
+    }
+
+    fn skip_while(&self, mut pos: usize, f: impl Fn(u8) -> bool) -> usize {
+        let bytes = self.query.as_bytes();
+        while pos < bytes.len() && f(bytes[pos]) {
+            pos += 1;
+        }
+        pos
+    }
+
+    fn string_end(&self, mut pos: usize) -> usize {
+        let bytes = self.query.as_bytes();
+        while pos < bytes.len() {
+            match bytes[pos] {
+                b'\\' => pos += 2,
+                b'"' => return pos + 1,
+                _ => pos += 1,
+            }
+        }
+        bytes.len()
+    }
+
+    fn block_string_end(&self, mut pos: usize) -> usize {
+        let bytes = self.query.as_bytes();
+        while pos < bytes.len() {
+            if bytes[pos..].starts_with(br#"\""""#) {
+                pos += 4;
+            } else if bytes[pos..].starts_with(br#"""""#) {
+                return pos + 3;
+            } else {
+                pos += 1;
+            }
+        }
+        bytes.len()
+    }
+
+    fn number_end(&self, mut pos: usize) -> usize {
+        let bytes = self.query.as_bytes();
+        while pos < bytes.len() {
+            match bytes[pos] {
+                b'+' | b'-' if matches!(bytes[pos - 1], b'e' | b'E') => pos += 1,
+                byte if is_word(byte) || byte == b'.' => pos += 1,
+                _ => break,
+            }
+        }
+        pos
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let query = self.query;
+        let bytes = query.as_bytes();
+
+        loop {
+            let start = self.pos;
+            let end = match *bytes.get(start)? {
+                b'#' => {
+                    self.pos = self.skip_while(start, |b| b != b'\n' && b != b'\r');
+                    continue;
+                }
+                b' ' | b'\t' | b'\n' | b'\r' | b',' => {
+                    self.pos += 1;
+                    continue;
+                }
+                b'"' if bytes[start..].starts_with(br#"""""#) => self.block_string_end(start + 3),
+                b'"' => self.string_end(start + 1),
+                b'.' if bytes[start..].starts_with(b"...") => start + 3,
+                b'-' | b'0'..=b'9' => self.number_end(start + 1),
+                b'_' | b'a'..=b'z' | b'A'..=b'Z' => self.skip_while(start + 1, is_word),
+                byte if byte.is_ascii() => start + 1,
+                _ => {
+                    // outside of strings and comments the only valid non-ascii
+                    // character is the byte order mark, which is ignored
+                    let ch = query[start..].chars().next()?;
+                    self.pos += ch.len_utf8();
+                    continue;
+                }
+            };
+
+            self.pos = end;
+            return Some(&query[start..end]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let query = r#"
+            # fetch the user
+            query  Users($id: Int!,  $name: String = "John  Doe") {
+              user(id: $id, name: $name) { id   name, ...UserFields }
+              count: total(min: -1, max: 1.5e+3)
+            }
+            fragment UserFields on User { email }
+        "#;
+
+        let actual = normalize(query).unwrap();
+        let expected = r#"query Users($id:Int!$name:String="John  Doe"){user(id:$id name:$name){id name...UserFields}count:total(min:-1 max:1.5e+3)}fragment UserFields on User{email}"#;
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_normalize_block_string() {
+        let query = r#"{ posts(filter: """  a "quoted" \""" value  """) { id } }"#;
+
+        let actual = normalize(query).unwrap();
+        let expected = r#"{posts(filter:"""  a "quoted" \""" value  """){id}}"#;
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_hash_ignores_whitespace_and_comments() {
+        let a = hash("query { posts { id title } }").unwrap();
+        let b = hash("query {\n  posts {\n    id\n    title\n  }\n}\n").unwrap();
+        let c = hash("query{posts{id,title}}").unwrap();
+        let d = hash("# comment\nquery { posts { id # id\n title } }").unwrap();
+
+        assert_eq!(a, b);
+        assert_eq!(a, c);
+        assert_eq!(a, d);
+    }
+
+    #[test]
+    fn test_hash_differs_for_different_queries() {
+        let a = hash("query { posts { id title } }").unwrap();
+        let b = hash("query { posts { title id } }").unwrap();
+        let c = hash("query { posts { id } }").unwrap();
+        let d = hash(r#"query { user(name: "a b") { id } }"#).unwrap();
+        let e = hash(r#"query { user(name: "ab") { id } }"#).unwrap();
+
+        assert_ne!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(d, e);
+    }
+
+    #[test]
+    fn test_hash_invalid_query() {
+        assert!(hash("query { posts { id }").is_err());
+    }
+}
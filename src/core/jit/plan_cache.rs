@@ -0,0 +1,41 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_graphql_value::Value;
+use dashmap::DashMap;
+
+use super::{OPHash, OperationPlan, Result};
+
+/// Keeps the plans of the operations that were already executed, so that a
+/// repeated query skips the planning and only resolves its variables.
+///
+/// NOTE: the plans depend on the blueprint they were built from. The cache is
+/// owned by the `AppContext` of that blueprint and is dropped along with it
+/// whenever the schema changes.
+#[derive(Default)]
+pub struct PlanCache {
+    plans: DashMap<OPHash, OperationPlan<Value>>,
+    builds: AtomicUsize,
+}
+
+impl PlanCache {
+    /// Returns the cached plan of the operation or builds and caches a new one
+    pub fn get_or_build(
+        &self,
+        hash: &OPHash,
+        build: impl FnOnce() -> Result<OperationPlan<Value>>,
+    ) -> Result<OperationPlan<Value>> {
+        if let Some(plan) = self.plans.get(hash) {
+            return Ok(plan.value().clone());
+        }
+
+        let plan = build()?;
+        self.builds.fetch_add(1, Ordering::Relaxed);
+        self.plans.insert(hash.clone(), plan.clone());
+        Ok(plan)
+    }
+
+    /// Returns the number of plans that were built since the cache was created
+    pub fn builds(&self) -> usize {
+        self.builds.load(Ordering::Relaxed)
+    }
+}
@@ -45,7 +45,9 @@ impl Request<ConstValue> {
         let builder = Builder::new(blueprint, &doc);
         let plan = builder.build(self.operation_name.as_deref())?;
 
-        transform::CheckConst::new()
+        transform::LazyResolver::new()
+            .when(blueprint.server.enable_lazy_resolvers)
+            .pipe(transform::CheckConst::new())
             .pipe(transform::CheckProtected::new())
             .pipe(transform::AuthPlanner::new())
             .pipe(transform::CheckDedupe::new())
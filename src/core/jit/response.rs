@@ -4,7 +4,7 @@ use derive_setters::Setters;
 use serde::Serialize;
 
 use super::graphql_error::GraphQLError;
-use super::Positioned;
+use super::{PathSegment, Positioned};
 use crate::core::async_graphql_hyper::CacheControl;
 use crate::core::jit;
 use crate::core::json::{JsonLike, JsonObjectLike};
@@ -90,6 +90,54 @@ where
     }
 }
 
+/// The first payload of an incremental delivery response, it contains the
+/// response without the deferred fragments.
+/// See [spec](https://github.com/graphql/graphql-wg/blob/main/rfcs/DeferStream.md)
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitialPayload<Value> {
+    #[serde(flatten)]
+    pub response: Response<Value>,
+    pub has_next: bool,
+}
+
+/// A payload that follows the [InitialPayload] and delivers the result of a
+/// deferred fragment or the items of a streamed list.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsequentPayload<Value> {
+    /// Empty in the last payload of a stream whose end is known only after
+    /// its last item was delivered
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub incremental: Vec<IncrementalResult<Value>>,
+    pub has_next: bool,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum IncrementalResult<Value> {
+    /// Fields of a deferred fragment
+    Defer {
+        #[serde(flatten)]
+        response: Response<Value>,
+        /// Path to the object the deferred fields belong to
+        path: Vec<PathSegment<'static>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+    },
+    /// Items of a streamed list
+    Stream {
+        items: Vec<Value>,
+        /// Path to the first of the items in the list
+        path: Vec<PathSegment<'static>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+        /// Errors raised while the items were resolved
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        errors: Vec<GraphQLError>,
+    },
+}
+
 /// Represents a GraphQL response in a serialized byte format.
 #[derive(Clone)]
 pub struct AnyResponse<Body> {
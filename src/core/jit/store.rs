@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-
 use crate::core::jit::model::FieldId;
 
 /// Path to the data in the store with info
@@ -19,11 +17,15 @@ impl DataPath {
         Self(Vec::new())
     }
 
-    /// Create new DataPath with specified additional entry
-    pub fn with_index(mut self, index: usize) -> Self {
+    /// Enters the item of a list at the specified index. The same path is
+    /// reused for all the items, so it must be left with [DataPath::pop].
+    pub fn push(&mut self, index: usize) {
         self.0.push(index);
+    }
 
-        Self(self.0)
+    /// Leaves the item of a list entered with [DataPath::push]
+    pub fn pop(&mut self) {
+        self.0.pop();
     }
 
     /// Iterator over indexes only for multiple paths.
@@ -34,9 +36,11 @@ impl DataPath {
     }
 }
 
+/// Data of the resolved fields, indexed by the id of the field. Field ids are
+/// dense, so the data is kept in a vector instead of a map.
 #[derive(Debug)]
 pub struct Store<Data> {
-    data: HashMap<usize, Data>,
+    data: Vec<Option<Data>>,
 }
 
 impl<Data> Default for Store<Data> {
@@ -47,18 +51,28 @@ impl<Data> Default for Store<Data> {
 
 impl<Data> Store<Data> {
     pub fn new() -> Self {
-        Store { data: HashMap::new() }
+        Store { data: Vec::new() }
+    }
+
+    /// Creates a store pre-sized for the given number of fields, e.g. the size
+    /// of the operation plan
+    pub fn with_capacity(capacity: usize) -> Self {
+        Store { data: Vec::with_capacity(capacity) }
     }
 
     pub fn set_data(&mut self, field_id: FieldId, data: Data) {
-        self.data.insert(field_id.as_usize(), data);
+        self.set(&field_id, data);
     }
 
     pub fn set(&mut self, field_id: &FieldId, data: Data) {
-        self.data.insert(field_id.as_usize(), data);
+        let index = field_id.as_usize();
+        if index >= self.data.len() {
+            self.data.resize_with(index + 1, || None);
+        }
+        self.data[index] = Some(data);
     }
 
     pub fn get(&self, field_id: &FieldId) -> Option<&Data> {
-        self.data.get(&field_id.as_usize())
+        self.data.get(field_id.as_usize()).and_then(Option::as_ref)
     }
 }
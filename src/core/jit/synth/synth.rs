@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::sync::Mutex;
 
 use crate::core::jit::model::{Field, OperationPlan, Variables};
 use crate::core::jit::store::{DataPath, Store};
@@ -11,6 +12,11 @@ pub struct Synth<'a, Value> {
     plan: &'a OperationPlan<Value>,
     store: ValueStore<Value>,
     variables: Variables<Value>,
+    strict: bool,
+    isolate_errors: bool,
+    /// Errors of the fields that were set to null in place of failing the
+    /// whole response
+    errors: Mutex<Vec<Positioned<Error>>>,
 }
 
 impl<'a, Value> Synth<'a, Value> {
@@ -20,7 +26,34 @@ impl<'a, Value> Synth<'a, Value> {
         store: ValueStore<Value>,
         variables: Variables<Value>,
     ) -> Self {
-        Self { plan, store, variables }
+        Self {
+            plan,
+            store,
+            variables,
+            strict: false,
+            isolate_errors: false,
+            errors: Mutex::new(vec![]),
+        }
+    }
+
+    /// In strict mode the values that don't match the type of the field
+    /// produce an error instead of being coerced.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// When errors are isolated a failed field is set to null, even if it's
+    /// non-null, instead of failing the whole response. The errors of such
+    /// fields are available with [Synth::take_errors].
+    pub fn isolate_errors(mut self, isolate_errors: bool) -> Self {
+        self.isolate_errors = isolate_errors;
+        self
+    }
+
+    /// Returns the errors of the fields that were set to null
+    pub fn take_errors(&self) -> Vec<Positioned<Error>> {
+        std::mem::take(&mut *self.errors.lock().unwrap())
     }
 }
 
@@ -33,6 +66,23 @@ where
         !field.skip(&self.variables)
     }
 
+    /// Sets the failed field to null when errors are isolated
+    fn isolate<Output>(
+        &self,
+        result: Result<Output, Positioned<Error>>,
+    ) -> Result<Output, Positioned<Error>>
+    where
+        Output: JsonLike<'a>,
+    {
+        match result {
+            Err(error) if self.isolate_errors => {
+                self.errors.lock().unwrap().push(error);
+                Ok(Output::null())
+            }
+            result => result,
+        }
+    }
+
     #[inline(always)]
     pub fn synthesize<Output>(&'a self) -> Result<Output, Positioned<Error>>
     where
@@ -40,15 +90,15 @@ where
     {
         let mut data = Output::JsonObject::with_capacity(self.plan.selection.len());
         let mut path = Vec::new();
+        let mut data_path = DataPath::new();
         let root_name = self.plan.root_name();
 
         for child in self.plan.selection.iter() {
             if !self.include(child) {
                 continue;
             }
-            // TODO: in case of error set `child.output_name` to null
-            // and append error to response error array
-            let val = self.iter(child, None, &DataPath::new(), &mut path, Some(root_name))?;
+            let val =
+                self.isolate(self.iter(child, None, &mut data_path, &mut path, Some(root_name)))?;
             data.insert_key(&child.output_name, val);
         }
 
@@ -61,7 +111,7 @@ where
         &'a self,
         node: &'a Field<Value>,
         value: Option<&'a Value>,
-        data_path: &DataPath,
+        data_path: &mut DataPath,
         path: &mut Vec<PathSegment<'a>>,
         root_name: Option<&'a str>,
     ) -> Result<Output, Positioned<Error>>
@@ -69,8 +119,26 @@ where
         Output: JsonLike<'a>,
     {
         path.push(PathSegment::Field(Cow::Borrowed(&node.output_name)));
+        let result = self.iter_field(node, value, data_path, path, root_name);
+        path.pop();
 
-        let result = match self.store.get(&node.id) {
+        result
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[inline(always)]
+    fn iter_field<Output>(
+        &'a self,
+        node: &'a Field<Value>,
+        value: Option<&'a Value>,
+        data_path: &mut DataPath,
+        path: &mut Vec<PathSegment<'a>>,
+        root_name: Option<&'a str>,
+    ) -> Result<Output, Positioned<Error>>
+    where
+        Output: JsonLike<'a>,
+    {
+        match self.store.get(&node.id) {
             Some(value) => {
                 let mut value = value.as_ref().map_err(Clone::clone)?;
 
@@ -82,19 +150,36 @@ where
                     }
                 }
 
+                if self.is_type_mismatch(node, value) {
+                    return Err(self.to_location_error(type_mismatch(node), node, path));
+                }
                 if node.type_of.is_list() != value.as_array().is_some() {
                     return self.node_nullable_guard(node, path, None);
                 }
                 self.iter_inner(node, value, data_path, path)
             }
             None => match value {
+                Some(result) if self.is_type_mismatch(node, result) => {
+                    Err(self.to_location_error(type_mismatch(node), node, path))
+                }
                 Some(result) => self.iter_inner(node, result, data_path, path),
                 None => self.node_nullable_guard(node, path, root_name),
             },
-        };
+        }
+    }
 
-        path.pop();
-        result
+    /// Checks in strict mode that list fields receive arrays and the other
+    /// fields don't, except for scalars like `JSON` that can hold any value.
+    fn is_type_mismatch(&self, node: &Field<Value>, value: &Value) -> bool {
+        if !self.strict || value.is_null() {
+            return false;
+        }
+
+        if node.type_of.is_list() {
+            value.as_array().is_none()
+        } else {
+            node.scalar.is_none() && value.as_array().is_some()
+        }
     }
 
     /// This guard ensures to return Null value only if node type permits it, in
@@ -128,7 +213,7 @@ where
         &'a self,
         node: &'a Field<Value>,
         value: &'a Value,
-        data_path: &DataPath,
+        data_path: &mut DataPath,
         path: &mut Vec<PathSegment<'a>>,
     ) -> Result<Output, Positioned<Error>>
     where
@@ -202,7 +287,7 @@ where
                                 Output::string(node.value_type(value).into())
                             } else {
                                 let val = obj.get_key(child.name.as_str());
-                                self.iter(child, val, data_path, path, None)?
+                                self.isolate(self.iter(child, val, data_path, path, None))?
                             };
                             fields.push((child.output_name.as_str(), value));
                         }
@@ -214,13 +299,15 @@ where
                     let mut ans = Vec::with_capacity(arr.len());
                     for (i, val) in arr.iter().enumerate() {
                         path.push(PathSegment::Index(i));
-                        let val =
-                            self.iter_inner(node, val, &data_path.clone().with_index(i), path)?;
+                        data_path.push(i);
+                        let val = self.iter_inner(node, val, data_path, path);
+                        data_path.pop();
                         path.pop();
-                        ans.push(val);
+                        ans.push(self.isolate(val)?);
                     }
                     Ok(Output::array(ans))
                 }
+                _ if self.strict => Err(type_mismatch(node)),
                 _ => Ok(Output::clone_from(value)),
             }
         };
@@ -247,8 +334,14 @@ where
     }
 }
 
+fn type_mismatch<Value>(node: &Field<Value>) -> Error {
+    ValidationError::TypeMismatch { type_of: format!("{:?}", node.type_of) }.into()
+}
+
 #[cfg(test)]
 mod tests {
+    use std::borrow::Cow;
+
     use async_graphql_value::ConstValue;
     use serde::{Deserialize, Serialize};
     use tailcall_valid::Validator;
@@ -261,7 +354,7 @@ mod tests {
     use crate::core::jit::model::{FieldId, Variables};
     use crate::core::jit::store::Store;
     use crate::core::jit::synth::Synth;
-    use crate::core::jit::OperationPlan;
+    use crate::core::jit::{OperationPlan, PathSegment};
     use crate::core::json::JsonLike;
 
     const POSTS: &str = r#"
@@ -438,6 +531,46 @@ mod tests {
         assert_synths(query, store);
     }
 
+    #[test]
+    fn test_type_mismatch_lenient() {
+        let store = vec![(FieldId::new(0), TestData::User1)];
+        let query = r#"
+            query {
+                posts { id }
+            }
+        "#;
+        let (plan, value_store, vars) = make_store::<ConstValue>(query, store);
+        let synth = Synth::new(&plan, value_store, vars);
+
+        let actual: ConstValue = synth.synthesize().unwrap();
+        let expected = ConstValue::from_json(serde_json::json!({"posts": null})).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_type_mismatch_strict() {
+        let store = vec![(FieldId::new(0), TestData::User1)];
+        let query = r#"
+            query {
+                posts { id }
+            }
+        "#;
+        let (plan, value_store, vars) = make_store::<ConstValue>(query, store);
+        let synth = Synth::new(&plan, value_store, vars).strict(true);
+
+        let actual = synth.synthesize::<ConstValue>().unwrap_err();
+
+        assert_eq!(
+            actual.value.to_string(),
+            r#"internal: invalid value for type "[Post]""#
+        );
+        assert_eq!(
+            actual.path,
+            vec![PathSegment::Field(Cow::Owned("posts".to_string()))]
+        );
+    }
+
     #[test]
     fn test_json_placeholder() {
         let jp: JP<async_graphql::Value> =
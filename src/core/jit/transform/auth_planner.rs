@@ -78,7 +78,7 @@ pub fn update_ir(ir: &mut IR, vec: &mut Vec<Auth>) {
         IR::Discriminate(_, ir) => {
             update_ir(ir, vec);
         }
-        IR::Merge(irs) => {
+        IR::Merge(irs) | IR::Join(_, irs) => {
             irs.iter_mut().for_each(|ir| update_ir(ir, vec));
         }
     }
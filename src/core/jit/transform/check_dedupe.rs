@@ -21,7 +21,7 @@ fn check_dedupe(ir: &IR) -> bool {
         IR::Path(ir, _) => check_dedupe(ir),
         IR::Protect(_, ir) => check_dedupe(ir),
         IR::Pipe(ir, ir1) => check_dedupe(ir) && check_dedupe(ir1),
-        IR::Merge(vec) => vec.iter().all(check_dedupe),
+        IR::Merge(vec) | IR::Join(_, vec) => vec.iter().all(check_dedupe),
         IR::Discriminate(_, ir) => check_dedupe(ir),
         IR::Entity(hash_map) => hash_map.values().all(check_dedupe),
         IR::Dynamic(_) => true,
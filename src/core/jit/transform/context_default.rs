@@ -0,0 +1,240 @@
+use async_graphql_value::{ConstValue, Value};
+use tailcall_valid::Valid;
+
+use crate::core::blueprint::Index;
+use crate::core::jit::{Field, OperationPlan, ResolveInputError};
+use crate::core::path::PathString;
+use crate::core::{Transform, Type};
+
+/// Supplies the arguments omitted by the client from the request context
+/// using the templates defined with the `@default` directive. The rendered
+/// template is coerced to the declared type of the argument, e.g. `"42"` stays
+/// a string for a `String` argument and becomes a number for an `Int`, and
+/// the request fails if it can't be. Arguments whose template refers to a
+/// missing header or variable are left omitted, so they fall back to the
+/// static default value or fail as required arguments.
+pub struct ContextDefault<'a, Ctx> {
+    ctx: &'a Ctx,
+}
+
+impl<'a, Ctx: PathString> ContextDefault<'a, Ctx> {
+    pub fn new(ctx: &'a Ctx) -> Self {
+        Self { ctx }
+    }
+
+    fn resolve(&self, index: &Index, fields: &mut [Field<Value>]) -> Result<(), ResolveInputError> {
+        for field in fields {
+            for arg in field.args.iter_mut() {
+                let Some(mustache) = arg.context_default.as_ref() else {
+                    continue;
+                };
+                if arg.value.is_some() {
+                    continue;
+                }
+
+                let is_resolved = mustache
+                    .expression_segments()
+                    .iter()
+                    .all(|path| self.ctx.path_string(path.as_slice()).is_some());
+
+                if is_resolved {
+                    let rendered = mustache.render(self.ctx);
+                    let value = coerce(index, &arg.type_of, &rendered).ok_or_else(|| {
+                        ResolveInputError::InvalidScalarValue {
+                            value: rendered.clone(),
+                            type_of: arg.type_of.name().to_string(),
+                            arg_name: arg.name.clone(),
+                            field_name: field.name.clone(),
+                        }
+                    })?;
+                    arg.value = Some(value.into_value());
+                }
+            }
+
+            self.resolve(index, &mut field.selection)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Coerces the rendered template to the declared type of the argument. Lists
+/// and input objects are parsed as JSON, the numbers and booleans are parsed
+/// from their text and any other type, e.g. `String`, `ID` or an enum, takes
+/// the text as it is.
+fn coerce(index: &Index, type_of: &Type, rendered: &str) -> Option<ConstValue> {
+    let type_name = type_of.name().as_str();
+    if type_of.is_list() || index.get_input_type_definition(type_name).is_some() {
+        let value = serde_json::from_str(rendered).ok()?;
+        return ConstValue::from_json(value).ok();
+    }
+
+    match type_name {
+        "Int" => rendered.trim().parse::<i64>().ok().map(ConstValue::from),
+        "Float" => rendered
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(ConstValue::Number),
+        "Boolean" => rendered.trim().parse::<bool>().ok().map(ConstValue::from),
+        _ => Some(ConstValue::from(rendered)),
+    }
+}
+
+impl<Ctx: PathString> Transform for ContextDefault<'_, Ctx> {
+    type Value = OperationPlan<Value>;
+    type Error = ResolveInputError;
+
+    fn transform(&self, mut plan: Self::Value) -> Valid<Self::Value, Self::Error> {
+        match self.resolve(&plan.index, &mut plan.selection) {
+            Ok(()) => Valid::succeed(plan),
+            Err(err) => Valid::fail(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql_value::{ConstValue, Value};
+    use serde_json::json;
+    use tailcall_valid::Validator;
+
+    use super::ContextDefault;
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::{Config, ConfigModule};
+    use crate::core::jit::builder::Builder;
+    use crate::core::jit::transform::InputResolver;
+    use crate::core::jit::{OperationPlan, ResolveInputError, Variables};
+    use crate::core::Transform;
+
+    const CONFIG: &str = r#"
+        schema {
+          query: Query
+        }
+
+        type Query {
+          users(
+            region: String @default(value: "{{.headers.x-region}}")
+            limit: Int = 10 @default(value: "{{.headers.x-limit}}")
+            tag: String @default(value: "{{.headers.x-tag}}")
+            ids: [Int] @default(value: "{{.headers.x-ids}}")
+          ): [User] @http(url: "http://upstream/users", query: [{key: "region", value: "{{.args.region}}"}])
+          orders(tenant: ID! @default(value: "{{.headers.x-tenant}}")): [User]
+            @http(url: "http://upstream/orders")
+        }
+
+        type User {
+          id: Int
+        }
+    "#;
+
+    fn resolve(
+        query: &str,
+        ctx: serde_json::Value,
+    ) -> Result<Vec<(String, Option<ConstValue>)>, ResolveInputError> {
+        let config = Config::from_sdl(CONFIG).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let document = async_graphql::parser::parse_query(query).unwrap();
+        let plan: OperationPlan<Value> = Builder::new(&blueprint, &document).build(None).unwrap();
+
+        let plan = ContextDefault::new(&ctx)
+            .transform(plan)
+            .to_result()
+            .map_err(|err| err.as_vec()[0].message.clone())?;
+        let plan = InputResolver::new(plan).resolve_input(&Variables::new())?;
+
+        Ok(plan.selection[0]
+            .args
+            .iter()
+            .map(|arg| (arg.name.clone(), arg.value.clone()))
+            .collect())
+    }
+
+    #[test]
+    fn test_resolve_from_header() {
+        let ctx = json!({"headers": {"x-region": "eu-west", "x-limit": "5", "x-ids": "[1, 2]"}});
+        let actual = resolve("query { users { id } }", ctx).unwrap();
+        let expected = vec![
+            ("region".to_string(), Some(ConstValue::from("eu-west"))),
+            ("limit".to_string(), Some(ConstValue::from(5))),
+            ("tag".to_string(), None),
+            (
+                "ids".to_string(),
+                Some(ConstValue::List(vec![
+                    ConstValue::from(1),
+                    ConstValue::from(2),
+                ])),
+            ),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_coerce_to_argument_type() {
+        // a number in the header stays a string for a `String` argument
+        let ctx = json!({"headers": {"x-region": "42", "x-tag": "true"}});
+        let actual = resolve("query { users { id } }", ctx).unwrap();
+
+        assert_eq!(actual[0].1, Some(ConstValue::from("42")));
+        assert_eq!(actual[2].1, Some(ConstValue::from("true")));
+
+        let ctx = json!({"headers": {"x-limit": "five"}});
+        let actual = resolve("query { users { id } }", ctx).unwrap_err();
+        let expected = ResolveInputError::InvalidScalarValue {
+            value: "five".to_string(),
+            type_of: "Int".to_string(),
+            arg_name: "limit".to_string(),
+            field_name: "users".to_string(),
+        };
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_client_value_wins() {
+        let ctx = json!({"headers": {"x-region": "eu-west", "x-limit": "5"}});
+        let actual = resolve(
+            r#"query { users(region: "us-east", limit: 1) { id } }"#,
+            ctx,
+        )
+        .unwrap();
+        let expected = vec![
+            ("region".to_string(), Some(ConstValue::from("us-east"))),
+            ("limit".to_string(), Some(ConstValue::from(1))),
+            ("tag".to_string(), None),
+            ("ids".to_string(), None),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_missing_header() {
+        // the argument is omitted, so the static default value is used
+        let actual = resolve("query { users { id } }", json!({"headers": {}})).unwrap();
+        let expected = vec![
+            ("region".to_string(), None),
+            ("limit".to_string(), Some(ConstValue::from(10))),
+            ("tag".to_string(), None),
+            ("ids".to_string(), None),
+        ];
+
+        assert_eq!(actual, expected);
+
+        // and a required argument without a default value is reported
+        let actual = resolve("query { orders { id } }", json!({"headers": {}})).unwrap_err();
+        let expected = ResolveInputError::ArgumentIsRequired {
+            arg_name: "tenant".to_string(),
+            field_name: "orders".to_string(),
+        };
+
+        assert_eq!(actual, expected);
+
+        let ctx = json!({"headers": {"x-tenant": "7"}});
+        let actual = resolve("query { orders { id } }", ctx).unwrap();
+
+        assert_eq!(actual[0].1, Some(ConstValue::from("7")));
+    }
+}
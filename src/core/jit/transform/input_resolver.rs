@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::fmt::Display;
 
 use async_graphql_value::{ConstValue, Value};
@@ -5,7 +6,8 @@ use async_graphql_value::{ConstValue, Value};
 use super::super::{Arg, Field, OperationPlan, ResolveInputError, Variables};
 use crate::core::blueprint::Index;
 use crate::core::ir::model::IO;
-use crate::core::json::{JsonLikeOwned, JsonObjectLike};
+use crate::core::json::{JsonLikeOwned, JsonObjectLike, JsonPrimitive};
+use crate::core::scalar::Scalar;
 use crate::core::Type;
 
 /// Trait to represent conversion from some dynamic type (with variables)
@@ -19,6 +21,10 @@ pub trait InputResolvable {
         self,
         variables: &Variables<Self::Output>,
     ) -> Result<Self::Output, ResolveInputError>;
+
+    /// Checks if the value refers to a variable that is not provided in the
+    /// request. Arguments with such values are treated as omitted.
+    fn is_missing_variable(&self, variables: &Variables<Self::Output>) -> bool;
 }
 
 impl InputResolvable for Value {
@@ -32,6 +38,10 @@ impl InputResolvable for Value {
                 .ok_or_else(|| ResolveInputError::VariableIsNotFound(name.to_string()))
         })
     }
+
+    fn is_missing_variable(&self, variables: &Variables<ConstValue>) -> bool {
+        matches!(self, Value::Variable(name) if variables.get(name).is_none())
+    }
 }
 
 /// Transforms [OperationPlan] values the way that all the input values
@@ -62,6 +72,7 @@ where
             .plan
             .selection
             .into_iter()
+            .map(|field| Self::omit_missing_variables(field, variables))
             .map(|field| field.try_map(&|value| value.resolve(variables)))
             // Call `resolve_field` to verify/populate defaults for args
             // because the previous map will just try convert values based on
@@ -87,9 +98,36 @@ where
             interfaces: None,
             selection,
             before: self.plan.before,
+            variables: self.plan.variables,
         })
     }
 
+    // arguments that refer to variables not provided in the request are
+    // considered as omitted by the client, so the default value of the argument
+    // is used instead of failing with unknown variable.
+    fn omit_missing_variables(
+        mut field: Field<Input>,
+        variables: &Variables<Output>,
+    ) -> Field<Input> {
+        for arg in field.args.iter_mut() {
+            if arg
+                .value
+                .as_ref()
+                .is_some_and(|value| value.is_missing_variable(variables))
+            {
+                arg.value = None;
+            }
+        }
+
+        field.selection = field
+            .selection
+            .into_iter()
+            .map(|field| Self::omit_missing_variables(field, variables))
+            .collect();
+
+        field
+    }
+
     // resolves the variables in selection set mustache template for graphql query.
     fn resolve_graphql_selection_set(
         base_field: &mut [Field<Output>],
@@ -126,6 +164,15 @@ where
                     &arg.default_value,
                     arg.value,
                 )?;
+                if let (Some(constraint), Some(value)) = (&arg.constraint, &value) {
+                    constraint.validate(value).map_err(|message| {
+                        ResolveInputError::ConstraintViolation {
+                            message,
+                            arg_name: arg.name.clone(),
+                            field_name: field.name.clone(),
+                        }
+                    })?;
+                }
                 Ok(Arg { value, ..arg })
             })
             .collect::<Result<_, _>>()?;
@@ -139,6 +186,98 @@ where
         Ok(Field { args, selection, ..field })
     }
 
+    // coerces the default value to the declared type of the argument.
+    // According to the spec a single value provided for a list type is
+    // treated as a list with that single item.
+    fn coerce_default(type_of: &Type, value: Output) -> Output {
+        if type_of.is_list() && !value.is_null() && value.as_array().is_none() {
+            Output::array(vec![value])
+        } else {
+            value
+        }
+    }
+
+    // checks that the value (or every item of the list) is one of the variants
+    // of the enum before it is passed to any resolver.
+    fn validate_enum_value(
+        index: &Index,
+        parent_name: &str,
+        arg_name: &str,
+        type_of: &Type,
+        value: &Output,
+    ) -> Result<(), ResolveInputError> {
+        if let Some(arr) = value.as_array() {
+            return arr.iter().try_for_each(|item| {
+                Self::validate_enum_value(index, parent_name, arg_name, type_of, item)
+            });
+        }
+
+        match value.as_primitive() {
+            Some(JsonPrimitive::Null) => Ok(()),
+            Some(JsonPrimitive::Str(variant))
+                if index.validate_enum_value(type_of.name(), variant) =>
+            {
+                Ok(())
+            }
+            _ => Err(ResolveInputError::InvalidEnumValue {
+                value: value.to_string(),
+                type_of: type_of.name().to_string(),
+                arg_name: arg_name.to_string(),
+                field_name: parent_name.to_string(),
+            }),
+        }
+    }
+
+    // checks that the value (or every item of the list) is accepted by the
+    // custom scalar, e.g. matches the pattern of `@regex`, so the invalid
+    // values aren't passed to the resolvers. The built-in scalars are checked
+    // when the value is returned.
+    fn validate_scalar_value(
+        scalar: &Scalar,
+        parent_name: &str,
+        arg_name: &str,
+        type_of: &Type,
+        value: &Output,
+    ) -> Result<(), ResolveInputError> {
+        if let Some(arr) = value.as_array() {
+            return arr.iter().try_for_each(|item| {
+                Self::validate_scalar_value(scalar, parent_name, arg_name, type_of, item)
+            });
+        }
+
+        if value.is_null() || scalar.validate(value) {
+            Ok(())
+        } else {
+            Err(ResolveInputError::InvalidScalarValue {
+                value: value.to_string(),
+                type_of: type_of.name().to_string(),
+                arg_name: arg_name.to_string(),
+                field_name: parent_name.to_string(),
+            })
+        }
+    }
+
+    // replaces the enum value (or every item of the list) with the value that
+    // stands for it in the upstream, if it's set with `@upstreamValue`.
+    fn to_upstream_enum_value(index: &Index, type_of: &Type, value: &mut Output) {
+        if let Some(arr) = value.as_array_mut() {
+            for item in arr.iter_mut() {
+                Self::to_upstream_enum_value(index, type_of, item);
+            }
+            return;
+        }
+
+        let upstream_value = match value.as_primitive() {
+            Some(JsonPrimitive::Str(variant)) => {
+                index.get_upstream_enum_value(type_of.name(), variant)
+            }
+            _ => None,
+        };
+        if let Some(upstream_value) = upstream_value {
+            *value = Output::string(Cow::Owned(upstream_value.to_string()));
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn recursive_parse_arg(
         index: &Index,
@@ -152,17 +291,21 @@ where
         let value = if !type_of.is_nullable() && value.is_none() {
             let default_value = default_value.clone();
 
-            Some(default_value.ok_or(ResolveInputError::ArgumentIsRequired {
+            let default_value = default_value.ok_or(ResolveInputError::ArgumentIsRequired {
                 arg_name: arg_name.to_string(),
                 field_name: parent_name.to_string(),
-            })?)
+            })?;
+
+            Some(Self::coerce_default(type_of, default_value))
         } else if !type_of.is_nullable() && is_value_null {
             return Err(ResolveInputError::ArgumentIsRequired {
                 arg_name: arg_name.to_string(),
                 field_name: parent_name.to_string(),
             });
         } else if value.is_none() {
-            default_value.clone()
+            default_value
+                .clone()
+                .map(|value| Self::coerce_default(type_of, value))
         } else {
             value
         };
@@ -171,6 +314,19 @@ where
             return Ok(None);
         };
 
+        if index.type_is_enum(type_of.name()) {
+            Self::validate_enum_value(index, parent_name, arg_name, type_of, &value)?;
+            Self::to_upstream_enum_value(index, type_of, &mut value);
+
+            return Ok(Some(value));
+        }
+
+        if let Some(scalar @ Scalar::Custom(_)) = index.get_scalar(type_of.name()) {
+            Self::validate_scalar_value(&scalar, parent_name, arg_name, type_of, &value)?;
+
+            return Ok(Some(value));
+        }
+
         let Some(def) = index.get_input_type_definition(type_of.name()) else {
             return Ok(Some(value));
         };
@@ -196,14 +352,20 @@ where
                 }
             }
         } else if let Some(arr) = value.as_array_mut() {
-            for (i, item) in arr.iter_mut().enumerate() {
-                let parent_name = format!("{}.{}.{}", parent_name, arg_name, i);
+            // items are checked against the wrapped type, so required items
+            // and their nested fields are reported with the index in the path
+            let item_type = match type_of {
+                Type::List { of_type, .. } => of_type.as_ref(),
+                Type::Named { .. } => type_of,
+            };
+            let parent_name = format!("{}.{}", parent_name, arg_name);
 
+            for (i, item) in arr.iter_mut().enumerate() {
                 *item = Self::recursive_parse_arg(
                     index,
                     &parent_name,
                     &i.to_string(),
-                    type_of,
+                    item_type,
                     &None,
                     Some(item.clone()),
                 )?
@@ -214,3 +376,312 @@ where
         Ok(Some(value))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use async_graphql_value::{ConstValue, Value};
+    use serde_json::json;
+    use tailcall_valid::Validator;
+
+    use super::InputResolver;
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::{Config, ConfigModule};
+    use crate::core::jit::builder::Builder;
+    use crate::core::jit::{OperationPlan, ResolveInputError, Variables};
+
+    const CONFIG: &str = r#"
+        schema {
+          query: Query
+        }
+
+        enum Status {
+          ACTIVE
+          INACTIVE
+        }
+
+        enum Role {
+          ADMIN @upstreamValue(name: "role_admin")
+          USER
+        }
+
+        input Filter {
+          status: Status = ACTIVE
+          role: Role
+          range: Range
+        }
+
+        input Range {
+          min: Int!
+          max: Int = 100
+        }
+
+        type Query {
+          users(status: Status, statuses: [Status], role: Role, roles: [Role], filter: Filter, filters: [Filter!]): [User]
+            @http(url: "http://upstream/users", query: [{key: "status", value: "{{.args.status}}"}])
+          posts(
+            limit: Int @constraint(min: 1, max: 100)
+            title: String @constraint(minLength: 3, maxLength: 10)
+            slug: String @constraint(pattern: "^[a-z-]+$")
+            tags: [String] @constraint(maxLength: 5)
+          ): [User] @http(url: "http://upstream/posts")
+          products(sku: Sku, skus: [Sku]): [Product] @http(url: "http://upstream/products")
+        }
+
+        type User {
+          id: Int
+        }
+
+        scalar Sku @regex(pattern: "^SKU-[0-9]+$")
+
+        type Product {
+          sku: Sku
+        }
+    "#;
+
+    fn resolve(
+        query: &str,
+        variables: Variables<ConstValue>,
+    ) -> Result<OperationPlan<ConstValue>, ResolveInputError> {
+        let config = Config::from_sdl(CONFIG).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let document = async_graphql::parser::parse_query(query).unwrap();
+        let plan: OperationPlan<Value> = Builder::new(&blueprint, &document).build(None).unwrap();
+
+        InputResolver::new(plan).resolve_input(&variables)
+    }
+
+    #[test]
+    fn test_valid_enum_value() {
+        assert!(resolve("query { users(status: ACTIVE) { id } }", Variables::new()).is_ok());
+        assert!(resolve(
+            "query { users(statuses: [ACTIVE, INACTIVE]) { id } }",
+            Variables::new()
+        )
+        .is_ok());
+
+        let variables =
+            Variables::from_iter([("status".to_string(), ConstValue::from("INACTIVE"))]);
+        assert!(resolve(
+            "query ($status: Status) { users(status: $status) { id } }",
+            variables
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_invalid_enum_value() {
+        let actual = resolve("query { users(status: DELETED) { id } }", Variables::new());
+        let expected = ResolveInputError::InvalidEnumValue {
+            value: "DELETED".to_string(),
+            type_of: "Status".to_string(),
+            arg_name: "status".to_string(),
+            field_name: "users".to_string(),
+        };
+        assert_eq!(actual.unwrap_err(), expected);
+
+        let variables = Variables::from_iter([("status".to_string(), ConstValue::from("unknown"))]);
+        let actual = resolve(
+            "query ($status: Status) { users(statuses: [ACTIVE, $status]) { id } }",
+            variables,
+        );
+        assert!(matches!(
+            actual,
+            Err(ResolveInputError::InvalidEnumValue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_regex_scalar_value() {
+        assert!(resolve(
+            "query { products(sku: \"SKU-1\") { sku } }",
+            Variables::new()
+        )
+        .is_ok());
+
+        let actual = resolve(
+            "query { products(sku: \"ABC-1\") { sku } }",
+            Variables::new(),
+        );
+        let expected = ResolveInputError::InvalidScalarValue {
+            value: "\"ABC-1\"".to_string(),
+            type_of: "Sku".to_string(),
+            arg_name: "sku".to_string(),
+            field_name: "products".to_string(),
+        };
+        assert_eq!(actual.unwrap_err(), expected);
+
+        let variables = Variables::from_iter([("sku".to_string(), ConstValue::from("ABC-2"))]);
+        let actual = resolve(
+            "query ($sku: Sku) { products(skus: [\"SKU-1\", $sku]) { sku } }",
+            variables,
+        );
+        assert!(matches!(
+            actual,
+            Err(ResolveInputError::InvalidScalarValue { .. })
+        ));
+    }
+
+    fn resolved_arg(plan: &OperationPlan<ConstValue>, name: &str) -> Option<ConstValue> {
+        plan.iter_dfs()
+            .find(|field| field.name == "users")
+            .and_then(|field| field.args.iter().find(|arg| arg.name == name))
+            .and_then(|arg| arg.value.clone())
+    }
+
+    #[test]
+    fn test_nested_defaults() {
+        let plan = resolve(
+            "query { users(filter: {range: {min: 1}}) { id } }",
+            Variables::new(),
+        )
+        .unwrap();
+
+        let actual = resolved_arg(&plan, "filter").unwrap();
+        let expected = json!({"status": "ACTIVE", "range": {"min": 1, "max": 100}});
+
+        assert_eq!(actual.into_json().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_nested_defaults_in_list() {
+        let variables = Variables::from_iter([(
+            "filters".to_string(),
+            ConstValue::from_json(
+                json!([{"status": "INACTIVE"}, {"range": {"min": 5, "max": 10}}]),
+            )
+            .unwrap(),
+        )]);
+        let plan = resolve(
+            "query ($filters: [Filter!]) { users(filters: $filters) { id } }",
+            variables,
+        )
+        .unwrap();
+
+        let actual = resolved_arg(&plan, "filters").unwrap();
+        let expected = json!([
+            {"status": "INACTIVE"},
+            {"status": "ACTIVE", "range": {"min": 5, "max": 10}}
+        ]);
+
+        assert_eq!(actual.into_json().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_upstream_enum_value() {
+        let variables = Variables::from_iter([("role".to_string(), ConstValue::from("ADMIN"))]);
+        let plan = resolve(
+            "query ($role: Role) { users(role: $role, roles: [ADMIN, USER], filter: {role: ADMIN}) { id } }",
+            variables,
+        )
+        .unwrap();
+
+        let actual = resolved_arg(&plan, "role").unwrap();
+        assert_eq!(actual.into_json().unwrap(), json!("role_admin"));
+
+        let actual = resolved_arg(&plan, "roles").unwrap();
+        assert_eq!(actual.into_json().unwrap(), json!(["role_admin", "USER"]));
+
+        let actual = resolved_arg(&plan, "filter").unwrap();
+        assert_eq!(
+            actual.into_json().unwrap(),
+            json!({"status": "ACTIVE", "role": "role_admin"})
+        );
+    }
+
+    #[test]
+    fn test_missing_required_nested_field() {
+        let actual = resolve(
+            "query { users(filter: {range: {max: 1}}) { id } }",
+            Variables::new(),
+        );
+        let expected = ResolveInputError::ArgumentIsRequired {
+            arg_name: "min".to_string(),
+            field_name: "users.filter.range".to_string(),
+        };
+        assert_eq!(actual.unwrap_err(), expected);
+
+        let variables = Variables::from_iter([(
+            "filters".to_string(),
+            ConstValue::from_json(json!([{"range": {"min": 1}}, {"range": {}}])).unwrap(),
+        )]);
+        let actual = resolve(
+            "query ($filters: [Filter!]) { users(filters: $filters) { id } }",
+            variables,
+        );
+        let expected = ResolveInputError::ArgumentIsRequired {
+            arg_name: "min".to_string(),
+            field_name: "users.filters.1.range".to_string(),
+        };
+        assert_eq!(actual.unwrap_err(), expected);
+    }
+
+    fn constraint_violation(message: &str, arg_name: &str) -> ResolveInputError {
+        ResolveInputError::ConstraintViolation {
+            message: message.to_string(),
+            arg_name: arg_name.to_string(),
+            field_name: "posts".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_constraint_min_max() {
+        assert!(resolve("query { posts(limit: 1) { id } }", Variables::new()).is_ok());
+        assert!(resolve("query { posts(limit: 100) { id } }", Variables::new()).is_ok());
+
+        let actual = resolve("query { posts(limit: 0) { id } }", Variables::new());
+        let expected = constraint_violation("must be at least 1", "limit");
+        assert_eq!(actual.unwrap_err(), expected);
+
+        let variables = Variables::from_iter([("limit".to_string(), ConstValue::from(101))]);
+        let actual = resolve(
+            "query ($limit: Int) { posts(limit: $limit) { id } }",
+            variables,
+        );
+        let expected = constraint_violation("must be at most 100", "limit");
+        assert_eq!(actual.unwrap_err(), expected);
+    }
+
+    #[test]
+    fn test_constraint_length() {
+        assert!(resolve(r#"query { posts(title: "abc") { id } }"#, Variables::new()).is_ok());
+        assert!(resolve(
+            r#"query { posts(tags: ["a", "abcde"]) { id } }"#,
+            Variables::new()
+        )
+        .is_ok());
+
+        let actual = resolve(r#"query { posts(title: "ab") { id } }"#, Variables::new());
+        let expected = constraint_violation("must be at least 3 characters long", "title");
+        assert_eq!(actual.unwrap_err(), expected);
+
+        let actual = resolve(
+            r#"query { posts(title: "abcdefghijk") { id } }"#,
+            Variables::new(),
+        );
+        let expected = constraint_violation("must be at most 10 characters long", "title");
+        assert_eq!(actual.unwrap_err(), expected);
+
+        let actual = resolve(
+            r#"query { posts(tags: ["a", "abcdef"]) { id } }"#,
+            Variables::new(),
+        );
+        let expected = constraint_violation("must be at most 5 characters long", "tags");
+        assert_eq!(actual.unwrap_err(), expected);
+    }
+
+    #[test]
+    fn test_constraint_pattern() {
+        assert!(resolve(
+            r#"query { posts(slug: "hello-world") { id } }"#,
+            Variables::new()
+        )
+        .is_ok());
+
+        let actual = resolve(
+            r#"query { posts(slug: "Hello World") { id } }"#,
+            Variables::new(),
+        );
+        let expected = constraint_violation("must match the pattern `^[a-z-]+$`", "slug");
+        assert_eq!(actual.unwrap_err(), expected);
+    }
+}
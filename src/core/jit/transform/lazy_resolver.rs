@@ -0,0 +1,207 @@
+use std::convert::Infallible;
+use std::marker::PhantomData;
+
+use async_graphql_value::ConstValue;
+use tailcall_valid::Valid;
+
+use crate::core::blueprint::{DynamicValue, Index};
+use crate::core::ir::model::{IO, IR};
+use crate::core::jit::{Field, OperationPlan};
+use crate::core::Transform;
+
+/// Skips the upstream calls whose response isn't read by any of the selected
+/// fields, e.g. when the client selects only the fields that are resolved from
+/// their own upstream. The call is replaced with an empty object, so the
+/// selected fields are still resolved. It's disabled with the `lazyResolvers`
+/// option of the server.
+pub struct LazyResolver<A>(PhantomData<A>);
+impl<A> LazyResolver<A> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+/// Whether skipping the call can't change the response other than by hiding
+/// its failures, i.e. the call isn't conditional and the failures aren't
+/// handled in a specific way.
+fn is_skippable(io: &IO) -> bool {
+    match io {
+        IO::Http { when, hook, on_status, .. } => {
+            when.is_none() && hook.is_none() && on_status.is_empty()
+        }
+        IO::GraphQL { .. } => true,
+        IO::Grpc { hook, .. } => hook.is_none(),
+        IO::Js { .. } => false,
+    }
+}
+
+/// Returns the IR that stands in for the resolver when its response isn't
+/// needed, only the resolvers that call an upstream are replaced.
+fn placeholder(ir: &IR) -> Option<IR> {
+    let io = match ir {
+        IR::IO(io) => io,
+        IR::Cache(cache) => cache.io.as_ref(),
+        IR::Path(ir, _) => return placeholder(ir),
+        // the field stays protected even when its upstream isn't called
+        IR::Protect(auth, ir) => {
+            return placeholder(ir).map(|ir| IR::Protect(auth.clone(), Box::new(ir)))
+        }
+        _ => return None,
+    };
+
+    is_skippable(io)
+        .then(|| IR::Dynamic(DynamicValue::Value(ConstValue::Object(Default::default()))))
+}
+
+fn is_read<A>(field: &Field<A>) -> bool {
+    match &field.ir {
+        Some(ir) => ir.reads_parent(),
+        None => field.name != "__typename",
+    }
+}
+
+fn make_lazy<A>(field: &mut Field<A>, index: &Index) {
+    // the children are visited first, so a chain of unread resolvers is
+    // skipped altogether
+    for child in field.selection.iter_mut() {
+        make_lazy(child, index);
+    }
+
+    // a nullable field could be null in the response of the upstream, so
+    // its selection would be resolved only when the upstream is called
+    let is_unread = !field.type_of.is_list()
+        && !field.type_of.is_nullable()
+        && index.type_is_object(field.type_of.name())
+        && !field.selection.is_empty()
+        && !field.selection.iter().any(is_read);
+
+    if is_unread {
+        if let Some(ir) = field.ir.as_ref().and_then(placeholder) {
+            field.ir = Some(ir);
+        }
+    }
+}
+
+impl<A> Transform for LazyResolver<A> {
+    type Value = OperationPlan<A>;
+    type Error = Infallible;
+
+    fn transform(&self, mut plan: Self::Value) -> Valid<Self::Value, Self::Error> {
+        // the resolvers of mutations are executed for their side effects
+        if plan.is_query() {
+            let index = plan.index.clone();
+            for field in plan.selection.iter_mut() {
+                make_lazy(field, &index);
+            }
+        }
+
+        Valid::succeed(plan)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql_value::{ConstValue, Value};
+    use tailcall_valid::Validator;
+
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::{Config, ConfigModule};
+    use crate::core::ir::model::IR;
+    use crate::core::jit::{OperationPlan, Request};
+
+    const CONFIG: &str = r#"
+        schema @server {
+          query: Query
+        }
+
+        type Query {
+          user(id: Int!): User! @http(url: "http://upstream/users/{{.args.id}}")
+          users: [User] @http(url: "http://upstream/users")
+          optionalUser(id: Int!): User @http(url: "http://upstream/users/{{.args.id}}")
+          activeUser(id: Int!): User!
+            @http(url: "http://upstream/users/{{.args.id}}", when: "{{.args.id}}")
+          checkedUser(id: Int!): User!
+            @http(
+              url: "http://upstream/users/{{.args.id}}"
+              onStatus: [{status: 404, action: Error}]
+            )
+        }
+
+        type User {
+          id: Int
+          name: String
+          stats: Stats @http(url: "http://upstream/stats")
+          posts: [Post] @http(url: "http://upstream/users/{{.value.id}}/posts")
+        }
+
+        type Stats {
+          count: Int
+        }
+
+        type Post {
+          id: Int
+        }
+    "#;
+
+    fn plan(query: &str) -> OperationPlan<Value> {
+        plan_with(CONFIG, query)
+    }
+
+    fn plan_with(config: &str, query: &str) -> OperationPlan<Value> {
+        let config = Config::from_sdl(config).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+
+        Request::<ConstValue>::new(query)
+            .create_plan(&blueprint)
+            .unwrap()
+    }
+
+    fn is_io(plan: &OperationPlan<Value>, name: &str) -> bool {
+        plan.iter_dfs()
+            .find(|field| field.name == name)
+            .is_some_and(|field| matches!(field.ir, Some(IR::IO(_))))
+    }
+
+    #[test]
+    fn test_unread_resolver_is_skipped() {
+        let plan = plan("{ user(id: 1) { stats { count } } }");
+
+        assert!(!is_io(&plan, "user"));
+        assert!(is_io(&plan, "stats"));
+    }
+
+    #[test]
+    fn test_read_resolver_is_kept() {
+        assert!(is_io(&plan("{ user(id: 1) { name } }"), "user"));
+        assert!(is_io(&plan("{ user(id: 1) { posts { id } } }"), "user"));
+        assert!(is_io(
+            &plan("{ user(id: 1) { name stats { count } } }"),
+            "user"
+        ));
+        // the items of a list are known only from the response
+        assert!(is_io(&plan("{ users { stats { count } } }"), "users"));
+    }
+
+    #[test]
+    fn test_resolver_that_changes_the_response_is_kept() {
+        // the upstream could respond with null
+        let plan = plan("{ optionalUser(id: 1) { stats { count } } }");
+        assert!(is_io(&plan, "optionalUser"));
+
+        // the call is conditional
+        let plan = plan("{ activeUser(id: 1) { stats { count } } }");
+        assert!(is_io(&plan, "activeUser"));
+
+        // the failures are handled with `onStatus`
+        let plan = plan("{ checkedUser(id: 1) { stats { count } } }");
+        assert!(is_io(&plan, "checkedUser"));
+    }
+
+    #[test]
+    fn test_disabled() {
+        let config = CONFIG.replace("@server", "@server(lazyResolvers: false)");
+        let plan = plan_with(&config, "{ user(id: 1) { stats { count } } }");
+
+        assert!(is_io(&plan, "user"));
+    }
+}
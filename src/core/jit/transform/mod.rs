@@ -3,8 +3,10 @@ mod check_cache;
 mod check_const;
 mod check_dedupe;
 mod check_protected;
+mod context_default;
 mod graphql;
 mod input_resolver;
+mod lazy_resolver;
 mod skip;
 
 pub use auth_planner::*;
@@ -12,6 +14,8 @@ pub use check_cache::*;
 pub use check_const::*;
 pub use check_dedupe::*;
 pub use check_protected::*;
+pub use context_default::*;
 pub use graphql::*;
 pub use input_resolver::*;
+pub use lazy_resolver::*;
 pub use skip::*;
@@ -205,6 +205,33 @@ mod tests {
         assert_eq!(actual, expected)
     }
 
+    #[test]
+    fn test_gather_path_matches_nested_key() {
+        let input = json!({
+            "data": [
+                {"name": "foo", "meta": {"user": {"id": "1"}}},
+                {"name": "bar", "meta": {"user": {"id": "2"}}},
+                {"name": "baz"}
+            ]
+        });
+
+        let actual = serde_json::to_value(gather_path_matches(
+            &input,
+            &["data".into(), "meta.user.id".into()],
+            vec![],
+        ))
+        .unwrap();
+
+        let expected = json!(
+            [
+              ["1", {"name": "foo", "meta": {"user": {"id": "1"}}}],
+              ["2", {"name": "bar", "meta": {"user": {"id": "2"}}}],
+            ]
+        );
+
+        assert_eq!(actual, expected)
+    }
+
     #[test]
     fn test_group_by_key() {
         let arr = vec![
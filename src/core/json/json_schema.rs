@@ -226,6 +226,41 @@ impl JsonSchema {
         Valid::succeed(())
     }
 
+    /// Check if `self` has the same shape as `expected` i.e. both are lists,
+    /// objects or scalars and `self` isn't nullable when `expected` is
+    /// required. Unlike [JsonSchema::is_a] the objects aren't required to have
+    /// the same set of keys, only the common keys are compared.
+    pub fn is_compatible_with(&self, expected: &JsonSchema) -> Valid<(), String> {
+        match (self, expected) {
+            (JsonSchema::Any, _) | (_, JsonSchema::Any) => Valid::succeed(()),
+            (JsonSchema::Opt(actual), JsonSchema::Opt(expected)) => {
+                actual.is_compatible_with(expected)
+            }
+            (actual, JsonSchema::Opt(expected)) => actual.is_compatible_with(expected),
+            (JsonSchema::Opt(_), _) => Valid::fail("expected non-nullable output".to_string()),
+            (JsonSchema::Arr(actual), JsonSchema::Arr(expected)) => {
+                actual.is_compatible_with(expected)
+            }
+            (_, JsonSchema::Arr(_)) => Valid::fail(format!("expected array, found {}", self)),
+            (JsonSchema::Arr(_), _) => Valid::fail(format!("expected {}, found array", expected)),
+            (JsonSchema::Obj(actual), JsonSchema::Obj(expected)) => {
+                Valid::from_iter(actual.iter(), |(key, actual)| match expected.get(key) {
+                    Some(expected) => actual.is_compatible_with(expected).trace(key),
+                    None => Valid::succeed(()),
+                })
+                .unit()
+            }
+            (_, JsonSchema::Obj(_)) => Valid::fail(format!("expected object, found {}", self)),
+            (JsonSchema::Obj(_), _) => Valid::fail(format!("expected {}, found object", expected)),
+            (JsonSchema::Enum(_), JsonSchema::Str) | (JsonSchema::Str, JsonSchema::Enum(_)) => {
+                Valid::succeed(())
+            }
+            (JsonSchema::Enum(_), JsonSchema::Enum(_)) => Valid::succeed(()),
+            (actual, expected) if actual == expected => Valid::succeed(()),
+            (actual, expected) => Valid::fail(format!("expected {}, found {}", expected, actual)),
+        }
+    }
+
     pub fn optional(self) -> JsonSchema {
         JsonSchema::Opt(Box::new(self))
     }
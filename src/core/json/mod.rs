@@ -29,6 +29,15 @@ pub fn gather_path_matches<'json, J: JsonLike<'json>>(
             } else {
                 vector = gather_path_matches(value, tail, vector);
             }
+        } else if tail.is_empty() && key.contains('.') {
+            // a dotted last segment, e.g. `meta.userId`, is the path of a key
+            // nested within the item, which is paired with the whole item
+            if let Some(value) = key
+                .split('.')
+                .try_fold(root, |value, key| value.get_key(key))
+            {
+                vector.push((value, root));
+            }
         }
     }
 
@@ -34,6 +34,7 @@ pub mod resource_reader;
 pub mod rest;
 pub mod runtime;
 pub mod scalar;
+pub mod schema_diff;
 pub mod schema_extension;
 mod serde_value_ext;
 pub mod tracing;
@@ -53,7 +54,7 @@ use async_graphql::{Pos, Positioned};
 use async_graphql_value::ConstValue;
 pub use errata::Errata;
 pub use error::{Error, Result};
-use http::Response;
+use http::{BodyStream, Response};
 use ir::model::IoId;
 pub use mustache::Mustache;
 pub use tailcall_macros as macros;
@@ -75,12 +76,35 @@ pub trait HttpIO: Sync + Send + 'static {
         &self,
         request: reqwest::Request,
     ) -> anyhow::Result<Response<hyper::body::Bytes>>;
+
+    /// Executes the request and returns the body as its chunks are received,
+    /// e.g. to process the items of a streamed list as they arrive. By default
+    /// the whole body is received first.
+    async fn execute_stream(
+        &self,
+        request: reqwest::Request,
+    ) -> anyhow::Result<Response<BodyStream>> {
+        Ok(self.execute(request).await?.into_stream())
+    }
+
+    /// Whether the calls to the origin are currently let through, e.g. they
+    /// aren't cut off by an open circuit breaker.
+    fn is_available(&self, _origin: &str) -> bool {
+        true
+    }
 }
 
 #[async_trait::async_trait]
 pub trait FileIO: Send + Sync {
     async fn write<'a>(&'a self, path: &'a str, content: &'a [u8]) -> anyhow::Result<()>;
     async fn read<'a>(&'a self, path: &'a str) -> anyhow::Result<String>;
+
+    /// Returns the paths of the files in the directory and its subdirectories
+    /// relative to it, sorted, or `None` if the path isn't a directory, e.g.
+    /// in the runtimes without a file system.
+    async fn read_dir<'a>(&'a self, _path: &'a str) -> anyhow::Result<Option<Vec<String>>> {
+        Ok(None)
+    }
 }
 
 #[async_trait::async_trait]
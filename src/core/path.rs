@@ -75,6 +75,7 @@ impl<Ctx: ResolverContextLike> EvalContext<'_, Ctx> {
         if path.len() == 1 {
             return match path[0].as_ref() {
                 "value" => Some(ValueString::Value(ctx.path_value(&[] as &[T])?)),
+                "parent" => Some(ValueString::Value(ctx.path_parent(0, &[] as &[T])?)),
                 "args" => Some(ValueString::Value(ctx.path_arg::<&str>(&[])?)),
                 "vars" => Some(ValueString::String(Cow::Owned(
                     json!(ctx.vars()).to_string(),
@@ -86,6 +87,14 @@ impl<Ctx: ResolverContextLike> EvalContext<'_, Ctx> {
         path.split_first()
             .and_then(move |(head, tail)| match head.as_ref() {
                 "value" => Some(ValueString::Value(ctx.path_value(tail)?)),
+                "parent" => {
+                    // every leading `parent` goes one level further up
+                    let level = tail
+                        .iter()
+                        .take_while(|part| part.as_ref() == "parent")
+                        .count();
+                    Some(ValueString::Value(ctx.path_parent(level, &tail[level..])?))
+                }
                 "args" => Some(ValueString::Value(ctx.path_arg(tail)?)),
                 "headers" => Some(ValueString::String(Cow::Borrowed(
                     ctx.header(tail[0].as_ref())?,
@@ -94,6 +103,18 @@ impl<Ctx: ResolverContextLike> EvalContext<'_, Ctx> {
                     ctx.var(tail[0].as_ref())?,
                 ))),
                 "env" => Some(ValueString::String(ctx.env_var(tail[0].as_ref())?)),
+                "request" => match tail[0].as_ref() {
+                    "method" => Some(ValueString::String(Cow::Borrowed(ctx.request_method()?))),
+                    "path" => Some(ValueString::String(Cow::Borrowed(ctx.request_path()?))),
+                    "query" => Some(ValueString::String(
+                        ctx.request_query(tail.get(1)?.as_ref())?,
+                    )),
+                    "clientIp" => Some(ValueString::String(Cow::Owned(
+                        ctx.client_ip()?.to_string(),
+                    ))),
+                    "locale" => Some(ValueString::String(Cow::Borrowed(ctx.request_locale()?))),
+                    _ => None,
+                },
                 _ => None,
             })
     }
@@ -122,7 +143,8 @@ impl<Ctx: ResolverContextLike> PathGraphql for EvalContext<'_, Ctx> {
 
         self.to_raw_value(path).map(|value| match value {
             ValueString::Value(val) => val.to_string(),
-            ValueString::String(val) => format!(r#""{val}""#),
+            // printed as a GraphQL string literal, quoted and escaped
+            ValueString::String(val) => async_graphql::Value::String(val.into_owned()).to_string(),
         })
     }
 }
@@ -192,6 +214,16 @@ mod tests {
             root
         });
 
+        static TEST_PARENTS: Lazy<Vec<Value>> = Lazy::new(|| {
+            let parent = |id: i32| {
+                let mut map = IndexMap::new();
+                map.insert(Name::new("id"), Value::Number(Number::from(id)));
+                Value::Object(map)
+            };
+
+            vec![parent(1), parent(2)]
+        });
+
         static TEST_HEADERS: Lazy<HeaderMap> = Lazy::new(|| {
             let mut map = HeaderMap::new();
 
@@ -212,6 +244,7 @@ mod tests {
             let mut map = BTreeMap::new();
 
             map.insert("existing".to_owned(), "env".to_owned());
+            map.insert("quoted".to_owned(), "say \"hi\"\n\\o/".to_owned());
 
             map
         });
@@ -224,6 +257,10 @@ mod tests {
                 Some(&TEST_VALUES)
             }
 
+            fn parent(&self, level: usize) -> Option<&Value> {
+                TEST_PARENTS.get(level)
+            }
+
             fn args(&self) -> Option<&IndexMap<Name, Value>> {
                 Some(&TEST_ARGS)
             }
@@ -240,7 +277,12 @@ mod tests {
         }
 
         static REQ_CTX: Lazy<RequestContext> = Lazy::new(|| {
-            let mut req_ctx = RequestContext::default().allowed_headers(TEST_HEADERS.clone());
+            let mut req_ctx = RequestContext::default()
+                .allowed_headers(TEST_HEADERS.clone())
+                .client_ip("203.0.113.7".parse().ok())
+                .request_method(Some(http::Method::POST))
+                .request_uri("/graphql?source=web&q=a%20b".parse().ok())
+                .locale(Some("fr-CA".to_string()));
 
             req_ctx.server.vars = TEST_VARS.clone();
             req_ctx.runtime.env = Arc::new(Env::init(TEST_ENV_VARS.clone()));
@@ -373,6 +415,49 @@ mod tests {
             );
             assert_eq!(EVAL_CTX.raw_value(&["env", "x-missing"]), None);
 
+            // parents
+            assert_eq!(
+                EVAL_CTX.raw_value(&["parent", "id"]),
+                Some(ValueString::Value(Cow::Borrowed(&Value::Number(1.into()))))
+            );
+            assert_eq!(
+                EVAL_CTX.raw_value(&["parent", "parent", "id"]),
+                Some(ValueString::Value(Cow::Borrowed(&Value::Number(2.into()))))
+            );
+            assert_eq!(
+                EVAL_CTX.raw_value(&["parent", "parent", "parent", "id"]),
+                None
+            );
+
+            // request
+            assert_eq!(
+                EVAL_CTX.raw_value(&["request", "clientIp"]),
+                Some(ValueString::String(Cow::Borrowed("203.0.113.7")))
+            );
+            assert_eq!(
+                EVAL_CTX.raw_value(&["request", "method"]),
+                Some(ValueString::String(Cow::Borrowed("POST")))
+            );
+            assert_eq!(
+                EVAL_CTX.raw_value(&["request", "path"]),
+                Some(ValueString::String(Cow::Borrowed("/graphql")))
+            );
+            assert_eq!(
+                EVAL_CTX.raw_value(&["request", "query", "source"]),
+                Some(ValueString::String(Cow::Borrowed("web")))
+            );
+            assert_eq!(
+                EVAL_CTX.raw_value(&["request", "query", "q"]),
+                Some(ValueString::String(Cow::Borrowed("a b")))
+            );
+            assert_eq!(
+                EVAL_CTX.raw_value(&["request", "locale"]),
+                Some(ValueString::String(Cow::Borrowed("fr-CA")))
+            );
+            assert_eq!(EVAL_CTX.raw_value(&["request", "query", "missing"]), None);
+            assert_eq!(EVAL_CTX.raw_value(&["request", "query"]), None);
+            assert_eq!(EVAL_CTX.raw_value(&["request", "missing"]), None);
+
             // other value types
             assert_eq!(EVAL_CTX.raw_value(&["foo", "key"]), None);
             assert_eq!(EVAL_CTX.raw_value(&["bar", "key"]), None);
@@ -511,6 +596,12 @@ mod tests {
             );
             assert_eq!(EVAL_CTX.path_graphql(&["env", "x-missing"]), None);
 
+            // strings are escaped
+            assert_eq!(
+                EVAL_CTX.path_graphql(&["env", "quoted"]),
+                Some(r#""say \"hi\"\n\\o/""#.to_owned())
+            );
+
             // other value types
             assert_eq!(EVAL_CTX.path_graphql(&["foo", "key"]), None);
             assert_eq!(EVAL_CTX.path_graphql(&["bar", "key"]), None);
@@ -72,12 +72,16 @@ impl ProtoReader {
         Ok(resolved_protos)
     }
 
-    /// Reads a proto file from a path
+    /// Reads a proto file from a path, or all the proto files of a directory
     pub async fn read<T: AsRef<str>>(
         &self,
         path: T,
         proto_paths: Option<&[String]>,
     ) -> anyhow::Result<ProtoMetadata> {
+        if let Some(files) = self.runtime.file.read_dir(path.as_ref()).await? {
+            return self.read_dir(path.as_ref(), files, proto_paths).await;
+        }
+
         let file_read = self.read_proto(path.as_ref(), None, None).await?;
         Self::check_package(&file_read)?;
 
@@ -95,6 +99,51 @@ impl ProtoReader {
         Ok(metadata)
     }
 
+    /// Reads all the proto files of the directory and its subdirectories into a
+    /// single descriptor set. The files are named by their path relative to
+    /// the directory, so that the imports between them, which are looked up
+    /// in the directory first, resolve to the same files.
+    async fn read_dir(
+        &self,
+        dir: &str,
+        files: Vec<String>,
+        proto_paths: Option<&[String]>,
+    ) -> anyhow::Result<ProtoMetadata> {
+        let root = Path::new(dir);
+        let proto_paths = std::iter::once(dir.to_string())
+            .chain(proto_paths.unwrap_or_default().iter().cloned())
+            .collect::<Vec<_>>();
+        let lookup_paths = proto_paths.iter().map(PathBuf::from).collect::<Vec<_>>();
+
+        let mut descriptors: Vec<FileDescriptorProto> = vec![];
+        for file in files.iter().filter(|file| file.ends_with(".proto")) {
+            let file_read = self
+                .read_proto(file, Some(root), Some(lookup_paths.as_slice()))
+                .await?;
+            Self::check_package(&file_read)?;
+
+            let parent = root.join(file);
+            let resolved = self
+                .file_resolve(file_read, parent.parent(), Some(proto_paths.as_slice()))
+                .await?;
+
+            for descriptor in resolved {
+                if !descriptors.iter().any(|d| d.name() == descriptor.name()) {
+                    descriptors.push(descriptor);
+                }
+            }
+        }
+
+        if descriptors.is_empty() {
+            anyhow::bail!("No proto files found in {}", dir);
+        }
+
+        Ok(ProtoMetadata {
+            descriptor_set: FileDescriptorSet { file: descriptors },
+            path: dir.to_string(),
+        })
+    }
+
     /// Used as a helper file to resolve dependencies proto files
     async fn resolve_dependencies<F>(
         &self,
@@ -152,6 +201,7 @@ impl ProtoReader {
             async move {
                 this.read_proto(import, parent_path.as_deref(), proto_paths.as_deref())
                     .await
+                    .with_context(|| format!("Unable to resolve import {}", import))
             }
             .boxed()
         })
@@ -229,6 +279,7 @@ mod test_proto_config {
 
     use anyhow::Result;
     use pretty_assertions::assert_eq;
+    use prost_reflect::DescriptorPool;
     use tailcall_fixtures::protobuf;
 
     use crate::core::proto_reader::ProtoReader;
@@ -276,6 +327,91 @@ mod test_proto_config {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_read_dir() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir(dir.path().join("types"))?;
+        std::fs::write(
+            dir.path().join("news.proto"),
+            r#"
+            syntax = "proto3";
+            package news;
+            import "types/news_type.proto";
+            message NewsList { repeated News news = 1; }
+            "#,
+        )?;
+        std::fs::write(
+            dir.path().join("types/news_type.proto"),
+            r#"
+            syntax = "proto3";
+            package news;
+            message News { int32 id = 1; }
+            "#,
+        )?;
+
+        let runtime = crate::core::runtime::test::init(None);
+        let reader = ProtoReader::init(ResourceReader::<Cached>::cached(runtime.clone()), runtime);
+        let metadata = reader.read(dir.path().to_string_lossy(), None).await?;
+
+        let names = metadata
+            .descriptor_set
+            .file
+            .iter()
+            .map(|file| file.name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["types/news_type.proto", "news.proto"]);
+
+        let pool = DescriptorPool::from_file_descriptor_set(metadata.descriptor_set)?;
+        assert!(pool.get_message_by_name("news.News").is_some());
+        assert!(pool.get_message_by_name("news.NewsList").is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_proto_paths() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir_all(dir.path().join("include/common"))?;
+        std::fs::create_dir(dir.path().join("protos"))?;
+        std::fs::write(
+            dir.path().join("include/common/types.proto"),
+            r#"
+            syntax = "proto3";
+            package common;
+            message Id { int32 id = 1; }
+            "#,
+        )?;
+        let news = dir.path().join("protos/news.proto");
+        std::fs::write(
+            &news,
+            r#"
+            syntax = "proto3";
+            package news;
+            import "common/types.proto";
+            message News { common.Id id = 1; }
+            "#,
+        )?;
+
+        let runtime = crate::core::runtime::test::init(None);
+        let reader = ProtoReader::init(ResourceReader::<Cached>::cached(runtime.clone()), runtime);
+        let news = news.to_string_lossy();
+
+        let include = dir.path().join("include").to_string_lossy().to_string();
+        let metadata = reader.read(&news, Some(&[include])).await?;
+        let pool = DescriptorPool::from_file_descriptor_set(metadata.descriptor_set)?;
+        assert!(pool.get_message_by_name("news.News").is_some());
+        assert!(pool.get_message_by_name("common.Id").is_some());
+
+        // without the include path the import can't be found next to the file
+        let error = reader.read(&news, None).await.err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Unable to resolve import common/types.proto"
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_proto_no_pkg() -> Result<()> {
         let runtime = crate::core::runtime::test::init(None);
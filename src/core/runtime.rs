@@ -3,6 +3,7 @@ use std::sync::Arc;
 use async_graphql_value::ConstValue;
 
 use super::ir::model::IoId;
+use crate::core::http::BodyTransformer;
 use crate::core::schema_extension::SchemaExtension;
 use crate::core::worker::{Command, Event};
 use crate::core::{Cache, EnvIO, FileIO, HttpIO, WorkerIO};
@@ -28,6 +29,9 @@ pub struct TargetRuntime {
     /// A list of extensions that can be used to extend the runtime's
     /// functionality or integrate additional features.
     pub extensions: Arc<Vec<SchemaExtension>>,
+    /// Transformers applied to the serialized body of the `@http` requests
+    /// before they're sent to the upstream.
+    pub body_transformers: Arc<Vec<Arc<dyn BodyTransformer>>>,
     /// Worker middleware for handling HTTP requests.
     pub cmd_worker: Option<Arc<dyn WorkerIO<Event, Command>>>,
     /// Worker middleware for resolving data.
@@ -38,6 +42,10 @@ impl TargetRuntime {
     pub fn add_extensions(&mut self, extensions: Vec<SchemaExtension>) {
         self.extensions = Arc::new(extensions);
     }
+
+    pub fn add_body_transformer(&mut self, transformer: impl BodyTransformer) {
+        Arc::make_mut(&mut self.body_transformers).push(Arc::new(transformer));
+    }
 }
 
 #[cfg(test)]
@@ -57,6 +65,7 @@ pub mod test {
     use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
     use crate::cli::javascript::init_worker_io;
+    use crate::cli::runtime::NativeFileIO;
     use crate::core::blueprint::Upstream;
     use crate::core::cache::InMemoryCache;
     use crate::core::http::Response;
@@ -155,6 +164,10 @@ pub mod test {
                 .map_err(|e| anyhow!("{}", e))?;
             Ok(String::from_utf8(buffer)?)
         }
+
+        async fn read_dir<'a>(&'a self, path: &'a str) -> anyhow::Result<Option<Vec<String>>> {
+            NativeFileIO::init().read_dir(path).await
+        }
     }
 
     #[derive(Clone)]
@@ -188,6 +201,7 @@ pub mod test {
             file: Arc::new(file),
             cache: Arc::new(InMemoryCache::default()),
             extensions: Arc::new(vec![]),
+            body_transformers: Arc::new(vec![]),
             cmd_worker: match &script {
                 Some(script) => Some(init_worker_io::<Event, Command>(script.to_owned())),
                 None => None,
@@ -1,12 +1,18 @@
-use std::collections::HashMap;
-use std::fmt::Debug;
-
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Debug, Display};
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use base64::prelude::{BASE64_STANDARD, BASE64_URL_SAFE};
+use base64::Engine;
 use lazy_static::lazy_static;
 use schemars::schema::{InstanceType, Schema, SchemaObject};
 use strum::IntoEnumIterator;
 use tailcall_macros::{gen_doc, Doc};
 
 use crate::core::json::JsonLike;
+use crate::core::merge_right::MergeRight;
 
 const PREDEFINED_SCALARS: &[&str] = &["Boolean", "Float", "ID", "Int", "String"];
 
@@ -15,8 +21,82 @@ lazy_static! {
         Scalar::iter().map(|v| (v.name(), v)).collect();
 }
 
+/// Scalar supplied by an embedder on top of the built-in ones, e.g. a
+/// company-specific `AccountId`.
+pub trait CustomScalar: Debug + Send + Sync {
+    /// Name of the scalar as referenced from the config.
+    fn name(&self) -> &str;
+
+    /// Returns `true` if the value is a valid instance of the scalar.
+    fn validate(&self, value: &serde_json::Value) -> bool;
+}
+
+/// The scalars an embedder supplies for a config on top of the built-in ones.
+/// It's kept in the extensions of the config module, so every config only
+/// sees the scalars registered for it.
+#[derive(Clone, Debug, Default)]
+pub struct ScalarRegistry {
+    scalars: BTreeMap<String, Arc<dyn CustomScalar>>,
+}
+
+impl ScalarRegistry {
+    /// Registers a scalar, failing if the name is already taken by a built-in
+    /// or a previously registered scalar.
+    pub fn register(&mut self, scalar: Arc<dyn CustomScalar>) -> anyhow::Result<()> {
+        let name = scalar.name().to_string();
+        if Scalar::is_predefined(&name) {
+            anyhow::bail!("Scalar `{}` is already defined as a built-in scalar", name);
+        }
+        if self.scalars.contains_key(&name) {
+            anyhow::bail!("Scalar `{}` is already registered", name);
+        }
+        self.scalars.insert(name, scalar);
+
+        Ok(())
+    }
+
+    pub fn find(&self, name: &str) -> Option<Scalar> {
+        self.scalars.get(name).cloned().map(Scalar::Custom)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.scalars.keys()
+    }
+}
+
+impl MergeRight for ScalarRegistry {
+    fn merge_right(mut self, other: Self) -> Self {
+        self.scalars.extend(other.scalars);
+        self
+    }
+}
+
+/// Custom scalar declared in the config whose values are strings matching a
+/// regular expression.
+#[derive(Debug)]
+pub struct RegexScalar {
+    name: String,
+    regex: regex::Regex,
+}
+
+impl RegexScalar {
+    pub fn try_new(name: &str, pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self { name: name.to_string(), regex: regex::Regex::new(pattern)? })
+    }
+}
+
+impl CustomScalar for RegexScalar {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn validate(&self, value: &serde_json::Value) -> bool {
+        value.as_str().is_some_and(|s| self.regex.is_match(s))
+    }
+}
+
 #[derive(
-    schemars::JsonSchema, Debug, Clone, strum_macros::Display, strum_macros::EnumIter, Doc,
+    schemars::JsonSchema, Debug, Clone, strum_macros::IntoStaticStr, strum_macros::EnumIter, Doc,
 )]
 pub enum Scalar {
     /// Empty scalar type represents an empty value.
@@ -70,9 +150,26 @@ pub enum Scalar {
     /// Field whose value is a 128-bit unsigned integer.
     #[gen_doc(ty = "Integer")]
     UInt128,
-    /// Field whose value is a sequence of bytes.
+    /// Field whose value is a standard or URL-safe base64 encoded string.
     #[gen_doc(ty = "String")]
     Bytes,
+    /// Field whose value is an IPv4 or IPv6 address as specified in RFC 791 (https://datatracker.ietf.org/doc/html/rfc791) and RFC 4291 (https://datatracker.ietf.org/doc/html/rfc4291).
+    #[gen_doc(ty = "String")]
+    IPAddress,
+    /// Field whose value is validated by a scalar registered by the embedder.
+    #[gen_doc(ty = "String")]
+    #[schemars(skip)]
+    #[strum(disabled)]
+    Custom(Arc<dyn CustomScalar>),
+}
+
+impl Display for Scalar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Scalar::Custom(scalar) => f.write_str(scalar.name()),
+            scalar => f.write_str(<&'static str>::from(scalar)),
+        }
+    }
 }
 
 fn eval_str<'a, Value: JsonLike<'a>, F: Fn(&str) -> bool>(val: &'a Value, fxn: F) -> bool {
@@ -110,7 +207,7 @@ impl Scalar {
         if PREDEFINED_SCALARS.iter().any(|v| type_name.eq(*v)) {
             true
         } else {
-            CUSTOM_SCALARS.get(type_name).is_some()
+            CUSTOM_SCALARS.contains_key(type_name)
         }
     }
 
@@ -127,7 +224,10 @@ impl Scalar {
                 eval_str(value, |s| chrono::DateTime::parse_from_rfc3339(s).is_ok())
             }
             Scalar::Url => eval_str(value, |s| url::Url::parse(s).is_ok()),
-            Scalar::Bytes => value.as_str().is_some(),
+            Scalar::Bytes => eval_str(value, |s| {
+                BASE64_STANDARD.decode(s).is_ok() || BASE64_URL_SAFE.decode(s).is_ok()
+            }),
+            Scalar::IPAddress => eval_str(value, |s| IpAddr::from_str(s).is_ok()),
 
             Scalar::Int64 => eval_str(value, |s| s.parse::<i64>().is_ok()),
             Scalar::UInt64 => eval_str(value, |s| s.parse::<u64>().is_ok()),
@@ -141,10 +241,13 @@ impl Scalar {
             Scalar::UInt8 => eval_unsigned(value, u8::try_from),
             Scalar::UInt16 => eval_unsigned(value, u16::try_from),
             Scalar::UInt32 => eval_unsigned(value, u32::try_from),
+            Scalar::Custom(scalar) => {
+                scalar.validate(&<serde_json::Value as JsonLike>::clone_from(value))
+            }
         }
     }
-    pub fn find(name: &str) -> Option<&Scalar> {
-        CUSTOM_SCALARS.get(name)
+    pub fn find(name: &str) -> Option<Scalar> {
+        CUSTOM_SCALARS.get(name).cloned()
     }
     pub fn name(&self) -> String {
         self.to_string()
@@ -177,10 +280,15 @@ impl Scalar {
 
 #[cfg(test)]
 mod test {
+    use std::sync::Arc;
+
     use async_graphql_value::ConstValue;
     use schemars::schema::Schema;
+    use tailcall_valid::Validator;
 
-    use crate::core::scalar::{Scalar, CUSTOM_SCALARS};
+    use crate::core::blueprint::{Blueprint, Index};
+    use crate::core::config::{Config, ConfigModule};
+    use crate::core::scalar::{CustomScalar, Scalar, ScalarRegistry, CUSTOM_SCALARS};
 
     /// generates test asserts for valid scalar inputs
     #[macro_export]
@@ -219,12 +327,55 @@ mod test {
 
         test_scalar_valid! {
             Scalar::Bytes,
-            ConstValue::String("\0\0".to_string())
+            ConstValue::String("".to_string()),
+            ConstValue::String("aGVsbG8gd29ybGQ=".to_string()),
+            ConstValue::String("+/+/".to_string()),
+            ConstValue::String("-_-_".to_string())
         }
         test_scalar_invalid! {
             Scalar::Bytes,
             ConstValue::Null,
-            ConstValue::Number(Number::from_f64(1.25).unwrap())
+            ConstValue::Number(Number::from_f64(1.25).unwrap()),
+            ConstValue::String("\0\0".to_string()),
+            ConstValue::String("aGVsbG8gd29ybGQ".to_string()),
+            ConstValue::String("not base64!".to_string())
+        }
+    }
+
+    mod ip_address {
+        use super::{ConstValue, Scalar};
+
+        test_scalar_valid! {
+            Scalar::IPAddress,
+            ConstValue::String("192.168.0.1".to_string()),
+            ConstValue::String("2001:db8::1".to_string()),
+            ConstValue::String("::".to_string())
+        }
+
+        test_scalar_invalid! {
+            Scalar::IPAddress,
+            ConstValue::Null,
+            ConstValue::String("256.0.0.1".to_string()),
+            ConstValue::String("192.168.0.0/24".to_string()),
+            ConstValue::String("localhost".to_string())
+        }
+    }
+
+    mod regex {
+        use std::sync::Arc;
+
+        use super::{ConstValue, Scalar};
+        use crate::core::scalar::RegexScalar;
+
+        test_scalar_valid! {
+            Scalar::Custom(Arc::new(RegexScalar::try_new("Sku", "^SKU-[0-9]+$").unwrap())),
+            ConstValue::String("SKU-42".to_string())
+        }
+
+        test_scalar_invalid! {
+            Scalar::Custom(Arc::new(RegexScalar::try_new("Sku", "^SKU-[0-9]+$").unwrap())),
+            ConstValue::Null,
+            ConstValue::String("ABC-42".to_string())
         }
     }
 
@@ -541,4 +692,84 @@ mod test {
             assert_eq!(k.clone(), get_name(v.schema()));
         }
     }
+
+    #[derive(Debug)]
+    struct AccountId;
+
+    impl CustomScalar for AccountId {
+        fn name(&self) -> &str {
+            "AccountId"
+        }
+
+        fn validate(&self, value: &serde_json::Value) -> bool {
+            value.as_str().is_some_and(|s| s.starts_with("acc_"))
+        }
+    }
+
+    #[test]
+    fn test_register_custom_scalar() {
+        let mut registry = ScalarRegistry::default();
+        registry.register(Arc::new(AccountId)).unwrap();
+
+        assert!(!Scalar::is_predefined("AccountId"));
+        assert!(ScalarRegistry::default().find("AccountId").is_none());
+
+        let scalar = registry.find("AccountId").unwrap();
+        assert_eq!(scalar.to_string(), "AccountId");
+        assert!(scalar.validate(&ConstValue::String("acc_42".to_string())));
+        assert!(!scalar.validate(&ConstValue::String("42".to_string())));
+        assert!(!scalar.validate(&ConstValue::Null));
+
+        let error = registry.register(Arc::new(AccountId)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Scalar `AccountId` is already registered"
+        );
+    }
+
+    #[test]
+    fn test_scalar_registry_per_config_module() {
+        let sdl = r#"
+            schema { query: Query }
+            type Query { account: AccountId @expr(body: "acc_1") }
+        "#;
+        let config = Config::from_sdl(sdl).to_result().unwrap();
+
+        let mut registry = ScalarRegistry::default();
+        registry.register(Arc::new(AccountId)).unwrap();
+        let module = ConfigModule::from(config.clone())
+            .with_scalars(registry)
+            .unwrap();
+        let blueprint = Blueprint::try_from(&module).unwrap();
+        let scalar = Index::from(&blueprint).get_scalar("AccountId").unwrap();
+        assert!(scalar.validate(&ConstValue::String("acc_42".to_string())));
+        assert!(!scalar.validate(&ConstValue::String("42".to_string())));
+
+        // another module doesn't see the scalars of the registry
+        assert!(Blueprint::try_from(&ConfigModule::from(config)).is_err());
+    }
+
+    #[derive(Debug)]
+    struct CustomEmail;
+
+    impl CustomScalar for CustomEmail {
+        fn name(&self) -> &str {
+            "Email"
+        }
+
+        fn validate(&self, _: &serde_json::Value) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_register_builtin_collision() {
+        let error = ScalarRegistry::default()
+            .register(Arc::new(CustomEmail))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Scalar `Email` is already defined as a built-in scalar"
+        );
+    }
 }
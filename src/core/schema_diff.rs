@@ -0,0 +1,486 @@
+//! Structural diff between the public GraphQL schemas of two configs.
+//!
+//! The schemas are compared on their printed SDL, so the diff reflects exactly
+//! what the clients can see after the config has been compiled.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use anyhow::{anyhow, Result};
+use async_graphql::parser::types::{
+    FieldDefinition, InputValueDefinition, ServiceDocument, TypeDefinition, TypeKind,
+    TypeSystemDefinition,
+};
+
+use crate::core::blueprint::Blueprint;
+use crate::core::config::ConfigModule;
+use crate::core::print_schema::print_schema;
+use crate::core::Errata;
+
+/// A single change between two schemas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    TypeAdded {
+        type_name: String,
+    },
+    TypeRemoved {
+        type_name: String,
+    },
+    TypeKindChanged {
+        type_name: String,
+    },
+    FieldAdded {
+        type_name: String,
+        field: String,
+    },
+    FieldRemoved {
+        type_name: String,
+        field: String,
+    },
+    FieldTypeChanged {
+        type_name: String,
+        field: String,
+        from: String,
+        to: String,
+    },
+    ArgumentAdded {
+        type_name: String,
+        field: String,
+        arg: String,
+        required: bool,
+    },
+    ArgumentRemoved {
+        type_name: String,
+        field: String,
+        arg: String,
+    },
+    ArgumentTypeChanged {
+        type_name: String,
+        field: String,
+        arg: String,
+        from: String,
+        to: String,
+    },
+    InputFieldAdded {
+        type_name: String,
+        field: String,
+        required: bool,
+    },
+    InputFieldRemoved {
+        type_name: String,
+        field: String,
+    },
+    InputFieldTypeChanged {
+        type_name: String,
+        field: String,
+        from: String,
+        to: String,
+    },
+    EnumValueAdded {
+        type_name: String,
+        value: String,
+    },
+    EnumValueRemoved {
+        type_name: String,
+        value: String,
+    },
+    UnionMemberAdded {
+        type_name: String,
+        member: String,
+    },
+    UnionMemberRemoved {
+        type_name: String,
+        member: String,
+    },
+}
+
+impl Change {
+    /// Checks if the change can break the queries of existing clients.
+    pub fn is_breaking(&self) -> bool {
+        match self {
+            Change::TypeAdded { .. }
+            | Change::FieldAdded { .. }
+            | Change::EnumValueAdded { .. }
+            | Change::UnionMemberAdded { .. } => false,
+            Change::ArgumentAdded { required, .. } | Change::InputFieldAdded { required, .. } => {
+                *required
+            }
+            _ => true,
+        }
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::TypeAdded { type_name } => write!(f, "Type `{type_name}` was added"),
+            Change::TypeRemoved { type_name } => write!(f, "Type `{type_name}` was removed"),
+            Change::TypeKindChanged { type_name } => {
+                write!(f, "Type `{type_name}` changed its kind")
+            }
+            Change::FieldAdded { type_name, field } => {
+                write!(f, "Field `{type_name}.{field}` was added")
+            }
+            Change::FieldRemoved { type_name, field } => {
+                write!(f, "Field `{type_name}.{field}` was removed")
+            }
+            Change::FieldTypeChanged { type_name, field, from, to } => {
+                write!(f, "Field `{type_name}.{field}` changed type from `{from}` to `{to}`")
+            }
+            Change::ArgumentAdded { type_name, field, arg, .. } => {
+                write!(f, "Argument `{arg}` was added to field `{type_name}.{field}`")
+            }
+            Change::ArgumentRemoved { type_name, field, arg } => {
+                write!(f, "Argument `{arg}` was removed from field `{type_name}.{field}`")
+            }
+            Change::ArgumentTypeChanged { type_name, field, arg, from, to } => write!(
+                f,
+                "Argument `{arg}` of field `{type_name}.{field}` changed type from `{from}` to `{to}`"
+            ),
+            Change::InputFieldAdded { type_name, field, .. } => {
+                write!(f, "Input field `{type_name}.{field}` was added")
+            }
+            Change::InputFieldRemoved { type_name, field } => {
+                write!(f, "Input field `{type_name}.{field}` was removed")
+            }
+            Change::InputFieldTypeChanged { type_name, field, from, to } => write!(
+                f,
+                "Input field `{type_name}.{field}` changed type from `{from}` to `{to}`"
+            ),
+            Change::EnumValueAdded { type_name, value } => {
+                write!(f, "Enum value `{value}` was added to `{type_name}`")
+            }
+            Change::EnumValueRemoved { type_name, value } => {
+                write!(f, "Enum value `{value}` was removed from `{type_name}`")
+            }
+            Change::UnionMemberAdded { type_name, member } => {
+                write!(f, "Member `{member}` was added to union `{type_name}`")
+            }
+            Change::UnionMemberRemoved { type_name, member } => {
+                write!(f, "Member `{member}` was removed from union `{type_name}`")
+            }
+        }
+    }
+}
+
+/// Compiles both configs and returns the changes between their public
+/// schemas.
+pub fn diff(old: &ConfigModule, new: &ConfigModule) -> Result<Vec<Change>> {
+    let old = types(old)?;
+    let new = types(new)?;
+    let mut changes = Vec::new();
+
+    for (type_name, old_type) in old.iter() {
+        match new.get(type_name) {
+            Some(new_type) => diff_type(type_name, old_type, new_type, &mut changes),
+            None => changes.push(Change::TypeRemoved { type_name: type_name.clone() }),
+        }
+    }
+
+    for type_name in new.keys().filter(|name| !old.contains_key(*name)) {
+        changes.push(Change::TypeAdded { type_name: type_name.clone() });
+    }
+
+    Ok(changes)
+}
+
+fn types(config_module: &ConfigModule) -> Result<BTreeMap<String, TypeDefinition>> {
+    let blueprint = Blueprint::try_from(config_module).map_err(Errata::from)?;
+    let sdl = print_schema(blueprint.to_schema());
+    let document: ServiceDocument = async_graphql::parser::parse_schema(sdl)?;
+
+    Ok(document
+        .definitions
+        .into_iter()
+        .filter_map(|definition| match definition {
+            TypeSystemDefinition::Type(type_) => {
+                Some((type_.node.name.node.to_string(), type_.node))
+            }
+            _ => None,
+        })
+        .collect())
+}
+
+fn diff_type(
+    type_name: &str,
+    old: &TypeDefinition,
+    new: &TypeDefinition,
+    changes: &mut Vec<Change>,
+) {
+    match (&old.kind, &new.kind) {
+        (TypeKind::Object(old), TypeKind::Object(new)) => {
+            diff_fields(type_name, &old.fields, &new.fields, changes)
+        }
+        (TypeKind::Interface(old), TypeKind::Interface(new)) => {
+            diff_fields(type_name, &old.fields, &new.fields, changes)
+        }
+        (TypeKind::InputObject(old), TypeKind::InputObject(new)) => {
+            let old = by_name(old.fields.iter().map(|field| &field.node), |f| &f.name.node);
+            let new = by_name(new.fields.iter().map(|field| &field.node), |f| &f.name.node);
+
+            for (name, old_field) in old.iter() {
+                let field = name.to_string();
+                match new.get(name) {
+                    Some(new_field) if old_field.ty.node != new_field.ty.node => {
+                        changes.push(Change::InputFieldTypeChanged {
+                            type_name: type_name.to_string(),
+                            field,
+                            from: old_field.ty.node.to_string(),
+                            to: new_field.ty.node.to_string(),
+                        })
+                    }
+                    Some(_) => {}
+                    None => changes.push(Change::InputFieldRemoved {
+                        type_name: type_name.to_string(),
+                        field,
+                    }),
+                }
+            }
+
+            for (name, new_field) in new.iter().filter(|(name, _)| !old.contains_key(*name)) {
+                changes.push(Change::InputFieldAdded {
+                    type_name: type_name.to_string(),
+                    field: name.to_string(),
+                    required: is_required(new_field),
+                });
+            }
+        }
+        (TypeKind::Enum(old), TypeKind::Enum(new)) => {
+            let old = old
+                .values
+                .iter()
+                .map(|value| value.node.value.node.to_string());
+            let new = new
+                .values
+                .iter()
+                .map(|value| value.node.value.node.to_string());
+            let (removed, added) = diff_names(old, new);
+
+            changes.extend(
+                removed.into_iter().map(|value| Change::EnumValueRemoved {
+                    type_name: type_name.to_string(),
+                    value,
+                }),
+            );
+            changes.extend(
+                added.into_iter().map(|value| Change::EnumValueAdded {
+                    type_name: type_name.to_string(),
+                    value,
+                }),
+            );
+        }
+        (TypeKind::Union(old), TypeKind::Union(new)) => {
+            let old = old.members.iter().map(|member| member.node.to_string());
+            let new = new.members.iter().map(|member| member.node.to_string());
+            let (removed, added) = diff_names(old, new);
+
+            changes.extend(
+                removed
+                    .into_iter()
+                    .map(|member| Change::UnionMemberRemoved {
+                        type_name: type_name.to_string(),
+                        member,
+                    }),
+            );
+            changes.extend(added.into_iter().map(|member| Change::UnionMemberAdded {
+                type_name: type_name.to_string(),
+                member,
+            }));
+        }
+        (TypeKind::Scalar, TypeKind::Scalar) => {}
+        _ => changes.push(Change::TypeKindChanged { type_name: type_name.to_string() }),
+    }
+}
+
+fn diff_fields(
+    type_name: &str,
+    old: &[async_graphql::Positioned<FieldDefinition>],
+    new: &[async_graphql::Positioned<FieldDefinition>],
+    changes: &mut Vec<Change>,
+) {
+    let old = by_name(old.iter().map(|field| &field.node), |f| &f.name.node);
+    let new = by_name(new.iter().map(|field| &field.node), |f| &f.name.node);
+
+    for (name, old_field) in old.iter() {
+        let Some(new_field) = new.get(name) else {
+            changes.push(Change::FieldRemoved {
+                type_name: type_name.to_string(),
+                field: name.to_string(),
+            });
+            continue;
+        };
+
+        if old_field.ty.node != new_field.ty.node {
+            changes.push(Change::FieldTypeChanged {
+                type_name: type_name.to_string(),
+                field: name.to_string(),
+                from: old_field.ty.node.to_string(),
+                to: new_field.ty.node.to_string(),
+            });
+        }
+
+        let old_args = by_name(old_field.arguments.iter().map(|arg| &arg.node), |a| {
+            &a.name.node
+        });
+        let new_args = by_name(new_field.arguments.iter().map(|arg| &arg.node), |a| {
+            &a.name.node
+        });
+
+        for (arg, old_arg) in old_args.iter() {
+            match new_args.get(arg) {
+                Some(new_arg) if old_arg.ty.node != new_arg.ty.node => {
+                    changes.push(Change::ArgumentTypeChanged {
+                        type_name: type_name.to_string(),
+                        field: name.to_string(),
+                        arg: arg.to_string(),
+                        from: old_arg.ty.node.to_string(),
+                        to: new_arg.ty.node.to_string(),
+                    })
+                }
+                Some(_) => {}
+                None => changes.push(Change::ArgumentRemoved {
+                    type_name: type_name.to_string(),
+                    field: name.to_string(),
+                    arg: arg.to_string(),
+                }),
+            }
+        }
+
+        for (arg, new_arg) in new_args
+            .iter()
+            .filter(|(arg, _)| !old_args.contains_key(*arg))
+        {
+            changes.push(Change::ArgumentAdded {
+                type_name: type_name.to_string(),
+                field: name.to_string(),
+                arg: arg.to_string(),
+                required: is_required(new_arg),
+            });
+        }
+    }
+
+    for name in new.keys().filter(|name| !old.contains_key(*name)) {
+        changes
+            .push(Change::FieldAdded { type_name: type_name.to_string(), field: name.to_string() });
+    }
+}
+
+fn by_name<'a, T>(
+    items: impl Iterator<Item = &'a T>,
+    name: impl Fn(&T) -> &async_graphql::Name,
+) -> BTreeMap<&'a str, &'a T>
+where
+    T: 'a,
+{
+    items.map(|item| (name(item).as_str(), item)).collect()
+}
+
+/// Returns the removed and the added names.
+fn diff_names(
+    old: impl Iterator<Item = String>,
+    new: impl Iterator<Item = String>,
+) -> (Vec<String>, Vec<String>) {
+    let old = old.collect::<Vec<_>>();
+    let new = new.collect::<Vec<_>>();
+    let removed = old
+        .iter()
+        .filter(|name| !new.contains(name))
+        .cloned()
+        .collect();
+    let added = new
+        .iter()
+        .filter(|name| !old.contains(name))
+        .cloned()
+        .collect();
+
+    (removed, added)
+}
+
+fn is_required(value: &InputValueDefinition) -> bool {
+    !value.ty.node.nullable && value.default_value.is_none()
+}
+
+/// Fails if any of the changes is breaking.
+pub fn ensure_non_breaking(changes: &[Change]) -> Result<()> {
+    let breaking = changes
+        .iter()
+        .filter(|change| change.is_breaking())
+        .map(|change| change.to_string())
+        .collect::<Vec<_>>();
+
+    if breaking.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!("Breaking changes found:\n{}", breaking.join("\n")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tailcall_valid::Validator;
+
+    use super::*;
+    use crate::core::config::Config;
+
+    fn config_module(sdl: &str) -> ConfigModule {
+        ConfigModule::from(Config::from_sdl(sdl).to_result().unwrap())
+    }
+
+    const BASE: &str = r#"
+        schema {
+          query: Query
+        }
+
+        type Query {
+          posts: [Post] @http(url: "http://jsonplaceholder.typicode.com/posts")
+        }
+
+        type Post {
+          id: Int!
+          title: String
+        }
+    "#;
+
+    #[test]
+    fn test_added_field_is_not_breaking() {
+        let new = BASE.replace("title: String", "title: String\n          body: String");
+
+        let actual = diff(&config_module(BASE), &config_module(&new)).unwrap();
+        let expected =
+            vec![Change::FieldAdded { type_name: "Post".to_string(), field: "body".to_string() }];
+
+        assert_eq!(actual, expected);
+        assert!(ensure_non_breaking(&actual).is_ok());
+    }
+
+    #[test]
+    fn test_removed_field_is_breaking() {
+        let new = BASE.replace("title: String", "");
+
+        let actual = diff(&config_module(BASE), &config_module(&new)).unwrap();
+        let expected = vec![Change::FieldRemoved {
+            type_name: "Post".to_string(),
+            field: "title".to_string(),
+        }];
+
+        assert_eq!(actual, expected);
+        assert!(actual[0].is_breaking());
+        assert!(ensure_non_breaking(&actual).is_err());
+    }
+
+    #[test]
+    fn test_non_null_field_is_breaking() {
+        let new = BASE.replace("title: String", "title: String!");
+
+        let actual = diff(&config_module(BASE), &config_module(&new)).unwrap();
+        let expected = vec![Change::FieldTypeChanged {
+            type_name: "Post".to_string(),
+            field: "title".to_string(),
+            from: "String".to_string(),
+            to: "String!".to_string(),
+        }];
+
+        assert_eq!(actual, expected);
+        assert!(actual[0].is_breaking());
+    }
+}
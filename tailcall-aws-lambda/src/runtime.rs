@@ -60,6 +60,7 @@ pub fn init_runtime() -> TargetRuntime {
         env: init_env(),
         cache: init_cache(),
         extensions: Arc::new(vec![]),
+        body_transformers: Arc::new(vec![]),
         cmd_worker: None,
         worker: None,
     }
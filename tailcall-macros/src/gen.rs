@@ -61,7 +61,7 @@ pub fn doc(input: TokenStream) -> TokenStream {
             .join("\n");
 
         quote! {
-            #name::#variant_name => #docs.to_string(),
+            #name::#variant_name { .. } => #docs.to_string(),
         }
     });
 
@@ -77,7 +77,7 @@ pub fn doc(input: TokenStream) -> TokenStream {
         };
 
         quote! {
-            #name::#variant_name => #instance_type,
+            #name::#variant_name { .. } => #instance_type,
         }
     });
 
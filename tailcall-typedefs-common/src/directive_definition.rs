@@ -25,7 +25,10 @@ pub fn from_directive_location(str: DirectiveLocation) -> String {
         DirectiveLocation::Schema => String::from("SCHEMA"),
         DirectiveLocation::Object => String::from("OBJECT"),
         DirectiveLocation::FieldDefinition => String::from("FIELD_DEFINITION"),
+        DirectiveLocation::Enum => String::from("ENUM"),
         DirectiveLocation::EnumValue => String::from("ENUM_VALUE"),
+        DirectiveLocation::ArgumentDefinition => String::from("ARGUMENT_DEFINITION"),
+        DirectiveLocation::Scalar => String::from("SCALAR"),
         _ => String::from("FIELD_DEFINITION"),
     }
 }
@@ -35,7 +38,10 @@ fn into_directive_location(str: &str) -> DirectiveLocation {
         "Schema" => DirectiveLocation::Schema,
         "Object" => DirectiveLocation::Object,
         "FieldDefinition" => DirectiveLocation::FieldDefinition,
+        "Enum" => DirectiveLocation::Enum,
         "EnumValue" => DirectiveLocation::EnumValue,
+        "ArgumentDefinition" => DirectiveLocation::ArgumentDefinition,
+        "Scalar" => DirectiveLocation::Scalar,
         _ => DirectiveLocation::FieldDefinition,
     }
 }
@@ -39,6 +39,7 @@ pub fn init_rt() -> TargetRuntime {
         file,
         cache,
         extensions: Arc::new(vec![]),
+        body_transformers: Arc::new(vec![]),
         cmd_worker: None,
         worker: None,
     }
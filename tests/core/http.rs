@@ -8,6 +8,7 @@ use std::sync::Arc;
 use anyhow::anyhow;
 use http::header::{HeaderName, HeaderValue};
 use hyper::body::Bytes;
+use tailcall::core::blueprint::Upstream;
 use tailcall::core::http::Response;
 use tailcall::core::HttpIO;
 
@@ -114,10 +115,6 @@ impl HttpIO for Http {
         // Build the response with the status code from the mock.
         let status_code = reqwest::StatusCode::from_u16(mock_response.0.status)?;
 
-        if status_code.is_client_error() || status_code.is_server_error() {
-            return Err(anyhow::format_err!("Status code error"));
-        }
-
         let mut response = Response { status: status_code, ..Default::default() };
 
         // Insert headers from the mock into the response.
@@ -132,6 +129,10 @@ impl HttpIO for Http {
             response.body = Bytes::from(body.to_bytes());
         }
 
+        if status_code.is_client_error() || status_code.is_server_error() {
+            return Err(response.to_http_error(Upstream::default().error_body_size));
+        }
+
         Ok(response)
     }
 }
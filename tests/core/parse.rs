@@ -310,6 +310,7 @@ impl ExecutionSpec {
             env: Arc::new(Env::init(env)),
             cache: Arc::new(InMemoryCache::default()),
             extensions: Arc::new(vec![]),
+            body_transformers: Arc::new(vec![]),
             cmd_worker: http_worker,
             worker,
         };
@@ -81,6 +81,7 @@ pub fn create_runtime(
         file: Arc::new(file),
         cache: Arc::new(InMemoryCache::default()),
         extensions: Arc::new(vec![]),
+        body_transformers: Arc::new(vec![]),
         cmd_worker: match &script {
             Some(script) => Some(init_worker_io::<Event, Command>(script.to_owned())),
             None => None,
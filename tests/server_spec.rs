@@ -145,6 +145,7 @@ pub mod test {
             file: Arc::new(file),
             cache: Arc::new(InMemoryCache::default()),
             extensions: Arc::new(vec![]),
+            body_transformers: Arc::new(vec![]),
             cmd_worker: match &script {
                 Some(script) => Some(init_worker_io::<Event, Command>(script.to_owned())),
                 None => None,
@@ -159,10 +160,15 @@ pub mod test {
 
 #[cfg(test)]
 mod server_spec {
+    use std::time::Duration;
+
     use reqwest::Client;
     use serde_json::json;
     use tailcall::cli::server::Server;
     use tailcall::core::config::reader::ConfigReader;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+    use tokio::time::timeout;
 
     async fn test_server(configs: &[&str], url: &str) {
         let runtime = crate::test::init(None);
@@ -266,4 +272,139 @@ mod server_spec {
         )
         .await
     }
+
+    #[tokio::test]
+    async fn server_timeouts() {
+        let configs = &["tests/server/config/server-timeouts.graphql"];
+        let runtime = crate::test::init(None);
+        let reader = ConfigReader::init(runtime);
+        let config = reader.read_all(configs).await.unwrap();
+        let mut server = Server::new(config);
+        let server_up_receiver = server.server_up_receiver();
+
+        tokio::spawn(async move {
+            server.start().await.unwrap();
+        });
+
+        server_up_receiver
+            .await
+            .expect("Server did not start up correctly");
+
+        // the connection is closed when the headers aren't sent in time
+        let mut stream = TcpStream::connect("127.0.0.1:8806").await.unwrap();
+        stream
+            .write_all(b"POST /graphql HTTP/1.1\r\n")
+            .await
+            .unwrap();
+        let mut buf = vec![];
+        timeout(Duration::from_secs(2), stream.read_to_end(&mut buf))
+            .await
+            .expect("Connection should be closed when the headers are late");
+
+        // the connection is closed after the response when keep-alive is off
+        let body = r#"{"query": "{ greet }"}"#;
+        let request = format!(
+            "POST /graphql HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let mut stream = TcpStream::connect("127.0.0.1:8806").await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        timeout(Duration::from_secs(2), stream.read_to_string(&mut response))
+            .await
+            .expect("Connection should be closed after the response")
+            .unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with(r#"{"data":{"greet":"Hello World!"}}"#));
+    }
+
+    #[tokio::test]
+    async fn server_idle_timeout() {
+        let configs = &["tests/server/config/server-idle-timeout.graphql"];
+        let runtime = crate::test::init(None);
+        let reader = ConfigReader::init(runtime);
+        let config = reader.read_all(configs).await.unwrap();
+        let mut server = Server::new(config);
+        let server_up_receiver = server.server_up_receiver();
+
+        tokio::spawn(async move {
+            server.start().await.unwrap();
+        });
+
+        server_up_receiver
+            .await
+            .expect("Server did not start up correctly");
+
+        // the connection is kept open after the response until it's idle for
+        // the keep-alive timeout
+        let body = r#"{"query": "{ greet }"}"#;
+        let request = format!(
+            "POST /graphql HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let mut stream = TcpStream::connect("127.0.0.1:8808").await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let start = std::time::Instant::now();
+        let mut response = String::new();
+        timeout(Duration::from_secs(2), stream.read_to_string(&mut response))
+            .await
+            .expect("Connection should be closed once it's idle")
+            .unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with(r#"{"data":{"greet":"Hello World!"}}"#));
+    }
+
+    #[tokio::test]
+    async fn server_proxy_protocol() {
+        let configs = &["tests/server/config/server-proxy-protocol.graphql"];
+        let runtime = crate::test::init(None);
+        let reader = ConfigReader::init(runtime);
+        let config = reader.read_all(configs).await.unwrap();
+        let mut server = Server::new(config);
+        let server_up_receiver = server.server_up_receiver();
+
+        tokio::spawn(async move {
+            server.start().await.unwrap();
+        });
+
+        server_up_receiver
+            .await
+            .expect("Server did not start up correctly");
+
+        let body = r#"{"query": "{ ip }"}"#;
+        let request = format!(
+            "POST /graphql HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+
+        // the client address is read from the PROXY protocol header
+        let mut stream = TcpStream::connect("127.0.0.1:8807").await.unwrap();
+        let header = "PROXY TCP4 203.0.113.7 10.0.0.1 56324 8807\r\n";
+        stream.write_all(header.as_bytes()).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with(r#"{"data":{"ip":"203.0.113.7"}}"#));
+
+        // the connections without a valid header are rejected
+        let mut stream = TcpStream::connect("127.0.0.1:8807").await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        let _ = stream.read_to_string(&mut response).await;
+        assert!(response.is_empty());
+    }
+
+    #[tokio::test]
+    async fn server_start_https() {
+        test_server(
+            &["tests/server/config/server-start-https.graphql"],
+            "https://localhost:8805/graphql",
+        )
+        .await
+    }
 }